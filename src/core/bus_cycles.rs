@@ -0,0 +1,128 @@
+//! Bus-cycle-granular timing.
+//!
+//! With bus-cycle timing enabled, every bus cycle the CPU runs is reported to
+//! [`AddressBus::bus_cycle`] together with its clock offset within the current instruction,
+//! and the bus answers with wait states. This lets a machine interleave CPU cycles with DMA
+//! slots and stretch cycles whose device is slow to assert DTACK.
+//!
+//! The offset counts the clocks of the instruction's earlier bus cycles, including their
+//! wait states. Internal operation clocks are not placed between cycles: the timing tables
+//! only give per-instruction totals, so they are all counted at the end.
+//!
+//! Cache hits run no bus cycle and are not reported.
+//!
+//! The 68008 splits every word and long access into byte cycles whether or not bus-cycle
+//! timing is on. The timing tables count 16-bit bus cycles, so the clocks of the extra
+//! byte cycles are charged to the instruction along with its wait states.
+
+use super::cpu::CpuCore;
+use super::memory::{AddressBus, BusCycle, BusFault};
+use super::types::CpuType;
+
+impl CpuCore {
+    /// Enable/disable bus-cycle timing. While off, [`AddressBus::bus_cycle`] is never called.
+    pub fn set_bus_cycle_timing(&mut self, on: bool) {
+        self.bus_cycle_timing = on;
+    }
+
+    /// Width of the data bus in bytes, and the clocks of a bus cycle without wait states.
+    fn bus_geometry(&self) -> (u8, i32) {
+        match self.cpu_type {
+            CpuType::M68008 => (1, 4),
+            CpuType::M68000 | CpuType::M68010 | CpuType::M68012 | CpuType::SCC68070 => (2, 4),
+            CpuType::CPU32 => (2, 3),
+            CpuType::M68EC020 | CpuType::M68020 | CpuType::M68EC030 | CpuType::M68030 => (4, 3),
+            _ => (4, 2),
+        }
+    }
+
+    /// Report the bus cycles of a `size`-byte access at `address` and charge their wait
+    /// states to the current instruction.
+    pub(crate) fn bus_cycle<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        fc: u8,
+        address: u32,
+        size: u8,
+        write: bool,
+    ) {
+        let (width, clocks) = self.bus_geometry();
+        let step = size.min(width);
+        if width == 1 {
+            // Byte cycles beyond the word cycles the timing tables count.
+            self.wait_states += clocks * (size - size.div_ceil(2)) as i32;
+        }
+        if !self.bus_cycle_timing {
+            return;
+        }
+        for i in 0..size.div_ceil(step) {
+            let wait = bus.bus_cycle(BusCycle {
+                address: address.wrapping_add((i * step) as u32),
+                fc,
+                offset: self.bus_cycle_offset,
+                size: step,
+                write,
+            }) as i32;
+            self.bus_cycle_offset += clocks + wait;
+            self.wait_states += wait;
+        }
+    }
+
+    /// Read `size` bytes (1, 2 or 4) at physical `address` in space `fc`.
+    pub(crate) fn bus_read_cycle<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        fc: u8,
+        address: u32,
+        size: u8,
+    ) -> Result<u32, BusFault> {
+        self.bus_cycle(bus, fc, address, size, false);
+        if size > 1 && self.bus_geometry().0 == 1 {
+            let mut value = 0;
+            for i in 0..size as u32 {
+                let byte = bus.try_read_byte_fc(fc, self.address(address.wrapping_add(i)))?;
+                value = (value << 8) | byte as u32;
+            }
+            return Ok(value);
+        }
+        match size {
+            1 => bus.try_read_byte_fc(fc, address).map(u32::from),
+            2 => bus.try_read_word_fc(fc, address).map(u32::from),
+            _ => bus.try_read_long_fc(fc, address),
+        }
+    }
+
+    /// Write the low `size` bytes (1, 2 or 4) of `value` at physical `address` in space `fc`.
+    pub(crate) fn bus_write_cycle<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        fc: u8,
+        address: u32,
+        size: u8,
+        value: u32,
+    ) -> Result<(), BusFault> {
+        self.bus_cycle(bus, fc, address, size, true);
+        if size > 1 && self.bus_geometry().0 == 1 {
+            for i in 0..size as u32 {
+                let byte = (value >> (8 * (size as u32 - 1 - i))) as u8;
+                bus.try_write_byte_fc(fc, self.address(address.wrapping_add(i)), byte)?;
+            }
+            return Ok(());
+        }
+        match size {
+            1 => bus.try_write_byte_fc(fc, address, value as u8),
+            2 => bus.try_write_word_fc(fc, address, value as u16),
+            _ => bus.try_write_long_fc(fc, address, value),
+        }
+    }
+
+    /// Start timing a new instruction's bus cycles.
+    pub(crate) fn begin_bus_cycles(&mut self) {
+        self.bus_cycle_offset = 0;
+    }
+
+    /// Wait states, and 68008 byte cycle clocks, charged since the last call.
+    pub(crate) fn take_wait_states(&mut self) -> i32 {
+        std::mem::take(&mut self.wait_states)
+    }
+}
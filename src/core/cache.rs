@@ -0,0 +1,675 @@
+//! On-chip caches.
+//!
+//! - 68020/68030 instruction cache ([`InstructionCache`]): 256 bytes tagged with the
+//!   logical address and FC2. The 68020 has 64 entries of one long word; the 68030 has 16
+//!   lines of four long words that share a tag, each long word with its own valid bit.
+//! - 68030 data cache ([`LineCache`]): the same 16 x 16-byte geometry, tagged with the
+//!   logical address and full function code, write-through.
+//! - 68040 instruction and data caches ([`LineCache`]): 4 KB each, 64 sets of four
+//!   16-byte lines, physically tagged. Each page or TTR selects write-through, copyback
+//!   or cache-inhibited; copyback lines only reach the bus when pushed by CPUSH or
+//!   evicted, and CINV discards them.
+//! - 68060 instruction and data caches: the 68040 model at 8 KB each (128 sets). Its
+//!   branch cache only affects timing and is not modelled; CACR just stores its enable.
+//!
+//! None of the caches snoop, so code or data changed behind the CPU's back stays stale
+//! until the guest clears, invalidates or pushes the cache.
+//!
+//! Cache emulation is off by default (see [`CpuCore::set_cache_emulation`]); accesses then
+//! always go to the bus and CACR is only stored.
+
+use super::cpu::{CpuCore, FC_SUPERVISOR_DATA};
+use super::memory::{AddressBus, BusFault};
+use super::types::CpuType;
+
+/// 68020/68030 CACR instruction cache bits.
+pub mod cacr {
+    /// Enable the instruction cache.
+    pub const ENABLE_I: u32 = 0x0001;
+    /// Freeze: hits are still served but misses no longer replace entries.
+    pub const FREEZE_I: u32 = 0x0002;
+    /// Clear the entry selected by CAAR. Write-only.
+    pub const CLEAR_ENTRY_I: u32 = 0x0004;
+    /// Clear the whole instruction cache. Write-only.
+    pub const CLEAR_I: u32 = 0x0008;
+
+    /// 68030 data cache: enable, freeze, clear entry, clear all, write allocate.
+    pub const ENABLE_D: u32 = 0x0100;
+    pub const FREEZE_D: u32 = 0x0200;
+    pub const CLEAR_ENTRY_D: u32 = 0x0400;
+    pub const CLEAR_D: u32 = 0x0800;
+    pub const WRITE_ALLOCATE: u32 = 0x2000;
+
+    /// 68040 data and instruction cache enables.
+    pub const ENABLE_D_040: u32 = 0x8000_0000;
+    pub const ENABLE_I_040: u32 = 0x0000_8000;
+
+    /// Bits that read back on the 68020.
+    pub const MASK_020: u32 = 0x0000_0003;
+    /// Bits that read back on the 68030 (instruction and data cache control).
+    pub const MASK_030: u32 = 0x0000_3313;
+    /// Bits that read back on the 68040.
+    pub const MASK_040: u32 = 0x8000_8000;
+
+    /// 68060 branch cache: enable, and clear all or only the user entries (write-only).
+    pub const ENABLE_B_060: u32 = 0x0080_0000;
+    pub const CLEAR_B_060: u32 = 0x0040_0000;
+    pub const CLEAR_USER_B_060: u32 = 0x0020_0000;
+    /// Bits that read back on the 68060: the 68040 enables plus the data cache
+    /// no-allocate, store buffer, push inhibit and half-cache modes, the branch cache
+    /// enable and the instruction cache no-allocate and half-cache modes.
+    pub const MASK_060: u32 = 0xF880_E000;
+}
+
+/// How the caches treat an access, from a page descriptor or TTR.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheMode {
+    /// Cachable; writes update the cache and go to the bus.
+    #[default]
+    WriteThrough,
+    /// 68040 cachable copyback: writes stay in the data cache until the line is pushed.
+    Copyback,
+    /// Bypasses the caches.
+    Inhibited,
+}
+
+impl CacheMode {
+    /// Decode a 68040 CM field (page descriptor or TTR bits 6-5).
+    pub fn from_cm_040(cm: u8) -> Self {
+        match cm & 3 {
+            0 => Self::WriteThrough,
+            1 => Self::Copyback,
+            _ => Self::Inhibited,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct CacheLine {
+    /// Logical address bits 31-8, with FC2 in bit 0.
+    tag: u32,
+    /// One bit per long word.
+    valid: u8,
+    data: [u32; 4],
+}
+
+/// An on-chip instruction cache; empty on CPUs without one.
+#[derive(Debug, Clone, Default)]
+pub struct InstructionCache {
+    lines: Vec<CacheLine>,
+    longs_per_line: u32,
+    /// Fetches served from the cache.
+    pub hits: u64,
+    /// Fetches that went to the bus while the cache was enabled.
+    pub misses: u64,
+}
+
+impl InstructionCache {
+    /// Cache geometry for `cpu_type`.
+    pub fn for_cpu(cpu_type: CpuType) -> Self {
+        let (lines, longs_per_line) = match cpu_type {
+            CpuType::M68EC020 | CpuType::M68020 => (64, 1),
+            CpuType::M68EC030 | CpuType::M68030 => (16, 4),
+            _ => return Self::default(),
+        };
+        Self {
+            lines: vec![CacheLine::default(); lines],
+            longs_per_line,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn is_present(&self) -> bool {
+        !self.lines.is_empty()
+    }
+
+    /// Line index, long word index within the line, and tag for `logical`.
+    fn locate(&self, logical: u32, supervisor: bool) -> (usize, usize, u32) {
+        let long = logical >> 2;
+        let line = (long / self.longs_per_line) as usize % self.lines.len();
+        let slot = (long % self.longs_per_line) as usize;
+        (line, slot, (logical & !0xFF) | supervisor as u32)
+    }
+
+    /// The cached long word containing `logical`, if valid.
+    pub fn lookup(&self, logical: u32, supervisor: bool) -> Option<u32> {
+        let (line, slot, tag) = self.locate(logical, supervisor);
+        let line = &self.lines[line];
+        (line.tag == tag && line.valid & (1 << slot) != 0).then_some(line.data[slot])
+    }
+
+    /// Load the long word containing `logical`. A line holding another tag is invalidated
+    /// first.
+    pub fn fill(&mut self, logical: u32, supervisor: bool, data: u32) {
+        let (line, slot, tag) = self.locate(logical, supervisor);
+        let line = &mut self.lines[line];
+        if line.tag != tag {
+            line.tag = tag;
+            line.valid = 0;
+        }
+        line.data[slot] = data;
+        line.valid |= 1 << slot;
+    }
+
+    /// Invalidate the long word CAAR selects: bits 7-2 on the 68020, bits 7-4 (line) and
+    /// 3-2 (long word) on the 68030.
+    pub fn invalidate_entry(&mut self, caar: u32) {
+        let long = (caar >> 2) & 0x3F;
+        let line = (long / self.longs_per_line) as usize % self.lines.len();
+        self.lines[line].valid &= !(1 << (long % self.longs_per_line));
+    }
+
+    pub fn invalidate_all(&mut self) {
+        for line in &mut self.lines {
+            line.valid = 0;
+        }
+    }
+}
+
+/// A 16-byte cache line.
+#[derive(Debug, Clone, Copy, Default)]
+struct Line {
+    /// Line-aligned tag address: logical on the 68030, physical on the 68040.
+    address: u32,
+    /// Function code the line was loaded under (68030 only).
+    space: u8,
+    /// One valid and one dirty bit per long word.
+    valid: u8,
+    dirty: u8,
+    data: [u8; 16],
+}
+
+/// A set-associative cache of 16-byte lines: the 68030 data cache and the 68040 caches.
+#[derive(Debug, Clone, Default)]
+pub struct LineCache {
+    lines: Vec<Line>,
+    ways: usize,
+    /// Round-robin replacement pointer for each set.
+    next_victim: Vec<usize>,
+}
+
+impl LineCache {
+    fn new(sets: usize, ways: usize) -> Self {
+        Self {
+            lines: vec![Line::default(); sets * ways],
+            ways,
+            next_victim: vec![0; sets],
+        }
+    }
+
+    /// Data cache geometry for `cpu_type`.
+    pub fn data_for_cpu(cpu_type: CpuType) -> Self {
+        match cpu_type {
+            CpuType::M68EC030 | CpuType::M68030 => Self::new(16, 1),
+            CpuType::M68EC040 | CpuType::M68LC040 | CpuType::M68040 => Self::new(64, 4),
+            CpuType::M68EC060 | CpuType::M68LC060 | CpuType::M68060 => Self::new(128, 4),
+            _ => Self::default(),
+        }
+    }
+
+    /// Physically tagged instruction cache geometry for `cpu_type` (68040/68060 only).
+    pub fn instruction_for_cpu(cpu_type: CpuType) -> Self {
+        match cpu_type {
+            CpuType::M68EC040 | CpuType::M68LC040 | CpuType::M68040 => Self::new(64, 4),
+            CpuType::M68EC060 | CpuType::M68LC060 | CpuType::M68060 => Self::new(128, 4),
+            _ => Self::default(),
+        }
+    }
+
+    pub fn is_present(&self) -> bool {
+        !self.lines.is_empty()
+    }
+
+    fn set_range(&self, address: u32) -> std::ops::Range<usize> {
+        let set = (address >> 4) as usize % self.next_victim.len();
+        set * self.ways..(set + 1) * self.ways
+    }
+
+    /// Index of the line tagged with `address`/`space`, whether or not the long word
+    /// holding `address` is valid.
+    fn find(&self, address: u32, space: u8) -> Option<usize> {
+        self.set_range(address).find(|&i| {
+            let line = &self.lines[i];
+            line.valid != 0 && line.address == address & !0xF && line.space == space
+        })
+    }
+
+    /// True if the long word holding `address` is cached.
+    pub fn contains(&self, address: u32, space: u8) -> bool {
+        self.find(address, space)
+            .is_some_and(|i| self.lines[i].valid & long_bit(address) != 0)
+    }
+
+    /// Claim a line for `address`/`space`, returning its index and the victim it replaced
+    /// if that held dirty data.
+    fn allocate(&mut self, address: u32, space: u8) -> (usize, Option<Line>) {
+        if let Some(i) = self.find(address, space) {
+            return (i, None);
+        }
+        let range = self.set_range(address);
+        let set = range.start / self.ways;
+        let index = match range.clone().find(|&i| self.lines[i].valid == 0) {
+            Some(i) => i,
+            None => {
+                let i = range.start + self.next_victim[set];
+                self.next_victim[set] = (self.next_victim[set] + 1) % self.ways;
+                i
+            }
+        };
+        let victim = self.lines[index];
+        self.lines[index] = Line {
+            address: address & !0xF,
+            space,
+            ..Line::default()
+        };
+        (
+            index,
+            (victim.valid != 0 && victim.dirty != 0).then_some(victim),
+        )
+    }
+
+    /// Invalidate the long word holding `address` in every line tagged with it.
+    fn invalidate_long(&mut self, address: u32) {
+        let range = self.set_range(address);
+        for line in &mut self.lines[range] {
+            if line.address == address & !0xF {
+                line.valid &= !long_bit(address);
+            }
+        }
+    }
+
+    /// Remove every line whose address satisfies `remove`, returning those holding dirty
+    /// data as `(address, data)` so the caller can push them.
+    pub fn remove(&mut self, remove: impl Fn(u32) -> bool) -> Vec<(u32, [u8; 16])> {
+        let mut dirty = Vec::new();
+        for line in &mut self.lines {
+            if line.valid != 0 && remove(line.address) {
+                if line.dirty != 0 {
+                    dirty.push((line.address, line.data));
+                }
+                *line = Line::default();
+            }
+        }
+        dirty
+    }
+
+    /// Addresses of the lines holding dirty data.
+    pub fn dirty_lines(&self) -> Vec<u32> {
+        self.lines
+            .iter()
+            .filter(|l| l.valid != 0 && l.dirty != 0)
+            .map(|l| l.address)
+            .collect()
+    }
+}
+
+/// Valid/dirty bit of the long word holding `address` within its line.
+fn long_bit(address: u32) -> u8 {
+    1 << ((address >> 2) & 3)
+}
+
+impl CpuCore {
+    /// Enable/disable the on-chip cache model. While off, CACR has no effect on accesses
+    /// and 68020+ instruction timings assume every fetch hits; while on, fetches that miss
+    /// or bypass the instruction cache add their bus time. Switch it before running code:
+    /// lines still cached when it is turned off are neither used nor pushed.
+    pub fn set_cache_emulation(&mut self, on: bool) {
+        self.cache_emulation = on;
+    }
+
+    /// Write CACR, applying its clear bits to the 68020/68030 caches.
+    pub(crate) fn write_cacr(&mut self, value: u32) {
+        let mask = match self.cpu_type {
+            CpuType::M68EC020 | CpuType::M68020 => cacr::MASK_020,
+            CpuType::M68EC030 | CpuType::M68030 => cacr::MASK_030,
+            CpuType::M68EC040 | CpuType::M68LC040 | CpuType::M68040 => cacr::MASK_040,
+            CpuType::M68EC060 | CpuType::M68LC060 | CpuType::M68060 => cacr::MASK_060,
+            _ => {
+                self.cacr = value;
+                return;
+            }
+        };
+        if value & cacr::CLEAR_I != 0 {
+            self.icache.invalidate_all();
+        } else if value & cacr::CLEAR_ENTRY_I != 0 {
+            self.icache.invalidate_entry(self.caar);
+        }
+        if mask == cacr::MASK_030 {
+            // The 68030 data cache is write-through, so clearing it loses nothing.
+            if value & cacr::CLEAR_D != 0 {
+                self.dcache.remove(|_| true);
+            } else if value & cacr::CLEAR_ENTRY_D != 0 {
+                self.dcache.invalidate_long(self.caar);
+            }
+        }
+        self.cacr = value & mask;
+    }
+
+    /// 68040 and 68060: physically tagged copyback caches controlled the 68040 way.
+    fn is_040_family(&self) -> bool {
+        matches!(
+            self.cpu_type,
+            CpuType::M68EC040 | CpuType::M68LC040 | CpuType::M68040
+        ) || self.is_060()
+    }
+
+    /// Cache mode of a data access to `logical` in space `fc`, or `None` when the data
+    /// cache is off.
+    fn data_cache_mode(&self, fc: u8, logical: u32) -> Option<CacheMode> {
+        if !self.cache_emulation || !self.dcache.is_present() {
+            return None;
+        }
+        let enable = if self.is_040_family() {
+            cacr::ENABLE_D_040
+        } else {
+            cacr::ENABLE_D
+        };
+        if self.cacr & enable == 0 {
+            return None;
+        }
+        Some(crate::mmu::cache_mode(self, logical, fc & 4 != 0, false))
+    }
+
+    /// Tag address and space of a data access: the 68030 caches logical addresses under
+    /// their function code, the 68040 physical addresses.
+    fn dcache_key(&self, fc: u8, logical: u32, physical: u32) -> (u32, u8) {
+        if self.is_040_family() {
+            (physical, 0)
+        } else {
+            (logical, fc)
+        }
+    }
+
+    /// Read `size` bytes at `physical` for a data access to `logical` in space `fc`,
+    /// through the data cache when it is enabled for the page.
+    pub(crate) fn bus_read<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        fc: u8,
+        logical: u32,
+        physical: u32,
+        size: u8,
+    ) -> Result<u32, BusFault> {
+        match self.data_cache_mode(fc, logical) {
+            Some(CacheMode::WriteThrough | CacheMode::Copyback) => {
+                let mut value = 0;
+                for i in 0..size as u32 {
+                    let byte = self.dcache_read_byte(
+                        bus,
+                        fc,
+                        logical.wrapping_add(i),
+                        physical.wrapping_add(i),
+                    )?;
+                    value = (value << 8) | byte as u32;
+                }
+                Ok(value)
+            }
+            _ => self.bus_read_cycle(bus, fc, physical, size),
+        }
+    }
+
+    /// Write `size` bytes at `physical` for a data access to `logical` in space `fc`.
+    /// Write-through pages update cached copies and the bus; copyback pages only the cache.
+    pub(crate) fn bus_write<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        fc: u8,
+        logical: u32,
+        physical: u32,
+        size: u8,
+        value: u32,
+    ) -> Result<(), BusFault> {
+        let bytes = value.to_be_bytes();
+        let bytes = &bytes[4 - size as usize..];
+        match self.data_cache_mode(fc, logical) {
+            Some(CacheMode::Copyback) => {
+                for (i, &byte) in bytes.iter().enumerate() {
+                    let i = i as u32;
+                    let index = self.dcache_line(
+                        bus,
+                        fc,
+                        logical.wrapping_add(i),
+                        physical.wrapping_add(i),
+                    )?;
+                    let offset = (physical.wrapping_add(i) & 0xF) as usize;
+                    let line = &mut self.dcache.lines[index];
+                    line.data[offset] = byte;
+                    line.dirty |= long_bit(offset as u32);
+                }
+                Ok(())
+            }
+            Some(CacheMode::WriteThrough) => {
+                self.bus_write_cycle(bus, fc, physical, size, value)?;
+                // The 68030 with WA set allocates on an aligned long word write miss.
+                let allocate = !self.is_040_family()
+                    && size == 4
+                    && logical & 3 == 0
+                    && self.cacr & (cacr::WRITE_ALLOCATE | cacr::FREEZE_D) == cacr::WRITE_ALLOCATE;
+                for (i, &byte) in bytes.iter().enumerate() {
+                    let i = i as u32;
+                    let (key, space) =
+                        self.dcache_key(fc, logical.wrapping_add(i), physical.wrapping_add(i));
+                    let index = match self.dcache.find(key, space) {
+                        Some(index) => index,
+                        None if allocate => self.dcache.allocate(key, space).0,
+                        None => continue,
+                    };
+                    let line = &mut self.dcache.lines[index];
+                    if allocate || line.valid & long_bit(key) != 0 {
+                        line.data[(key & 0xF) as usize] = byte;
+                        line.valid |= long_bit(key);
+                    }
+                }
+                Ok(())
+            }
+            _ => self.bus_write_cycle(bus, fc, physical, size, value),
+        }
+    }
+
+    fn dcache_read_byte<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        fc: u8,
+        logical: u32,
+        physical: u32,
+    ) -> Result<u8, BusFault> {
+        let (key, space) = self.dcache_key(fc, logical, physical);
+        if !self.dcache.contains(key, space)
+            && !self.is_040_family()
+            && self.cacr & cacr::FREEZE_D != 0
+        {
+            return self.bus_read_cycle(bus, fc, physical, 1).map(|v| v as u8);
+        }
+        let index = self.dcache_line(bus, fc, logical, physical)?;
+        Ok(self.dcache.lines[index].data[(key & 0xF) as usize])
+    }
+
+    /// Index of the data cache line holding the byte at `physical`, filling it on a miss.
+    /// The 68030 loads the missing long word; the 68040 loads the whole line, pushing a
+    /// dirty victim first.
+    fn dcache_line<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        fc: u8,
+        logical: u32,
+        physical: u32,
+    ) -> Result<usize, BusFault> {
+        let (key, space) = self.dcache_key(fc, logical, physical);
+        if self.dcache.contains(key, space) {
+            return Ok(self.dcache.find(key, space).unwrap_or_default());
+        }
+        let fill = if self.is_040_family() {
+            0xF
+        } else {
+            long_bit(key)
+        };
+        let mut data = [0u8; 16];
+        for i in 0..4u32 {
+            if fill & (1 << i) != 0 {
+                let long = self.bus_read_cycle(bus, fc, (physical & !0xF) + i * 4, 4)?;
+                data[(i * 4) as usize..(i * 4 + 4) as usize].copy_from_slice(&long.to_be_bytes());
+            }
+        }
+        let (index, victim) = self.dcache.allocate(key, space);
+        if let Some(victim) = victim {
+            self.push_line(bus, fc, victim.address, &victim.data);
+        }
+        let line = &mut self.dcache.lines[index];
+        for i in 0..4 {
+            if fill & (1 << i) != 0 {
+                line.data[i * 4..i * 4 + 4].copy_from_slice(&data[i * 4..i * 4 + 4]);
+            }
+        }
+        line.valid |= fill;
+        Ok(index)
+    }
+
+    /// True if 68040 instruction fetches from `logical` go through the instruction cache.
+    pub(crate) fn icache_040_enabled(&self, logical: u32) -> bool {
+        self.cache_emulation
+            && self.icache_040.is_present()
+            && self.cacr & cacr::ENABLE_I_040 != 0
+            && crate::mmu::cache_mode(self, logical, self.is_supervisor(), true)
+                != CacheMode::Inhibited
+    }
+
+    /// Instruction word at `physical` from the 68040 instruction cache, filling the line
+    /// on a miss.
+    pub(crate) fn icache_040_read<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        fc: u8,
+        physical: u32,
+    ) -> Result<u16, BusFault> {
+        let index = match self.icache_040.find(physical, 0) {
+            Some(index) => index,
+            None => {
+                let mut data = [0u8; 16];
+                for i in 0..4u32 {
+                    let long = self.bus_read_cycle(bus, fc, (physical & !0xF) + i * 4, 4)?;
+                    data[(i * 4) as usize..(i * 4 + 4) as usize]
+                        .copy_from_slice(&long.to_be_bytes());
+                }
+                self.fetch_penalty += self.instruction_timing.line_fill_cycles();
+                let (index, _) = self.icache_040.allocate(physical, 0);
+                let line = &mut self.icache_040.lines[index];
+                line.data = data;
+                line.valid = 0xF;
+                index
+            }
+        };
+        let offset = (physical & 0xE) as usize;
+        let data = &self.icache_040.lines[index].data;
+        Ok(u16::from_be_bytes([data[offset], data[offset + 1]]))
+    }
+
+    /// Write a pushed line back to the bus in space `fc`. A push that faults is lost.
+    fn push_line<B: AddressBus>(&mut self, bus: &mut B, fc: u8, address: u32, data: &[u8; 16]) {
+        for (i, long) in data.chunks_exact(4).enumerate() {
+            let value = u32::from_be_bytes([long[0], long[1], long[2], long[3]]);
+            let _ = self.bus_write_cycle(bus, fc, address + (i as u32) * 4, 4, value);
+        }
+    }
+
+    /// Push the data cache line holding `physical` to the bus if dirty and invalidate it.
+    /// MOVE16 transfers lines behind the caches, so it does this to both of its lines.
+    pub(crate) fn dcache_push_line<B: AddressBus>(&mut self, bus: &mut B, physical: u32) {
+        if !self.cache_emulation {
+            return;
+        }
+        let fc = self.data_fc();
+        for (address, data) in self.dcache.remove(|a| a == physical & !0xF) {
+            self.push_line(bus, fc, address, &data);
+        }
+    }
+
+    /// 68040 CINV/CPUSH: `1111 0100 ccps srrr`, with cc the caches (01 data, 10
+    /// instruction, 11 both), p set for CPUSH, and ss the scope (01 line, 10 page, 11 all)
+    /// of the physical address in An. CPUSH writes dirty data lines back before
+    /// invalidating them; CINV discards them.
+    pub(crate) fn exec_cinv_cpush_040<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
+        let caches = (opcode >> 6) & 3;
+        let push = opcode & 0x20 != 0;
+        let address = self.a((opcode & 7) as usize);
+        let page_mask = if self.tc & crate::mmu::m68040::TC_PAGE_8K != 0 {
+            0x1FFF
+        } else {
+            0x0FFF
+        };
+        let selected = |line: u32| match (opcode >> 3) & 3 {
+            1 => line == address & !0xF,
+            2 => line & !page_mask == address & !page_mask,
+            3 => true,
+            _ => false,
+        };
+        if caches & 1 != 0 {
+            for (line, data) in self.dcache.remove(selected) {
+                if push {
+                    self.push_line(bus, FC_SUPERVISOR_DATA as u8, line, &data);
+                }
+            }
+        }
+        if caches & 2 != 0 {
+            self.icache_040.remove(selected);
+        }
+        if push { 16 } else { 4 }
+    }
+
+    /// True if instruction fetches go through the cache.
+    #[inline]
+    pub(crate) fn icache_enabled(&self) -> bool {
+        self.cache_emulation && self.icache.is_present() && self.cacr & cacr::ENABLE_I != 0
+    }
+
+    /// Word of the instruction stream at `logical` if the cache holds it.
+    pub(crate) fn icache_read(&mut self, logical: u32) -> Option<u16> {
+        let long = self.icache.lookup(logical, self.is_supervisor())?;
+        self.icache.hits += 1;
+        Some(if logical & 2 == 0 {
+            (long >> 16) as u16
+        } else {
+            long as u16
+        })
+    }
+
+    /// Word of the instruction stream at `logical` (`physical` after translation) if either
+    /// instruction cache holds it, without counting a hit.
+    pub(crate) fn icache_peek(&self, logical: u32, physical: u32) -> Option<u16> {
+        if self.icache_enabled() {
+            let long = self.icache.lookup(logical, self.is_supervisor())?;
+            return Some(if logical & 2 == 0 {
+                (long >> 16) as u16
+            } else {
+                long as u16
+            });
+        }
+        if self.icache_040_enabled(logical) {
+            let index = self.icache_040.find(physical, 0)?;
+            let offset = (physical & 0xE) as usize;
+            let data = &self.icache_040.lines[index].data;
+            return Some(u16::from_be_bytes([data[offset], data[offset + 1]]));
+        }
+        None
+    }
+
+    /// Charge an instruction fetch that went to the bus. Instruction timings assume the
+    /// cache hits, so this only costs extra while the cache model is on.
+    #[inline]
+    pub(crate) fn charge_uncached_fetch(&mut self) {
+        if self.cache_emulation {
+            self.fetch_penalty += self.instruction_timing.fetch_miss_cycles();
+        }
+    }
+
+    /// Account for a fetch that missed the enabled cache, loading `long` (the aligned long
+    /// word containing `logical`) unless the cache is frozen.
+    pub(crate) fn icache_miss(&mut self, logical: u32, long: u32) {
+        self.icache.misses += 1;
+        self.charge_uncached_fetch();
+        if self.cacr & cacr::FREEZE_I == 0 {
+            let supervisor = self.is_supervisor();
+            self.icache.fill(logical, supervisor, long);
+        }
+    }
+}
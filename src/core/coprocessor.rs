@@ -0,0 +1,980 @@
+//! 68020/68030 coprocessor interface.
+//!
+//! The 68020 and 68030 run the F-line instructions of an off-chip coprocessor by talking
+//! to it through its coprocessor interface registers (CIRs), which sit in CPU space at
+//! `$2_0000 | cpid << 13`. The CPU writes the command or condition word and then reads
+//! the response CIR. This holds a *response primitive* that tells the CPU what to do next:
+//! fetch or store an operand, move registers, take an exception, or finish.
+//!
+//! A [`Coprocessor`] attached with [`CpuCore::attach_coprocessor`] is the device behind one
+//! coprocessor ID. The CPU runs the protocol against it for cpGEN, cpBcc, cpScc, cpDBcc,
+//! cpTRAPcc, cpSAVE and cpRESTORE, and reports the CIR accesses as CPU space bus cycles
+//! while bus-cycle timing is on. IDs with nothing attached behave as before: ID 0 holds the
+//! MMU instructions, ID 1 the built-in FPU, and the rest raise the F-line exception, as
+//! when no device answers the CIR access.
+//!
+//! A primitive that is undefined, or that the instruction's category does not accept,
+//! takes the protocol violation exception (vector 13). That exception, like the
+//! mid-instruction exception primitive, stacks a format $9 frame; RTE from it reads the
+//! response CIR again and carries on with the instruction.
+//!
+//! The 68040 has no coprocessor interface and ignores attached coprocessors.
+
+use std::fmt;
+
+use super::cpu::{CpuCore, FC_CPU_SPACE, SFLAG_SET};
+use super::decode::FLINE_TRAP_SENTINEL;
+use super::ea::{AddressingMode, EaResult};
+use super::exceptions::vector;
+use super::memory::AddressBus;
+use super::types::{CpuType, Size};
+
+/// CIR offsets within a coprocessor's CPU space block.
+pub mod cir {
+    /// Response primitive (read, word).
+    pub const RESPONSE: u8 = 0x00;
+    /// Exception acknowledge and abort (write, word); see [`super::control`].
+    pub const CONTROL: u8 = 0x02;
+    /// cpSAVE format word (read, word).
+    pub const SAVE: u8 = 0x04;
+    /// cpRESTORE format word (read/write, word).
+    pub const RESTORE: u8 = 0x06;
+    /// Opcode of the current instruction (write, word).
+    pub const OPERATION_WORD: u8 = 0x08;
+    /// cpGEN command word (write, word).
+    pub const COMMAND: u8 = 0x0A;
+    /// Condition of cpBcc, cpScc, cpDBcc and cpTRAPcc (write, word).
+    pub const CONDITION: u8 = 0x0E;
+    /// Operand data (read/write, any size).
+    pub const OPERAND: u8 = 0x10;
+    /// Register mask or control register number (read, word).
+    pub const REGISTER_SELECT: u8 = 0x14;
+    /// Address of the current instruction, or the scan PC (read/write, long).
+    pub const INSTRUCTION_ADDRESS: u8 = 0x18;
+    /// Operand address (read/write, long).
+    pub const OPERAND_ADDRESS: u8 = 0x1C;
+}
+
+/// Bits the CPU writes to the control CIR.
+pub mod control {
+    /// The CPU is taking the exception the coprocessor asked for.
+    pub const XA: u16 = 0x0002;
+    /// The CPU abandoned the instruction.
+    pub const AB: u16 = 0x0001;
+}
+
+/// Response primitive encodings, as read from the response CIR.
+///
+/// Every primitive carries [`CA`] (read the response CIR again afterwards) and [`PC`]
+/// (first write the instruction address to its CIR); the data transfers also carry
+/// [`DR`]. The transfer lengths are in bytes.
+pub mod response {
+    /// Come again.
+    pub const CA: u16 = 0x8000;
+    /// Pass the instruction address.
+    pub const PC: u16 = 0x4000;
+    /// Direction: set moves data from the coprocessor to the CPU.
+    pub const DR: u16 = 0x2000;
+
+    /// Restart the instruction; the CPU may take interrupts first.
+    pub const BUSY: u16 = 0x2400;
+    /// Nothing to do. Without CA the instruction is done and [`TF`] is its condition;
+    /// with CA the instruction is restarted, as for [`BUSY`].
+    pub const NULL: u16 = 0x0800;
+    /// Null: interrupts may be taken while coming again.
+    pub const IA: u16 = 0x0100;
+    /// Null: processing finished.
+    pub const PF: u16 = 0x0002;
+    /// Null: the condition is true.
+    pub const TF: u16 = 0x0001;
+    /// Take the privilege violation exception unless in supervisor mode.
+    pub const SUPERVISOR_CHECK: u16 = 0x0400;
+    /// Write the opcode to the operation word CIR.
+    pub const TRANSFER_OPERATION_WORD: u16 = 0x0700;
+    /// Copy `length` (even) bytes of the instruction stream to the operand CIR.
+    pub const TRANSFER_FROM_INSTRUCTION_STREAM: u16 = 0x0F00;
+    /// Write the address of the instruction's control mode EA to the operand address CIR.
+    pub const EVALUATE_AND_TRANSFER_EA: u16 = 0x0A00;
+    /// Move `length` bytes between the instruction's EA and the operand CIR; bits 10-8
+    /// give the modes allowed ([`super::ea_class`]).
+    pub const EVALUATE_EA_AND_TRANSFER_DATA: u16 = 0x1000;
+    /// Write `length` bytes from the operand CIR to the address of the last evaluated EA.
+    pub const WRITE_TO_PREVIOUSLY_EVALUATED_EA: u16 = 0x2000;
+    /// Move `length` bytes between the address read from the operand address CIR and the
+    /// operand CIR.
+    pub const TAKE_ADDRESS_AND_TRANSFER_DATA: u16 = 0x0500;
+    /// Pop `length` bytes to the operand CIR, or push them from it.
+    pub const TRANSFER_TOP_OF_STACK: u16 = 0x0E00;
+    /// Move the register in bits 3-0 (D0-D7, A0-A7) through the operand CIR.
+    pub const TRANSFER_SINGLE_REGISTER: u16 = 0x0C00;
+    /// Move the control register named by the register select CIR (MOVEC numbering).
+    pub const TRANSFER_CONTROL_REGISTER: u16 = 0x0D00;
+    /// Move the registers masked by the register select CIR (bit 0 D0 to bit 15 A7).
+    pub const TRANSFER_MULTIPLE_REGISTERS: u16 = 0x0600;
+    /// Move `length` bytes per bit set in the register select CIR between the
+    /// instruction's EA and the operand CIR.
+    pub const TRANSFER_MULTIPLE_COPROCESSOR_REGISTERS: u16 = 0x0100;
+    /// Move SR through the operand CIR and, with [`SP`], the scan PC through the
+    /// instruction address CIR.
+    pub const TRANSFER_STATUS_AND_SCANPC: u16 = 0x0200;
+    /// Status and scan PC: include the scan PC.
+    pub const SP: u16 = 0x0100;
+    /// Take the exception in bits 7-0 and restart the instruction on return.
+    pub const PRE_INSTRUCTION_EXCEPTION: u16 = 0x1C00;
+    /// Take the exception in bits 7-0 with a format $9 frame, continuing the instruction
+    /// on return.
+    pub const MID_INSTRUCTION_EXCEPTION: u16 = 0x1D00;
+    /// Take the exception in bits 7-0 with the instruction finished.
+    pub const POST_INSTRUCTION_EXCEPTION: u16 = 0x1E00;
+}
+
+/// Effective address classes of [`response::EVALUATE_EA_AND_TRANSFER_DATA`].
+pub mod ea_class {
+    pub const CONTROL_ALTERABLE: u16 = 0;
+    pub const DATA_ALTERABLE: u16 = 1;
+    pub const MEMORY_ALTERABLE: u16 = 2;
+    pub const ALTERABLE: u16 = 3;
+    pub const CONTROL: u16 = 4;
+    pub const DATA: u16 = 5;
+    pub const MEMORY: u16 = 6;
+    pub const ANY: u16 = 7;
+}
+
+/// cpSAVE/cpRESTORE format codes (the high byte of the format word; the low byte is
+/// the length of the state that follows it).
+pub mod format {
+    /// Null state: nothing follows.
+    pub const EMPTY: u8 = 0x00;
+    /// cpSAVE: the coprocessor is not ready to save; the instruction is restarted.
+    pub const NOT_READY: u8 = 0x01;
+    /// cpRESTORE: the coprocessor rejected the frame (format error exception).
+    pub const INVALID: u8 = 0x02;
+}
+
+/// An external coprocessor, seen through its interface registers.
+///
+/// Offsets are those of [`cir`]; `size` is 1, 2 or 4 bytes and the value is in the low
+/// bytes. The coprocessor drives the CPU with the primitives it returns from the response
+/// CIR.
+pub trait Coprocessor {
+    fn read_cir(&mut self, offset: u8, size: u8) -> u32;
+    fn write_cir(&mut self, offset: u8, size: u8, value: u32);
+}
+
+/// Coprocessors attached to coprocessor IDs 0-7.
+#[derive(Default)]
+pub struct Coprocessors([Option<Box<dyn Coprocessor>>; 8]);
+
+impl fmt::Debug for Coprocessors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ids: Vec<usize> = (0..8).filter(|&id| self.0[id].is_some()).collect();
+        f.debug_tuple("Coprocessors").field(&ids).finish()
+    }
+}
+
+/// CPU space address of a CIR.
+pub fn cir_address(cpid: u8, offset: u8) -> u32 {
+    0x0002_0000 | ((cpid as u32 & 7) << 13) | offset as u32
+}
+
+/// Clocks of a CIR access: an asynchronous 68020/68030 bus cycle without wait states.
+const CIR_ACCESS_CYCLES: i32 = 3;
+
+/// Clocks of the instruction on top of its CIR accesses.
+const BASE_CYCLES: i32 = 4;
+
+/// Format $9 internal word: the saved EA address is valid.
+const FRAME_EA_VALID: u16 = 0x0001;
+
+/// State of one instruction's dialog with its coprocessor.
+struct Dialog {
+    cpid: u8,
+    opcode: u16,
+    /// Address of the last evaluated EA.
+    ea: Option<u32>,
+    /// Clocks spent on CIR accesses.
+    cycles: i32,
+}
+
+/// How a dialog ended.
+enum Outcome {
+    /// A null primitive without CA; the condition for conditional instructions.
+    Done(bool),
+    /// An exception was taken or the instruction will be restarted. Holds the cycles
+    /// beyond the CIR accesses.
+    Ended(i32),
+}
+
+/// Instruction categories, from opcode bits 8-6.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Category {
+    General,
+    Conditional,
+    Branch,
+    Save,
+    Restore,
+}
+
+fn category(opcode: u16) -> Option<Category> {
+    match (opcode >> 6) & 7 {
+        0 => Some(Category::General),
+        1 => Some(Category::Conditional),
+        2 | 3 => Some(Category::Branch),
+        4 => Some(Category::Save),
+        5 => Some(Category::Restore),
+        _ => None,
+    }
+}
+
+fn ea_mode(opcode: u16) -> Option<AddressingMode> {
+    AddressingMode::decode(((opcode >> 3) & 7) as u8, (opcode & 7) as u8)
+}
+
+fn is_control(mode: AddressingMode) -> bool {
+    !matches!(
+        mode,
+        AddressingMode::DataDirect(_)
+            | AddressingMode::AddressDirect(_)
+            | AddressingMode::PostIncrement(_)
+            | AddressingMode::PreDecrement(_)
+            | AddressingMode::Immediate
+    )
+}
+
+fn is_alterable(mode: AddressingMode) -> bool {
+    !matches!(
+        mode,
+        AddressingMode::PcDisplacement | AddressingMode::PcIndex | AddressingMode::Immediate
+    )
+}
+
+/// Whether `mode` belongs to the [`ea_class`] `class`.
+fn in_class(mode: AddressingMode, class: u16) -> bool {
+    let data = !matches!(mode, AddressingMode::AddressDirect(_));
+    let memory = !mode.is_register_direct();
+    match class {
+        ea_class::CONTROL_ALTERABLE => is_control(mode) && is_alterable(mode),
+        ea_class::DATA_ALTERABLE => data && is_alterable(mode),
+        ea_class::MEMORY_ALTERABLE => memory && is_alterable(mode),
+        ea_class::ALTERABLE => is_alterable(mode),
+        ea_class::CONTROL => is_control(mode),
+        ea_class::DATA => data,
+        ea_class::MEMORY => memory,
+        _ => true,
+    }
+}
+
+/// Operand CIR access sizes that move `length` bytes: long words, then a word and a byte.
+fn chunks(length: u32) -> impl Iterator<Item = u8> {
+    let tail = [
+        (length & 2 != 0).then_some(2),
+        (length & 1 != 0).then_some(1),
+    ];
+    std::iter::repeat_n(4, (length / 4) as usize).chain(tail.into_iter().flatten())
+}
+
+impl CpuCore {
+    /// Attach `coprocessor` to coprocessor ID `cpid` (0-7), replacing any attached before.
+    /// It takes the ID's instructions on the 68020 and 68030, in place of the built-in
+    /// FPU for ID 1 and, on CPUs without an on-chip MMU, the MMU instructions for ID 0.
+    pub fn attach_coprocessor(&mut self, cpid: u8, coprocessor: Box<dyn Coprocessor>) {
+        self.coprocessors.0[(cpid & 7) as usize] = Some(coprocessor);
+    }
+
+    /// Detach and return the coprocessor attached to `cpid`.
+    pub fn detach_coprocessor(&mut self, cpid: u8) -> Option<Box<dyn Coprocessor>> {
+        self.coprocessors.0[(cpid & 7) as usize].take()
+    }
+
+    /// The coprocessor attached to `cpid`.
+    pub fn coprocessor_mut(&mut self, cpid: u8) -> Option<&mut (dyn Coprocessor + 'static)> {
+        self.coprocessors.0[(cpid & 7) as usize].as_deref_mut()
+    }
+
+    /// Whether instructions for `cpid` go to an attached coprocessor.
+    pub(crate) fn coprocessor_attached(&self, cpid: u8) -> bool {
+        let interface = matches!(
+            self.cpu_type,
+            CpuType::M68EC020 | CpuType::M68020 | CpuType::M68EC030 | CpuType::M68030
+        );
+        // The 68030's own MMU answers to ID 0.
+        interface
+            && self.coprocessors.0[(cpid & 7) as usize].is_some()
+            && !(cpid == 0 && self.has_pmmu)
+    }
+
+    /// Run `f` with the coprocessor attached to `cpid` taken out of the CPU.
+    fn with_coprocessor<R>(
+        &mut self,
+        cpid: u8,
+        f: impl FnOnce(&mut Self, &mut dyn Coprocessor) -> R,
+    ) -> R {
+        let slot = (cpid & 7) as usize;
+        let mut coprocessor = self.coprocessors.0[slot]
+            .take()
+            .expect("coprocessor attached");
+        let result = f(self, coprocessor.as_mut());
+        self.coprocessors.0[slot] = Some(coprocessor);
+        result
+    }
+
+    /// Execute the F-line instruction `opcode` on its attached coprocessor.
+    pub(crate) fn exec_coprocessor<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
+        let cpid = ((opcode >> 9) & 7) as u8;
+        let mode = ea_mode(opcode);
+
+        // Undefined categories and effective addresses are refused before any extension
+        // word is read, so they can still be intercepted as F-line traps.
+        let valid = match category(opcode) {
+            Some(Category::General | Category::Branch) => true,
+            Some(Category::Conditional) => match (opcode >> 3) & 7 {
+                1 => true,
+                7 if (2..=4).contains(&(opcode & 7)) => true,
+                _ => mode.is_some_and(|m| in_class(m, ea_class::DATA_ALTERABLE)),
+            },
+            Some(Category::Save) => mode.is_some_and(|m| {
+                matches!(m, AddressingMode::PreDecrement(_)) || (is_control(m) && is_alterable(m))
+            }),
+            Some(Category::Restore) => {
+                mode.is_some_and(|m| matches!(m, AddressingMode::PostIncrement(_)) || is_control(m))
+            }
+            None => false,
+        };
+        if !valid {
+            return FLINE_TRAP_SENTINEL;
+        }
+
+        let dialog = Dialog {
+            cpid,
+            opcode,
+            ea: None,
+            cycles: 0,
+        };
+        self.with_coprocessor(cpid, |cpu, cp| {
+            cpu.coprocessor_instruction(bus, cp, dialog, false)
+        })
+    }
+
+    /// Run the instruction of `d`, or with `resume` continue it after RTE from a format $9
+    /// frame.
+    fn coprocessor_instruction<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        cp: &mut dyn Coprocessor,
+        mut d: Dialog,
+        resume: bool,
+    ) -> i32 {
+        let cycles = match category(d.opcode) {
+            Some(Category::General) => {
+                if !resume {
+                    let command = self.read_imm_16(bus);
+                    self.cir_write(bus, cp, &mut d, cir::COMMAND, 2, command as u32);
+                }
+                match self.coprocessor_dialog(bus, cp, &mut d, Category::General) {
+                    Outcome::Done(_) => BASE_CYCLES,
+                    Outcome::Ended(cycles) => cycles,
+                }
+            }
+            Some(Category::Conditional) => {
+                if !resume {
+                    let condition = self.read_imm_16(bus) & 0x3F;
+                    self.cir_write(bus, cp, &mut d, cir::CONDITION, 2, condition as u32);
+                }
+                match self.coprocessor_dialog(bus, cp, &mut d, Category::Conditional) {
+                    Outcome::Done(condition) => self.complete_conditional(bus, d.opcode, condition),
+                    Outcome::Ended(cycles) => cycles,
+                }
+            }
+            Some(Category::Branch) => {
+                if !resume {
+                    let condition = d.opcode & 0x3F;
+                    self.cir_write(bus, cp, &mut d, cir::CONDITION, 2, condition as u32);
+                }
+                match self.coprocessor_dialog(bus, cp, &mut d, Category::Branch) {
+                    Outcome::Done(condition) => {
+                        // The displacement follows any words the coprocessor took from the
+                        // instruction stream and is relative to its own address.
+                        let base = self.pc;
+                        let disp = if d.opcode & 0x0040 != 0 {
+                            self.read_imm_32(bus) as i32
+                        } else {
+                            self.read_imm_16(bus) as i16 as i32
+                        };
+                        if condition {
+                            self.change_of_flow = true;
+                            self.pc = (base as i32).wrapping_add(disp) as u32;
+                        }
+                        BASE_CYCLES
+                    }
+                    Outcome::Ended(cycles) => cycles,
+                }
+            }
+            Some(Category::Save) => self.coprocessor_save(bus, cp, &mut d),
+            Some(Category::Restore) => self.coprocessor_restore(bus, cp, &mut d),
+            None => unreachable!("refused by exec_coprocessor"),
+        };
+        cycles + d.cycles
+    }
+
+    /// Finish cpScc, cpDBcc or cpTRAPcc once the coprocessor has evaluated `condition`.
+    fn complete_conditional<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        opcode: u16,
+        condition: bool,
+    ) -> i32 {
+        match ((opcode >> 3) & 7, opcode & 7) {
+            (1, reg) => {
+                // cpDBcc: the displacement is relative to its own extension word.
+                let base = self.pc;
+                let disp = self.read_imm_16(bus) as i16 as i32;
+                if !condition {
+                    let reg = reg as usize;
+                    let counter = (self.d(reg) as u16).wrapping_sub(1);
+                    self.set_d(reg, (self.d(reg) & 0xFFFF_0000) | counter as u32);
+                    if counter != 0xFFFF {
+                        self.change_of_flow = true;
+                        self.pc = (base as i32).wrapping_add(disp) as u32;
+                    }
+                }
+                BASE_CYCLES
+            }
+            (7, reg @ 2..=4) => {
+                // cpTRAPcc with a word, long or no operand.
+                let operand_words = match reg {
+                    2 => 1,
+                    3 => 2,
+                    _ => 0,
+                };
+                self.pc = self.pc.wrapping_add(operand_words * 2);
+                if condition {
+                    return self.take_exception(bus, vector::TRAPV);
+                }
+                BASE_CYCLES
+            }
+            _ => {
+                // cpScc
+                let value = if condition { 0xFF } else { 0x00 };
+                if let Some(mode) = ea_mode(opcode) {
+                    self.write_ea(bus, mode, Size::Byte, value);
+                }
+                BASE_CYCLES
+            }
+        }
+    }
+
+    /// Read the response CIR and carry out primitives until one ends the instruction.
+    fn coprocessor_dialog<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        cp: &mut dyn Coprocessor,
+        d: &mut Dialog,
+        category: Category,
+    ) -> Outcome {
+        loop {
+            let primitive = self.cir_read(bus, cp, d, cir::RESPONSE, 2) as u16;
+            if primitive & response::PC != 0 {
+                self.cir_write(bus, cp, d, cir::INSTRUCTION_ADDRESS, 4, self.ppc);
+            }
+            let general = category == Category::General;
+            let to_cpu = primitive & response::DR != 0;
+            let length = (primitive & 0xFF) as u32;
+
+            match primitive & 0x3F00 {
+                response::BUSY => {
+                    // Restart the instruction, so pending interrupts are taken first.
+                    self.pc = self.ppc;
+                    return Outcome::Ended(BASE_CYCLES);
+                }
+                kind if kind & !response::IA == response::NULL => {
+                    if primitive & response::CA == 0 {
+                        return Outcome::Done(primitive & response::TF != 0);
+                    }
+                    // Come again: restart like busy rather than poll within one step.
+                    self.pc = self.ppc;
+                    return Outcome::Ended(BASE_CYCLES);
+                }
+                response::SUPERVISOR_CHECK => {
+                    if self.s_flag == 0 {
+                        self.cir_write(bus, cp, d, cir::CONTROL, 2, control::AB as u32);
+                        self.pc = self.ppc;
+                        return Outcome::Ended(
+                            self.take_exception(bus, vector::PRIVILEGE_VIOLATION),
+                        );
+                    }
+                }
+                response::TRANSFER_OPERATION_WORD => {
+                    self.cir_write(bus, cp, d, cir::OPERATION_WORD, 2, d.opcode as u32);
+                }
+                response::TRANSFER_FROM_INSTRUCTION_STREAM if length.is_multiple_of(2) => {
+                    for size in chunks(length) {
+                        let value = if size == 4 {
+                            self.read_imm_32(bus)
+                        } else {
+                            self.read_imm_16(bus) as u32
+                        };
+                        self.cir_write(bus, cp, d, cir::OPERAND, size, value);
+                    }
+                }
+                response::PRE_INSTRUCTION_EXCEPTION => {
+                    self.cir_write(bus, cp, d, cir::CONTROL, 2, control::XA as u32);
+                    self.pc = self.ppc;
+                    return Outcome::Ended(self.take_exception(bus, length));
+                }
+                response::MID_INSTRUCTION_EXCEPTION => {
+                    self.cir_write(bus, cp, d, cir::CONTROL, 2, control::XA as u32);
+                    return Outcome::Ended(self.coprocessor_mid_exception(bus, d, length));
+                }
+                response::POST_INSTRUCTION_EXCEPTION if general => {
+                    self.cir_write(bus, cp, d, cir::CONTROL, 2, control::XA as u32);
+                    return Outcome::Ended(self.coprocessor_post_exception(bus, length));
+                }
+                // Conditional instructions take none of the operand transfers.
+                _ if !general => return Outcome::Ended(self.protocol_violation(bus, d)),
+                response::EVALUATE_AND_TRANSFER_EA => {
+                    let Some(mode) = ea_mode(d.opcode).filter(|&m| is_control(m)) else {
+                        return Outcome::Ended(self.coprocessor_fline(bus, cp, d));
+                    };
+                    let EaResult::Memory(address) = self.resolve_ea(bus, mode, Size::Long) else {
+                        unreachable!("control mode");
+                    };
+                    d.ea = Some(address);
+                    self.cir_write(bus, cp, d, cir::OPERAND_ADDRESS, 4, address);
+                }
+                response::WRITE_TO_PREVIOUSLY_EVALUATED_EA => {
+                    let Some(address) = d.ea else {
+                        return Outcome::Ended(self.protocol_violation(bus, d));
+                    };
+                    self.transfer_memory(bus, cp, d, address, length, true);
+                }
+                kind if kind & 0x1800 == response::EVALUATE_EA_AND_TRANSFER_DATA => {
+                    let class = (primitive >> 8) & 7;
+                    let Some(mode) = ea_mode(d.opcode).filter(|&m| in_class(m, class)) else {
+                        return Outcome::Ended(self.coprocessor_fline(bus, cp, d));
+                    };
+                    if !self.transfer_ea_data(bus, cp, d, mode, length, to_cpu) {
+                        return Outcome::Ended(self.coprocessor_fline(bus, cp, d));
+                    }
+                }
+                kind => match kind & !response::DR {
+                    response::TAKE_ADDRESS_AND_TRANSFER_DATA => {
+                        let address = self.cir_read(bus, cp, d, cir::OPERAND_ADDRESS, 4);
+                        self.transfer_memory(bus, cp, d, address, length, to_cpu);
+                    }
+                    response::TRANSFER_TOP_OF_STACK => {
+                        // Byte operands keep the stack pointer even.
+                        let step = length.max(2);
+                        if to_cpu {
+                            let address = self.a(7).wrapping_sub(step);
+                            self.set_a(7, address);
+                            self.transfer_memory(bus, cp, d, address, length, true);
+                        } else {
+                            let address = self.a(7);
+                            self.transfer_memory(bus, cp, d, address, length, false);
+                            self.set_a(7, address.wrapping_add(step));
+                        }
+                    }
+                    response::TRANSFER_SINGLE_REGISTER => {
+                        let reg = (primitive & 0xF) as usize;
+                        if to_cpu {
+                            self.dar[reg] = self.cir_read(bus, cp, d, cir::OPERAND, 4);
+                        } else {
+                            self.cir_write(bus, cp, d, cir::OPERAND, 4, self.dar[reg]);
+                        }
+                    }
+                    response::TRANSFER_CONTROL_REGISTER => {
+                        let reg = self.cir_read(bus, cp, d, cir::REGISTER_SELECT, 2) as u16;
+                        if to_cpu {
+                            let value = self.cir_read(bus, cp, d, cir::OPERAND, 4);
+                            self.write_control_register(reg, value);
+                        } else {
+                            let value = self.read_control_register(reg);
+                            self.cir_write(bus, cp, d, cir::OPERAND, 4, value);
+                        }
+                    }
+                    response::TRANSFER_MULTIPLE_REGISTERS => {
+                        let mask = self.cir_read(bus, cp, d, cir::REGISTER_SELECT, 2);
+                        for reg in (0..16).filter(|r| mask & (1 << r) != 0) {
+                            if to_cpu {
+                                self.dar[reg] = self.cir_read(bus, cp, d, cir::OPERAND, 4);
+                            } else {
+                                self.cir_write(bus, cp, d, cir::OPERAND, 4, self.dar[reg]);
+                            }
+                        }
+                    }
+                    response::TRANSFER_MULTIPLE_COPROCESSOR_REGISTERS => {
+                        let mask = self.cir_read(bus, cp, d, cir::REGISTER_SELECT, 2);
+                        let total = mask.count_ones() * length;
+                        let Some(address) = self.multiple_register_address(bus, d, total, to_cpu)
+                        else {
+                            return Outcome::Ended(self.coprocessor_fline(bus, cp, d));
+                        };
+                        self.transfer_memory(bus, cp, d, address, total, to_cpu);
+                    }
+                    kind if kind & !response::SP == response::TRANSFER_STATUS_AND_SCANPC => {
+                        let scan_pc = kind & response::SP != 0;
+                        if to_cpu {
+                            if scan_pc {
+                                self.pc = self.cir_read(bus, cp, d, cir::INSTRUCTION_ADDRESS, 4);
+                            }
+                            let sr = self.cir_read(bus, cp, d, cir::OPERAND, 2) as u16;
+                            self.set_sr(sr);
+                        } else {
+                            if scan_pc {
+                                self.cir_write(bus, cp, d, cir::INSTRUCTION_ADDRESS, 4, self.pc);
+                            }
+                            let sr = self.get_sr();
+                            self.cir_write(bus, cp, d, cir::OPERAND, 2, sr as u32);
+                        }
+                    }
+                    _ => return Outcome::Ended(self.protocol_violation(bus, d)),
+                },
+            }
+
+            if self.faulted() {
+                return Outcome::Ended(0);
+            }
+            if primitive & response::CA == 0 {
+                return Outcome::Done(false);
+            }
+        }
+    }
+
+    /// Evaluate-EA-and-transfer-data: move `length` bytes between the EA `mode` and the
+    /// operand CIR. Returns false for an operand that does not fit the mode.
+    fn transfer_ea_data<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        cp: &mut dyn Coprocessor,
+        d: &mut Dialog,
+        mode: AddressingMode,
+        length: u32,
+        to_cpu: bool,
+    ) -> bool {
+        let size = match length {
+            1 => Some(Size::Byte),
+            2 => Some(Size::Word),
+            4 => Some(Size::Long),
+            _ => None,
+        };
+        match mode {
+            AddressingMode::DataDirect(_) | AddressingMode::AddressDirect(_) => {
+                let Some(size) = size else {
+                    return false;
+                };
+                if to_cpu {
+                    let value = self.cir_read(bus, cp, d, cir::OPERAND, length as u8);
+                    self.write_ea(bus, mode, size, value);
+                } else {
+                    let value = self.read_ea(bus, mode, size);
+                    self.cir_write(bus, cp, d, cir::OPERAND, length as u8, value);
+                }
+            }
+            AddressingMode::Immediate => {
+                if to_cpu {
+                    return false;
+                }
+                // A byte operand takes the low half of a word.
+                for size in chunks(length) {
+                    let value = match size {
+                        4 => self.read_imm_32(bus),
+                        2 => self.read_imm_16(bus) as u32,
+                        _ => self.read_imm_16(bus) as u32 & 0xFF,
+                    };
+                    self.cir_write(bus, cp, d, cir::OPERAND, size, value);
+                }
+            }
+            AddressingMode::PostIncrement(reg) | AddressingMode::PreDecrement(reg) => {
+                let step = if reg == 7 && length == 1 { 2 } else { length };
+                let an = self.a(reg as usize);
+                let address = if matches!(mode, AddressingMode::PreDecrement(_)) {
+                    let address = an.wrapping_sub(step);
+                    self.set_a(reg as usize, address);
+                    address
+                } else {
+                    self.set_a(reg as usize, an.wrapping_add(step));
+                    an
+                };
+                d.ea = Some(address);
+                self.transfer_memory(bus, cp, d, address, length, to_cpu);
+            }
+            _ => {
+                let EaResult::Memory(address) = self.resolve_ea(bus, mode, Size::Long) else {
+                    return false;
+                };
+                d.ea = Some(address);
+                self.transfer_memory(bus, cp, d, address, length, to_cpu);
+            }
+        }
+        true
+    }
+
+    /// Address of the registers moved by a transfer-multiple-coprocessor-registers
+    /// primitive: a control mode, (An)+ when loading the coprocessor or -(An) when
+    /// storing. The address register is updated for `total` bytes.
+    fn multiple_register_address<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        d: &mut Dialog,
+        total: u32,
+        to_memory: bool,
+    ) -> Option<u32> {
+        let address = match ea_mode(d.opcode)? {
+            AddressingMode::PreDecrement(reg) if to_memory => {
+                let address = self.a(reg as usize).wrapping_sub(total);
+                self.set_a(reg as usize, address);
+                address
+            }
+            AddressingMode::PostIncrement(reg) if !to_memory => {
+                let address = self.a(reg as usize);
+                self.set_a(reg as usize, address.wrapping_add(total));
+                address
+            }
+            mode if is_control(mode) && (is_alterable(mode) || !to_memory) => {
+                match self.resolve_ea(bus, mode, Size::Long) {
+                    EaResult::Memory(address) => address,
+                    _ => return None,
+                }
+            }
+            _ => return None,
+        };
+        d.ea = Some(address);
+        Some(address)
+    }
+
+    /// Move `length` bytes between memory at `address` and the operand CIR, to memory
+    /// when `to_memory`.
+    fn transfer_memory<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        cp: &mut dyn Coprocessor,
+        d: &mut Dialog,
+        address: u32,
+        length: u32,
+        to_memory: bool,
+    ) {
+        let mut address = address;
+        for size in chunks(length) {
+            if to_memory {
+                let value = self.cir_read(bus, cp, d, cir::OPERAND, size);
+                match size {
+                    4 => self.write_32(bus, address, value),
+                    2 => self.write_16(bus, address, value as u16),
+                    _ => self.write_8(bus, address, value as u8),
+                }
+            } else {
+                let value = match size {
+                    4 => self.read_32(bus, address),
+                    2 => self.read_16(bus, address) as u32,
+                    _ => self.read_8(bus, address) as u32,
+                };
+                self.cir_write(bus, cp, d, cir::OPERAND, size, value);
+            }
+            if self.faulted() {
+                return;
+            }
+            address = address.wrapping_add(size as u32);
+        }
+    }
+
+    /// cpSAVE: store the format word and the state the coprocessor hands over.
+    fn coprocessor_save<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        cp: &mut dyn Coprocessor,
+        d: &mut Dialog,
+    ) -> i32 {
+        if self.s_flag == 0 {
+            return self.take_exception(bus, vector::PRIVILEGE_VIOLATION);
+        }
+        // A control mode is evaluated first; -(An) needs the length of the state.
+        let mode = ea_mode(d.opcode).expect("checked by exec_coprocessor");
+        let control_address = match mode {
+            AddressingMode::PreDecrement(_) => 0,
+            _ => match self.resolve_ea(bus, mode, Size::Long) {
+                EaResult::Memory(address) => address,
+                _ => unreachable!("control mode"),
+            },
+        };
+
+        let word = self.cir_read(bus, cp, d, cir::SAVE, 2);
+        if (word >> 8) as u8 == format::NOT_READY {
+            // Restart the instruction, so pending interrupts are taken first.
+            self.pc = self.ppc;
+            return BASE_CYCLES;
+        }
+        let length = word & 0xFF;
+        if (word >> 8) as u8 == format::INVALID || !length.is_multiple_of(4) {
+            self.cir_write(bus, cp, d, cir::CONTROL, 2, control::AB as u32);
+            return self.take_exception(bus, vector::FORMAT_ERROR);
+        }
+
+        let address = if let AddressingMode::PreDecrement(reg) = mode {
+            let address = self.a(reg as usize).wrapping_sub(4 + length);
+            self.set_a(reg as usize, address);
+            address
+        } else {
+            control_address
+        };
+        self.write_32(bus, address, word << 16);
+        self.transfer_memory(bus, cp, d, address.wrapping_add(4), length, true);
+        BASE_CYCLES
+    }
+
+    /// cpRESTORE: hand a saved format word and state back to the coprocessor.
+    fn coprocessor_restore<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        cp: &mut dyn Coprocessor,
+        d: &mut Dialog,
+    ) -> i32 {
+        if self.s_flag == 0 {
+            return self.take_exception(bus, vector::PRIVILEGE_VIOLATION);
+        }
+        let mode = ea_mode(d.opcode).expect("checked by exec_coprocessor");
+        let address = match mode {
+            AddressingMode::PostIncrement(reg) => self.a(reg as usize),
+            _ => match self.resolve_ea(bus, mode, Size::Long) {
+                EaResult::Memory(address) => address,
+                _ => unreachable!("control mode"),
+            },
+        };
+
+        let word = self.read_32(bus, address) >> 16;
+        if self.faulted() {
+            return 0;
+        }
+        self.cir_write(bus, cp, d, cir::RESTORE, 2, word);
+        let accepted = self.cir_read(bus, cp, d, cir::RESTORE, 2);
+        if (accepted >> 8) as u8 == format::INVALID {
+            self.cir_write(bus, cp, d, cir::CONTROL, 2, control::AB as u32);
+            return self.take_exception(bus, vector::FORMAT_ERROR);
+        }
+
+        let length = word & 0xFF;
+        self.transfer_memory(bus, cp, d, address.wrapping_add(4), length, false);
+        if let AddressingMode::PostIncrement(reg) = mode {
+            self.set_a(reg as usize, address.wrapping_add(4 + length));
+        }
+        BASE_CYCLES
+    }
+
+    /// Take the protocol violation exception for a primitive the instruction cannot use.
+    fn protocol_violation<B: AddressBus>(&mut self, bus: &mut B, d: &Dialog) -> i32 {
+        self.coprocessor_mid_exception(bus, d, vector::PROTOCOL_VIOLATION)
+    }
+
+    /// Abandon the instruction for an effective address the primitive cannot use.
+    fn coprocessor_fline<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        cp: &mut dyn Coprocessor,
+        d: &mut Dialog,
+    ) -> i32 {
+        self.cir_write(bus, cp, d, cir::CONTROL, 2, control::AB as u32);
+        self.pc = self.ppc;
+        self.take_exception(bus, vector::LINE_1111)
+    }
+
+    /// Take `vector` with a format $9 frame: SR, scan PC, format word, instruction
+    /// address, then the opcode and evaluated EA that RTE needs to continue.
+    fn coprocessor_mid_exception<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        d: &Dialog,
+        vector: u32,
+    ) -> i32 {
+        let old_sr = self.get_sr();
+        self.set_s_flag(SFLAG_SET);
+        self.t1_flag = 0;
+        self.t0_flag = 0;
+
+        let flags = if d.ea.is_some() { FRAME_EA_VALID } else { 0 };
+        self.push_16(bus, flags);
+        self.push_32(bus, d.ea.unwrap_or(0));
+        self.push_16(bus, d.opcode);
+        self.push_32(bus, self.ppc);
+        self.push_16(bus, 0x9000 | ((vector as u16) << 2));
+        self.push_32(bus, self.pc);
+        self.push_16(bus, old_sr);
+
+        self.jump_vector(bus, vector);
+        self.exception_timing.cycles(vector)
+    }
+
+    /// Take `vector` with a format $2 frame holding the next PC and instruction address.
+    fn coprocessor_post_exception<B: AddressBus>(&mut self, bus: &mut B, vector: u32) -> i32 {
+        let old_sr = self.get_sr();
+        self.set_s_flag(SFLAG_SET);
+        self.t1_flag = 0;
+        self.t0_flag = 0;
+
+        self.push_32(bus, self.ppc);
+        self.push_16(bus, 0x2000 | ((vector as u16) << 2));
+        self.push_32(bus, self.pc);
+        self.push_16(bus, old_sr);
+
+        self.jump_vector(bus, vector);
+        self.exception_timing.cycles(vector)
+    }
+
+    /// RTE from a format $9 frame at SP: restore SR and the scan PC, then continue the
+    /// coprocessor instruction by reading its response CIR again.
+    pub(crate) fn rte_coprocessor_frame<B: AddressBus>(&mut self, bus: &mut B) -> i32 {
+        let sr = self.pull_16(bus);
+        let pc = self.pull_32(bus);
+        let _ = self.pull_16(bus); // format word
+        let address = self.pull_32(bus);
+        let opcode = self.pull_16(bus);
+        let ea = self.pull_32(bus);
+        let flags = self.pull_16(bus);
+        self.set_sr(sr);
+        self.pc = pc;
+        self.ppc = address;
+
+        let cpid = ((opcode >> 9) & 7) as u8;
+        if opcode >> 12 != 0xF || !self.coprocessor_attached(cpid) {
+            return self.take_exception(bus, vector::LINE_1111);
+        }
+        let dialog = Dialog {
+            cpid,
+            opcode,
+            ea: (flags & FRAME_EA_VALID != 0).then_some(ea),
+            cycles: 0,
+        };
+        self.with_coprocessor(cpid, |cpu, cp| {
+            cpu.coprocessor_instruction(bus, cp, dialog, true)
+        })
+    }
+
+    /// Read a CIR of `d`'s coprocessor.
+    fn cir_read<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        cp: &mut dyn Coprocessor,
+        d: &mut Dialog,
+        offset: u8,
+        size: u8,
+    ) -> u32 {
+        let address = cir_address(d.cpid, offset);
+        self.bus_cycle(bus, FC_CPU_SPACE as u8, address, size, false);
+        d.cycles += CIR_ACCESS_CYCLES;
+        cp.read_cir(offset, size)
+    }
+
+    /// Write a CIR of `d`'s coprocessor.
+    fn cir_write<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        cp: &mut dyn Coprocessor,
+        d: &mut Dialog,
+        offset: u8,
+        size: u8,
+        value: u32,
+    ) {
+        let address = cir_address(d.cpid, offset);
+        self.bus_cycle(bus, FC_CPU_SPACE as u8, address, size, true);
+        d.cycles += CIR_ACCESS_CYCLES;
+        cp.write_cir(offset, size, value);
+    }
+}
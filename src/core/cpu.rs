@@ -2,9 +2,17 @@
 //!
 //! Mirrors Musashi's `m68ki_cpu_core` for complete M68000 family emulation.
 
+use super::cache::{InstructionCache, LineCache};
+use super::coprocessor::Coprocessors;
+use super::emac::Emac;
+use super::exceptions::{CompletedCycle, FaultedCycle, vector};
 use super::execute::RUN_MODE_BERR_AERR_RESET;
+use super::instructions::coldfire::Isa;
 use super::memory::{AddressBus, BusFaultKind};
+use super::timing::{ExceptionTiming, InstructionTiming, TimingFacts};
 use super::types::CpuType;
+use crate::fpu::{FloatX80, FpuType};
+use crate::mmu::atc::{Atc, AtcEntry};
 
 /// Flag constants for SR bits.
 pub const XFLAG_SET: u32 = 0x100;
@@ -19,6 +27,24 @@ pub const FC_USER_DATA: u32 = 1;
 pub const FC_USER_PROGRAM: u32 = 2;
 pub const FC_SUPERVISOR_DATA: u32 = 5;
 pub const FC_SUPERVISOR_PROGRAM: u32 = 6;
+pub const FC_CPU_SPACE: u32 = 7;
+
+/// 68060 Processor Configuration Register (MOVEC $808).
+pub mod pcr {
+    /// Identification field (bits 31-16) of the 68060 and of the EC/LC parts.
+    pub const ID_68060: u32 = 0x0430_0000;
+    pub const ID_68EC060: u32 = 0x0431_0000;
+    /// Revision number field (bits 15-8).
+    pub const REVISION: u32 = 0x0000_0100;
+    /// Enable debug features.
+    pub const EDEBUG: u32 = 0x80;
+    /// Disable the floating-point unit: FPU instructions take the Line-F exception.
+    pub const DFP: u32 = 0x02;
+    /// Enable superscalar dispatch.
+    pub const ESS: u32 = 0x01;
+    /// Bits MOVEC can change; the rest are read-only.
+    pub const WRITABLE: u32 = EDEBUG | DFP | ESS;
+}
 
 /// The main CPU state structure.
 ///
@@ -57,12 +83,24 @@ pub struct CpuCore {
     pub dtt0: u32,
     /// Data Transparent Translation 1 (68040)
     pub dtt1: u32,
+    /// Bus Control Register (68060)
+    pub buscr: u32,
+    /// Processor Configuration Register (68060); see [`pcr`].
+    pub pcr: u32,
+    /// Access Control Registers ACR0-ACR3 (ColdFire)
+    pub acr: [u32; 4],
+    /// RAM Base Address Registers RAMBAR0/RAMBAR1 (ColdFire)
+    pub rambar: [u32; 2],
+    /// ROM Base Address Registers ROMBAR0/ROMBAR1 (ColdFire)
+    pub rombar: [u32; 2],
+    /// Module Base Address Register (ColdFire)
+    pub mbar: u32,
     /// Instruction Register (current opcode)
     pub ir: u32,
 
     // ========== FPU Registers (68881/68882/68040) ==========
-    /// FPU Data Registers (FP0-FP7) - stored as f64 for simplicity
-    pub fpr: [f64; 8],
+    /// FPU Data Registers (FP0-FP7), 80-bit extended precision
+    pub fpr: [FloatX80; 8],
     /// FPU Instruction Address Register
     pub fpiar: u32,
     /// FPU Status Register
@@ -70,6 +108,12 @@ pub struct CpuCore {
     /// FPU Control Register
     pub fpcr: u32,
 
+    // ========== EMAC (ColdFire) ==========
+    /// Enhanced multiply-accumulate unit registers
+    pub emac: Emac,
+    /// EMAC fitted; see [`CpuCore::set_emac`].
+    pub has_emac: bool,
+
     // ========== Flags (stored separately for speed) ==========
     /// Trace 1 flag (T1 bit of SR)
     pub t1_flag: u32,
@@ -101,10 +145,14 @@ pub struct CpuCore {
     pub change_of_flow: bool,
 
     // ========== Prefetch ==========
-    /// Last prefetch address
+    /// Address of the first word in the prefetch queue
     pub pref_addr: u32,
-    /// Data in prefetch queue
+    /// Prefetch queue: the word at `pref_addr` in the high half, the next one in the low
     pub pref_data: u32,
+    /// Number of valid words in the prefetch queue (0-2)
+    pub pref_words: u8,
+    /// Emulate the 68000/68010 prefetch queue; see [`CpuCore::set_prefetch_emulation`].
+    pub prefetch_emulation: bool,
 
     // ========== CPU Configuration ==========
     /// CPU type
@@ -119,36 +167,60 @@ pub struct CpuCore {
     pub run_mode: u32,
     /// True while processing an exception (for double-fault detection)
     pub exception_processing: bool,
+    /// Data cycle in progress when the last bus error was raised.
+    pub faulted_cycle: FaultedCycle,
+    /// Exception processing cycles of the bus or address error the current instruction
+    /// took; charged in place of the aborted instruction.
+    pub fault_cycles: i32,
+    /// Data cycle the bus error handler completed in software; RTE arms it and the
+    /// restarted instruction consumes it instead of repeating the access.
+    pub completed_cycle: Option<CompletedCycle>,
+    /// Function code of the data accesses MOVES is making (SFC or DFC).
+    pub moves_fc: Option<u8>,
+    /// CPU32 background debug mode enabled; see [`CpuCore::set_background_debug`].
+    pub bdm_enabled: bool,
 
     // ========== MMU State ==========
     /// Has PMMU
     pub has_pmmu: bool,
     /// PMMU enabled
     pub pmmu_enabled: bool,
+    /// FPU fitted; see [`CpuCore::set_fpu_type`].
+    pub fpu_type: FpuType,
     /// FPU just reset
     pub fpu_just_reset: bool,
+    /// Enabled FPU exception waiting to be taken by the next FPU instruction (vector number)
+    pub fpu_pending_exception: Option<u32>,
     /// Reset cycles counter
     pub reset_cycles: u32,
+    /// External coprocessors by coprocessor ID; see [`CpuCore::attach_coprocessor`].
+    pub coprocessors: Coprocessors,
 
     // ========== Cycle Timing ==========
-    /// Cycles for Bcc not taken (byte)
+    /// Cycles added for Bcc not taken (byte)
     pub cyc_bcc_notake_b: i32,
-    /// Cycles for Bcc not taken (word)
+    /// Cycles added for Bcc not taken (word)
     pub cyc_bcc_notake_w: i32,
-    /// Cycles for DBcc false, no expiration
+    /// Cycles added for DBcc false, no expiration
     pub cyc_dbcc_f_noexp: i32,
-    /// Cycles for DBcc false, expiration
+    /// Cycles added for DBcc false, expiration
     pub cyc_dbcc_f_exp: i32,
-    /// Cycles for Scc register true
+    /// Cycles added for Scc register true
     pub cyc_scc_r_true: i32,
-    /// Cycles per word for MOVEM
+    /// Cycles per word for MOVEM, as a shift
     pub cyc_movem_w: i32,
-    /// Cycles per long for MOVEM
+    /// Cycles per long for MOVEM, as a shift
     pub cyc_movem_l: i32,
-    /// Cycles per shift count
+    /// Cycles per shift count, as a shift
     pub cyc_shift: i32,
     /// Cycles for RESET instruction
     pub cyc_reset: i32,
+    /// Exception, interrupt and reset processing times for the CPU type
+    pub exception_timing: ExceptionTiming,
+    /// Instruction timing model for the CPU type
+    pub instruction_timing: InstructionTiming,
+    /// Timing-relevant details of the instruction being executed
+    pub timing_facts: TimingFacts,
 
     // ========== Virtual IRQ ==========
     pub virq_state: u32,
@@ -173,6 +245,26 @@ pub struct CpuCore {
     pub dacr1: u32, // Data Access Control 1 (0x009)
     pub iacr0: u32, // Instruction Access Control 0 (0x00A)
     pub iacr1: u32, // Instruction Access Control 1 (0x00B)
+    /// Address translation cache(s), sized for the CPU model by `set_cpu_type`.
+    pub atc: Atc,
+    /// On-chip instruction cache (68020/68030), sized by `set_cpu_type`.
+    pub icache: InstructionCache,
+    /// On-chip data cache (68030, 68040), sized by `set_cpu_type`.
+    pub dcache: LineCache,
+    /// Physically tagged 68040 instruction cache, sized by `set_cpu_type`.
+    pub icache_040: LineCache,
+    /// Model the on-chip caches; see [`CpuCore::set_cache_emulation`].
+    pub cache_emulation: bool,
+    /// Bus clocks spent on instruction fetches by the current instruction beyond what the
+    /// timing tables assume.
+    pub fetch_penalty: i32,
+    /// Report bus cycles to the bus; see [`CpuCore::set_bus_cycle_timing`].
+    pub bus_cycle_timing: bool,
+    /// Clock offset of the next bus cycle within the current instruction.
+    pub bus_cycle_offset: i32,
+    /// Wait states returned by the bus that the current instruction has not yet been
+    /// charged for.
+    pub wait_states: i32,
 
     // ========== Execution State ==========
     /// Remaining cycles in current timeslice
@@ -210,11 +302,19 @@ impl CpuCore {
             itt1: 0,
             dtt0: 0,
             dtt1: 0,
+            buscr: 0,
+            pcr: 0,
+            acr: [0; 4],
+            rambar: [0; 2],
+            rombar: [0; 2],
+            mbar: 0,
             ir: 0,
-            fpr: [0.0; 8],
+            fpr: [FloatX80::ZERO; 8],
             fpiar: 0,
             fpsr: 0,
             fpcr: 0,
+            emac: Emac::default(),
+            has_emac: false,
             t1_flag: 0,
             t0_flag: 0,
             s_flag: SFLAG_SET, // Start in supervisor mode
@@ -230,16 +330,26 @@ impl CpuCore {
             change_of_flow: false,
             pref_addr: 0,
             pref_data: 0,
+            pref_words: 0,
+            prefetch_emulation: false,
             cpu_type: CpuType::M68000,
             address_mask: 0x00FFFFFF, // 24-bit for 68000
             sr_mask: 0xA71F,          // T1 -- S -- -- I2 I1 I0 -- -- -- X N Z V C
             instr_mode: 0,
             run_mode: 0,
             exception_processing: false,
+            faulted_cycle: FaultedCycle::default(),
+            fault_cycles: 0,
+            completed_cycle: None,
+            moves_fc: None,
+            bdm_enabled: false,
             has_pmmu: false,
             pmmu_enabled: false,
+            fpu_type: FpuType::None,
             fpu_just_reset: false,
+            fpu_pending_exception: None,
             reset_cycles: 0,
+            coprocessors: Coprocessors::default(),
             cyc_bcc_notake_b: -2,
             cyc_bcc_notake_w: 2,
             cyc_dbcc_f_noexp: -2,
@@ -249,6 +359,9 @@ impl CpuCore {
             cyc_movem_l: 3,
             cyc_shift: 1,
             cyc_reset: 132,
+            exception_timing: ExceptionTiming::default(),
+            instruction_timing: InstructionTiming::default(),
+            timing_facts: TimingFacts::default(),
             virq_state: 0,
             nmi_pending: 0,
             mmu_crp_aptr: 0,
@@ -267,6 +380,15 @@ impl CpuCore {
             dacr1: 0,
             iacr0: 0,
             iacr1: 0,
+            atc: Atc::default(),
+            icache: InstructionCache::default(),
+            dcache: LineCache::default(),
+            icache_040: LineCache::default(),
+            cache_emulation: false,
+            fetch_penalty: 0,
+            bus_cycle_timing: false,
+            bus_cycle_offset: 0,
+            wait_states: 0,
             cycles_remaining: 0,
             initial_cycles: 0,
             sst_m68000_compat: false,
@@ -284,17 +406,38 @@ impl CpuCore {
     /// Set CPU type and configure appropriate masks/timing.
     pub fn set_cpu_type(&mut self, cpu_type: CpuType) {
         self.cpu_type = cpu_type;
+        self.atc = Atc::for_cpu(cpu_type);
+        self.icache = InstructionCache::for_cpu(cpu_type);
+        self.dcache = LineCache::data_for_cpu(cpu_type);
+        self.icache_040 = LineCache::instruction_for_cpu(cpu_type);
+        self.exception_timing = ExceptionTiming::for_cpu(cpu_type);
+        self.instruction_timing = InstructionTiming::for_cpu(cpu_type);
+        self.fpu_type = FpuType::for_cpu(cpu_type);
+        self.set_cycle_adjustments(cpu_type);
         match cpu_type {
             CpuType::M68000 => {
                 self.address_mask = 0x00FFFFFF;
                 self.sr_mask = 0xA71F;
                 self.has_pmmu = false;
             }
+            CpuType::M68008 => {
+                // 48-pin DIP: A0-A19. The PLCC package brings out A20-A21 as well; see
+                // set_address_bits.
+                self.address_mask = 0x000FFFFF;
+                self.sr_mask = 0xA71F;
+                self.has_pmmu = false;
+            }
             CpuType::M68010 => {
                 self.address_mask = 0x00FFFFFF;
                 self.sr_mask = 0xA71F;
                 self.has_pmmu = false;
             }
+            CpuType::M68012 => {
+                // A 68010 with A24-A30 brought out.
+                self.address_mask = 0x7FFFFFFF;
+                self.sr_mask = 0xA71F;
+                self.has_pmmu = false;
+            }
             CpuType::SCC68070 => {
                 // SCC68070 is a 68010 with a 32-bit data bus.
                 // Instruction set is 68010-compatible, but the address bus is 32-bit.
@@ -317,18 +460,141 @@ impl CpuCore {
                 self.sr_mask = 0xF71F;
                 self.has_pmmu = true;
             }
-            CpuType::M68EC040 | CpuType::M68LC040 => {
+            CpuType::M68EC040 => {
                 self.address_mask = 0xFFFFFFFF;
                 self.sr_mask = 0xF71F;
                 self.has_pmmu = false;
             }
-            CpuType::M68040 => {
+            CpuType::M68LC040 | CpuType::M68040 => {
                 self.address_mask = 0xFFFFFFFF;
                 self.sr_mask = 0xF71F;
                 self.has_pmmu = true;
             }
+            CpuType::CPU32 => {
+                // The 68010 programming model plus T0 trace; no master mode.
+                self.address_mask = 0xFFFFFFFF;
+                self.sr_mask = 0xE71F;
+                self.has_pmmu = false;
+            }
+            CpuType::M68EC060 | CpuType::M68LC060 | CpuType::M68060 => {
+                // No master mode and no T0 trace.
+                self.address_mask = 0xFFFFFFFF;
+                self.sr_mask = 0xA71F;
+                self.has_pmmu = cpu_type != CpuType::M68EC060;
+            }
+            CpuType::ColdFireIsaA | CpuType::ColdFireIsaB | CpuType::ColdFireIsaC => {
+                // SR has an M bit, but it selects no stack, so it is not kept.
+                self.address_mask = 0xFFFFFFFF;
+                self.sr_mask = 0xA71F;
+                self.has_pmmu = false;
+            }
             _ => {}
         }
+        self.has_emac = self.is_coldfire();
+        self.pcr = match cpu_type {
+            CpuType::M68060 => pcr::ID_68060 | pcr::REVISION,
+            CpuType::M68EC060 | CpuType::M68LC060 => pcr::ID_68EC060 | pcr::REVISION,
+            _ => 0,
+        };
+    }
+
+    /// Select the FPU model, overriding the one [`set_cpu_type`](Self::set_cpu_type) chose.
+    ///
+    /// `FpuType::None` makes every FPU instruction take the Line-F exception. The
+    /// 68881 and 68882 only attach to CPUs with a coprocessor interface (68020/68030).
+    pub fn set_fpu_type(&mut self, fpu_type: FpuType) {
+        self.fpu_type = fpu_type;
+    }
+
+    /// Whether FPU instructions execute: an FPU is fitted and, on the 68060, PCR has not
+    /// disabled it.
+    pub(crate) fn fpu_present(&self) -> bool {
+        self.fpu_type != FpuType::None && self.pcr & pcr::DFP == 0
+    }
+
+    /// Set the number of address lines the CPU drives, overriding the mask
+    /// [`set_cpu_type`](Self::set_cpu_type) chose: for example 22 for a 68008 in the
+    /// 52-pin PLCC package.
+    pub fn set_address_bits(&mut self, bits: u32) {
+        self.address_mask = u32::MAX >> (32 - bits.clamp(1, 32));
+    }
+
+    /// Whether the CPU runs the 68000 programming model (68000, 68008).
+    #[inline]
+    pub(crate) fn is_68000(&self) -> bool {
+        matches!(self.cpu_type, CpuType::M68000 | CpuType::M68008)
+    }
+
+    /// Whether the CPU is one of the 68060 variants.
+    #[inline]
+    pub(crate) fn is_060(&self) -> bool {
+        matches!(
+            self.cpu_type,
+            CpuType::M68EC060 | CpuType::M68LC060 | CpuType::M68060
+        )
+    }
+
+    /// Whether the CPU is a CPU32 core.
+    #[inline]
+    pub(crate) fn is_cpu32(&self) -> bool {
+        self.cpu_type == CpuType::CPU32
+    }
+
+    /// Whether the CPU is a ColdFire core.
+    #[inline]
+    pub(crate) fn is_coldfire(&self) -> bool {
+        self.coldfire_isa().is_some()
+    }
+
+    /// ColdFire instruction set revision, or `None` for the 68000 family.
+    #[inline]
+    pub(crate) fn coldfire_isa(&self) -> Option<Isa> {
+        match self.cpu_type {
+            CpuType::ColdFireIsaA => Some(Isa::A),
+            CpuType::ColdFireIsaB => Some(Isa::B),
+            CpuType::ColdFireIsaC => Some(Isa::C),
+            _ => None,
+        }
+    }
+
+    /// Fit or remove the ColdFire EMAC unit; [`set_cpu_type`](Self::set_cpu_type) fits it
+    /// on every ColdFire core. Without it the MAC instructions take the Line-A exception.
+    pub fn set_emac(&mut self, on: bool) {
+        self.has_emac = on;
+    }
+
+    /// Enable/disable CPU32 background debug mode, as sampled from BKPT at reset. While
+    /// enabled BGND stops the CPU in background mode instead of taking the illegal
+    /// instruction exception; see [`CpuCore::leave_background`].
+    pub fn set_background_debug(&mut self, on: bool) {
+        self.bdm_enabled = on;
+    }
+
+    /// Load the data-dependent cycle adjustments (Musashi's CYC_* values; the 68040 ones
+    /// fit its own instruction tables).
+    fn set_cycle_adjustments(&mut self, cpu_type: CpuType) {
+        let (bcc_b, bcc_w, dbcc_noexp, dbcc_exp, scc, movem_w, movem_l, shift, reset) =
+            match InstructionTiming::for_cpu(cpu_type) {
+                InstructionTiming::M68000
+                    if matches!(cpu_type, CpuType::M68000 | CpuType::M68008) =>
+                {
+                    (-2, 2, -2, 2, 2, 2, 3, 1, 132)
+                }
+                InstructionTiming::M68000 => (-4, 0, 0, 6, 0, 2, 3, 1, 130),
+                InstructionTiming::M68020 | InstructionTiming::M68030 => {
+                    (-2, 0, 0, 6, 0, 2, 2, 0, 518)
+                }
+                InstructionTiming::M68040 => (1, 1, 0, 1, 0, 0, 0, 0, 518),
+            };
+        self.cyc_bcc_notake_b = bcc_b;
+        self.cyc_bcc_notake_w = bcc_w;
+        self.cyc_dbcc_f_noexp = dbcc_noexp;
+        self.cyc_dbcc_f_exp = dbcc_exp;
+        self.cyc_scc_r_true = scc;
+        self.cyc_movem_w = movem_w;
+        self.cyc_movem_l = movem_l;
+        self.cyc_shift = shift;
+        self.cyc_reset = reset;
     }
 
     // ========== Stack Pointer Banking ==========
@@ -340,6 +606,10 @@ impl CpuCore {
     /// Get the current stack pointer bank index.
     #[inline]
     fn sp_index(&self) -> usize {
+        // ColdFire ISA_A has one A7 for both modes.
+        if self.cpu_type == CpuType::ColdFireIsaA {
+            return SFLAG_SET as usize;
+        }
         (self.s_flag | ((self.s_flag >> 1) & self.m_flag)) as usize
     }
 
@@ -391,6 +661,8 @@ impl CpuCore {
         self.vbr = 0;
         self.pref_addr = 0;
         self.pref_data = 0;
+        self.pref_words = 0;
+        self.reset_cycles = self.exception_timing.reset() as u32;
 
         // Condition codes after reset: clear X/N/V/C, set Z (Musashi-compatible default).
         self.x_flag = 0;
@@ -409,17 +681,15 @@ impl CpuCore {
         self.pulse_reset();
 
         // Read initial SSP from vector 0
-        let ssp = bus.read_long(0);
+        let fc = FC_SUPERVISOR_PROGRAM as u8;
+        let ssp = self.bus_read_cycle(bus, fc, 0, 4).unwrap_or_default();
         self.dar[15] = ssp;
         self.sp[SFLAG_SET as usize] = ssp; // ISP bank
         // Initialize MSP too (for 68020+ MSP/ISP banking). Harmless on 68000.
         self.sp[(SFLAG_SET | MFLAG_SET) as usize] = ssp;
 
         // Read initial PC from vector 1
-        self.pc = bus.read_long(4);
-
-        // Use reset cycles
-        self.cycles_remaining -= self.cyc_reset;
+        self.pc = self.bus_read_cycle(bus, fc, 4, 4).unwrap_or_default();
     }
 
     /// Soft reset (compatible with old API - no bus access).
@@ -502,21 +772,27 @@ impl CpuCore {
     /// 0x000 = SFC, 0x001 = DFC, 0x002 = CACR, 0x003 = TC (68040)
     /// 0x004-0x007 = ITT0/ITT1/DTT0/DTT1, 0x008-0x00B = DACR0/DACR1/IACR0/IACR1
     /// 0x800 = USP, 0x801 = VBR, 0x802 = CAAR, 0x803 = MSP, 0x804 = ISP
-    /// 0x805 = MMUSR, 0x806 = URP, 0x807 = SRP
+    /// 0x805 = MMUSR, 0x806 = URP, 0x807 = SRP, 0x808 = PCR (68060)
+    /// On the 68060, 0x008 is BUSCR.
     pub fn read_control_register(&self, reg: u16) -> u32 {
         match reg {
-            0x000 => self.sfc,   // Source Function Code
-            0x001 => self.dfc,   // Destination Function Code
-            0x002 => self.cacr,  // Cache Control Register
-            0x003 => self.tc,    // Translation Control (68040)
-            0x004 => self.itt0,  // Instruction TTR 0 (68040)
-            0x005 => self.itt1,  // Instruction TTR 1 (68040)
-            0x006 => self.dtt0,  // Data TTR 0 (68040)
-            0x007 => self.dtt1,  // Data TTR 1 (68040)
-            0x008 => self.dacr0, // Data Access Control 0 (68040)
-            0x009 => self.dacr1, // Data Access Control 1 (68040)
-            0x00A => self.iacr0, // Instruction Access Control 0 (68040)
-            0x00B => self.iacr1, // Instruction Access Control 1 (68040)
+            0x004..=0x007 if self.is_coldfire() => {
+                // ACR0-3 (ColdFire)
+                self.acr[reg as usize - 4]
+            }
+            0x000 => self.sfc,                    // Source Function Code
+            0x001 => self.dfc,                    // Destination Function Code
+            0x002 => self.cacr,                   // Cache Control Register
+            0x003 => self.tc,                     // Translation Control (68040)
+            0x004 => self.itt0,                   // Instruction TTR 0 (68040)
+            0x005 => self.itt1,                   // Instruction TTR 1 (68040)
+            0x006 => self.dtt0,                   // Data TTR 0 (68040)
+            0x007 => self.dtt1,                   // Data TTR 1 (68040)
+            0x008 if self.is_060() => self.buscr, // Bus Control (68060)
+            0x008 => self.dacr0,                  // Data Access Control 0 (68040)
+            0x009 => self.dacr1,                  // Data Access Control 1 (68040)
+            0x00A => self.iacr0,                  // Instruction Access Control 0 (68040)
+            0x00B => self.iacr1,                  // Instruction Access Control 1 (68040)
             0x800 => {
                 // USP
                 if self.s_flag == 0 {
@@ -546,6 +822,10 @@ impl CpuCore {
             0x805 => self.mmusr, // MMU Status Register (68040)
             0x806 => self.urp,   // User Root Pointer (68040)
             0x807 => self.srp,   // Supervisor Root Pointer (68040)
+            0x808 => self.pcr,   // Processor Configuration (68060)
+            0xC00 | 0xC01 => self.rombar[reg as usize & 1], // ROMBARn (ColdFire)
+            0xC04 | 0xC05 => self.rambar[reg as usize & 1], // RAMBARn (ColdFire)
+            0xC0F => self.mbar,  // Module Base Address (ColdFire)
             _ => 0,              // Unknown register
         }
     }
@@ -553,18 +833,24 @@ impl CpuCore {
     /// Write control register for MOVEC instruction.
     pub fn write_control_register(&mut self, reg: u16, value: u32) {
         match reg {
-            0x000 => self.sfc = value & 7, // SFC (3 bits)
-            0x001 => self.dfc = value & 7, // DFC (3 bits)
-            0x002 => self.cacr = value,    // CACR
-            0x003 => self.tc = value,      // Translation Control (68040)
-            0x004 => self.itt0 = value,    // Instruction TTR 0 (68040)
-            0x005 => self.itt1 = value,    // Instruction TTR 1 (68040)
-            0x006 => self.dtt0 = value,    // Data TTR 0 (68040)
-            0x007 => self.dtt1 = value,    // Data TTR 1 (68040)
-            0x008 => self.dacr0 = value,   // Data Access Control 0 (68040)
-            0x009 => self.dacr1 = value,   // Data Access Control 1 (68040)
-            0x00A => self.iacr0 = value,   // Instruction Access Control 0 (68040)
-            0x00B => self.iacr1 = value,   // Instruction Access Control 1 (68040)
+            0x000 => self.sfc = value & 7,   // SFC (3 bits)
+            0x001 => self.dfc = value & 7,   // DFC (3 bits)
+            0x002 => self.write_cacr(value), // CACR
+            0x003 => {
+                // Translation Control (68040): E switches the native table walk on.
+                self.tc = value;
+                self.pmmu_enabled = self.has_pmmu && (value & crate::mmu::m68040::TC_ENABLE) != 0;
+            }
+            0x004..=0x007 if self.is_coldfire() => self.acr[reg as usize - 4] = value, // ACRn
+            0x004 => self.itt0 = value, // Instruction TTR 0 (68040)
+            0x005 => self.itt1 = value, // Instruction TTR 1 (68040)
+            0x006 => self.dtt0 = value, // Data TTR 0 (68040)
+            0x007 => self.dtt1 = value, // Data TTR 1 (68040)
+            0x008 if self.is_060() => self.buscr = value, // Bus Control (68060)
+            0x008 => self.dacr0 = value, // Data Access Control 0 (68040)
+            0x009 => self.dacr1 = value, // Data Access Control 1 (68040)
+            0x00A => self.iacr0 = value, // Instruction Access Control 0 (68040)
+            0x00B => self.iacr1 = value, // Instruction Access Control 1 (68040)
             0x800 => {
                 // USP
                 if self.s_flag == 0 {
@@ -594,7 +880,11 @@ impl CpuCore {
             0x805 => self.mmusr = value, // MMU Status Register (68040)
             0x806 => self.urp = value,   // User Root Pointer (68040)
             0x807 => self.srp = value,   // Supervisor Root Pointer (68040)
-            _ => {}                      // Unknown register - ignore
+            0x808 => self.pcr = (self.pcr & !pcr::WRITABLE) | (value & pcr::WRITABLE),
+            0xC00 | 0xC01 => self.rombar[reg as usize & 1] = value, // ROMBARn (ColdFire)
+            0xC04 | 0xC05 => self.rambar[reg as usize & 1] = value, // RAMBARn (ColdFire)
+            0xC0F => self.mbar = value, // Module Base Address (ColdFire)
+            _ => {}                     // Unknown register - ignore
         }
     }
 
@@ -607,7 +897,7 @@ impl CpuCore {
     }
 
     #[inline]
-    fn faulted(&self) -> bool {
+    pub(crate) fn faulted(&self) -> bool {
         self.run_mode == RUN_MODE_BERR_AERR_RESET
     }
 
@@ -628,7 +918,7 @@ impl CpuCore {
         // The execute loop saved a snapshot at the start of the instruction.
         self.set_sr_noint_nosp(self.sr_save);
         self.dar = self.dar_save;
-        let _ = self.exception_address_error(bus, address, write, instruction);
+        self.fault_cycles = self.exception_address_error(bus, address, write, instruction);
         self.run_mode = RUN_MODE_BERR_AERR_RESET;
     }
 
@@ -648,29 +938,83 @@ impl CpuCore {
         // Roll back any partially-applied register side effects from the faulting instruction.
         self.set_sr_noint_nosp(self.sr_save);
         self.dar = self.dar_save;
-        let _ = self.exception_bus_error(bus, address, write, instruction);
+        self.fault_cycles = self.exception_bus_error(bus, address, write, instruction);
         self.run_mode = RUN_MODE_BERR_AERR_RESET;
     }
 
+    /// Take the illegal instruction exception for an encoding only found to be illegal after
+    /// its extension words were read (a CPU32 memory indirect mode), and mark the
+    /// instruction as faulted like a bus error.
+    pub(crate) fn trigger_illegal_instruction<B: AddressBus>(&mut self, bus: &mut B) {
+        if self.faulted() {
+            return;
+        }
+
+        self.set_sr_noint_nosp(self.sr_save);
+        self.dar = self.dar_save;
+        self.pc = self.ppc;
+        self.fault_cycles = self.take_exception(bus, vector::ILLEGAL_INSTRUCTION);
+        self.run_mode = RUN_MODE_BERR_AERR_RESET;
+    }
+
+    /// Consume the completed data cycle if this access, made by the instruction RTE
+    /// continued, is the one it stands for.
+    fn take_completed_cycle(&mut self, addr: u32, write: bool) -> Option<CompletedCycle> {
+        let cycle = self.completed_cycle?;
+        if cycle.pc != self.ppc || cycle.address != addr || cycle.write != write {
+            return None;
+        }
+        self.completed_cycle = None;
+        Some(cycle)
+    }
+
+    /// Function code of a data access: SFC/DFC while MOVES runs, otherwise user or
+    /// supervisor data.
+    #[inline]
+    pub fn data_fc(&self) -> u8 {
+        match self.moves_fc {
+            Some(fc) => fc,
+            None if self.is_supervisor() => FC_SUPERVISOR_DATA as u8,
+            None => FC_USER_DATA as u8,
+        }
+    }
+
+    /// Function code of an instruction fetch.
+    #[inline]
+    pub fn program_fc(&self) -> u8 {
+        if self.is_supervisor() {
+            FC_SUPERVISOR_PROGRAM as u8
+        } else {
+            FC_USER_PROGRAM as u8
+        }
+    }
+
     /// Read byte from memory (data space).
     #[inline]
     pub fn read_8<B: AddressBus>(&mut self, bus: &mut B, addr: u32) -> u8 {
         if self.faulted() {
             return 0;
         }
-        let mut addr = self.address(addr);
+        let logical = self.address(addr);
+        let fc = self.data_fc();
+        if let Some(c) = self.take_completed_cycle(logical, false) {
+            return c.data as u8;
+        }
+        let mut addr = logical;
         {
-            if self.has_pmmu && self.pmmu_enabled {
+            // CPU space is never translated.
+            if self.has_pmmu && self.pmmu_enabled && fc != FC_CPU_SPACE as u8 {
                 match crate::mmu::translate_address(
                     self,
                     bus,
                     addr,
                     /*write=*/ false,
-                    self.is_supervisor(),
+                    fc & 4 != 0,
                     /*instruction=*/ false,
                 ) {
                     Ok(p) => addr = self.address(p),
                     Err(f) => {
+                        self.faulted_cycle = FaultedCycle::new(1, 0);
                         self.handle_mmu_fault(
                             bus, f, /*write=*/ false, /*instruction=*/ false,
                         );
@@ -679,11 +1023,12 @@ impl CpuCore {
                 }
             }
         }
-        match bus.try_read_byte(addr) {
-            Ok(v) => v,
+        match self.bus_read(bus, fc, logical, addr, 1) {
+            Ok(v) => v as u8,
             Err(f) => {
                 if matches!(f.kind, BusFaultKind::BusError) {
-                    self.trigger_bus_error(bus, addr, false, false);
+                    self.faulted_cycle = FaultedCycle::new(1, 0);
+                    self.trigger_bus_error(bus, logical, false, false);
                 }
                 0
             }
@@ -696,27 +1041,39 @@ impl CpuCore {
         if self.faulted() {
             return 0;
         }
-        let mut addr = self.address(addr);
+        let logical = self.address(addr);
+        let fc = self.data_fc();
+        if let Some(c) = self.take_completed_cycle(logical, false) {
+            return c.data as u16;
+        }
+        let mut addr = logical;
         if matches!(
             self.cpu_type,
-            CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+            CpuType::M68000
+                | CpuType::M68008
+                | CpuType::M68010
+                | CpuType::M68012
+                | CpuType::SCC68070
+                | CpuType::CPU32
         ) && (addr & 1) != 0
         {
+            self.faulted_cycle = FaultedCycle::new(2, 0);
             self.trigger_address_error(bus, addr, false, false);
             return 0;
         }
         {
-            if self.has_pmmu && self.pmmu_enabled {
+            if self.has_pmmu && self.pmmu_enabled && fc != FC_CPU_SPACE as u8 {
                 match crate::mmu::translate_address(
                     self,
                     bus,
                     addr,
                     /*write=*/ false,
-                    self.is_supervisor(),
+                    fc & 4 != 0,
                     /*instruction=*/ false,
                 ) {
                     Ok(p) => addr = self.address(p),
                     Err(f) => {
+                        self.faulted_cycle = FaultedCycle::new(2, 0);
                         self.handle_mmu_fault(
                             bus, f, /*write=*/ false, /*instruction=*/ false,
                         );
@@ -725,11 +1082,12 @@ impl CpuCore {
                 }
             }
         }
-        match bus.try_read_word(addr) {
-            Ok(v) => v,
+        match self.bus_read(bus, fc, logical, addr, 2) {
+            Ok(v) => v as u16,
             Err(f) => {
                 if matches!(f.kind, BusFaultKind::BusError) {
-                    self.trigger_bus_error(bus, addr, false, false);
+                    self.faulted_cycle = FaultedCycle::new(2, 0);
+                    self.trigger_bus_error(bus, logical, false, false);
                 }
                 0
             }
@@ -742,27 +1100,43 @@ impl CpuCore {
         if self.faulted() {
             return 0;
         }
-        let mut addr = self.address(addr);
+        let logical = self.address(addr);
+        let fc = self.data_fc();
+        if let Some(c) = self.take_completed_cycle(logical, false) {
+            if c.width < 4 {
+                // Only the high word cycle was completed; the low word still has to run.
+                return (c.data << 16) | self.read_16(bus, logical.wrapping_add(2)) as u32;
+            }
+            return c.data;
+        }
+        let mut addr = logical;
         if matches!(
             self.cpu_type,
-            CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+            CpuType::M68000
+                | CpuType::M68008
+                | CpuType::M68010
+                | CpuType::M68012
+                | CpuType::SCC68070
+                | CpuType::CPU32
         ) && (addr & 1) != 0
         {
+            self.faulted_cycle = FaultedCycle::new(4, 0);
             self.trigger_address_error(bus, addr, false, false);
             return 0;
         }
         {
-            if self.has_pmmu && self.pmmu_enabled {
+            if self.has_pmmu && self.pmmu_enabled && fc != FC_CPU_SPACE as u8 {
                 match crate::mmu::translate_address(
                     self,
                     bus,
                     addr,
                     /*write=*/ false,
-                    self.is_supervisor(),
+                    fc & 4 != 0,
                     /*instruction=*/ false,
                 ) {
                     Ok(p) => addr = self.address(p),
                     Err(f) => {
+                        self.faulted_cycle = FaultedCycle::new(4, 0);
                         self.handle_mmu_fault(
                             bus, f, /*write=*/ false, /*instruction=*/ false,
                         );
@@ -771,11 +1145,12 @@ impl CpuCore {
                 }
             }
         }
-        match bus.try_read_long(addr) {
+        match self.bus_read(bus, fc, logical, addr, 4) {
             Ok(v) => v,
             Err(f) => {
                 if matches!(f.kind, BusFaultKind::BusError) {
-                    self.trigger_bus_error(bus, addr, false, false);
+                    self.faulted_cycle = FaultedCycle::new(4, 0);
+                    self.trigger_bus_error(bus, logical, false, false);
                 }
                 0
             }
@@ -788,19 +1163,25 @@ impl CpuCore {
         if self.faulted() {
             return;
         }
-        let mut addr = self.address(addr);
+        let logical = self.address(addr);
+        let fc = self.data_fc();
+        if self.take_completed_cycle(logical, true).is_some() {
+            return;
+        }
+        let mut addr = logical;
         {
-            if self.has_pmmu && self.pmmu_enabled {
+            if self.has_pmmu && self.pmmu_enabled && fc != FC_CPU_SPACE as u8 {
                 match crate::mmu::translate_address(
                     self,
                     bus,
                     addr,
                     /*write=*/ true,
-                    self.is_supervisor(),
+                    fc & 4 != 0,
                     /*instruction=*/ false,
                 ) {
                     Ok(p) => addr = self.address(p),
                     Err(f) => {
+                        self.faulted_cycle = FaultedCycle::new(1, value as u32);
                         self.handle_mmu_fault(
                             bus, f, /*write=*/ true, /*instruction=*/ false,
                         );
@@ -809,10 +1190,11 @@ impl CpuCore {
                 }
             }
         }
-        if let Err(f) = bus.try_write_byte(addr, value)
+        if let Err(f) = self.bus_write(bus, fc, logical, addr, 1, value as u32)
             && matches!(f.kind, BusFaultKind::BusError)
         {
-            self.trigger_bus_error(bus, addr, true, false);
+            self.faulted_cycle = FaultedCycle::new(1, value as u32);
+            self.trigger_bus_error(bus, logical, true, false);
         }
     }
 
@@ -822,27 +1204,39 @@ impl CpuCore {
         if self.faulted() {
             return;
         }
-        let mut addr = self.address(addr);
+        let logical = self.address(addr);
+        let fc = self.data_fc();
+        if self.take_completed_cycle(logical, true).is_some() {
+            return;
+        }
+        let mut addr = logical;
         if matches!(
             self.cpu_type,
-            CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+            CpuType::M68000
+                | CpuType::M68008
+                | CpuType::M68010
+                | CpuType::M68012
+                | CpuType::SCC68070
+                | CpuType::CPU32
         ) && (addr & 1) != 0
         {
+            self.faulted_cycle = FaultedCycle::new(2, value as u32);
             self.trigger_address_error(bus, addr, true, false);
             return;
         }
         {
-            if self.has_pmmu && self.pmmu_enabled {
+            if self.has_pmmu && self.pmmu_enabled && fc != FC_CPU_SPACE as u8 {
                 match crate::mmu::translate_address(
                     self,
                     bus,
                     addr,
                     /*write=*/ true,
-                    self.is_supervisor(),
+                    fc & 4 != 0,
                     /*instruction=*/ false,
                 ) {
                     Ok(p) => addr = self.address(p),
                     Err(f) => {
+                        self.faulted_cycle = FaultedCycle::new(2, value as u32);
                         self.handle_mmu_fault(
                             bus, f, /*write=*/ true, /*instruction=*/ false,
                         );
@@ -851,10 +1245,11 @@ impl CpuCore {
                 }
             }
         }
-        if let Err(f) = bus.try_write_word(addr, value)
+        if let Err(f) = self.bus_write(bus, fc, logical, addr, 2, value as u32)
             && matches!(f.kind, BusFaultKind::BusError)
         {
-            self.trigger_bus_error(bus, addr, true, false);
+            self.faulted_cycle = FaultedCycle::new(2, value as u32);
+            self.trigger_bus_error(bus, logical, true, false);
         }
     }
 
@@ -864,27 +1259,42 @@ impl CpuCore {
         if self.faulted() {
             return;
         }
-        let mut addr = self.address(addr);
+        let logical = self.address(addr);
+        let fc = self.data_fc();
+        if let Some(c) = self.take_completed_cycle(logical, true) {
+            if c.width < 4 {
+                self.write_16(bus, logical.wrapping_add(2), value as u16);
+            }
+            return;
+        }
+        let mut addr = logical;
         if matches!(
             self.cpu_type,
-            CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+            CpuType::M68000
+                | CpuType::M68008
+                | CpuType::M68010
+                | CpuType::M68012
+                | CpuType::SCC68070
+                | CpuType::CPU32
         ) && (addr & 1) != 0
         {
+            self.faulted_cycle = FaultedCycle::new(4, value);
             self.trigger_address_error(bus, addr, true, false);
             return;
         }
         {
-            if self.has_pmmu && self.pmmu_enabled {
+            if self.has_pmmu && self.pmmu_enabled && fc != FC_CPU_SPACE as u8 {
                 match crate::mmu::translate_address(
                     self,
                     bus,
                     addr,
                     /*write=*/ true,
-                    self.is_supervisor(),
+                    fc & 4 != 0,
                     /*instruction=*/ false,
                 ) {
                     Ok(p) => addr = self.address(p),
                     Err(f) => {
+                        self.faulted_cycle = FaultedCycle::new(4, value);
                         self.handle_mmu_fault(
                             bus, f, /*write=*/ true, /*instruction=*/ false,
                         );
@@ -893,10 +1303,26 @@ impl CpuCore {
                 }
             }
         }
-        if let Err(f) = bus.try_write_long(addr, value)
+        if let Err(f) = self.bus_write(bus, fc, logical, addr, 4, value)
             && matches!(f.kind, BusFaultKind::BusError)
         {
-            self.trigger_bus_error(bus, addr, true, false);
+            self.faulted_cycle = FaultedCycle::new(4, value);
+            self.trigger_bus_error(bus, logical, true, false);
+        }
+    }
+
+    /// FSLW cause bits of a 68060 translation fault. A refused page is left in the ATC
+    /// by the table search, so a hit means a protection fault and a miss an invalid
+    /// descriptor.
+    fn mmu_fault_cause_060(&self, logical: u32, write: bool, instruction: bool) -> u32 {
+        use crate::core::exceptions::fslw;
+
+        let supervisor = self.s_flag != 0;
+        let fc = if supervisor { 4 } else { 0 };
+        match self.atc.bank(instruction).lookup(fc, logical) {
+            Some(entry) if write && entry.write_protected => fslw::WP,
+            Some(entry) if entry.supervisor_only && !supervisor => fslw::SP,
+            _ => fslw::PF,
         }
     }
 
@@ -914,10 +1340,33 @@ impl CpuCore {
         // 1. exception_processing flag in translate() bypasses MMU during exception handling
         // 2. Double-fault detection in take_exception() halts CPU on recursive faults
 
+        // The 68040 and 68060 have no dedicated MMU vectors; every translation fault is an
+        // access error.
+        if matches!(
+            self.cpu_type,
+            CpuType::M68040 | CpuType::M68LC040 | CpuType::M68060 | CpuType::M68LC060
+        ) {
+            self.faulted_cycle.atc = true;
+            if self.is_060() {
+                self.faulted_cycle.mmu_cause =
+                    self.mmu_fault_cause_060(fault.address, write, instruction);
+            }
+            self.trigger_bus_error(bus, fault.address, write, instruction);
+            return;
+        }
+
         match fault.kind {
             MmuFaultKind::BusError => {
                 self.trigger_bus_error(bus, fault.address, write, instruction)
             }
+            // Invalid, write-protected and supervisor-only pages and limit violations are
+            // bus errors on the 68030; vector 58 is only used by the 68851.
+            MmuFaultKind::AccessLevelViolation if self.cpu_type == CpuType::M68030 => {
+                self.trigger_bus_error(bus, fault.address, write, instruction)
+            }
+            MmuFaultKind::LimitViolation => {
+                self.trigger_bus_error(bus, fault.address, write, instruction)
+            }
             MmuFaultKind::ConfigurationError => {
                 let _ = self.take_exception(bus, vector::MMU_CONFIGURATION_ERROR);
                 self.run_mode = RUN_MODE_BERR_AERR_RESET;
@@ -933,13 +1382,125 @@ impl CpuCore {
         }
     }
 
+    /// Decode the 5-bit function code field of a 68030 PMMU instruction.
+    ///
+    /// `00000` = SFC, `00001` = DFC, `01rrr` = Dn bits 2-0, `10ddd` = immediate.
+    fn pmmu_fc_operand(&self, field: u16) -> Option<u8> {
+        match field & 0x1F {
+            0x00 => Some(self.sfc as u8),
+            0x01 => Some(self.dfc as u8),
+            0x08..=0x0F => Some((self.d((field & 7) as usize) & 7) as u8),
+            0x10..=0x17 => Some((field & 7) as u8),
+            _ => None,
+        }
+    }
+
+    /// Memory address of a 68030 PMMU instruction's control-mode `<ea>` operand.
+    fn pmmu_ea_address<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> Option<u32> {
+        use super::ea::{AddressingMode, EaResult};
+        use super::types::Size;
+
+        let am = AddressingMode::decode(((opcode >> 3) & 7) as u8, (opcode & 7) as u8)?;
+        match self.resolve_ea(bus, am, Size::Long) {
+            EaResult::Memory(addr) => Some(addr),
+            _ => None,
+        }
+    }
+
+    /// 68030 PFLUSHA / PFLUSH FC,#mask[,<ea>] (extension word `modes`).
+    fn exec_pflush_030<B: AddressBus>(&mut self, bus: &mut B, opcode: u16, modes: u16) -> i32 {
+        let mode = (modes >> 10) & 7;
+        if mode == 1 {
+            self.atc.flush_all();
+            return 4;
+        }
+        let Some(fc) = self.pmmu_fc_operand(modes) else {
+            return 0;
+        };
+        let mask = ((modes >> 5) & 7) as u8;
+        let fc_matches = move |e: &AtcEntry| (e.fc ^ fc) & mask == 0;
+        if mode == 4 {
+            self.atc.flush(fc_matches);
+            return 4;
+        }
+
+        let Some(addr) = self.pmmu_ea_address(bus, opcode) else {
+            return 0;
+        };
+        self.atc
+            .flush(|e| fc_matches(e) && e.logical == addr & !e.page_mask);
+        8
+    }
+
+    /// 68030 PTESTR/PTESTW FC,<ea>,#level[,An]: fill MMUSR from an ATC (level 0) or table
+    /// search, optionally returning the last descriptor address in An.
+    fn exec_ptest_030<B: AddressBus>(&mut self, bus: &mut B, opcode: u16, modes: u16) -> i32 {
+        let Some(fc) = self.pmmu_fc_operand(modes) else {
+            return 0;
+        };
+        let Some(addr) = self.pmmu_ea_address(bus, opcode) else {
+            return 0;
+        };
+        let level = ((modes >> 10) & 7) as u8;
+        let write = modes & 0x0200 == 0;
+        let (mmusr, descriptor_address) = crate::mmu::ptest_030(self, bus, addr, fc, level, write);
+        self.mmu_sr = mmusr;
+        if modes & 0x0100 != 0 {
+            self.set_a(((modes >> 5) & 7) as usize, descriptor_address);
+        }
+        8
+    }
+
+    /// 68030 PLOADR/PLOADW FC,<ea>: preload the ATC with the translation of `<ea>`.
+    fn exec_pload_030<B: AddressBus>(&mut self, bus: &mut B, opcode: u16, modes: u16) -> i32 {
+        let Some(fc) = self.pmmu_fc_operand(modes) else {
+            return 0;
+        };
+        let Some(addr) = self.pmmu_ea_address(bus, opcode) else {
+            return 0;
+        };
+        crate::mmu::pload_030(self, bus, addr, fc, modes & 0x0200 == 0);
+        8
+    }
+
+    /// 68040 PFLUSHN (An), PFLUSH (An), PFLUSHAN and PFLUSHA (0xF500-0xF51F).
+    ///
+    /// The per-page forms flush entries for the page of An under DFC's user/supervisor
+    /// bit; the N forms keep global entries.
+    pub fn exec_pflush_040(&mut self, opcode: u16) -> i32 {
+        let keep_global = opcode & 0x0008 == 0;
+        if opcode & 0x0010 == 0 {
+            let fc = (self.dfc & 4) as u8;
+            let addr = self.a((opcode & 7) as usize);
+            self.atc.flush(|e| {
+                e.fc == fc && e.logical == addr & !e.page_mask && !(keep_global && e.global)
+            });
+        } else {
+            self.atc.flush(|e| !(keep_global && e.global));
+        }
+        4
+    }
+
+    /// 68040 PTESTW (An) / PTESTR (An) (0xF548 / 0xF568): search for An under DFC and
+    /// report the result in MMUSR.
+    pub fn exec_ptest_040<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
+        let addr = self.a((opcode & 7) as usize);
+        let write = opcode & 0x0020 == 0;
+        self.mmusr = crate::mmu::m68040::ptest(self, bus, addr, self.dfc as u8, write);
+        4
+    }
+
     /// Execute COP0 / PMMU op0 (0xF0xx) style instructions.
     ///
-    /// Currently supports only PMOVE to/from a subset of PMMU registers:
+    /// Currently supports PMOVE to/from a subset of PMMU registers:
     /// - TC (32-bit)
     /// - SRP (64-bit) (limit:aptr)
     /// - CRP (64-bit) (limit:aptr)
     ///
+    /// - MMUSR (16-bit)
+    ///
+    /// and the 68030 PFLUSH, PTEST and PLOAD instructions.
+    ///
     /// Returns 0 if not recognized/supported (caller should treat as LINE 1111).
     pub fn exec_mmu_op0<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
         use super::ea::AddressingMode;
@@ -956,20 +1517,33 @@ impl CpuCore {
         // Extension word immediately after opcode.
         let modes = self.read_imm_16(bus);
 
-        // Only handle PMOVE-family encodings for now.
-        // Reject known-but-unimplemented ops (PLOAD/PFLUSH/PTEST/etc).
-        // However, on 68040 treat PTEST as NOP since we don't have real MMU.
+        // Handle PMOVE, PFLUSH, PTEST and PLOAD; reject other known-but-unimplemented ops.
+        // The 68040 and 68060 have no 0xF0xx PTEST; keep treating it as a NOP there.
         let is_ptest = (modes & 0xE000) == 0x8000;
         let is_040 = matches!(
             self.cpu_type,
             super::types::CpuType::M68EC040
                 | super::types::CpuType::M68LC040
                 | super::types::CpuType::M68040
+                | super::types::CpuType::M68EC060
+                | super::types::CpuType::M68LC060
+                | super::types::CpuType::M68060
         );
         if is_ptest && is_040 {
             // PTEST on 68040 - treat as NOP
             return 4;
         }
+        // PFLUSHA (mode 001), PFLUSH FC,#mask (100) and PFLUSH FC,#mask,<ea> (110).
+        if !is_040 && (modes & 0xE200) == 0x2000 && matches!((modes >> 10) & 7, 1 | 4 | 6) {
+            return self.exec_pflush_030(bus, opcode, modes);
+        }
+        if !is_040 && is_ptest {
+            return self.exec_ptest_030(bus, opcode, modes);
+        }
+        // PLOADR/PLOADW FC,<ea>: 0010 00R0 000f ffff
+        if !is_040 && (modes & 0xFDE0) == 0x2000 {
+            return self.exec_pload_030(bus, opcode, modes);
+        }
         if (modes & 0xFDE0) == 0x2000
             || (modes & 0xE200) == 0x2000
             || modes == 0xA000
@@ -992,6 +1566,17 @@ impl CpuCore {
         let to_ea = (modes & 0x0200) != 0;
         let regsel = ((modes >> 10) & 0x7) as u8;
 
+        // PMOVE MMUSR (16-bit): 0110 00R0 0000 0000
+        if (modes & 0xFDFF) == 0x6000 {
+            let ea = self.resolve_ea(bus, am, Size::Word);
+            if to_ea {
+                self.write_resolved_ea(bus, ea, Size::Word, self.mmu_sr as u32);
+            } else {
+                self.mmu_sr = self.read_resolved_ea(bus, ea, Size::Word) as u16;
+            }
+            return 4;
+        }
+
         // Helper: resolve EA and require memory for 64-bit transfers.
         let ea = self.resolve_ea(bus, am, Size::Long);
 
@@ -1026,10 +1611,20 @@ impl CpuCore {
                 _ => 0,
             }
         } else {
+            // Loading TC/SRP/CRP flushes the ATC unless FD (flush disable) is set.
+            if (modes & 0x0100) == 0 && matches!(regsel, 0 | 2 | 3) {
+                self.atc.flush_all();
+            }
+            // On the 68030 an invalid TC or root pointer takes an MMU configuration error
+            // and is not loaded.
             match regsel {
                 0 => {
                     // TC (32)
                     let v = self.read_resolved_ea(bus, ea, Size::Long);
+                    if !is_040 && let Err(f) = crate::mmu::validate_tc_030(v) {
+                        self.handle_mmu_fault(bus, f, false, false);
+                        return 4;
+                    }
                     self.mmu_tc = v;
                     // Enable PMMU based on TC high bit (common convention).
                     self.pmmu_enabled = (self.mmu_tc & 0x8000_0000) != 0;
@@ -1040,6 +1635,10 @@ impl CpuCore {
                     let Some(a) = ea_addr_only(ea) else { return 0 };
                     let limit = self.read_32(bus, a);
                     let aptr = self.read_32(bus, a.wrapping_add(4));
+                    if !is_040 && let Err(f) = crate::mmu::validate_root_pointer_030(limit) {
+                        self.handle_mmu_fault(bus, f, false, false);
+                        return 8;
+                    }
                     self.mmu_srp_limit = limit;
                     self.mmu_srp_aptr = aptr;
                     8
@@ -1049,6 +1648,10 @@ impl CpuCore {
                     let Some(a) = ea_addr_only(ea) else { return 0 };
                     let limit = self.read_32(bus, a);
                     let aptr = self.read_32(bus, a.wrapping_add(4));
+                    if !is_040 && let Err(f) = crate::mmu::validate_root_pointer_030(limit) {
+                        self.handle_mmu_fault(bus, f, false, false);
+                        return 8;
+                    }
                     self.mmu_crp_limit = limit;
                     self.mmu_crp_aptr = aptr;
                     8
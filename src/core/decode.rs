@@ -4,9 +4,15 @@
 
 use super::cpu::CpuCore;
 use super::ea::{AddressingMode, EaResult};
-use super::execute::RUN_MODE_BERR_AERR_RESET;
+use super::emac::is_emac_opcode;
+use super::exceptions::vector;
+use super::execute::{RUN_MODE_BERR_AERR_RESET, STOP_LEVEL_BACKGROUND};
+use super::instructions::coldfire::{Isa, STRLDSR_COMMAND};
+use super::instructions::cpu32::{LPSTOP_COMMAND, is_table_lookup};
 use super::memory::AddressBus;
+use super::timing::TimingFacts;
 use super::types::{CpuType, InternalStepResult, Size};
+use crate::fpu::FpuType;
 
 // ============================================================================
 // Trap Interception Sentinels
@@ -23,10 +29,15 @@ use super::types::{CpuType, InternalStepResult, Size};
 //
 // Currently safe instructions:
 // - A-line (0xAxxx): Detected immediately by group dispatch
-// - F-line (0xFxxx): Detected immediately by group dispatch (68000/68010)
+// - F-line (0xFxxx): Detected immediately by group dispatch (68000/68010); on the CPU32
+//   after the LPSTOP/TBL command word has only been peeked
+// - Coprocessor instructions: refused by exec_coprocessor before any extension word
+// - FPU instructions with no FPU fitted, or left to software by the 68060: checked
+//   against the peeked command word only
 // - TRAP #n: Pattern match on opcode bits only, no EA decoding
 // - BKPT #n: Pattern match on opcode bits only, no EA decoding
 // - ILLEGAL (0x4AFC): Explicit early match, no EA decoding
+// - BGND (0x4AFA) on a CPU32 with background debug mode disabled: same
 //
 // If adding new interceptable instructions, verify they meet these criteria!
 // ============================================================================
@@ -58,30 +69,31 @@ pub(crate) fn dispatch_instruction<B: AddressBus>(
     bus: &mut B,
     opcode: u16,
 ) -> InternalStepResult {
-    // Get the top 4 bits for group dispatch
-    let group = (opcode >> 12) & 0xF;
+    cpu.timing_facts = TimingFacts::default();
 
-    // Dispatch by group
-    let cycles = match group {
-        0x0 => dispatch_group_0(cpu, bus, opcode), // Bit ops, MOVEP, Imm
-        0x1 => dispatch_move(cpu, bus, opcode, Size::Byte),
-        0x2 => dispatch_move(cpu, bus, opcode, Size::Long),
-        0x3 => dispatch_move(cpu, bus, opcode, Size::Word),
-        0x4 => dispatch_group_4(cpu, bus, opcode), // Misc (LEA, TRAP, etc.)
-        0x5 => dispatch_group_5(cpu, bus, opcode), // ADDQ/SUBQ/Scc/DBcc
-        0x6 => dispatch_group_6(cpu, bus, opcode), // Bcc/BSR
-        0x7 => dispatch_moveq(cpu, opcode),
-        0x8 => dispatch_group_8(cpu, bus, opcode), // OR/DIV/SBCD
-        0x9 => dispatch_group_9(cpu, bus, opcode), // SUB/SUBX
-        0xA => exception_1010(cpu, opcode),
-        0xB => dispatch_group_b(cpu, bus, opcode), // CMP/EOR
-        0xC => dispatch_group_c(cpu, bus, opcode), // AND/MUL/ABCD/EXG
-        0xD => dispatch_group_d(cpu, bus, opcode), // ADD/ADDX
-        0xE => dispatch_group_e(cpu, bus, opcode), // Shift/Rotate
-        0xF => dispatch_group_f(cpu, bus, opcode),
-        _ => unreachable!(),
+    let cycles = if cpu.is_coldfire() {
+        dispatch_coldfire(cpu, bus, opcode)
+    } else {
+        dispatch_group(cpu, bus, opcode)
     };
 
+    cpu.prefetch_fill(bus);
+    let fetch_penalty = std::mem::take(&mut cpu.fetch_penalty);
+
+    // A cycle completed by a bus error handler only stands in for an access of the
+    // instruction RTE continued.
+    if cpu.completed_cycle.is_some_and(|c| c.pc == cpu.ppc) {
+        cpu.completed_cycle = None;
+    }
+
+    // An instruction aborted by a bus or address error costs the exception processing
+    // time instead of its own.
+    if cpu.faulted() {
+        return InternalStepResult::Ok {
+            cycles: std::mem::take(&mut cpu.fault_cycles) + cpu.take_wait_states(),
+        };
+    }
+
     // Check for A-line trap sentinel
     if cycles == ALINE_TRAP_SENTINEL {
         return InternalStepResult::AlineTrap { opcode };
@@ -109,7 +121,264 @@ pub(crate) fn dispatch_instruction<B: AddressBus>(
         return InternalStepResult::IllegalInstruction { opcode };
     }
 
-    InternalStepResult::Ok { cycles }
+    // The 68020 and later replace the handler's 68000-style count with their own.
+    let cycles = cpu.instruction_timing.cycles(cpu, opcode).unwrap_or(cycles);
+
+    InternalStepResult::Ok {
+        cycles: cycles + fetch_penalty + cpu.take_wait_states(),
+    }
+}
+
+/// Dispatch by the top 4 bits of the opcode.
+fn dispatch_group<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16) -> i32 {
+    match (opcode >> 12) & 0xF {
+        0x0 => dispatch_group_0(cpu, bus, opcode), // Bit ops, MOVEP, Imm
+        0x1 => dispatch_move(cpu, bus, opcode, Size::Byte),
+        0x2 => dispatch_move(cpu, bus, opcode, Size::Long),
+        0x3 => dispatch_move(cpu, bus, opcode, Size::Word),
+        0x4 => dispatch_group_4(cpu, bus, opcode), // Misc (LEA, TRAP, etc.)
+        0x5 => dispatch_group_5(cpu, bus, opcode), // ADDQ/SUBQ/Scc/DBcc
+        0x6 => dispatch_group_6(cpu, bus, opcode), // Bcc/BSR
+        0x7 => dispatch_moveq(cpu, opcode),
+        0x8 => dispatch_group_8(cpu, bus, opcode), // OR/DIV/SBCD
+        0x9 => dispatch_group_9(cpu, bus, opcode), // SUB/SUBX
+        0xA => exception_1010(cpu, opcode),
+        0xB => dispatch_group_b(cpu, bus, opcode), // CMP/EOR
+        0xC => dispatch_group_c(cpu, bus, opcode), // AND/MUL/ABCD/EXG
+        0xD => dispatch_group_d(cpu, bus, opcode), // ADD/ADDX
+        0xE => dispatch_group_e(cpu, bus, opcode), // Shift/Rotate
+        0xF => dispatch_group_f(cpu, bus, opcode),
+        _ => unreachable!(),
+    }
+}
+
+// ============================================================================
+// ColdFire
+// ============================================================================
+
+/// Dispatch on a ColdFire core: decode its own instructions, refuse the 68000 forms it
+/// left out, and hand the rest to the shared group dispatchers.
+fn dispatch_coldfire<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16) -> i32 {
+    let isa = cpu.coldfire_isa().unwrap();
+    let ea_mode = (opcode >> 3) & 7;
+    let ea_reg = opcode & 7;
+    let reg = (opcode & 7) as usize;
+
+    match opcode {
+        // BITREV, BYTEREV, FF1 Dx
+        _ if (opcode & 0xF9F8) == 0x00C0 && opcode & 0x0600 != 0x0600 && isa >= Isa::C => {
+            return match (opcode >> 9) & 3 {
+                0 => cpu.exec_bitrev(reg),
+                1 => cpu.exec_byterev(reg),
+                _ => cpu.exec_ff1(reg),
+            };
+        }
+        // HALT
+        0x4AC8 => {
+            if !cpu.is_supervisor() {
+                return cpu.exception_privilege(bus);
+            }
+            cpu.halt();
+            return 1;
+        }
+        // PULSE: only drives the debug module's processor status outputs.
+        0x4ACC => return 1,
+        _ if (opcode & 0xFFF8) == 0x4C80 && isa >= Isa::B => return cpu.exec_sats(reg),
+        0x40E7 if isa >= Isa::C && cpu.peek_imm_16(bus) == STRLDSR_COMMAND => {
+            return cpu.exec_strldsr(bus);
+        }
+        // REMS/REMU.L: DIVS/DIVU.L with a remainder register other than the quotient's.
+        _ if (opcode & 0xFFC0) == 0x4C40 => {
+            let ext = cpu.peek_imm_16(bus);
+            if (ext >> 12) & 7 != ext & 7 && ext & 0x8400 == 0 && coldfire_mul_div_ea(ea_mode) {
+                return cpu.exec_rem(bus, opcode);
+            }
+        }
+        // MVS/MVZ
+        _ if (opcode & 0xF100) == 0x7100 => {
+            return if isa >= Isa::B && coldfire_any(ea_mode, ea_reg) {
+                cpu.exec_mvs_mvz(bus, opcode)
+            } else {
+                illegal_instruction(cpu, bus)
+            };
+        }
+        _ if (opcode & 0xF000) == 0xA000 => {
+            return if cpu.has_emac && is_emac_opcode(opcode) {
+                cpu.exec_emac(bus, opcode)
+            } else if (opcode & 0x01C0) == 0x0140 && isa >= Isa::B && (ea_mode <= 6 || ea_reg <= 1)
+            {
+                cpu.exec_mov3q(bus, opcode)
+            } else {
+                exception_1010(cpu, opcode)
+            };
+        }
+        // CPUSHL and INTOUCH are cache maintenance the emulated caches do not need.
+        _ if (opcode & 0xFF38) == 0xF428 => {
+            return if cpu.is_supervisor() {
+                1
+            } else {
+                cpu.exception_privilege(bus)
+            };
+        }
+        _ if (opcode & 0xF000) == 0xF000 => return exception_1111(cpu, opcode),
+        _ => {}
+    }
+
+    if !coldfire_supports(cpu, bus, isa, opcode) {
+        return illegal_instruction(cpu, bus);
+    }
+
+    let cycles = dispatch_group(cpu, bus, opcode);
+
+    // ColdFire multiplies and arithmetic left shifts never report overflow.
+    let is_mul_long = (opcode & 0xFFC0) == 0x4C00;
+    let is_asl = (opcode & 0xF118) == 0xE100;
+    if (is_mul_long || is_asl) && !cpu.faulted() {
+        cpu.v_flag = 0;
+    }
+    cycles
+}
+
+/// Whether a ColdFire core implements `opcode`, one of the instructions it shares with
+/// the 68000 family. ColdFire keeps the long-word forms of most arithmetic and logical
+/// instructions, and limits the memory-to-memory forms of MOVE.
+fn coldfire_supports<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, isa: Isa, opcode: u16) -> bool {
+    let ea_mode = (opcode >> 3) & 7;
+    let ea_reg = opcode & 7;
+    let size_bits = (opcode >> 6) & 3;
+    let op_mode = (opcode >> 6) & 7;
+    let data_reg_or_mem = ea_mode == 0 || coldfire_alterable_memory(ea_mode, ea_reg);
+
+    match opcode >> 12 {
+        0x0 if opcode & 0x0100 != 0 => {
+            // BTST/BCHG/BCLR/BSET Dn,<ea> (MOVEP is gone)
+            if ea_mode == 1 {
+                false
+            } else if size_bits == 0 {
+                coldfire_any(ea_mode, ea_reg) && !(ea_mode == 7 && ea_reg == 4)
+            } else {
+                data_reg_or_mem
+            }
+        }
+        0x0 => match (opcode >> 8) & 0xF {
+            // ORI, ANDI, SUBI, ADDI, EORI: .L #<data>,Dn only
+            0x0 | 0x2 | 0x4 | 0x6 | 0xA => size_bits == 2 && ea_mode == 0,
+            // BTST/BCHG/BCLR/BSET #<data>,<ea>: Dn or (An) through (d16,An)
+            0x8 => ea_mode == 0 || (2..=5).contains(&ea_mode),
+            // CMPI
+            0xC => ea_mode == 0 && (size_bits == 2 || (isa >= Isa::B && size_bits < 2)),
+            _ => false,
+        },
+        0x1..=0x3 => {
+            let size = match opcode >> 12 {
+                1 => Size::Byte,
+                2 => Size::Long,
+                _ => Size::Word,
+            };
+            let dst_mode = (opcode >> 6) & 7;
+            let dst_reg = (opcode >> 9) & 7;
+            let dst_ok = match dst_mode {
+                1 => size != Size::Byte,
+                0 => true,
+                _ => coldfire_alterable_memory(dst_mode, dst_reg),
+            };
+            let src_ok = coldfire_any(ea_mode, ea_reg) && !(ea_mode == 1 && size == Size::Byte);
+            // A displacement source reaches (d16,Ax) at most; an index, absolute or
+            // immediate source only the register-indirect destinations, bar ISA_B's
+            // MOVE.B/W #<data>,(d16,Ax).
+            let pair_ok = match (ea_mode, ea_reg) {
+                (5, _) | (7, 2) => dst_mode <= 5,
+                (6, _) | (7, 0) | (7, 1) | (7, 3) => dst_mode <= 4,
+                (7, 4) => dst_mode <= 4 || (dst_mode == 5 && isa >= Isa::B && size != Size::Long),
+                _ => true,
+            };
+            src_ok && dst_ok && pair_ok
+        }
+        0x4 => match opcode {
+            0x4AFC | 0x4E71 | 0x4E72 | 0x4E73 | 0x4E75 => true,
+            0x4E7B => true,
+            _ if (opcode & 0xFFF0) == 0x4E40 => true,
+            _ if (opcode & 0xFFF0) == 0x4E50 => true,
+            _ if (opcode & 0xFFF0) == 0x4E60 => isa >= Isa::B,
+            // EXT.W, EXT.L, EXTB.L
+            0x4880..=0x4887 | 0x48C0..=0x48C7 | 0x49C0..=0x49C7 => true,
+            _ if (opcode & 0xFF80) == 0x4E80 => coldfire_control(ea_mode, ea_reg),
+            _ if (opcode & 0xF1C0) == 0x41C0 => coldfire_control(ea_mode, ea_reg),
+            _ if (opcode & 0xFFC0) == 0x4840 => ea_mode == 0 || coldfire_control(ea_mode, ea_reg),
+            // MOVE from SR/CCR to Dn; MOVE to CCR/SR from Dn or #<data>
+            0x40C0..=0x40C7 | 0x42C0..=0x42C7 => true,
+            _ if matches!(opcode & 0xFFC0, 0x44C0 | 0x46C0) => {
+                ea_mode == 0 || (ea_mode == 7 && ea_reg == 4)
+            }
+            // NEGX.L, NEG.L, NOT.L Dn
+            0x4080..=0x4087 | 0x4480..=0x4487 | 0x4680..=0x4687 => true,
+            _ if (opcode & 0xFF00) == 0x4200 => size_bits != 3 && data_reg_or_mem,
+            _ if (opcode & 0xFF00) == 0x4A00 && size_bits != 3 => {
+                coldfire_any(ea_mode, ea_reg) && !(ea_mode == 1 && size_bits == 0)
+            }
+            _ if (opcode & 0xFFC0) == 0x4AC0 => {
+                isa >= Isa::B && coldfire_alterable_memory(ea_mode, ea_reg)
+            }
+            // MOVEM.L to or from (An) and (d16,An)
+            _ if matches!(opcode & 0xFFC0, 0x48C0 | 0x4CC0) => matches!(ea_mode, 2 | 5),
+            // MULS/MULU.L and DIVS/DIVU.L: 32-bit results only
+            _ if matches!(opcode & 0xFFC0, 0x4C00 | 0x4C40) => {
+                cpu.peek_imm_16(bus) & 0x8400 == 0 && coldfire_mul_div_ea(ea_mode)
+            }
+            _ => false,
+        },
+        0x5 if size_bits == 3 => match ea_mode {
+            0 => true,
+            1 => false,
+            // TPF, TPF.W, TPF.L
+            _ => matches!(opcode, 0x51FA..=0x51FC),
+        },
+        0x5 => size_bits == 2 && (ea_mode <= 1 || coldfire_alterable_memory(ea_mode, ea_reg)),
+        0x6 => opcode & 0xFF != 0xFF || isa >= Isa::B,
+        0x7 => true,
+        0x8 | 0xC => match op_mode {
+            2 => ea_mode != 1 && coldfire_any(ea_mode, ea_reg),
+            6 => coldfire_alterable_memory(ea_mode, ea_reg),
+            3 | 7 => ea_mode != 1 && coldfire_any(ea_mode, ea_reg),
+            _ => false,
+        },
+        0x9 | 0xD => match op_mode {
+            2 | 7 => coldfire_any(ea_mode, ea_reg),
+            6 => ea_mode == 0 || coldfire_alterable_memory(ea_mode, ea_reg),
+            _ => false,
+        },
+        0xB => match op_mode {
+            0 | 1 | 3 => {
+                isa >= Isa::B && coldfire_any(ea_mode, ea_reg) && !(ea_mode == 1 && op_mode == 0)
+            }
+            2 | 7 => coldfire_any(ea_mode, ea_reg),
+            6 => ea_mode == 0 || coldfire_alterable_memory(ea_mode, ea_reg),
+            _ => false,
+        },
+        // ASL/ASR/LSL/LSR.L on a data register
+        0xE => size_bits == 2 && (opcode >> 3) & 2 == 0,
+        _ => false,
+    }
+}
+
+/// Any addressing mode.
+fn coldfire_any(ea_mode: u16, ea_reg: u16) -> bool {
+    ea_mode != 7 || ea_reg <= 4
+}
+
+/// (An), (An)+, -(An), (d16,An), (d8,An,Xi), (xxx).W and (xxx).L.
+fn coldfire_alterable_memory(ea_mode: u16, ea_reg: u16) -> bool {
+    (2..=6).contains(&ea_mode) || (ea_mode == 7 && ea_reg <= 1)
+}
+
+/// (An), (d16,An), (d8,An,Xi), the absolute and the PC-relative modes.
+fn coldfire_control(ea_mode: u16, ea_reg: u16) -> bool {
+    matches!(ea_mode, 2 | 5 | 6) || (ea_mode == 7 && ea_reg <= 3)
+}
+
+/// Dn, (An), (An)+, -(An) and (d16,An), the operands of MUL.L, DIV.L and REM.L.
+fn coldfire_mul_div_ea(ea_mode: u16) -> bool {
+    ea_mode == 0 || (2..=5).contains(&ea_mode)
 }
 
 // ============================================================================
@@ -122,16 +391,39 @@ fn dispatch_group_f<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
     // - 040fpu1: 1111 0011 ........  (0xF3xx) -> m68040_fpu_op1
     //
     // FPU coprocessor interface is available on 68020+ (via external 68881/82 or integrated 68040 FPU).
-    // 68000/68010/SCC68070 don't have the coprocessor interface, so all F-line opcodes are Line-F exceptions.
+    // 68000/68010/SCC68070/CPU32 don't have the coprocessor interface, so their other F-line
+    // opcodes are Line-F exceptions.
     let has_coproc_interface = !matches!(
         cpu.cpu_type,
-        CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+        CpuType::M68000
+            | CpuType::M68008
+            | CpuType::M68010
+            | CpuType::M68012
+            | CpuType::SCC68070
+            | CpuType::CPU32
     );
 
+    // The CPU32 has no coprocessor interface either, but does have LPSTOP and the table
+    // lookups in 1111 1000 00 mmm rrr; other command words are Line-F.
+    if cpu.is_cpu32() && (opcode & 0xFFC0) == 0xF800 {
+        let command = cpu.peek_imm_16(bus);
+        if opcode == 0xF800 && command == LPSTOP_COMMAND {
+            return cpu.exec_lpstop(bus);
+        }
+        if is_table_lookup(opcode, command) {
+            return cpu.exec_tbl(bus, opcode);
+        }
+    }
+
     if !has_coproc_interface {
         return exception_1111(cpu, opcode);
     }
 
+    // An attached external coprocessor takes every instruction with its ID.
+    if cpu.coprocessor_attached(((opcode >> 9) & 7) as u8) {
+        return cpu.exec_coprocessor(bus, opcode);
+    }
+
     let sub = (opcode >> 8) & 0xF;
 
     // MOVE16 (68030/68040): 16-byte aligned block transfer
@@ -144,6 +436,9 @@ fn dispatch_group_f<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
                 | CpuType::M68EC040
                 | CpuType::M68LC040
                 | CpuType::M68040
+                | CpuType::M68EC060
+                | CpuType::M68LC060
+                | CpuType::M68060
         );
         if !supports_move16 {
             return illegal_instruction(cpu, bus);
@@ -154,7 +449,7 @@ fn dispatch_group_f<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
     // 68030/68040 Cache Instructions: CINV and CPUSH (F-line, privileged)
     // CINVA/CPUSHA: 1111 0100 x1x1 1000 (0xF418, 0xF438, 0xF458, 0xF478, etc.)
     // CINV/CPUSH line/page: 1111 010x xxxx xaaa
-    // We treat these as NOPs since there's no cache to invalidate/push.
+    // The 68040/68060 forms act on the cache model; on the 68030 they are NOPs.
     let is_cache_cpu = matches!(
         cpu.cpu_type,
         CpuType::M68EC030
@@ -162,26 +457,51 @@ fn dispatch_group_f<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
             | CpuType::M68EC040
             | CpuType::M68LC040
             | CpuType::M68040
+            | CpuType::M68EC060
+            | CpuType::M68LC060
+            | CpuType::M68060
     );
     if is_cache_cpu && (opcode >> 8) & 0xF == 4 {
         // Check for supervisor mode (cache ops are privileged)
         if !cpu.is_supervisor() {
             return cpu.take_exception(bus, 8); // Privilege violation
         }
-        // All CINV/CPUSH variants are NOPs for us
+        if matches!(
+            cpu.cpu_type,
+            CpuType::M68EC040 | CpuType::M68LC040 | CpuType::M68040
+        ) || cpu.is_060()
+        {
+            return cpu.exec_cinv_cpush_040(bus, opcode);
+        }
         return 4;
     }
 
-    // 68030/68040 PFLUSH instructions (F-line, privileged): 0xF5xx
-    // PFLUSHA: 1111 0101 0001 1000 (0xF518)
-    // PFLUSHN: 1111 0101 0000 0xxx (0xF500-0xF507)
-    // PFLUSH: 1111 0101 0010 0xxx (0xF520-0xF527)
-    // We treat these as NOPs since there's no TLB to flush.
+    // 68040 PFLUSH/PTEST instructions (F-line, privileged): 0xF5xx
+    // PFLUSHN (An): 1111 0101 0000 0rrr (0xF500-0xF507)
+    // PFLUSH (An):  1111 0101 0000 1rrr (0xF508-0xF50F)
+    // PFLUSHAN:     1111 0101 0001 0000 (0xF510)
+    // PFLUSHA:      1111 0101 0001 1000 (0xF518)
+    // PTESTW/R (An): 1111 0101 01x0 1rrr (0xF548, 0xF568)
+    // Other 0xF5xx opcodes (and these on the 68030) are NOPs for us. The 68060 has the
+    // 68040 PFLUSH forms but no PTEST, which is Line-F there.
     if is_cache_cpu && (opcode >> 8) & 0xF == 5 {
         if !cpu.is_supervisor() {
             return cpu.take_exception(bus, 8); // Privilege violation
         }
-        // All PFLUSH variants are NOPs for us
+        if cpu.is_060() && (opcode & 0xFFD8) == 0xF548 {
+            return FLINE_TRAP_SENTINEL;
+        }
+        let is_040 = matches!(
+            cpu.cpu_type,
+            CpuType::M68EC040 | CpuType::M68LC040 | CpuType::M68040
+        ) || cpu.is_060();
+        if is_040 && (opcode & 0xFFE0) == 0xF500 {
+            return cpu.exec_pflush_040(opcode);
+        }
+        // PTESTW (An): 0xF548-0xF54F, PTESTR (An): 0xF568-0xF56F
+        if is_040 && cpu.has_pmmu && (opcode & 0xFFD8) == 0xF548 {
+            return cpu.exec_ptest_040(bus, opcode);
+        }
         return 4;
     }
 
@@ -194,6 +514,35 @@ fn dispatch_group_f<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
         }
     }
 
+    // The FPU answers to coprocessor ID 1; with none fitted its instructions are Line-F.
+    if ((opcode >> 9) & 0x7) == 1 && !cpu.fpu_present() {
+        return FLINE_TRAP_SENTINEL;
+    }
+
+    // The 68060 leaves FDBcc, FScc and FTRAPcc to software.
+    if (opcode & 0xFFC0) == 0xF240 && cpu.fpu_type == FpuType::M68060 {
+        return FLINE_TRAP_SENTINEL;
+    }
+
+    // FDBcc: 1111 0010 0100 1rrr (0xF248-0xF24F) - decrement and branch on FPU condition
+    if (opcode & 0xFFF8) == 0xF248 {
+        let w2 = cpu.read_imm_16(bus);
+        let cond = (w2 & 0x3F) as u8;
+        return cpu.exec_fdbcc(bus, (opcode & 7) as usize, cond);
+    }
+
+    // FTRAPcc: 1111 0010 0111 1mmm (0xF27A-0xF27C) - trap on FPU condition
+    if (0xF27A..=0xF27C).contains(&opcode) {
+        let w2 = cpu.read_imm_16(bus);
+        let cond = (w2 & 0x3F) as u8;
+        let operand_words = match opcode & 7 {
+            2 => 1, // FTRAPcc.W #<data>
+            3 => 2, // FTRAPcc.L #<data>
+            _ => 0, // FTRAPcc
+        };
+        return cpu.exec_ftrapcc(bus, cond, operand_words);
+    }
+
     // FScc: 1111 0010 01mm mrrr (0xF240-0xF27F) - set byte on FPU condition
     if (opcode & 0xFFC0) == 0xF240 {
         let ea_mode = ((opcode >> 3) & 7) as u8;
@@ -209,13 +558,13 @@ fn dispatch_group_f<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
         // FBcc.W - 16-bit displacement
         let cond = (opcode & 0x3F) as u8;
         let disp = cpu.read_imm_16(bus) as i16 as i32;
-        return cpu.exec_fbcc(cond, disp);
+        return cpu.exec_fbcc(bus, cond, disp);
     }
     if (opcode & 0xFFC0) == 0xF2C0 {
         // FBcc.L - 32-bit displacement
         let cond = (opcode & 0x3F) as u8;
         let disp = cpu.read_imm_32(bus) as i32;
-        return cpu.exec_fbcc(cond, disp);
+        return cpu.exec_fbcc(bus, cond, disp);
     }
 
     let cycles = match sub {
@@ -288,19 +637,28 @@ fn dispatch_group_0<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
     // CAS2: 0000 1ss0 1111 1100 with two extension words
     if opcode == 0x0EFC || opcode == 0x0CFC || opcode == 0x0AFC {
         if cpu.cpu_type == CpuType::M68000
+            || cpu.cpu_type == CpuType::M68008
             || cpu.cpu_type == CpuType::M68010
+            || cpu.cpu_type == CpuType::M68012
             || cpu.cpu_type == CpuType::SCC68070
+            || cpu.cpu_type == CpuType::CPU32
         {
             return illegal_instruction(cpu, bus);
         }
+        if cpu.is_060() {
+            return unimplemented_integer(cpu, bus);
+        }
         return cpu.exec_cas2(bus, opcode);
     }
     // CAS: 0000 1ss0 11 mmm rrr with extension word (Du/Dc)
     // ss encodes size (A=byte, C=word, E=long) in bits 11..9.
     if (opcode & 0x0FC0) == 0x0AC0 || (opcode & 0x0FC0) == 0x0CC0 || (opcode & 0x0FC0) == 0x0EC0 {
         if cpu.cpu_type == CpuType::M68000
+            || cpu.cpu_type == CpuType::M68008
             || cpu.cpu_type == CpuType::M68010
+            || cpu.cpu_type == CpuType::M68012
             || cpu.cpu_type == CpuType::SCC68070
+            || cpu.cpu_type == CpuType::CPU32
         {
             return illegal_instruction(cpu, bus);
         }
@@ -310,7 +668,7 @@ fn dispatch_group_0<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
     // 68010+ MOVES - Move to/from address space
     // Pattern: 0000 1110 ssmm mrrr (0x0E00-0x0EFF)
     if (opcode & 0xFF00) == 0x0E00 {
-        if cpu.cpu_type == CpuType::M68000 {
+        if cpu.is_68000() {
             return illegal_instruction(cpu, bus);
         }
         return cpu.exec_moves(bus, opcode);
@@ -363,10 +721,17 @@ fn dispatch_group_0<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
     {
         if matches!(
             cpu.cpu_type,
-            CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+            CpuType::M68000
+                | CpuType::M68008
+                | CpuType::M68010
+                | CpuType::M68012
+                | CpuType::SCC68070
         ) {
             return illegal_instruction(cpu, bus);
         }
+        if cpu.is_060() {
+            return unimplemented_integer(cpu, bus);
+        }
         return cpu.exec_cmp2_chk2(bus, opcode);
     }
 
@@ -374,6 +739,9 @@ fn dispatch_group_0<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
     // 0000 ddd 1 s 0 0 1 aaa  with extension word = displacement (d16,An)
     // s: 0=word, 1=long. direction: bit7 (0=mem->reg, 1=reg->mem)
     if (opcode & 0xF138) == 0x0108 {
+        if cpu.is_060() {
+            return unimplemented_integer(cpu, bus);
+        }
         let dreg = ((opcode >> 9) & 7) as usize;
         let areg = (opcode & 7) as usize;
         let is_long = (opcode & 0x0040) != 0;
@@ -544,7 +912,11 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
     if (opcode & 0xFFF8) == 0x4808 {
         if matches!(
             cpu.cpu_type,
-            CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+            CpuType::M68000
+                | CpuType::M68008
+                | CpuType::M68010
+                | CpuType::M68012
+                | CpuType::SCC68070
         ) {
             return illegal_instruction(cpu, bus);
         }
@@ -556,19 +928,35 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
     if (opcode & 0xFFC0) == 0x4C00 {
         if matches!(
             cpu.cpu_type,
-            CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+            CpuType::M68000
+                | CpuType::M68008
+                | CpuType::M68010
+                | CpuType::M68012
+                | CpuType::SCC68070
         ) {
             return illegal_instruction(cpu, bus);
         }
+        // The 68060 leaves the 64-bit product (Dh:Dl) form to software.
+        if cpu.is_060() && cpu.peek_imm_16(bus) & 0x0400 != 0 {
+            return unimplemented_integer(cpu, bus);
+        }
         return cpu.exec_mull(bus, opcode);
     }
     if (opcode & 0xFFC0) == 0x4C40 {
         if matches!(
             cpu.cpu_type,
-            CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+            CpuType::M68000
+                | CpuType::M68008
+                | CpuType::M68010
+                | CpuType::M68012
+                | CpuType::SCC68070
         ) {
             return illegal_instruction(cpu, bus);
         }
+        // The 68060 leaves the 64-bit dividend (Dr:Dq) form to software.
+        if cpu.is_060() && cpu.peek_imm_16(bus) & 0x0400 != 0 {
+            return unimplemented_integer(cpu, bus);
+        }
         return cpu.exec_divl(bus, opcode);
     }
 
@@ -584,7 +972,7 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
     // 68010+ MOVE from CCR: 0100 0010 11 mmm rrr (0x42C0..0x42FF)
     // Writes CCR (word) to <ea>. Does not affect flags.
     if (opcode & 0xFFC0) == 0x42C0 {
-        if cpu.cpu_type == CpuType::M68000 {
+        if cpu.is_68000() {
             return illegal_instruction(cpu, bus);
         }
         let mode = AddressingMode::decode(ea_mode, ea_reg).unwrap();
@@ -630,16 +1018,21 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
             // RTE
             if cpu.is_supervisor() {
                 match cpu.cpu_type {
-                    CpuType::M68000 => {
+                    CpuType::M68000 | CpuType::M68008 => {
                         let sr = cpu.pull_16(bus);
                         cpu.pc = cpu.pull_32(bus);
                         cpu.set_sr(sr);
                         20
                     }
-                    CpuType::M68010 | CpuType::SCC68070 => {
+                    CpuType::M68010 | CpuType::M68012 | CpuType::SCC68070 => {
                         // Musashi m68k_in.c: format word at (SP+6) >> 12
                         let sp = cpu.a(7);
                         let format = cpu.read_16(bus, sp.wrapping_add(6)) >> 12;
+                        if format == 8 {
+                            // Bus/address error: continue the faulted instruction.
+                            cpu.rte_bus_fault_frame_010(bus);
+                            return 20;
+                        }
                         if format != 0 {
                             return cpu.take_exception(bus, 14); // format error
                         }
@@ -649,6 +1042,9 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
                         cpu.set_sr(sr);
                         20
                     }
+                    CpuType::ColdFireIsaA | CpuType::ColdFireIsaB | CpuType::ColdFireIsaC => {
+                        cpu.rte_coldfire(bus)
+                    }
                     _ => {
                         // 68020+ RTE loop (Musashi m68k_in.c)
                         loop {
@@ -680,6 +1076,51 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
                                     cpu.set_sr(sr);
                                     return 20;
                                 }
+                                9 if matches!(
+                                    cpu.cpu_type,
+                                    CpuType::M68EC020
+                                        | CpuType::M68020
+                                        | CpuType::M68EC030
+                                        | CpuType::M68030
+                                ) =>
+                                {
+                                    // Coprocessor mid-instruction: continue the dialog.
+                                    return 20 + cpu.rte_coprocessor_frame(bus);
+                                }
+                                0xA | 0xB
+                                    if matches!(
+                                        cpu.cpu_type,
+                                        CpuType::M68EC020
+                                            | CpuType::M68020
+                                            | CpuType::M68EC030
+                                            | CpuType::M68030
+                                    ) =>
+                                {
+                                    // Short/long bus cycle fault: continue the faulted
+                                    // instruction.
+                                    cpu.rte_bus_fault_frame(bus, format == 0xB);
+                                    return 20;
+                                }
+                                7 if matches!(
+                                    cpu.cpu_type,
+                                    CpuType::M68EC040 | CpuType::M68LC040 | CpuType::M68040
+                                ) =>
+                                {
+                                    // Access error: restart the faulted instruction.
+                                    cpu.rte_access_error_frame_040(bus);
+                                    return 20;
+                                }
+                                0xC if cpu.is_cpu32() => {
+                                    // Bus or address error: restart the faulted
+                                    // instruction.
+                                    cpu.rte_bus_fault_frame_cpu32(bus);
+                                    return 20;
+                                }
+                                4 if cpu.is_060() => {
+                                    // Access fault: restart the faulted instruction.
+                                    cpu.rte_access_fault_frame_060(bus);
+                                    return 20;
+                                }
                                 _ => {
                                     return cpu.take_exception(bus, 14); // format error
                                 }
@@ -694,7 +1135,7 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
         0x4E74 => {
             // RTD (68010+): return and deallocate stack arguments.
             // Pop return PC, then add signed word displacement to SP.
-            if cpu.cpu_type == CpuType::M68000 {
+            if cpu.is_68000() {
                 illegal_instruction(cpu, bus)
             } else {
                 let disp = cpu.read_imm_16(bus) as i16 as i32;
@@ -727,16 +1168,17 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
         }
         0x4E7A => {
             // MOVEC Rc,Rn - Move from control register (68010+)
-            if cpu.cpu_type == CpuType::M68000 {
+            if cpu.is_68000() {
                 return illegal_instruction(cpu, bus);
             }
-            let ext = bus.read_word(cpu.pc);
-            cpu.pc += 2;
+            let ext = cpu.read_imm_16(bus);
             let reg_type = (ext >> 15) & 1; // 0=Dn, 1=An
             let reg_num = ((ext >> 12) & 7) as usize;
             let ctrl_reg = ext & 0xFFF;
-            if matches!(cpu.cpu_type, CpuType::M68010 | CpuType::SCC68070)
-                && !matches!(ctrl_reg, 0x000 | 0x001 | 0x800 | 0x801)
+            if matches!(
+                cpu.cpu_type,
+                CpuType::M68010 | CpuType::M68012 | CpuType::SCC68070 | CpuType::CPU32
+            ) && !matches!(ctrl_reg, 0x000 | 0x001 | 0x800 | 0x801)
             {
                 return illegal_instruction(cpu, bus);
             }
@@ -756,6 +1198,14 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
             {
                 return illegal_instruction(cpu, bus);
             }
+            if cpu.is_060()
+                && !matches!(
+                    ctrl_reg,
+                    0x000..=0x008 | 0x800 | 0x801 | 0x806 | 0x807 | 0x808
+                )
+            {
+                return illegal_instruction(cpu, bus);
+            }
             if !cpu.is_supervisor() {
                 return cpu.take_exception(bus, 8); // Privilege violation
             }
@@ -769,16 +1219,17 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
         }
         0x4E7B => {
             // MOVEC Rn,Rc - Move to control register (68010+)
-            if cpu.cpu_type == CpuType::M68000 {
+            if cpu.is_68000() {
                 return illegal_instruction(cpu, bus);
             }
-            let ext = bus.read_word(cpu.pc);
-            cpu.pc += 2;
+            let ext = cpu.read_imm_16(bus);
             let reg_type = (ext >> 15) & 1; // 0=Dn, 1=An
             let reg_num = ((ext >> 12) & 7) as usize;
             let ctrl_reg = ext & 0xFFF;
-            if matches!(cpu.cpu_type, CpuType::M68010 | CpuType::SCC68070)
-                && !matches!(ctrl_reg, 0x000 | 0x001 | 0x800 | 0x801)
+            if matches!(
+                cpu.cpu_type,
+                CpuType::M68010 | CpuType::M68012 | CpuType::SCC68070 | CpuType::CPU32
+            ) && !matches!(ctrl_reg, 0x000 | 0x001 | 0x800 | 0x801)
             {
                 return illegal_instruction(cpu, bus);
             }
@@ -798,6 +1249,25 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
             {
                 return illegal_instruction(cpu, bus);
             }
+            if cpu.is_060()
+                && !matches!(
+                    ctrl_reg,
+                    0x000..=0x008 | 0x800 | 0x801 | 0x806 | 0x807 | 0x808
+                )
+            {
+                return illegal_instruction(cpu, bus);
+            }
+            // ColdFire: CACR, ACR0-3, VBR, ROMBAR, RAMBAR and MBAR, plus the other stack
+            // pointer from ISA_B.
+            if let Some(isa) = cpu.coldfire_isa()
+                && !matches!(
+                    ctrl_reg,
+                    0x002 | 0x004..=0x007 | 0x801 | 0xC00 | 0xC01 | 0xC04 | 0xC05 | 0xC0F
+                )
+                && !(ctrl_reg == 0x800 && isa >= Isa::B)
+            {
+                return illegal_instruction(cpu, bus);
+            }
             if !cpu.is_supervisor() {
                 return cpu.take_exception(bus, 8); // Privilege violation
             }
@@ -885,13 +1355,27 @@ fn dispatch_group_4<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
                     // EXTB.L (68020+) - sign extend byte to long
                     if matches!(
                         cpu.cpu_type,
-                        CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+                        CpuType::M68000
+                            | CpuType::M68008
+                            | CpuType::M68010
+                            | CpuType::M68012
+                            | CpuType::SCC68070
                     ) {
                         illegal_instruction(cpu, bus)
                     } else {
                         cpu.exec_extb(ea_reg as usize)
                     }
                 }
+                0xA if opcode == 0x4AFA && cpu.is_cpu32() => {
+                    // BGND (CPU32): enter background debug mode, or the illegal
+                    // instruction exception while it is disabled.
+                    if cpu.bdm_enabled {
+                        cpu.stopped |= STOP_LEVEL_BACKGROUND;
+                        4
+                    } else {
+                        ILLEGAL_SENTINEL
+                    }
+                }
                 0xA if opcode == 0x4AFC => {
                     // ILLEGAL instruction - return sentinel for interception
                     ILLEGAL_SENTINEL
@@ -1037,6 +1521,13 @@ fn dispatch_group_5<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
                 | CpuType::M68EC040
                 | CpuType::M68LC040
                 | CpuType::M68040
+                | CpuType::M68EC060
+                | CpuType::M68LC060
+                | CpuType::M68060
+                | CpuType::CPU32
+                | CpuType::ColdFireIsaA
+                | CpuType::ColdFireIsaB
+                | CpuType::ColdFireIsaC
         );
         if is_020_plus && ea_mode == 7 && (ea_reg == 2 || ea_reg == 3 || ea_reg == 4) {
             let condition = ((opcode >> 8) & 0xF) as u8;
@@ -1199,7 +1690,12 @@ fn dispatch_group_8<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
                 // y=0: PACK Ds, Dd, #adj  y=1: PACK -(As), -(Ad), #adj
                 if matches!(
                     cpu.cpu_type,
-                    CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+                    CpuType::M68000
+                        | CpuType::M68008
+                        | CpuType::M68010
+                        | CpuType::M68012
+                        | CpuType::SCC68070
+                        | CpuType::CPU32
                 ) {
                     return illegal_instruction(cpu, bus);
                 }
@@ -1213,7 +1709,12 @@ fn dispatch_group_8<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
                 // UNPK (68020+): 1000 xxx1 1000 yrrr
                 if matches!(
                     cpu.cpu_type,
-                    CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+                    CpuType::M68000
+                        | CpuType::M68008
+                        | CpuType::M68010
+                        | CpuType::M68012
+                        | CpuType::SCC68070
+                        | CpuType::CPU32
                 ) {
                     return illegal_instruction(cpu, bus);
                 }
@@ -1302,7 +1803,7 @@ fn dispatch_group_9<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
 
                 // If the store faults (misaligned word/long), the instruction should not update
                 // flags; pre-check alignment to avoid mutating flags before the fault.
-                if cpu.cpu_type == CpuType::M68000
+                if cpu.is_68000()
                     && size != Size::Byte
                     && let EaResult::Memory(addr) = dst_ea
                     && (addr & 1) != 0
@@ -1508,7 +2009,7 @@ fn dispatch_group_d<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
 
                 // If the store faults (misaligned word/long), the instruction should not update
                 // flags; pre-check alignment to avoid mutating flags before the fault.
-                if cpu.cpu_type == CpuType::M68000
+                if cpu.is_68000()
                     && size != Size::Byte
                     && let EaResult::Memory(addr) = dst_ea
                     && (addr & 1) != 0
@@ -1551,7 +2052,12 @@ fn dispatch_group_e<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, opcode: u16)
     if (opcode & 0x00C0) == 0x00C0 && ((opcode >> 8) & 0xF) >= 0x8 {
         if matches!(
             cpu.cpu_type,
-            CpuType::M68000 | CpuType::M68010 | CpuType::SCC68070
+            CpuType::M68000
+                | CpuType::M68008
+                | CpuType::M68010
+                | CpuType::M68012
+                | CpuType::SCC68070
+                | CpuType::CPU32
         ) {
             return illegal_instruction(cpu, bus);
         }
@@ -1651,6 +2157,12 @@ fn illegal_instruction<B: AddressBus>(_cpu: &mut CpuCore, _bus: &mut B) -> i32 {
     ILLEGAL_SENTINEL
 }
 
+/// 68060 unimplemented integer instruction. Taken before any extension word is read;
+/// the frame's PC is the instruction itself, for the software package to emulate it.
+fn unimplemented_integer<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B) -> i32 {
+    cpu.take_exception(bus, vector::UNIMPLEMENTED_INTEGER)
+}
+
 /// Return sentinel value for A-line trap interception.
 /// The caller (dispatch_instruction) converts this to StepResult::AlineTrap.
 fn exception_1010(_cpu: &mut CpuCore, _opcode: u16) -> i32 {
@@ -168,6 +168,11 @@ impl CpuCore {
 
     /// Compute indexed address from extension word.
     fn compute_index<B: AddressBus>(&mut self, base: u32, ext: u16, bus: &mut B) -> u32 {
+        // ColdFire keeps only the brief format, with a long index scaled by 1, 2 or 4.
+        if self.is_coldfire() && ((ext & 0x0900) != 0x0800 || (ext >> 9) & 3 == 3) {
+            self.trigger_address_error(bus, self.ppc, false, true);
+            return 0;
+        }
         let d8 = (ext & 0xFF) as i8 as i32;
         let idx_reg = ((ext >> 12) & 0xF) as usize;
         let idx_is_addr = (ext & 0x8000) != 0;
@@ -211,6 +216,7 @@ impl CpuCore {
         idx_val: i32,
         bus: &mut B,
     ) -> u32 {
+        self.timing_facts.index_cycles += self.instruction_timing.full_index_cycles(ext);
         let bs = (ext & 0x0080) != 0; // Base suppress
         let is = (ext & 0x0040) != 0; // Index suppress
         let bd_size = (ext >> 4) & 0x03;
@@ -226,7 +232,11 @@ impl CpuCore {
             _ => 0,
         };
 
-        // Memory indirect modes
+        // Memory indirect modes; the CPU32 has the full format without them.
+        if i_is != 0 && self.is_cpu32() {
+            self.trigger_illegal_instruction(bus);
+            return 0;
+        }
         if i_is != 0 {
             let outer_disp = match i_is & 0x03 {
                 0 | 1 => 0i32,
@@ -264,9 +274,40 @@ impl CpuCore {
                 | CpuType::M68EC040
                 | CpuType::M68LC040
                 | CpuType::M68040
+                | CpuType::M68EC060
+                | CpuType::M68LC060
+                | CpuType::M68060
+                | CpuType::CPU32
+                | CpuType::ColdFireIsaA
+                | CpuType::ColdFireIsaB
+                | CpuType::ColdFireIsaC
         )
     }
 
+    /// Instruction stream word at PC, for decoders that must see an extension word before
+    /// committing to an instruction. Unlike `read_imm_16` it neither advances PC nor runs
+    /// a bus cycle: the word comes from the prefetch queue or an instruction cache when
+    /// they hold it, otherwise straight from program space. Returns 0 if PC is odd or
+    /// does not translate; the fetch proper then takes the fault.
+    pub(crate) fn peek_imm_16<B: AddressBus>(&self, bus: &mut B) -> u16 {
+        let pc = self.pc;
+        if pc & 1 != 0 {
+            return 0;
+        }
+        if self.prefetch_active() && self.pref_words > 0 && self.pref_addr == pc {
+            return (self.pref_data >> 16) as u16;
+        }
+        let logical = self.address(pc);
+        let Some(physical) = crate::mmu::probe_instruction(self, bus, logical) else {
+            return 0;
+        };
+        let addr = self.address(physical);
+        if let Some(word) = self.icache_peek(logical, addr) {
+            return word;
+        }
+        bus.read_immediate_word(addr)
+    }
+
     /// Read immediate 16-bit value and advance PC.
     #[inline]
     pub fn read_imm_16<B: AddressBus>(&mut self, bus: &mut B) -> u16 {
@@ -275,7 +316,16 @@ impl CpuCore {
             self.trigger_address_error(bus, addr, false, true);
             return 0;
         }
-        let mut addr = self.address(addr);
+        if self.prefetch_active() {
+            return self.read_imm_16_prefetched(bus);
+        }
+        let logical = self.address(addr);
+        let cached = self.icache_enabled();
+        if cached && let Some(v) = self.icache_read(logical) {
+            self.pc = self.pc.wrapping_add(2);
+            return v;
+        }
+        let mut addr = logical;
         if self.has_pmmu && self.pmmu_enabled {
             match crate::mmu::translate_address(
                 self,
@@ -294,7 +344,33 @@ impl CpuCore {
                 }
             }
         }
-        match bus.try_read_word(addr) {
+        if cached {
+            // A miss loads the whole long word containing the fetch.
+            return match self.bus_read_cycle(bus, self.program_fc(), addr & !3, 4) {
+                Ok(long) => {
+                    self.icache_miss(logical, long);
+                    self.pc = self.pc.wrapping_add(2);
+                    if logical & 2 == 0 {
+                        (long >> 16) as u16
+                    } else {
+                        long as u16
+                    }
+                }
+                Err(_) => {
+                    self.trigger_bus_error(bus, addr, false, true);
+                    0
+                }
+            };
+        }
+        let fc = self.program_fc();
+        let fetched = if self.icache_040_enabled(logical) {
+            self.icache_040_read(bus, fc, addr)
+        } else {
+            let fetched = self.bus_read_cycle(bus, fc, addr, 2).map(|v| v as u16);
+            self.charge_uncached_fetch();
+            fetched
+        };
+        match fetched {
             Ok(v) => {
                 self.pc = self.pc.wrapping_add(2);
                 v
@@ -314,6 +390,13 @@ impl CpuCore {
             self.trigger_address_error(bus, addr, false, true);
             return 0;
         }
+        if self.prefetch_active() || self.icache_enabled() || self.icache_040_enabled(addr) {
+            let high = self.read_imm_16(bus);
+            if self.faulted() {
+                return 0;
+            }
+            return ((high as u32) << 16) | self.read_imm_16(bus) as u32;
+        }
         let mut addr = self.address(addr);
         if self.has_pmmu && self.pmmu_enabled {
             match crate::mmu::translate_address(
@@ -333,8 +416,9 @@ impl CpuCore {
                 }
             }
         }
-        match bus.try_read_long(addr) {
+        match self.bus_read_cycle(bus, self.program_fc(), addr, 4) {
             Ok(v) => {
+                self.charge_uncached_fetch();
                 self.pc = self.pc.wrapping_add(4);
                 v
             }
@@ -0,0 +1,312 @@
+//! ColdFire enhanced multiply-accumulate unit (EMAC).
+//!
+//! The EMAC adds four accumulators, a status register (MACSR) and an address mask
+//! register (MASK), all reached through Line-A opcodes:
+//!
+//!   MAC/MSAC Ry,Rx,ACCn          1010 xxx 0 a X 00 Y yyy   - sz sf S U U - A ----
+//!   MAC/MSAC Ry,Rx,<ea>,Rw       1010 www 0 a W mmm rrr    X xxx sz sf S U U M A Y yyy
+//!   MOVE.L <ea>,<reg>            1010 fff 100 mmm rrr      (fff: 0-3 ACCn, 4 MACSR,
+//!   MOVE.L <reg>,Rx              1010 fff 110 00 R rrr       5 ACCEXT01, 6 MASK,
+//!                                                            7 ACCEXT23)
+//!   MOVCLR.L ACCn,Rx             1010 0nn 111 00 R rrr
+//!   MOVE.L MACSR,CCR             1010 100 111 000 000
+//!
+//! The accumulator number of a MAC is split between `a` (its low bit) and `A` (its high
+//! bit). `sz` picks word or long operands, `U` the upper or lower word of each register,
+//! `sf` scales the product (01 left, 11 right) and `S` makes it MSAC. The load forms also
+//! fetch a long word into Rw, using the operands as they were before the load; `M` ANDs
+//! the updated address register with MASK for modulo addressing.
+//!
+//! The accumulators hold 48 bits: the 32 of ACCn and 16 extension bits, which ACCEXT01
+//! and ACCEXT23 expose two accumulators at a time (ACC0 or ACC2 in the upper word).
+//! MACSR selects signed, unsigned or fractional (1.31) arithmetic, and with OMC set an
+//! accumulation that leaves the 32-bit range saturates instead of spilling into the
+//! extension.
+
+use super::cpu::CpuCore;
+use super::ea::AddressingMode;
+use super::memory::AddressBus;
+use super::types::Size;
+
+/// MACSR bits.
+pub mod macsr {
+    /// Product/accumulation overflow, one sticky bit per accumulator (bits 11-8).
+    pub const PAV0: u32 = 0x0100;
+    /// Overflow mode: saturate the accumulators instead of using the extension bits.
+    pub const OMC: u32 = 0x80;
+    /// Unsigned integer operands (integer mode only).
+    pub const SU: u32 = 0x40;
+    /// Fractional mode.
+    pub const FI: u32 = 0x20;
+    /// Round (rather than truncate) fractional long products.
+    pub const RT: u32 = 0x10;
+    /// Result flags of the last MAC operation.
+    pub const N: u32 = 0x08;
+    pub const Z: u32 = 0x04;
+    pub const V: u32 = 0x02;
+    /// The result uses the extension bits.
+    pub const EV: u32 = 0x01;
+    /// Bits MOVE to MACSR can change.
+    pub const WRITABLE: u32 = 0x0FFF;
+}
+
+/// EMAC register file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Emac {
+    /// ACC0-ACC3 with their extension bits, as 48-bit values.
+    pub acc: [i64; 4],
+    pub macsr: u32,
+    /// Upper word always reads as ones.
+    pub mask: u32,
+}
+
+/// Whether `opcode` is an EMAC instruction.
+pub(crate) fn is_emac_opcode(opcode: u16) -> bool {
+    let mode = (opcode >> 3) & 7;
+    let reg = opcode & 7;
+    let field = (opcode >> 9) & 7;
+    match opcode & 0x01C0 {
+        0x0000..=0x00C0 => mode <= 5,
+        0x0100 => mode <= 1 || (mode == 7 && reg == 4),
+        0x0180 => opcode & 0x0030 == 0,
+        0x01C0 => (field <= 3 && opcode & 0x0030 == 0) || opcode == 0xA9C0,
+        _ => false,
+    }
+}
+
+impl Emac {
+    /// Whether `value` fits the 32-bit accumulator in the current mode.
+    fn fits_32(&self, value: i128) -> bool {
+        if self.macsr & macsr::SU != 0 && self.macsr & macsr::FI == 0 {
+            (0..=u32::MAX as i128).contains(&value)
+        } else {
+            (i32::MIN as i128..=i32::MAX as i128).contains(&value)
+        }
+    }
+
+    /// The 32-bit limit nearest to `value`.
+    fn saturate_32(&self, value: i128) -> i64 {
+        let unsigned = self.macsr & macsr::SU != 0 && self.macsr & macsr::FI == 0;
+        match (unsigned, value < 0) {
+            (true, true) => 0,
+            (true, false) => u32::MAX as i64,
+            (false, true) => i32::MIN as i64,
+            (false, false) => i32::MAX as i64,
+        }
+    }
+
+    /// Set N, Z and EV from accumulator `value`; V is set by the caller.
+    fn set_flags(&mut self, value: i64, overflow: bool) {
+        let mut flags = 0;
+        if value & (1 << 47) != 0 {
+            flags |= macsr::N;
+        }
+        if value == 0 {
+            flags |= macsr::Z;
+        }
+        if overflow {
+            flags |= macsr::V;
+        }
+        if !self.fits_32(value as i128) {
+            flags |= macsr::EV;
+        }
+        self.macsr = (self.macsr & !0xF) | flags;
+    }
+
+    /// Load ACCn with a 32-bit value, extended per the operand mode.
+    fn load(&mut self, n: usize, value: u32) {
+        let unsigned = self.macsr & macsr::SU != 0 && self.macsr & macsr::FI == 0;
+        let value = if unsigned {
+            value as i64
+        } else {
+            value as i32 as i64
+        };
+        self.acc[n] = value;
+        self.macsr &= !(macsr::PAV0 << n);
+        self.set_flags(value, false);
+    }
+
+    /// ACCn as MOVE.L ACCn,Rx reads it: saturated in overflow mode when it does not fit.
+    fn store(&self, n: usize) -> u32 {
+        let value = self.acc[n];
+        if self.macsr & macsr::OMC != 0 && !self.fits_32(value as i128) {
+            self.saturate_32(value as i128) as u32
+        } else {
+            value as u32
+        }
+    }
+
+    /// ACCEXT01 (`pair` 0) or ACCEXT23 (`pair` 1): the extension bits of ACC0 or ACC2 in
+    /// the upper word and of ACC1 or ACC3 in the lower.
+    fn extension(&self, pair: usize) -> u32 {
+        let ext = |n: usize| ((self.acc[n] >> 32) & 0xFFFF) as u32;
+        ext(2 * pair) << 16 | ext(2 * pair + 1)
+    }
+
+    /// Load ACCEXT01 or ACCEXT23, keeping the low 32 bits of both accumulators.
+    fn set_extension(&mut self, pair: usize, value: u32) {
+        let unsigned = self.macsr & macsr::SU != 0 && self.macsr & macsr::FI == 0;
+        for (n, ext) in [(2 * pair, value >> 16), (2 * pair + 1, value & 0xFFFF)] {
+            let raw = (ext as i64) << 32 | (self.acc[n] & 0xFFFF_FFFF);
+            self.acc[n] = if unsigned { raw } else { (raw << 16) >> 16 };
+        }
+    }
+
+    /// Add or subtract `product` to ACCn.
+    fn accumulate(&mut self, n: usize, product: i128, subtract: bool) {
+        let sum = if subtract {
+            self.acc[n] as i128 - product
+        } else {
+            self.acc[n] as i128 + product
+        };
+        let unsigned = self.macsr & macsr::SU != 0 && self.macsr & macsr::FI == 0;
+        let saturating = self.macsr & macsr::OMC != 0;
+        let overflow = if saturating {
+            !self.fits_32(sum)
+        } else if unsigned {
+            !(0..1 << 48).contains(&sum)
+        } else {
+            !(-(1 << 47)..1 << 47).contains(&sum)
+        };
+        let value = if overflow && saturating {
+            self.saturate_32(sum)
+        } else if unsigned {
+            (sum as i64) & 0xFFFF_FFFF_FFFF
+        } else {
+            ((sum as i64) << 16) >> 16
+        };
+        if overflow {
+            self.macsr |= macsr::PAV0 << n;
+        }
+        self.acc[n] = value;
+        self.set_flags(value, overflow);
+    }
+}
+
+impl CpuCore {
+    /// Execute an EMAC instruction (see [`is_emac_opcode`]).
+    pub fn exec_emac<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
+        let field = ((opcode >> 9) & 7) as usize;
+        let rx = (opcode & 0xF) as usize;
+        match opcode & 0x01C0 {
+            0x0000..=0x00C0 => self.exec_mac(bus, opcode),
+            0x0100 => {
+                let mode =
+                    AddressingMode::decode(((opcode >> 3) & 7) as u8, (opcode & 7) as u8).unwrap();
+                let value = self.read_ea(bus, mode, Size::Long);
+                match field {
+                    0..=3 => self.emac.load(field, value),
+                    4 => self.emac.macsr = value & macsr::WRITABLE,
+                    6 => self.emac.mask = value | 0xFFFF_0000,
+                    _ => self.emac.set_extension(field / 7, value),
+                }
+                1
+            }
+            0x0180 => {
+                let value = match field {
+                    0..=3 => self.emac.store(field),
+                    4 => self.emac.macsr,
+                    6 => self.emac.mask,
+                    _ => self.emac.extension(field / 7),
+                };
+                self.dar[rx] = value;
+                1
+            }
+            _ if opcode == 0xA9C0 => {
+                // MOVE.L MACSR,CCR: N, Z and V, and EV into C.
+                self.set_ccr((self.emac.macsr & 0xF) as u8);
+                1
+            }
+            _ => {
+                // MOVCLR.L
+                self.dar[rx] = self.emac.store(field);
+                self.emac.acc[field] = 0;
+                self.emac.macsr &= !(macsr::PAV0 << field);
+                1
+            }
+        }
+    }
+
+    /// MAC/MSAC, with or without a parallel load.
+    fn exec_mac<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
+        let ext = self.read_imm_16(bus);
+        let mode = ((opcode >> 3) & 7) as u8;
+        let first = (((opcode >> 6) & 1) << 3 | ((opcode >> 9) & 7)) as usize;
+        let load = mode >= 2;
+        let (rx, ry) = if load {
+            ((ext >> 12) as usize, (ext & 0xF) as usize)
+        } else {
+            (first, (opcode & 0xF) as usize)
+        };
+        let acc = (((ext >> 3) & 2) | ((opcode >> 7) & 1)) as usize;
+        let long = ext & 0x0800 != 0;
+        let subtract = ext & 0x0100 != 0;
+
+        let x = self.mac_operand(rx, ext & 0x0080 != 0, long);
+        let y = self.mac_operand(ry, ext & 0x0040 != 0, long);
+
+        if load {
+            let reg = (opcode & 7) as usize;
+            let masked = ext & 0x0020 != 0;
+            let ay = self.a(reg);
+            let (address, updated) = match mode {
+                2 => (ay, None),
+                3 => (ay, Some(ay.wrapping_add(4))),
+                4 => (ay.wrapping_sub(4), Some(ay.wrapping_sub(4))),
+                _ => {
+                    let disp = self.read_imm_16(bus) as i16 as i32 as u32;
+                    (ay.wrapping_add(disp), None)
+                }
+            };
+            let value = self.read_32(bus, address);
+            if self.faulted() {
+                return 0;
+            }
+            if let Some(updated) = updated {
+                let mask = if masked { self.emac.mask } else { u32::MAX };
+                self.set_a(reg, updated & mask);
+            }
+            self.dar[first] = value;
+        }
+
+        let fractional = self.emac.macsr & macsr::FI != 0;
+        let mut product = x * y;
+        if fractional {
+            product <<= 1;
+            if long {
+                if self.emac.macsr & macsr::RT != 0 {
+                    product += 1 << 31;
+                }
+                product >>= 32;
+            }
+        }
+        match (ext >> 9) & 3 {
+            1 => product <<= 1,
+            3 => product >>= 1,
+            _ => {}
+        }
+        self.emac.accumulate(acc, product, subtract);
+
+        let cycles = if long { 3 } else { 1 };
+        if load { cycles + 1 } else { cycles }
+    }
+
+    /// A MAC operand: register `reg` (D0-D7, A0-A7) as a long word, or its upper or lower
+    /// word, extended per MACSR.
+    fn mac_operand(&self, reg: usize, upper: bool, long: bool) -> i128 {
+        let value = self.dar[reg];
+        let signed = self.emac.macsr & (macsr::SU | macsr::FI) != macsr::SU;
+        match (long, signed) {
+            (true, true) => value as i32 as i128,
+            (true, false) => value as i128,
+            (false, _) => {
+                let word = if upper { value >> 16 } else { value } as u16;
+                if signed {
+                    word as i16 as i128
+                } else {
+                    word as i128
+                }
+            }
+        }
+    }
+}
@@ -21,15 +21,51 @@ pub mod vector {
     pub const TRACE: u32 = 9;
     pub const LINE_1010: u32 = 10;
     pub const LINE_1111: u32 = 11;
+    pub const PROTOCOL_VIOLATION: u32 = 13;
     pub const FORMAT_ERROR: u32 = 14;
     pub const UNINITIALIZED_INTERRUPT: u32 = 15;
     pub const SPURIOUS_INTERRUPT: u32 = 24;
     pub const TRAP_BASE: u32 = 32;
 
+    // FPU (68881/68882/68040) exceptions.
+    pub const FP_BSUN: u32 = 48;
+    pub const FP_INEXACT: u32 = 49;
+    pub const FP_DIVIDE_BY_ZERO: u32 = 50;
+    pub const FP_UNDERFLOW: u32 = 51;
+    pub const FP_OPERAND_ERROR: u32 = 52;
+    pub const FP_OVERFLOW: u32 = 53;
+    pub const FP_SIGNALING_NAN: u32 = 54;
+    /// 68060: an FPU operand in a data type left to software (packed decimal).
+    pub const FP_UNIMPLEMENTED_DATA_TYPE: u32 = 55;
+
     // 68020+ MMU exceptions (vector numbers per 68k docs; used by 68030/68040 PMMU).
     pub const MMU_CONFIGURATION_ERROR: u32 = 56;
     pub const MMU_ILLEGAL_OPERATION_ERROR: u32 = 57;
     pub const MMU_ACCESS_LEVEL_VIOLATION_ERROR: u32 = 58;
+
+    /// 68060: an integer instruction left to software (MOVEP, CAS2, CHK2/CMP2, 64-bit
+    /// MULx.L/DIVx.L).
+    pub const UNIMPLEMENTED_INTEGER: u32 = 61;
+}
+
+/// ColdFire exception frame fault status (FS) values.
+pub mod fs_coldfire {
+    /// Not an access or address error.
+    pub const NONE: u32 = 0b0000;
+    pub const FETCH: u32 = 0b0100;
+    pub const WRITE: u32 = 0b1000;
+    pub const READ: u32 = 0b1100;
+}
+
+/// The ColdFire fault status of a faulted access.
+fn coldfire_fault_status(write: bool, instruction: bool) -> u32 {
+    if instruction {
+        fs_coldfire::FETCH
+    } else if write {
+        fs_coldfire::WRITE
+    } else {
+        fs_coldfire::READ
+    }
 }
 
 /// Function code bits for exception stack frames.
@@ -40,27 +76,187 @@ pub mod fc {
     pub const SUPERVISOR_PROGRAM: u16 = 6;
 }
 
+/// 68020/68030 special status word bits (format $A/$B frames).
+pub mod ssw {
+    /// Fault on instruction pipe stage C / B.
+    pub const FC: u16 = 0x8000;
+    pub const FB: u16 = 0x4000;
+    /// Rerun stage C / B on RTE.
+    pub const RC: u16 = 0x2000;
+    pub const RB: u16 = 0x1000;
+    /// Data fault: rerun the data cycle on RTE.
+    pub const DF: u16 = 0x0100;
+    pub const RM: u16 = 0x0080;
+    /// Read (1) or write (0) data cycle.
+    pub const RW: u16 = 0x0040;
+    /// SIZE field, bits 5-4: long is 00.
+    pub const SIZE_BYTE: u16 = 0x0010;
+    pub const SIZE_WORD: u16 = 0x0020;
+}
+
+/// 68010 special status word bits (format $8 frame).
+pub mod ssw_010 {
+    /// Rerun flag: set by the handler when it has completed the faulted cycle itself.
+    pub const RR: u16 = 0x8000;
+    /// Fault on an instruction fetch.
+    pub const IF: u16 = 0x2000;
+    /// Fault on a data read.
+    pub const DF: u16 = 0x1000;
+    pub const RM: u16 = 0x0800;
+    /// Byte transfer, and whether it used the high (even address) byte lane.
+    pub const HB: u16 = 0x0400;
+    pub const BY: u16 = 0x0200;
+    /// Read (1) or write (0) cycle.
+    pub const RW: u16 = 0x0100;
+}
+
+/// CPU32 special status word bits (format $C frame).
+pub mod ssw_cpu32 {
+    /// Rerun write cycle: set by the handler when it has completed the faulted write itself.
+    pub const RR: u16 = 0x0200;
+    pub const RM: u16 = 0x0100;
+    /// Fault on an instruction fetch.
+    pub const IN: u16 = 0x0080;
+    /// Read (1) or write (0) cycle.
+    pub const RW: u16 = 0x0040;
+    /// The faulted operand is a long word.
+    pub const LG: u16 = 0x0020;
+    /// SIZ field, bits 4-3: the size of the operand still to transfer; long is 00.
+    pub const SIZE_BYTE: u16 = 0x0008;
+    pub const SIZE_WORD: u16 = 0x0010;
+}
+
+/// 68040 special status word bits (format $7 frame).
+pub mod ssw_040 {
+    /// Continuation of a MOVEM (CM) or trace (CT) pending when the fault occurred.
+    pub const CT: u16 = 0x2000;
+    pub const CM: u16 = 0x1000;
+    /// Misaligned access.
+    pub const MA: u16 = 0x0800;
+    /// Fault reported by the MMU (ATC miss, invalid, protected) rather than the bus.
+    pub const ATC: u16 = 0x0400;
+    /// Locked (read-modify-write) transfer.
+    pub const LK: u16 = 0x0200;
+    /// Read (1) or write (0).
+    pub const RW: u16 = 0x0100;
+    /// SIZE field, bits 6-5: long is 00.
+    pub const SIZE_BYTE: u16 = 0x0020;
+    pub const SIZE_WORD: u16 = 0x0040;
+    pub const SIZE_LINE: u16 = 0x0060;
+    /// TT field, bits 4-3: normal access is 00.
+    pub const TT_MASK: u16 = 0x0018;
+    pub const TT_MOVE16: u16 = 0x0008;
+    /// Write-back status words: valid bit. Bits 6-0 use the SSW's SIZE/TT/TM layout.
+    pub const WB_VALID: u16 = 0x0080;
+}
+
+/// 68060 fault status long word bits (format $4 frame).
+pub mod fslw {
+    /// Misaligned access.
+    pub const MA: u32 = 1 << 27;
+    /// RW field, bits 24-23: write is 01, read 10.
+    pub const RW_WRITE: u32 = 1 << 23;
+    pub const RW_READ: u32 = 2 << 23;
+    /// SIZE field, bits 22-21: long is 00.
+    pub const SIZE_BYTE: u32 = 1 << 21;
+    pub const SIZE_WORD: u32 = 2 << 21;
+    pub const SIZE_LINE: u32 = 3 << 21;
+    /// TT field, bits 20-19: normal access is 00.
+    pub const TT_MOVE16: u32 = 1 << 19;
+    /// TM field, bits 18-16: the function code of the access.
+    pub const TM_SHIFT: u32 = 16;
+    /// Fault on an instruction fetch.
+    pub const IO: u32 = 1 << 15;
+    /// Page fault: a descriptor on the table search was invalid.
+    pub const PF: u32 = 1 << 9;
+    /// Supervisor-only page accessed in user mode.
+    pub const SP: u32 = 1 << 8;
+    /// Write to a write-protected page.
+    pub const WP: u32 = 1 << 7;
+    /// Bus error on a read or a write.
+    pub const RE: u32 = 1 << 5;
+    pub const WE: u32 = 1 << 4;
+}
+
+/// Size and data of a data bus cycle that faulted, saved for the bus error frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FaultedCycle {
+    /// Operand size in bytes (1, 2 or 4, or 16 for a MOVE16 line).
+    pub size: u8,
+    /// Data being written; zero for reads.
+    pub data: u32,
+    /// 68040: the fault came from the MMU rather than the bus (SSW ATC bit).
+    pub atc: bool,
+    /// 68060: why the MMU refused the access (FSLW PF, SP or WP).
+    pub mmu_cause: u32,
+}
+
+impl FaultedCycle {
+    pub fn new(size: u8, data: u32) -> Self {
+        Self {
+            size,
+            data,
+            atc: false,
+            mmu_cause: 0,
+        }
+    }
+}
+
+/// A faulted data cycle that the bus error handler completed itself (cleared DF).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompletedCycle {
+    /// Address of the instruction RTE continues.
+    pub pc: u32,
+    pub address: u32,
+    pub write: bool,
+    /// Data input buffer, returned in place of a read.
+    pub data: u32,
+    /// Bytes the handler completed. The 68010 runs a long access as two word cycles and
+    /// its frame only covers the faulted one, so there this is 2 and the low word of a
+    /// long access still goes to the bus.
+    pub width: u8,
+}
+
+/// Size of the 68010 bus/address error frame (29 words).
+const FORMAT_8_BYTES: u32 = 58;
+/// Size of the 68040 access error frame (30 words).
+const FORMAT_7_BYTES: u32 = 60;
+/// Size of the 68060 access fault frame (8 words).
+const FORMAT_4_BYTES: u32 = 16;
+/// Size of the CPU32 bus error frame (12 words).
+const FORMAT_C_BYTES: u32 = 24;
+/// Sizes of the 68020/68030 short and long bus cycle fault frames.
+const FORMAT_A_BYTES: u32 = 32;
+const FORMAT_B_BYTES: u32 = 92;
+/// Internal register word at offset $08 of a format $A/$B frame. Bit 0 marks a data
+/// cycle fault, so RTE can tell a cycle completed by the handler from a clean one.
+const FRAME_DATA_FAULT: u16 = 0x0001;
+
 impl CpuCore {
     #[inline]
-    fn push_16_raw<B: AddressBus>(&mut self, bus: &mut B, value: u16) {
-        self.dar[15] = self.dar[15].wrapping_sub(2);
-        bus.write_word(self.address(self.dar[15]), value);
+    fn write_frame_16<B: AddressBus>(&mut self, bus: &mut B, addr: u32, value: u16) {
+        let addr = self.address(addr);
+        let _ = self.bus_write_cycle(bus, self.data_fc(), addr, 2, value as u32);
     }
 
     #[inline]
-    fn push_32_raw<B: AddressBus>(&mut self, bus: &mut B, value: u32) {
-        self.dar[15] = self.dar[15].wrapping_sub(4);
-        bus.write_long(self.address(self.dar[15]), value);
+    fn write_frame_32<B: AddressBus>(&mut self, bus: &mut B, addr: u32, value: u32) {
+        let addr = self.address(addr);
+        let _ = self.bus_write_cycle(bus, self.data_fc(), addr, 4, value);
     }
 
     #[inline]
-    fn fake_push_16_raw(&mut self) {
+    fn push_16_raw<B: AddressBus>(&mut self, bus: &mut B, value: u16) {
         self.dar[15] = self.dar[15].wrapping_sub(2);
+        let addr = self.address(self.dar[15]);
+        let _ = self.bus_write_cycle(bus, self.data_fc(), addr, 2, value as u32);
     }
 
     #[inline]
-    fn fake_push_32_raw(&mut self) {
+    fn push_32_raw<B: AddressBus>(&mut self, bus: &mut B, value: u32) {
         self.dar[15] = self.dar[15].wrapping_sub(4);
+        let addr = self.address(self.dar[15]);
+        let _ = self.bus_write_cycle(bus, self.data_fc(), addr, 4, value);
     }
 
     /// Process TRAP #n instruction.
@@ -95,7 +291,7 @@ impl CpuCore {
             self.push_16(bus, old_sr);
 
             self.jump_vector(bus, vector);
-            return self.exception_cycles(vector);
+            return self.exception_timing.cycles(vector);
         }
 
         self.take_exception(bus, vector)
@@ -117,11 +313,13 @@ impl CpuCore {
         //
         // CHK stacks the next PC (self.pc) and includes PPC in the 020+ format-2 frame.
         match self.cpu_type {
-            super::types::CpuType::M68000 => {
+            super::types::CpuType::M68000 | super::types::CpuType::M68008 => {
                 self.push_32(bus, self.pc);
                 self.push_16(bus, old_sr);
             }
-            super::types::CpuType::M68010 | super::types::CpuType::SCC68070 => {
+            super::types::CpuType::M68010
+            | super::types::CpuType::M68012
+            | super::types::CpuType::SCC68070 => {
                 self.push_16(bus, (vector::CHK as u16) << 2);
                 self.push_32(bus, self.pc);
                 self.push_16(bus, old_sr);
@@ -138,7 +336,7 @@ impl CpuCore {
         // Jump to vector
         self.jump_vector(bus, vector::CHK);
 
-        self.exception_cycles(vector::CHK)
+        self.exception_timing.cycles(vector::CHK)
     }
 
     /// Process zero divide exception.
@@ -168,6 +366,8 @@ impl CpuCore {
     ) -> i32 {
         let old_sr = self.get_sr();
         let was_supervisor = (old_sr & 0x2000) != 0;
+        // A faulted MOVES must not stack the frame in its alternate space.
+        self.moves_fc = None;
 
         // Enter supervisor mode, clear trace
         self.set_s_flag(SFLAG_SET);
@@ -190,7 +390,7 @@ impl CpuCore {
         let status_word = fc | if write { 0 } else { 0x10 } | if instruction { 0 } else { 0x08 };
 
         match self.cpu_type {
-            CpuType::M68000 => {
+            CpuType::M68000 | CpuType::M68008 => {
                 // 68000 address error frame (14 bytes):
                 // Push: PC (4), SR (2), IR (2), Access Address (4), Status Word (2)
                 //
@@ -202,48 +402,495 @@ impl CpuCore {
                 self.push_16_raw(bus, old_sr);
                 self.push_32_raw(bus, self.ppc);
             }
-            CpuType::M68010 | CpuType::SCC68070 => {
-                // 68010 uses the "format 8" (0x8) bus/address error stack frame (29 words).
-                // We intentionally mirror Musashi's placeholder implementation here: most internal
-                // words are zero/undefined and we primarily preserve the format/vector word, PC, SR.
-                //
-                // Layout (from Musashi m68kcpu.h m68ki_stack_frame_1000):
-                // - lots of internal words (mostly not written)
-                // - fault address (long) = 0
-                // - special status word = 0
-                // - format/vector word = 0x8000 | (vector<<2)
-                // - stacked PC (long)
-                // - stacked SR (word)
-                for _ in 0..8 {
-                    self.fake_push_32_raw();
-                }
-                self.push_16_raw(bus, 0); // instruction input buffer
-                self.fake_push_16_raw();
-                self.push_16_raw(bus, 0); // data input buffer
-                self.fake_push_16_raw();
-                self.push_16_raw(bus, 0); // data output buffer
-                self.fake_push_16_raw();
-                self.push_32_raw(bus, 0); // fault address
-                self.push_16_raw(bus, 0); // special status word
-                self.push_16_raw(bus, 0x8000 | ((vector::ADDRESS_ERROR as u16) << 2));
-                self.push_32_raw(bus, self.ppc);
-                self.push_16_raw(bus, old_sr);
+            CpuType::M68010 | CpuType::M68012 | CpuType::SCC68070 => {
+                self.push_bus_fault_frame_010(
+                    bus,
+                    vector::ADDRESS_ERROR,
+                    old_sr,
+                    fc,
+                    address,
+                    write,
+                    instruction,
+                );
+            }
+            CpuType::M68EC020 | CpuType::M68020 | CpuType::M68EC030 | CpuType::M68030 => {
+                // Only instruction fetches can be misaligned; they take the long frame.
+                self.push_bus_fault_frame_020(
+                    bus,
+                    vector::ADDRESS_ERROR,
+                    old_sr,
+                    fc,
+                    address,
+                    write,
+                    instruction,
+                );
+            }
+            CpuType::CPU32 => {
+                self.push_bus_fault_frame_cpu32(
+                    bus,
+                    vector::ADDRESS_ERROR,
+                    old_sr,
+                    fc,
+                    address,
+                    write,
+                    instruction,
+                );
+            }
+            CpuType::ColdFireIsaA | CpuType::ColdFireIsaB | CpuType::ColdFireIsaC => {
+                let fs = coldfire_fault_status(write, instruction);
+                self.push_coldfire_frame(bus, vector::ADDRESS_ERROR, fs, self.ppc, old_sr);
             }
             _ => {
-                // TODO: 68020+ address error stack frames (format A/B/7 variants) are not yet
-                // implemented. Use a minimal 68010+ format-0-like frame to avoid totally losing
-                // control flow, but this is not architecturally accurate.
-                self.push_16_raw(bus, (vector::ADDRESS_ERROR as u16) << 2);
+                // The 68040 stacks a format $2 frame holding the odd address.
+                self.push_32_raw(bus, address);
+                self.push_16_raw(bus, 0x2000 | ((vector::ADDRESS_ERROR as u16) << 2));
                 self.push_32_raw(bus, self.ppc);
                 self.push_16_raw(bus, old_sr);
-                let _ = (status_word, address); // currently unused in this fallback
+                let _ = status_word;
             }
         }
 
+        self.faulted_cycle = FaultedCycle::default();
+
         // Jump to vector
         self.jump_vector(bus, vector::ADDRESS_ERROR);
 
-        50 // Cycles for address error
+        self.exception_timing.cycles(vector::ADDRESS_ERROR)
+    }
+
+    /// Build a 68010 bus/address error frame (format $8).
+    ///
+    /// The 68010 moves at most a word per bus cycle, so a faulted long access is reported as
+    /// its high word cycle. Byte data sits in the low byte of the data buffers.
+    #[allow(clippy::too_many_arguments)]
+    fn push_bus_fault_frame_010<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        vector: u32,
+        old_sr: u16,
+        fc: u16,
+        address: u32,
+        write: bool,
+        instruction: bool,
+    ) {
+        let cycle = self.faulted_cycle;
+        let mut status = fc;
+        let mut data_out = 0;
+        if instruction {
+            status |= ssw_010::IF | ssw_010::RW;
+        } else {
+            if write {
+                data_out = if cycle.size == 4 {
+                    (cycle.data >> 16) as u16
+                } else {
+                    cycle.data as u16
+                };
+            } else {
+                status |= ssw_010::DF | ssw_010::RW;
+            }
+            if cycle.size == 1 {
+                status |= ssw_010::BY;
+                if address & 1 == 0 {
+                    status |= ssw_010::HB;
+                }
+            }
+        }
+
+        self.dar[15] = self.dar[15].wrapping_sub(FORMAT_8_BYTES);
+        let sp = self.dar[15];
+        // The internal state words are not modelled and read back as zero.
+        for offset in (0..FORMAT_8_BYTES).step_by(2) {
+            self.write_frame_16(bus, sp.wrapping_add(offset), 0);
+        }
+        self.write_frame_16(bus, sp, old_sr);
+        self.write_frame_32(bus, sp.wrapping_add(0x02), self.ppc);
+        self.write_frame_16(bus, sp.wrapping_add(0x06), 0x8000 | ((vector as u16) << 2));
+        self.write_frame_16(bus, sp.wrapping_add(0x08), status);
+        self.write_frame_32(bus, sp.wrapping_add(0x0A), address);
+        self.write_frame_16(bus, sp.wrapping_add(0x10), data_out);
+        self.write_frame_16(bus, sp.wrapping_add(0x18), self.ir as u16);
+    }
+
+    /// RTE from a 68010 format $8 frame at SP.
+    ///
+    /// As on the 68020/68030 the faulted instruction is restarted at the stacked PC. With RR
+    /// clear the processor reruns the faulted cycle; with RR set on a data fault the handler
+    /// has completed it, so that write is skipped or that read returns the data input buffer.
+    pub(crate) fn rte_bus_fault_frame_010<B: AddressBus>(&mut self, bus: &mut B) {
+        let sp = self.a(7);
+        let status = self.read_16(bus, sp.wrapping_add(0x08));
+        let address = self.read_32(bus, sp.wrapping_add(0x0A));
+        let data_in = self.read_16(bus, sp.wrapping_add(0x14));
+        let sr = self.pull_16(bus);
+        let pc = self.pull_32(bus);
+        self.dar[15] = sp.wrapping_add(FORMAT_8_BYTES);
+        self.set_sr(sr);
+        self.pc = pc;
+
+        self.completed_cycle =
+            (status & (ssw_010::RR | ssw_010::IF) == ssw_010::RR).then_some(CompletedCycle {
+                pc,
+                address,
+                write: status & ssw_010::RW == 0,
+                data: data_in as u32,
+                width: 2,
+            });
+    }
+
+    /// Build a 68020/68030 bus cycle fault frame: short (format $A) for data writes, long
+    /// (format $B) for data reads and instruction stream faults.
+    ///
+    /// The stacked PC is the faulted instruction; data faults set DF and instruction stream
+    /// faults set FB/RB, so an untouched frame makes RTE rerun the faulted cycle.
+    #[allow(clippy::too_many_arguments)]
+    fn push_bus_fault_frame_020<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        vector: u32,
+        old_sr: u16,
+        fc: u16,
+        address: u32,
+        write: bool,
+        instruction: bool,
+    ) {
+        let cycle = self.faulted_cycle;
+        let mut status = fc;
+        if instruction {
+            status |= ssw::FB | ssw::RB;
+        } else {
+            status |= ssw::DF;
+            status |= match cycle.size {
+                1 => ssw::SIZE_BYTE,
+                2 => ssw::SIZE_WORD,
+                _ => 0,
+            };
+            if !write {
+                status |= ssw::RW;
+            }
+        }
+
+        let long = instruction || !write;
+        let (format, bytes) = if long {
+            (0xB000, FORMAT_B_BYTES)
+        } else {
+            (0xA000, FORMAT_A_BYTES)
+        };
+        self.dar[15] = self.dar[15].wrapping_sub(bytes);
+        let sp = self.dar[15];
+        // Internal registers read back as zero.
+        for offset in (0..bytes).step_by(4) {
+            self.write_frame_32(bus, sp.wrapping_add(offset), 0);
+        }
+        self.write_frame_16(bus, sp, old_sr);
+        self.write_frame_32(bus, sp.wrapping_add(0x02), self.ppc);
+        self.write_frame_16(bus, sp.wrapping_add(0x06), format | ((vector as u16) << 2));
+        let internal = if instruction { 0 } else { FRAME_DATA_FAULT };
+        self.write_frame_16(bus, sp.wrapping_add(0x08), internal);
+        self.write_frame_16(bus, sp.wrapping_add(0x0A), status);
+        // Pipe stage C holds the opcode being executed.
+        self.write_frame_16(bus, sp.wrapping_add(0x0C), self.ir as u16);
+        self.write_frame_32(bus, sp.wrapping_add(0x10), address);
+        self.write_frame_32(
+            bus,
+            sp.wrapping_add(0x18),
+            if write { cycle.data } else { 0 },
+        );
+        if long {
+            let stage_b = if instruction { address } else { self.pc };
+            self.write_frame_32(bus, sp.wrapping_add(0x24), stage_b);
+        }
+    }
+
+    /// RTE from a 68020/68030 format $A or $B frame at SP.
+    ///
+    /// Faults are taken with the instruction's register effects rolled back, so RTE resumes
+    /// by restarting the instruction at the stacked PC, which reruns the faulted cycle. If
+    /// the handler cleared DF it has completed the data cycle itself: the restarted
+    /// instruction then skips that write, or takes the data input buffer for that read.
+    pub(crate) fn rte_bus_fault_frame<B: AddressBus>(&mut self, bus: &mut B, long: bool) {
+        let sp = self.a(7);
+        let status = self.read_16(bus, sp.wrapping_add(0x0A));
+        let internal = self.read_16(bus, sp.wrapping_add(0x08));
+        let address = self.read_32(bus, sp.wrapping_add(0x10));
+        let data_in = if long {
+            self.read_32(bus, sp.wrapping_add(0x2C))
+        } else {
+            0
+        };
+        let sr = self.pull_16(bus);
+        let pc = self.pull_32(bus);
+        let bytes = if long { FORMAT_B_BYTES } else { FORMAT_A_BYTES };
+        self.dar[15] = sp.wrapping_add(bytes);
+        self.set_sr(sr);
+        self.pc = pc;
+
+        self.completed_cycle = (internal & FRAME_DATA_FAULT != 0 && status & ssw::DF == 0)
+            .then_some(CompletedCycle {
+                pc,
+                address,
+                write: status & ssw::RW == 0,
+                data: data_in,
+                width: 4,
+            });
+    }
+
+    /// Build a 68040 access error frame (format $7).
+    ///
+    /// The stacked PC is the faulted instruction. A faulted data write is also reported as
+    /// pending write-back 3, the way the 68040's store buffer presents it; WB1 and WB2 are
+    /// never valid since there is no push buffer to hold other pending writes.
+    fn push_access_error_frame_040<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        old_sr: u16,
+        fc: u16,
+        address: u32,
+        write: bool,
+        instruction: bool,
+    ) {
+        let cycle = self.faulted_cycle;
+        let mut status = fc;
+        if instruction || !write {
+            status |= ssw_040::RW;
+        }
+        if cycle.atc {
+            status |= ssw_040::ATC;
+        }
+        if !instruction {
+            status |= match cycle.size {
+                1 => ssw_040::SIZE_BYTE,
+                2 => ssw_040::SIZE_WORD,
+                16 => ssw_040::SIZE_LINE | ssw_040::TT_MOVE16,
+                _ => 0,
+            };
+            if matches!(cycle.size, 2 | 4) && address & (cycle.size as u32 - 1) != 0 {
+                status |= ssw_040::MA;
+            }
+        }
+        // A MOVE16 write is rerun by restarting the instruction, not written back.
+        let write_back = !instruction && write && cycle.size != 16;
+
+        self.dar[15] = self.dar[15].wrapping_sub(FORMAT_7_BYTES);
+        let sp = self.dar[15];
+        for offset in (0..FORMAT_7_BYTES).step_by(4) {
+            self.write_frame_32(bus, sp.wrapping_add(offset), 0);
+        }
+        self.write_frame_16(bus, sp, old_sr);
+        self.write_frame_32(bus, sp.wrapping_add(0x02), self.ppc);
+        self.write_frame_16(
+            bus,
+            sp.wrapping_add(0x06),
+            0x7000 | ((vector::BUS_ERROR as u16) << 2),
+        );
+        if !instruction {
+            self.write_frame_32(bus, sp.wrapping_add(0x08), address);
+        }
+        self.write_frame_16(bus, sp.wrapping_add(0x0C), status);
+        self.write_frame_32(bus, sp.wrapping_add(0x14), address);
+        if write_back {
+            self.write_frame_16(
+                bus,
+                sp.wrapping_add(0x0E),
+                ssw_040::WB_VALID | (status & 0x7F),
+            );
+            self.write_frame_32(bus, sp.wrapping_add(0x18), address);
+            self.write_frame_32(bus, sp.wrapping_add(0x1C), cycle.data);
+        }
+    }
+
+    /// RTE from a 68040 format $7 frame at SP.
+    ///
+    /// The faulted instruction is restarted at the stacked PC. A handler that completed the
+    /// faulted write in software clears the WB3 valid bit, and the restarted instruction
+    /// then skips that write; left valid, the write is rerun.
+    pub(crate) fn rte_access_error_frame_040<B: AddressBus>(&mut self, bus: &mut B) {
+        let sp = self.a(7);
+        let status = self.read_16(bus, sp.wrapping_add(0x0C));
+        let wb3_status = self.read_16(bus, sp.wrapping_add(0x0E));
+        let address = self.read_32(bus, sp.wrapping_add(0x14));
+        let sr = self.pull_16(bus);
+        let pc = self.pull_32(bus);
+        self.dar[15] = sp.wrapping_add(FORMAT_7_BYTES);
+        self.set_sr(sr);
+        self.pc = pc;
+
+        let faulted_write = status & (ssw_040::RW | ssw_040::TT_MASK) == 0;
+        self.completed_cycle =
+            (faulted_write && wb3_status & ssw_040::WB_VALID == 0).then_some(CompletedCycle {
+                pc,
+                address,
+                write: true,
+                data: 0,
+                width: 4,
+            });
+    }
+
+    /// Build a 68060 access fault frame (format $4): the fault address and the fault
+    /// status long word.
+    ///
+    /// The stacked PC is the faulted instruction, which RTE restarts.
+    fn push_access_fault_frame_060<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        old_sr: u16,
+        fc: u16,
+        address: u32,
+        write: bool,
+        instruction: bool,
+    ) {
+        let cycle = self.faulted_cycle;
+        let mut status = (fc as u32) << fslw::TM_SHIFT | cycle.mmu_cause;
+        status |= if write { fslw::RW_WRITE } else { fslw::RW_READ };
+        if instruction {
+            status |= fslw::IO;
+        } else {
+            status |= match cycle.size {
+                1 => fslw::SIZE_BYTE,
+                2 => fslw::SIZE_WORD,
+                16 => fslw::SIZE_LINE | fslw::TT_MOVE16,
+                _ => 0,
+            };
+            if matches!(cycle.size, 2 | 4) && address & (cycle.size as u32 - 1) != 0 {
+                status |= fslw::MA;
+            }
+        }
+        if !cycle.atc {
+            status |= if write { fslw::WE } else { fslw::RE };
+        }
+
+        self.push_32_raw(bus, status);
+        self.push_32_raw(bus, address);
+        self.push_16_raw(bus, 0x4000 | ((vector::BUS_ERROR as u16) << 2));
+        self.push_32_raw(bus, self.ppc);
+        self.push_16_raw(bus, old_sr);
+    }
+
+    /// RTE from a 68060 format $4 frame at SP: the faulted instruction is restarted at the
+    /// stacked PC and reruns the faulted access.
+    pub(crate) fn rte_access_fault_frame_060<B: AddressBus>(&mut self, bus: &mut B) {
+        let sp = self.a(7);
+        let sr = self.pull_16(bus);
+        let pc = self.pull_32(bus);
+        self.dar[15] = sp.wrapping_add(FORMAT_4_BYTES);
+        self.set_sr(sr);
+        self.pc = pc;
+    }
+
+    /// Build a CPU32 bus error frame (format $C), also used for address errors.
+    ///
+    /// Faults are reported as type II: the faulted instruction is restarted at the stacked
+    /// PC, which is also the current instruction PC, and a write's data sits in DBUF.
+    #[allow(clippy::too_many_arguments)]
+    fn push_bus_fault_frame_cpu32<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        vector: u32,
+        old_sr: u16,
+        fc: u16,
+        address: u32,
+        write: bool,
+        instruction: bool,
+    ) {
+        let cycle = self.faulted_cycle;
+        let mut status = fc;
+        if instruction {
+            status |= ssw_cpu32::IN | ssw_cpu32::RW | ssw_cpu32::SIZE_WORD;
+        } else {
+            status |= match cycle.size {
+                1 => ssw_cpu32::SIZE_BYTE,
+                2 => ssw_cpu32::SIZE_WORD,
+                _ => ssw_cpu32::LG,
+            };
+            if !write {
+                status |= ssw_cpu32::RW;
+            }
+        }
+        let data_out = if write && !instruction { cycle.data } else { 0 };
+
+        self.push_16_raw(bus, status);
+        self.push_16_raw(bus, 0); // internal transfer count
+        self.push_32_raw(bus, self.ppc);
+        self.push_32_raw(bus, data_out);
+        self.push_32_raw(bus, address);
+        self.push_16_raw(bus, 0xC000 | ((vector as u16) << 2));
+        self.push_32_raw(bus, self.ppc);
+        self.push_16_raw(bus, old_sr);
+    }
+
+    /// RTE from a CPU32 format $C frame at SP.
+    ///
+    /// The faulted instruction is restarted at the stacked PC. If the handler set RR after a
+    /// faulted write it has completed that write, so the restarted instruction skips it.
+    pub(crate) fn rte_bus_fault_frame_cpu32<B: AddressBus>(&mut self, bus: &mut B) {
+        let sp = self.a(7);
+        let address = self.read_32(bus, sp.wrapping_add(0x08));
+        let data_out = self.read_32(bus, sp.wrapping_add(0x0C));
+        let status = self.read_16(bus, sp.wrapping_add(0x16));
+        let sr = self.pull_16(bus);
+        let pc = self.pull_32(bus);
+        self.dar[15] = sp.wrapping_add(FORMAT_C_BYTES);
+        self.set_sr(sr);
+        self.pc = pc;
+
+        let width = match status & (ssw_cpu32::SIZE_BYTE | ssw_cpu32::SIZE_WORD) {
+            ssw_cpu32::SIZE_BYTE => 1,
+            ssw_cpu32::SIZE_WORD => 2,
+            _ => 4,
+        };
+        self.completed_cycle = (status & (ssw_cpu32::RR | ssw_cpu32::IN | ssw_cpu32::RW)
+            == ssw_cpu32::RR)
+            .then_some(CompletedCycle {
+                pc,
+                address,
+                write: true,
+                data: data_out,
+                width,
+            });
+    }
+
+    /// Build a ColdFire exception frame: the PC, under a long word holding the format,
+    /// the fault status, the vector and the SR.
+    ///
+    /// The SP is first aligned down to a long word; the format (4-7) records by how many
+    /// bytes, for RTE to undo.
+    pub(crate) fn push_coldfire_frame<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        vector: u32,
+        fault_status: u32,
+        pc: u32,
+        sr: u16,
+    ) {
+        let format = 4 + (self.dar[15] & 3);
+        self.dar[15] &= !3;
+        self.push_32_raw(bus, pc);
+        self.push_32_raw(
+            bus,
+            format << 28
+                | (fault_status & 0xC) << 24
+                | (vector & 0xFF) << 18
+                | (fault_status & 3) << 16
+                | sr as u32,
+        );
+    }
+
+    /// RTE on a ColdFire core: restore SR and PC, then drop the frame and the alignment
+    /// its format recorded. Formats other than 4-7 take a format error.
+    pub(crate) fn rte_coldfire<B: AddressBus>(&mut self, bus: &mut B) -> i32 {
+        let sp = self.a(7);
+        let word = self.read_32(bus, sp);
+        let format = word >> 28;
+        if !(4..=7).contains(&format) {
+            return self.take_exception(bus, vector::FORMAT_ERROR);
+        }
+        let pc = self.read_32(bus, sp.wrapping_add(4));
+        if self.faulted() {
+            return 0;
+        }
+        self.dar[15] = sp.wrapping_add(8 + (format - 4));
+        self.set_sr(word as u16);
+        self.pc = pc;
+        20
     }
 
     /// Process bus error exception.
@@ -256,6 +903,7 @@ impl CpuCore {
     ) -> i32 {
         let old_sr = self.get_sr();
         let was_supervisor = (old_sr & 0x2000) != 0;
+        self.moves_fc = None;
 
         // Enter supervisor mode, clear trace
         self.set_s_flag(SFLAG_SET);
@@ -277,7 +925,7 @@ impl CpuCore {
         let status_word = fc | if write { 0 } else { 0x10 } | if instruction { 0 } else { 0x08 };
 
         match self.cpu_type {
-            CpuType::M68000 => {
+            CpuType::M68000 | CpuType::M68008 => {
                 // 68000 bus error frame (same as address error)
                 self.push_16_raw(bus, status_word);
                 self.push_32_raw(bus, address);
@@ -285,38 +933,61 @@ impl CpuCore {
                 self.push_16_raw(bus, old_sr);
                 self.push_32_raw(bus, self.ppc);
             }
-            CpuType::M68010 | CpuType::SCC68070 => {
-                // 68010 format 8 (0x8) bus error frame (placeholder, matching Musashi).
-                for _ in 0..8 {
-                    self.fake_push_32_raw();
-                }
-                self.push_16_raw(bus, 0); // instruction input buffer
-                self.fake_push_16_raw();
-                self.push_16_raw(bus, 0); // data input buffer
-                self.fake_push_16_raw();
-                self.push_16_raw(bus, 0); // data output buffer
-                self.fake_push_16_raw();
-                self.push_32_raw(bus, 0); // fault address
-                self.push_16_raw(bus, 0); // special status word
-                self.push_16_raw(bus, 0x8000 | ((vector::BUS_ERROR as u16) << 2));
-                self.push_32_raw(bus, self.ppc);
-                self.push_16_raw(bus, old_sr);
-                let _ = (status_word, address); // currently unused in this placeholder
+            CpuType::M68010 | CpuType::M68012 | CpuType::SCC68070 => {
+                self.push_bus_fault_frame_010(
+                    bus,
+                    vector::BUS_ERROR,
+                    old_sr,
+                    fc,
+                    address,
+                    write,
+                    instruction,
+                );
+            }
+            CpuType::M68EC020 | CpuType::M68020 | CpuType::M68EC030 | CpuType::M68030 => {
+                self.push_bus_fault_frame_020(
+                    bus,
+                    vector::BUS_ERROR,
+                    old_sr,
+                    fc,
+                    address,
+                    write,
+                    instruction,
+                );
+            }
+            CpuType::CPU32 => {
+                self.push_bus_fault_frame_cpu32(
+                    bus,
+                    vector::BUS_ERROR,
+                    old_sr,
+                    fc,
+                    address,
+                    write,
+                    instruction,
+                );
+            }
+            CpuType::M68EC060 | CpuType::M68LC060 | CpuType::M68060 => {
+                self.push_access_fault_frame_060(bus, old_sr, fc, address, write, instruction);
+            }
+            CpuType::ColdFireIsaA | CpuType::ColdFireIsaB | CpuType::ColdFireIsaC => {
+                let fs = coldfire_fault_status(write, instruction);
+                self.push_coldfire_frame(bus, vector::BUS_ERROR, fs, self.ppc, old_sr);
             }
             _ => {
-                // TODO: 68020+ bus error stack frames (format A/B/7 variants) are not yet
-                // implemented. Minimal fallback.
-                self.push_16_raw(bus, (vector::BUS_ERROR as u16) << 2);
-                self.push_32_raw(bus, self.ppc);
-                self.push_16_raw(bus, old_sr);
-                let _ = (status_word, address);
+                self.push_access_error_frame_040(bus, old_sr, fc, address, write, instruction);
+                let _ = status_word;
             }
         }
 
-        // Jump to vector
+        self.faulted_cycle = FaultedCycle::default();
+
+        // Jump to vector. Like take_exception(), the vector fetch bypasses translation so
+        // that a fault on an unmapped vector table cannot recurse.
+        let nested = std::mem::replace(&mut self.exception_processing, true);
         self.jump_vector(bus, vector::BUS_ERROR);
+        self.exception_processing = nested;
 
-        50 // Cycles for bus error
+        self.exception_timing.cycles(vector::BUS_ERROR)
     }
 
     /// Common exception processing (simple frame: SR, PC).
@@ -361,7 +1032,24 @@ impl CpuCore {
         // Match Musashi `m68ki_stack_frame_0000`:
         // - 68000: push PC, then SR (3-word frame)
         // - 68010+: push vector offset word (vector<<2), then PC, then SR (format 0)
-        if self.cpu_type == super::types::CpuType::M68000 {
+        if self.is_68000() {
+            self.push_32(bus, stacked_pc);
+            self.push_16(bus, old_sr);
+        } else if self.is_coldfire() {
+            // A ColdFire divide by zero stacks the instruction's own address.
+            let pc = if vector == vector::ZERO_DIVIDE {
+                self.ppc
+            } else {
+                stacked_pc
+            };
+            self.push_coldfire_frame(bus, vector, fs_coldfire::NONE, pc, old_sr);
+        } else if self.is_cpu32()
+            && matches!(vector, vector::ZERO_DIVIDE | vector::TRAPV | vector::TRACE)
+        {
+            // The CPU32 stacks a format $2 frame holding the instruction's address, as for
+            // CHK.
+            self.push_32(bus, self.ppc);
+            self.push_16(bus, 0x2000 | ((vector as u16) << 2));
             self.push_32(bus, stacked_pc);
             self.push_16(bus, old_sr);
         } else {
@@ -376,24 +1064,7 @@ impl CpuCore {
         // Done processing exception
         self.exception_processing = false;
 
-        self.exception_cycles(vector)
-    }
-
-    /// Get cycles for exception processing.
-    fn exception_cycles(&self, vector: u32) -> i32 {
-        match vector {
-            vector::RESET_SSP | vector::RESET_PC => 40,
-            vector::BUS_ERROR | vector::ADDRESS_ERROR => 50,
-            vector::ILLEGAL_INSTRUCTION => 34,
-            vector::ZERO_DIVIDE => 38,
-            vector::CHK => 40,
-            vector::TRAPV => 34,
-            vector::PRIVILEGE_VIOLATION => 34,
-            vector::TRACE => 34,
-            vector::LINE_1010 | vector::LINE_1111 => 34,
-            24..=31 => 44, // Autovector interrupts
-            _ => 34,       // TRAPs and user vectors
-        }
+        self.exception_timing.cycles(vector)
     }
 
     /// Check for trace exception after instruction execution.
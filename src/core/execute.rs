@@ -4,12 +4,14 @@
 
 use super::cpu::{CpuCore, SFLAG_SET};
 use super::decode::dispatch_instruction;
+use super::exceptions::fs_coldfire;
 use super::memory::AddressBus;
 use super::types::StepResult;
 
 /// Stop level constants.
 pub const STOP_LEVEL_STOP: u32 = 1;
 pub const STOP_LEVEL_HALT: u32 = 2;
+pub const STOP_LEVEL_BACKGROUND: u32 = 4;
 
 /// Run mode constants.
 pub const RUN_MODE_NORMAL: u32 = 0;
@@ -39,7 +41,7 @@ impl CpuCore {
         self.initial_cycles = num_cycles;
 
         // Check for pending interrupts
-        self.check_and_service_interrupts(bus);
+        self.cycles_remaining -= self.check_and_service_interrupts(bus);
 
         // If stopped, consume no cycles
         if self.stopped != 0 {
@@ -56,6 +58,7 @@ impl CpuCore {
             self.dar_save = self.dar;
             // Save SR for bus/address error recovery
             self.sr_save = self.get_sr();
+            self.begin_bus_cycles();
 
             // Fetch opcode
             self.ir = self.read_imm_16(bus) as u32;
@@ -63,6 +66,8 @@ impl CpuCore {
             // If a bus/address error occurred during fetch, the exception is already taken.
             if self.run_mode == RUN_MODE_BERR_AERR_RESET {
                 self.run_mode = RUN_MODE_NORMAL;
+                self.cycles_remaining -=
+                    std::mem::take(&mut self.fault_cycles) + self.take_wait_states();
                 continue;
             }
 
@@ -81,7 +86,7 @@ impl CpuCore {
                 InternalStepResult::Breakpoint { .. } => self.take_bkpt_exception(bus),
                 InternalStepResult::IllegalInstruction { .. } => self.take_illegal_exception(bus),
             };
-            self.cycles_remaining -= cycles;
+            self.cycles_remaining -= cycles + self.take_wait_states();
 
             // If a bus/address error occurred mid-instruction, we already built the exception frame
             // and jumped to the handler. Skip trace/interrupt checks for the faulting instruction.
@@ -98,8 +103,9 @@ impl CpuCore {
 
             // Check for interrupts after each instruction
             if self.int_level > 0 {
-                self.check_and_service_interrupts(bus);
+                self.cycles_remaining -= self.check_and_service_interrupts(bus);
             }
+            self.cycles_remaining -= self.take_wait_states();
 
             // Check if stopped/halted
             if self.stopped != 0 {
@@ -130,11 +136,14 @@ impl CpuCore {
         self.ppc = self.pc;
         self.dar_save = self.dar;
         self.sr_save = self.get_sr();
+        self.begin_bus_cycles();
         self.ir = self.read_imm_16(bus) as u32;
 
         if self.run_mode == RUN_MODE_BERR_AERR_RESET {
             self.run_mode = RUN_MODE_NORMAL;
-            return StepResult::Ok { cycles: 0 };
+            return StepResult::Ok {
+                cycles: std::mem::take(&mut self.fault_cycles) + self.take_wait_states(),
+            };
         }
 
         if self.run_mode == RUN_MODE_BERR_AERR_RESET {
@@ -173,14 +182,18 @@ impl CpuCore {
                 let trace_cycles = self.exception_trace(bus);
                 if let StepResult::Ok { cycles } = res {
                     return StepResult::Ok {
-                        cycles: cycles + trace_cycles,
+                        cycles: cycles + trace_cycles + self.take_wait_states(),
                     };
                 }
             }
 
             // Check for interrupts after instruction
-            if self.int_level > 0 {
-                self.check_and_service_interrupts(bus);
+            if self.int_level > 0
+                && let StepResult::Ok { cycles } = res
+            {
+                return StepResult::Ok {
+                    cycles: cycles + self.check_and_service_interrupts(bus),
+                };
             }
         }
 
@@ -226,11 +239,14 @@ impl CpuCore {
         self.ppc = self.pc;
         self.dar_save = self.dar;
         self.sr_save = self.get_sr();
+        self.begin_bus_cycles();
         self.ir = self.read_imm_16(bus) as u32;
 
         if self.run_mode == RUN_MODE_BERR_AERR_RESET {
             self.run_mode = RUN_MODE_NORMAL;
-            return StepResult::Ok { cycles: 0 };
+            return StepResult::Ok {
+                cycles: std::mem::take(&mut self.fault_cycles) + self.take_wait_states(),
+            };
         }
 
         let result = dispatch_instruction(self, bus, self.ir as u16);
@@ -273,7 +289,7 @@ impl CpuCore {
                     0
                 }
             }
-        };
+        } + self.take_wait_states();
 
         if self.run_mode == RUN_MODE_BERR_AERR_RESET {
             self.run_mode = RUN_MODE_NORMAL;
@@ -284,16 +300,20 @@ impl CpuCore {
         if !self.sst_m68000_compat && self.check_trace() {
             let trace_cycles = self.exception_trace(bus);
             return StepResult::Ok {
-                cycles: cycles + trace_cycles,
+                cycles: cycles + trace_cycles + self.take_wait_states(),
             };
         }
 
         // Check for interrupts after instruction
-        if self.int_level > 0 {
-            self.check_and_service_interrupts(bus);
-        }
+        let interrupt_cycles = if self.int_level > 0 {
+            self.check_and_service_interrupts(bus)
+        } else {
+            0
+        };
 
-        StepResult::Ok { cycles }
+        StepResult::Ok {
+            cycles: cycles + interrupt_cycles,
+        }
     }
 
     // step_with_trap_handler removed in favor of step_with_hle_handler.
@@ -341,7 +361,9 @@ impl CpuCore {
     /// Jump to an exception vector.
     pub fn jump_vector<B: AddressBus>(&mut self, bus: &mut B, vector: u32) {
         let addr = (vector << 2).wrapping_add(self.vbr);
+        self.timing_facts.vectored = true;
         self.pc = self.read_32(bus, addr);
+        self.prefetch_fill(bus);
     }
 
     /// Branch with 8-bit displacement.
@@ -364,24 +386,32 @@ impl CpuCore {
 
     // ========== Interrupt Handling ==========
 
-    /// Check and service pending interrupts.
-    fn check_and_service_interrupts<B: AddressBus>(&mut self, bus: &mut B) {
+    /// Check and service pending interrupts. Returns the cycles taken (0 if none).
+    fn check_and_service_interrupts<B: AddressBus>(&mut self, bus: &mut B) -> i32 {
+        // Background debug mode ignores interrupts.
+        if self.is_in_background() {
+            return 0;
+        }
+
         // NMI (level 7) always triggers, others compare to mask
         let mask_level = (self.int_mask >> 8) & 7;
         let int_level = self.int_level & 7;
 
         if int_level == 7 || int_level > mask_level {
-            self.service_interrupt(bus, int_level as u8);
+            let cycles = self.service_interrupt(bus, int_level as u8);
             // Clear pending interrupt level - bus.interrupt_acknowledge was called in
             // service_interrupt, so the device has had a chance to update its state.
             // We clear cpu.int_level here; the test harness will re-poll and set it
             // again in the next step if another interrupt is pending.
             self.int_level = 0;
+            cycles
+        } else {
+            0
         }
     }
 
-    /// Service an interrupt.
-    fn service_interrupt<B: AddressBus>(&mut self, bus: &mut B, level: u8) {
+    /// Service an interrupt. Returns the cycles taken.
+    fn service_interrupt<B: AddressBus>(&mut self, bus: &mut B, level: u8) -> i32 {
         // Get vector from interrupt acknowledge
         let vector = bus.interrupt_acknowledge(level);
         let vector = if vector == 0xFFFFFFFF {
@@ -405,10 +435,12 @@ impl CpuCore {
         let stacked_pc = self.pc;
         let vec_word = (vector as u16) << 2;
 
-        if self.cpu_type == super::types::CpuType::M68000 {
+        if self.is_68000() {
             // 68000: 3-word frame (PC, SR)
             self.push_32(bus, stacked_pc);
             self.push_16(bus, old_sr);
+        } else if self.is_coldfire() {
+            self.push_coldfire_frame(bus, vector, fs_coldfire::NONE, stacked_pc, old_sr);
         } else {
             // 68010+: format 0 frame: (vector<<2), PC, SR (vector word ends up at +6)
             self.push_16(bus, vec_word);
@@ -427,6 +459,9 @@ impl CpuCore {
                 | super::types::CpuType::M68EC040
                 | super::types::CpuType::M68LC040
                 | super::types::CpuType::M68040
+                | super::types::CpuType::M68EC060
+                | super::types::CpuType::M68LC060
+                | super::types::CpuType::M68060
         );
         if is_ec020_plus && self.m_flag != 0 {
             self.set_sm_flag(SFLAG_SET); // clear M => ISP active
@@ -442,8 +477,7 @@ impl CpuCore {
         // Clear stopped state
         self.stopped = 0;
 
-        // Use exception cycles
-        self.cycles_remaining -= 44; // Approximate interrupt cycles
+        self.exception_timing.interrupt(level)
     }
 
     /// Halt the CPU.
@@ -456,4 +490,15 @@ impl CpuCore {
         self.set_sr(new_sr);
         self.stopped |= STOP_LEVEL_STOP;
     }
+
+    /// Returns true while a CPU32 is in background debug mode (BGND). Interrupts do not
+    /// wake it; the debugger resumes it with [`leave_background`](Self::leave_background).
+    pub fn is_in_background(&self) -> bool {
+        self.stopped & STOP_LEVEL_BACKGROUND != 0
+    }
+
+    /// Leave background debug mode and resume execution at PC (the BDM GO command).
+    pub fn leave_background(&mut self) {
+        self.stopped &= !STOP_LEVEL_BACKGROUND;
+    }
 }
@@ -0,0 +1,148 @@
+//! ColdFire-only integer instructions.
+//!
+//! The ColdFire cores run a reduced 68000 instruction set (see `dispatch_coldfire` in
+//! the decoder for what is left out) and add a few instructions of their own:
+//!
+//!   MOV3Q #<data>,<ea>       ISA_B   1010 ddd 101 mmm rrr
+//!   MVS/MVZ <ea>,Dx          ISA_B   0111 xxx 1 Z s mmm rrr
+//!   SATS Dx                  ISA_B   0100 1100 1000 0 rrr
+//!   REMS/REMU.L <ea>,Dw:Dx   ISA_A   DIVS/DIVU.L with the remainder register not Dx
+//!   BITREV/BYTEREV/FF1 Dx    ISA_C   0000 0 ss0 1100 0 rrr
+//!   STRLDSR #<data>          ISA_C   0x40E7 0x46FC, then the new SR
+
+use crate::core::cpu::{CpuCore, VFLAG_SET};
+use crate::core::ea::AddressingMode;
+use crate::core::memory::AddressBus;
+use crate::core::types::Size;
+
+/// ColdFire instruction set revision. Each one extends the one before.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Isa {
+    A,
+    B,
+    C,
+}
+
+/// Second word of STRLDSR (a MOVE to SR from an immediate).
+pub(crate) const STRLDSR_COMMAND: u16 = 0x46FC;
+
+impl CpuCore {
+    /// MOV3Q: move a 3-bit immediate, where 0 stands for -1, as a long word.
+    pub fn exec_mov3q<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
+        let data = match (opcode >> 9) & 7 {
+            0 => 0xFFFF_FFFF,
+            d => d as u32,
+        };
+        let mode = AddressingMode::decode(((opcode >> 3) & 7) as u8, (opcode & 7) as u8).unwrap();
+        self.write_ea(bus, mode, Size::Long, data);
+        self.set_logic_flags(data, Size::Long);
+        if mode.is_register_direct() { 1 } else { 2 }
+    }
+
+    /// MVS/MVZ: move a byte or word into Dx, sign- or zero-extended.
+    pub fn exec_mvs_mvz<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
+        let dx = ((opcode >> 9) & 7) as usize;
+        let zero_extend = opcode & 0x0080 != 0;
+        let size = if opcode & 0x0040 != 0 {
+            Size::Word
+        } else {
+            Size::Byte
+        };
+        let mode = AddressingMode::decode(((opcode >> 3) & 7) as u8, (opcode & 7) as u8).unwrap();
+        let value = self.read_ea(bus, mode, size);
+        let value = match (size, zero_extend) {
+            (_, true) => value & size.mask(),
+            (Size::Byte, false) => value as i8 as i32 as u32,
+            (_, false) => value as i16 as i32 as u32,
+        };
+        self.set_d(dx, value);
+        self.set_logic_flags(value, Size::Long);
+        if mode.is_register_direct() { 1 } else { 2 }
+    }
+
+    /// SATS: if V is set, replace Dx with the 32-bit limit the overflowed operation was
+    /// heading for (its sign bit is inverted by the overflow).
+    pub fn exec_sats(&mut self, dx: usize) -> i32 {
+        if self.flag_v() {
+            let limit = if self.d(dx) & 0x8000_0000 != 0 {
+                0x7FFF_FFFF
+            } else {
+                0x8000_0000
+            };
+            self.set_d(dx, limit);
+        }
+        let value = self.d(dx);
+        self.set_logic_flags(value, Size::Long);
+        1
+    }
+
+    /// REMS.L/REMU.L: divide Dx by the operand and put the remainder in Dw, leaving Dx
+    /// alone. The flags follow the quotient, as for DIVx.L.
+    pub fn exec_rem<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
+        let ext = self.read_imm_16(bus);
+        let signed = ext & 0x0800 != 0;
+        let dx = ((ext >> 12) & 7) as usize;
+        let dw = (ext & 7) as usize;
+        let mode = AddressingMode::decode(((opcode >> 3) & 7) as u8, (opcode & 7) as u8).unwrap();
+        let divisor = self.read_ea(bus, mode, Size::Long);
+        if self.faulted() {
+            return 0;
+        }
+        if divisor == 0 {
+            return self.exception_zero_divide(bus);
+        }
+
+        let dividend = self.d(dx);
+        let (quotient, remainder) = if signed {
+            let (a, b) = (dividend as i32, divisor as i32);
+            if a == i32::MIN && b == -1 {
+                self.v_flag = VFLAG_SET;
+                self.c_flag = 0;
+                return 35;
+            }
+            ((a / b) as u32, (a % b) as u32)
+        } else {
+            (dividend / divisor, dividend % divisor)
+        };
+        self.set_d(dw, remainder);
+        self.set_logic_flags(quotient, Size::Long);
+        35
+    }
+
+    /// BITREV: reverse the bits of Dx. Flags are unaffected.
+    pub fn exec_bitrev(&mut self, dx: usize) -> i32 {
+        self.set_d(dx, self.d(dx).reverse_bits());
+        1
+    }
+
+    /// BYTEREV: reverse the bytes of Dx. Flags are unaffected.
+    pub fn exec_byterev(&mut self, dx: usize) -> i32 {
+        self.set_d(dx, self.d(dx).swap_bytes());
+        1
+    }
+
+    /// FF1: replace Dx with the offset of its most significant set bit counted from bit
+    /// 31, or 32 if it is zero. N and Z follow the original operand.
+    pub fn exec_ff1(&mut self, dx: usize) -> i32 {
+        let value = self.d(dx);
+        self.set_logic_flags(value, Size::Long);
+        self.set_d(dx, value.leading_zeros());
+        1
+    }
+
+    /// STRLDSR: push the zero-extended SR on the supervisor stack and load a new SR.
+    pub fn exec_strldsr<B: AddressBus>(&mut self, bus: &mut B) -> i32 {
+        if !self.is_supervisor() {
+            return self.exception_privilege(bus);
+        }
+        let _command = self.read_imm_16(bus);
+        let sr = self.read_imm_16(bus);
+        if self.faulted() {
+            return 0;
+        }
+        let old_sr = self.get_sr() as u32;
+        self.push_32(bus, old_sr);
+        self.set_sr(sr);
+        4
+    }
+}
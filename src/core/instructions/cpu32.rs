@@ -0,0 +1,136 @@
+//! CPU32-only instructions: LPSTOP and the table lookup and interpolate family.
+//!
+//! Both live in the F-line space the CPU32 has no coprocessor interface for, and share
+//! `1111 1000 00 mmm rrr`; the command word tells them apart:
+//!
+//!   LPSTOP #<data>:          opcode 0xF800, command 0x01C0, then the new SR
+//!   TBLx <ea>,Dx:            command 0 xxx S R 0 1 ss 000000
+//!   TBLx Dym:Dyn,Dx:         command 0 xxx S R 0 0 ss 000 nnn (opcode EA mode 0, Dym)
+//!
+//! S selects signed (TBLS) or unsigned (TBLU) entries, R the unrounded forms (TBLSN,
+//! TBLUN), bit 8 the table in memory or the two registers, and ss the entry size. Dx holds the table index in bits 15-8 and the
+//! interpolation fraction in bits 7-0.
+
+use crate::core::cpu::{CpuCore, FC_CPU_SPACE, VFLAG_SET};
+use crate::core::ea::{AddressingMode, EaResult};
+use crate::core::memory::AddressBus;
+use crate::core::types::Size;
+
+/// LPSTOP command word.
+pub(crate) const LPSTOP_COMMAND: u16 = 0x01C0;
+
+/// CPU space address of the LPSTOP broadcast cycle.
+const LPSTOP_BROADCAST: u32 = 0x0003_FFFE;
+
+/// Whether `command` is a valid table lookup command word for `opcode`.
+pub(crate) fn is_table_lookup(opcode: u16, command: u16) -> bool {
+    let ea_mode = (opcode >> 3) & 7;
+    let ea_reg = opcode & 7;
+    // A byte, word or long entry size.
+    let size_ok = (command >> 6) & 3 != 3;
+    let form_ok = if command & 0x0100 == 0 {
+        ea_mode == 0 && command & 0x8238 == 0
+    } else {
+        let ea_ok = match ea_mode {
+            2 | 5 | 6 => true,
+            7 => ea_reg <= 3,
+            _ => false,
+        };
+        ea_ok && command & 0x823F == 0
+    };
+    size_ok && form_ok
+}
+
+impl CpuCore {
+    /// Execute LPSTOP: load SR, broadcast the new interrupt mask, and stop.
+    pub fn exec_lpstop<B: AddressBus>(&mut self, bus: &mut B) -> i32 {
+        if !self.is_supervisor() {
+            return self.exception_privilege(bus);
+        }
+        let _command = self.read_imm_16(bus);
+        let sr = self.read_imm_16(bus);
+        if self.faulted() {
+            return 0;
+        }
+        self.stop(sr);
+        let mask = ((self.int_mask >> 8) & 7) as u8;
+        self.bus_cycle(bus, FC_CPU_SPACE as u8, LPSTOP_BROADCAST, 2, true);
+        bus.low_power_stop(mask);
+        30
+    }
+
+    /// Execute TBLS, TBLSN, TBLU or TBLUN.
+    ///
+    /// Interpolates between entries n and n + 1 (at `<ea>` + n * size, or Dym and Dyn)
+    /// by the fraction in Dx[7:0]. The rounded forms replace the low `size` bits of Dx
+    /// with the result; the unrounded ones keep its 8 fraction bits, so a byte result
+    /// fills the low word and word and long results the whole register.
+    pub fn exec_tbl<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
+        let command = self.read_imm_16(bus);
+        let dx = ((command >> 12) & 7) as usize;
+        let signed = command & 0x0800 != 0;
+        let rounded = command & 0x0400 == 0;
+        let registers = command & 0x0100 == 0;
+        let size = match (command >> 6) & 3 {
+            0 => Size::Byte,
+            1 => Size::Word,
+            _ => Size::Long,
+        };
+
+        let ea_mode = ((opcode >> 3) & 7) as u8;
+        let ea_reg = (opcode & 7) as u8;
+        let (y0, y1) = if registers {
+            (self.d(ea_reg as usize), self.d((command & 7) as usize))
+        } else {
+            let mode = AddressingMode::decode(ea_mode, ea_reg).unwrap();
+            let table = self.get_ea_address(bus, mode, size);
+            let entry = table.wrapping_add(((self.d(dx) >> 8) & 0xFF) * size.bytes());
+            let y0 = self.read_resolved_ea(bus, EaResult::Memory(entry), size);
+            let next = entry.wrapping_add(size.bytes());
+            let y1 = self.read_resolved_ea(bus, EaResult::Memory(next), size);
+            (y0, y1)
+        };
+        if self.faulted() {
+            return 0;
+        }
+
+        let extend = |v: u32| -> i64 {
+            let v = v & size.mask();
+            if signed && v & size.msb_mask() != 0 {
+                v as i64 - (size.mask() as i64 + 1)
+            } else {
+                v as i64
+            }
+        };
+        let (y0, y1) = (extend(y0), extend(y1));
+        let fraction = (self.d(dx) & 0xFF) as i64;
+        // Scaled by 256: the low 8 bits are the fraction.
+        let scaled = y0 * 256 + (y1 - y0) * fraction;
+
+        if rounded {
+            let result = ((scaled + 128) >> 8) as u32;
+            self.write_resolved_ea(bus, EaResult::DataReg(dx as u8), size, result);
+            self.set_logic_flags(result, size);
+        } else {
+            let result_size = if size == Size::Byte {
+                Size::Word
+            } else {
+                Size::Long
+            };
+            let result = scaled as u32;
+            self.write_resolved_ea(bus, EaResult::DataReg(dx as u8), result_size, result);
+            self.set_logic_flags(result, result_size);
+            let fits = if signed {
+                i32::try_from(scaled).is_ok()
+            } else {
+                u32::try_from(scaled).is_ok()
+            };
+            if !fits {
+                self.v_flag = VFLAG_SET;
+            }
+        }
+
+        let cycles = if registers { 26 } else { 32 };
+        cycles + if size == Size::Long { 4 } else { 0 }
+    }
+}
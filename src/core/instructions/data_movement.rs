@@ -216,6 +216,7 @@ impl CpuCore {
             }
         }
 
+        self.timing_facts.movem_registers = count as u32;
         8 + count * if size == Size::Long { 8 } else { 4 }
     }
 
@@ -278,6 +279,7 @@ impl CpuCore {
             }
         }
 
+        self.timing_facts.movem_registers = count as u32;
         12 + count * if size == Size::Long { 8 } else { 4 }
     }
 
@@ -5,7 +5,9 @@ pub mod bit_manip;
 pub mod bitfield;
 pub mod callm;
 pub mod cmp2_chk2;
+pub mod coldfire;
 pub mod compare_swap;
+pub mod cpu32;
 pub mod data_movement;
 pub mod integer_arith;
 pub mod logical;
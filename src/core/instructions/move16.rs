@@ -4,7 +4,8 @@
 // Transfers 16 bytes from source to destination, both addresses aligned to 16-byte boundary
 
 use crate::core::cpu::CpuCore;
-use crate::core::memory::AddressBus;
+use crate::core::exceptions::FaultedCycle;
+use crate::core::memory::{AddressBus, BusFaultKind};
 
 impl CpuCore {
     /// MOVE16 - 16-byte aligned block transfer (68030/68040).
@@ -37,11 +38,32 @@ impl CpuCore {
         let src_addr = src_raw;
         let dst_addr = dst_raw;
 
-        // Transfer 16 bytes (4 longwords) - use bus directly for transfers
-        for i in 0u32..4 {
-            let offset = i * 4;
-            let value = bus.read_long(src_addr + offset);
-            bus.write_long(dst_addr + offset, value);
+        // Transfer 16 bytes as one line read and one line write. The line never crosses a
+        // page, so each side is translated once.
+        let Some(src_phys) = self.move16_translate(bus, src_addr, false, 0) else {
+            return 0;
+        };
+        self.dcache_push_line(bus, src_phys);
+        let mut line = [0u32; 4];
+        for (i, value) in line.iter_mut().enumerate() {
+            match self.bus_read_cycle(bus, self.data_fc(), src_phys + (i as u32) * 4, 4) {
+                Ok(v) => *value = v,
+                Err(f) => {
+                    self.move16_bus_fault(bus, f.kind, src_addr, false, 0);
+                    return 0;
+                }
+            }
+        }
+        let Some(dst_phys) = self.move16_translate(bus, dst_addr, true, line[0]) else {
+            return 0;
+        };
+        self.dcache_push_line(bus, dst_phys);
+        for (i, value) in line.iter().enumerate() {
+            let addr = dst_phys + (i as u32) * 4;
+            if let Err(f) = self.bus_write_cycle(bus, self.data_fc(), addr, 4, *value) {
+                self.move16_bus_fault(bus, f.kind, dst_addr, true, line[0]);
+                return 0;
+            }
         }
 
         // Increment both registers by 16
@@ -51,4 +73,40 @@ impl CpuCore {
         // Condition codes are not affected
         4
     }
+
+    /// Physical address of the MOVE16 line at `addr`, or `None` after raising the MMU fault.
+    fn move16_translate<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        addr: u32,
+        write: bool,
+        data: u32,
+    ) -> Option<u32> {
+        if !(self.has_pmmu && self.pmmu_enabled) {
+            return Some(addr);
+        }
+        let supervisor = self.is_supervisor();
+        match crate::mmu::translate_address(self, bus, addr, write, supervisor, false) {
+            Ok(p) => Some(p),
+            Err(f) => {
+                self.faulted_cycle = FaultedCycle::new(16, data);
+                self.handle_mmu_fault(bus, f, write, false);
+                None
+            }
+        }
+    }
+
+    fn move16_bus_fault<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        kind: BusFaultKind,
+        addr: u32,
+        write: bool,
+        data: u32,
+    ) {
+        if matches!(kind, BusFaultKind::BusError) {
+            self.faulted_cycle = FaultedCycle::new(16, data);
+            self.trigger_bus_error(bus, addr, write, false);
+        }
+    }
 }
@@ -12,8 +12,8 @@ use crate::core::types::Size;
 
 impl CpuCore {
     /// MOVES - Move to/from address space using SFC/DFC.
-    /// In emulation, we treat this as a normal memory access since we don't
-    /// emulate separate address spaces.
+    /// The memory access is made with the SFC (read) or DFC (write) function code,
+    /// which also selects the user or supervisor translation tables.
     pub fn exec_moves<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
         // Check supervisor mode
         if self.s_flag == 0 {
@@ -51,18 +51,22 @@ impl CpuCore {
                 self.d(reg_idx)
             };
 
+            self.moves_fc = Some((self.dfc & 7) as u8);
             match size {
                 Size::Byte => self.write_8(bus, addr, value as u8),
                 Size::Word => self.write_16(bus, addr, value as u16),
                 Size::Long => self.write_32(bus, addr, value),
             }
+            self.moves_fc = None;
         } else {
             // EA to register (read)
+            self.moves_fc = Some((self.sfc & 7) as u8);
             let value = match size {
                 Size::Byte => self.read_8(bus, addr) as u32,
                 Size::Word => self.read_16(bus, addr) as u32,
                 Size::Long => self.read_32(bus, addr),
             };
+            self.moves_fc = None;
 
             if is_areg {
                 // Sign extend for address register
@@ -14,6 +14,21 @@ pub struct BusFault {
     pub address: u32,
 }
 
+/// One bus cycle, as reported to [`AddressBus::bus_cycle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BusCycle {
+    /// Address driven on the bus.
+    pub address: u32,
+    /// Function code (FC2-FC0) of the access.
+    pub fc: u8,
+    /// Clocks into the current instruction at which the cycle starts.
+    pub offset: i32,
+    /// Bytes transferred: 1, 2 or 4. CPUs with a 16-bit data bus report a long access as
+    /// two word cycles, and the 68008 every access as byte cycles.
+    pub size: u8,
+    pub write: bool,
+}
+
 pub trait AddressBus {
     fn read_byte(&mut self, address: u32) -> u8;
     fn read_word(&mut self, address: u32) -> u16;
@@ -54,14 +69,53 @@ pub trait AddressBus {
         Ok(())
     }
 
+    /// Function-code-aware variants: `fc` is the FC2-FC0 value the CPU drives for the
+    /// access (see `core::cpu::FC_*`), including CPU space (7) reached through MOVES.
+    ///
+    /// Default implementations ignore the function code and delegate to the variants above.
+    #[inline]
+    fn try_read_byte_fc(&mut self, _fc: u8, address: u32) -> Result<u8, BusFault> {
+        self.try_read_byte(address)
+    }
+    #[inline]
+    fn try_read_word_fc(&mut self, _fc: u8, address: u32) -> Result<u16, BusFault> {
+        self.try_read_word(address)
+    }
+    #[inline]
+    fn try_read_long_fc(&mut self, _fc: u8, address: u32) -> Result<u32, BusFault> {
+        self.try_read_long(address)
+    }
+    #[inline]
+    fn try_write_byte_fc(&mut self, _fc: u8, address: u32, value: u8) -> Result<(), BusFault> {
+        self.try_write_byte(address, value)
+    }
+    #[inline]
+    fn try_write_word_fc(&mut self, _fc: u8, address: u32, value: u16) -> Result<(), BusFault> {
+        self.try_write_word(address, value)
+    }
+    #[inline]
+    fn try_write_long_fc(&mut self, _fc: u8, address: u32, value: u32) -> Result<(), BusFault> {
+        self.try_write_long(address, value)
+    }
+
     fn read_immediate_word(&mut self, address: u32) -> u16 {
         self.read_word(address)
     }
     fn read_immediate_long(&mut self, address: u32) -> u32 {
         self.read_long(address)
     }
+    /// Called before each bus cycle while bus-cycle timing is enabled (see
+    /// `CpuCore::set_bus_cycle_timing`). Returns the number of wait states the device
+    /// inserts before asserting DTACK; they stall the CPU and are added to the instruction's
+    /// cycle count.
+    fn bus_cycle(&mut self, _cycle: BusCycle) -> u32 {
+        0
+    }
     fn interrupt_acknowledge(&mut self, _level: u8) -> u32 {
         0xFFFF_FFFF
     }
     fn reset_devices(&mut self) {}
+    /// CPU32 LPSTOP broadcast: the CPU has stopped with interrupt mask `int_mask`, and the
+    /// system integration module may stop its clocks until an interrupt above that level.
+    fn low_power_stop(&mut self, _int_mask: u8) {}
 }
@@ -3,14 +3,19 @@
 //! Core M68000 family CPU emulation engine.
 
 pub mod addressing;
+pub mod bus_cycles;
+pub mod cache;
+pub mod coprocessor;
 pub mod cpu;
 pub mod decode;
 pub mod ea;
+pub mod emac;
 pub mod exceptions;
 pub mod execute;
 pub mod instructions;
 pub mod interrupts;
 pub mod memory;
+pub mod prefetch;
 pub mod registers;
 pub mod status;
 pub mod timing;
@@ -0,0 +1,128 @@
+//! 68000/68010 prefetch queue.
+//!
+//! The 68000 and 68010 keep the two words following the opcode being decoded in IR and
+//! IRC. When an instruction starts, its opcode and the word after it have already been
+//! read; each extension word consumed from IRC triggers the read of the next one, and the
+//! instruction ends by prefetching the following opcode and the word after it. A change
+//! of flow discards the queue and the next two words are read from the new PC.
+//!
+//! Because of this, a write to one of the two words after the current instruction is not
+//! seen by the CPU, and the instruction stream is read in the same order as on hardware.
+//! The final prefetch is modelled after the instruction's own data cycles.
+//!
+//! A prefetch that faults is dropped and the word is read again when the instruction
+//! stream reaches it, so bus and address errors are still taken by the instruction that
+//! needs the word.
+//!
+//! Prefetch emulation is off by default (see [`CpuCore::set_prefetch_emulation`]); the
+//! instruction stream is then read word by word as it is consumed.
+
+use super::cpu::CpuCore;
+use super::memory::AddressBus;
+use super::types::CpuType;
+
+impl CpuCore {
+    /// Enable/disable the 68000/68010 prefetch queue. Other CPU types ignore it.
+    pub fn set_prefetch_emulation(&mut self, on: bool) {
+        self.prefetch_emulation = on;
+        self.flush_prefetch();
+    }
+
+    /// Discard the prefetched words, so the next fetch reads memory.
+    pub fn flush_prefetch(&mut self) {
+        self.pref_words = 0;
+    }
+
+    /// Load the queue with the words at `pc` and `pc + 2`, as after a completed
+    /// instruction (used to restore saved CPU state).
+    pub fn set_prefetch(&mut self, pc: u32, words: [u16; 2]) {
+        self.pref_addr = pc;
+        self.pref_data = ((words[0] as u32) << 16) | words[1] as u32;
+        self.pref_words = 2;
+    }
+
+    /// The prefetched words at PC and PC + 2 (IR and IRC after an instruction), if the
+    /// queue holds both.
+    pub fn prefetch(&self) -> Option<[u16; 2]> {
+        (self.prefetch_active() && self.pref_words == 2 && self.pref_addr == self.pc)
+            .then_some([(self.pref_data >> 16) as u16, self.pref_data as u16])
+    }
+
+    pub(crate) fn prefetch_active(&self) -> bool {
+        self.prefetch_emulation
+            && matches!(
+                self.cpu_type,
+                CpuType::M68000
+                    | CpuType::M68008
+                    | CpuType::M68010
+                    | CpuType::M68012
+                    | CpuType::SCC68070
+            )
+    }
+
+    /// Next instruction stream word at PC, from the queue when it holds it. Consuming a
+    /// word prefetches the one after it.
+    pub(crate) fn read_imm_16_prefetched<B: AddressBus>(&mut self, bus: &mut B) -> u16 {
+        let value = if self.pref_words > 0 && self.pref_addr == self.pc {
+            let value = (self.pref_data >> 16) as u16;
+            self.pref_data <<= 16;
+            self.pref_words -= 1;
+            self.pref_addr = self.pref_addr.wrapping_add(2);
+            value
+        } else {
+            self.pref_words = 0;
+            let addr = self.address(self.pc);
+            match self.bus_read_cycle(bus, self.program_fc(), addr, 2) {
+                Ok(v) => v as u16,
+                Err(_) => {
+                    self.trigger_bus_error(bus, addr, false, true);
+                    return 0;
+                }
+            }
+        };
+        self.pc = self.pc.wrapping_add(2);
+        if self.pref_words == 0 {
+            self.prefetch_extend(bus);
+        }
+        value
+    }
+
+    /// Fill the queue with the words at PC and PC + 2, as the end of an instruction or
+    /// exception does. Does nothing if it already holds them.
+    pub(crate) fn prefetch_fill<B: AddressBus>(&mut self, bus: &mut B) {
+        if !self.prefetch_active() || self.faulted() {
+            return;
+        }
+        if self.pref_addr != self.pc {
+            self.pref_words = 0;
+        }
+        while self.pref_words < 2 {
+            if !self.prefetch_extend(bus) {
+                break;
+            }
+        }
+    }
+
+    /// Read the word after the last queued one (or at PC if the queue is empty). Returns
+    /// false, leaving the queue as it was, if the read faults or the address is odd.
+    fn prefetch_extend<B: AddressBus>(&mut self, bus: &mut B) -> bool {
+        if self.pref_words == 0 {
+            self.pref_addr = self.pc;
+        }
+        let addr = self.pref_addr.wrapping_add(2 * self.pref_words as u32);
+        if addr & 1 != 0 {
+            return false;
+        }
+        let addr = self.address(addr);
+        let Ok(word) = self.bus_read_cycle(bus, self.program_fc(), addr, 2) else {
+            return false;
+        };
+        if self.pref_words == 0 {
+            self.pref_data = word << 16;
+        } else {
+            self.pref_data = (self.pref_data & 0xFFFF_0000) | word;
+        }
+        self.pref_words += 1;
+        true
+    }
+}
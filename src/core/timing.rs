@@ -1,6 +1,20 @@
 //! Cycle timing tables.
 //!
-//! Ported from Musashi m68kcpu.c - m68ki_exception_cycle_table
+//! Exception timing is ported from Musashi m68kcpu.c - m68ki_exception_cycle_table.
+//!
+//! Instruction timing ([`InstructionTiming`]) depends on the CPU generation. The 68000 and
+//! 68010 handlers compute their own counts, which the CPU32 shares. On the 68020 and later
+//! those are replaced by the manuals' cache-case times: every instruction word comes from
+//! the instruction cache and nothing overlaps with the neighbouring instructions. The 68030
+//! shares the 68020 tables, its integer unit being the same, and the 68060 and ColdFire
+//! cores use the 68040 ones (neither superscalar pairing nor the ColdFire pipelines are
+//! modelled). With the cache model on (see
+//! [`CpuCore::set_cache_emulation`]) fetches that miss or bypass the instruction cache
+//! add their bus time on top.
+
+use super::cpu::CpuCore;
+use super::ea::AddressingMode;
+use super::types::{CpuType, Size};
 
 /// Exception cycle counts for each CPU type.
 /// Index: 0=68000, 1=68010, 2=68020, 3=68030, 4=68040
@@ -322,3 +336,472 @@ pub const EXCEPTION_CYCLES: [[u8; 256]; 5] = [
         4, 4, 4, 4, 4, 4, 4, 4, 4, 4, 4,
     ],
 ];
+
+/// Exception processing times of one CPU model, from [`EXCEPTION_CYCLES`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExceptionTiming {
+    table: &'static [u8; 256],
+}
+
+impl Default for ExceptionTiming {
+    fn default() -> Self {
+        Self::for_cpu(CpuType::M68000)
+    }
+}
+
+impl ExceptionTiming {
+    /// Exception timing for `cpu_type`. The 68008 uses the 68000 figures, the 68012,
+    /// SCC68070 and CPU32 the 68010 ones, the 68060 and ColdFire cores the 68040 ones, and
+    /// the EC/LC variants those of the full part.
+    pub fn for_cpu(cpu_type: CpuType) -> Self {
+        let index = match cpu_type {
+            CpuType::Invalid | CpuType::M68000 | CpuType::M68008 => 0,
+            CpuType::M68010 | CpuType::M68012 | CpuType::SCC68070 | CpuType::CPU32 => 1,
+            CpuType::M68EC020 | CpuType::M68020 => 2,
+            CpuType::M68EC030 | CpuType::M68030 => 3,
+            CpuType::M68EC040
+            | CpuType::M68LC040
+            | CpuType::M68040
+            | CpuType::M68EC060
+            | CpuType::M68LC060
+            | CpuType::M68060
+            | CpuType::ColdFireIsaA
+            | CpuType::ColdFireIsaB
+            | CpuType::ColdFireIsaC => 4,
+        };
+        Self {
+            table: &EXCEPTION_CYCLES[index],
+        }
+    }
+
+    /// Cycles to process the exception through `vector`.
+    pub fn cycles(&self, vector: u32) -> i32 {
+        self.table[(vector & 0xFF) as usize] as i32
+    }
+
+    /// Cycles to acknowledge and process a level `level` interrupt. Vectored interrupts
+    /// cost the same as the autovector for their level (the user vector entries in the
+    /// table are placeholders).
+    pub fn interrupt(&self, level: u8) -> i32 {
+        self.cycles(24 + (level & 7) as u32)
+    }
+
+    /// Cycles from reset to the first instruction fetch.
+    pub fn reset(&self) -> i32 {
+        self.cycles(0)
+    }
+}
+
+/// Instruction timing of one CPU generation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InstructionTiming {
+    /// 68000, 68010 and SCC68070: the handlers' own counts.
+    #[default]
+    M68000,
+    /// 68020 cache case, asynchronous bus.
+    M68020,
+    /// 68030: the 68020 tables with a synchronous bus.
+    M68030,
+    /// 68040 integer unit, also used for the 68060.
+    M68040,
+}
+
+/// What the current instruction did that its 68020+ timing depends on beyond the opcode.
+/// Cleared before each instruction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimingFacts {
+    /// Registers transferred by MOVEM.
+    pub movem_registers: u32,
+    /// Clocks for full-format extension words (displacements and memory indirection).
+    pub index_cycles: i32,
+    /// An exception was taken; its processing time stands in for the instruction's.
+    pub vectored: bool,
+}
+
+/// Clocks to fetch an operand, by addressing mode: Dn, An, (An), (An)+, -(An), (d16,An),
+/// (d8,An,Xn), (xxx).W, (xxx).L, (d16,PC), (d8,PC,Xn), #<data> (byte or word).
+const FETCH_EA: [[i32; 12]; 2] = [
+    // 68020/68030
+    [0, 0, 4, 4, 5, 5, 7, 4, 4, 5, 7, 2],
+    // 68040
+    [0, 0, 1, 1, 1, 1, 3, 1, 1, 1, 3, 0],
+];
+
+/// Clocks to compute an address that is not read: destinations of MOVE, CLR and Scc,
+/// and the operands of LEA, PEA, JMP, JSR and MOVEM.
+const CALC_EA: [[i32; 12]; 2] = [
+    // 68020/68030
+    [0, 0, 2, 2, 3, 3, 5, 2, 4, 3, 5, 0],
+    // 68040
+    [0, 0, 0, 0, 0, 0, 2, 0, 0, 0, 2, 0],
+];
+
+fn ea_index(mode: AddressingMode) -> usize {
+    match mode {
+        AddressingMode::DataDirect(_) => 0,
+        AddressingMode::AddressDirect(_) => 1,
+        AddressingMode::AddressIndirect(_) => 2,
+        AddressingMode::PostIncrement(_) => 3,
+        AddressingMode::PreDecrement(_) => 4,
+        AddressingMode::Displacement(_) => 5,
+        AddressingMode::Index(_) => 6,
+        AddressingMode::AbsoluteShort => 7,
+        AddressingMode::AbsoluteLong => 8,
+        AddressingMode::PcDisplacement => 9,
+        AddressingMode::PcIndex => 10,
+        AddressingMode::Immediate => 11,
+    }
+}
+
+/// Operand size of the common `ss` field (bits 7-6).
+fn size_00(opcode: u16) -> Size {
+    match (opcode >> 6) & 3 {
+        0 => Size::Byte,
+        1 => Size::Word,
+        _ => Size::Long,
+    }
+}
+
+impl InstructionTiming {
+    /// Timing model for `cpu_type`.
+    pub fn for_cpu(cpu_type: CpuType) -> Self {
+        match cpu_type {
+            CpuType::Invalid
+            | CpuType::M68000
+            | CpuType::M68008
+            | CpuType::M68010
+            | CpuType::M68012
+            | CpuType::SCC68070
+            | CpuType::CPU32 => Self::M68000,
+            CpuType::M68EC020 | CpuType::M68020 => Self::M68020,
+            CpuType::M68EC030 | CpuType::M68030 => Self::M68030,
+            CpuType::M68EC040
+            | CpuType::M68LC040
+            | CpuType::M68040
+            | CpuType::M68EC060
+            | CpuType::M68LC060
+            | CpuType::M68060
+            | CpuType::ColdFireIsaA
+            | CpuType::ColdFireIsaB
+            | CpuType::ColdFireIsaC => Self::M68040,
+        }
+    }
+
+    /// Clocks an instruction fetch that misses or bypasses the instruction cache adds:
+    /// a long word read on the 68020 (three clocks) and 68030 (two), a word on the 68040.
+    pub fn fetch_miss_cycles(&self) -> i32 {
+        match self {
+            Self::M68000 => 0,
+            Self::M68020 => 3,
+            Self::M68030 | Self::M68040 => 2,
+        }
+    }
+
+    /// Clocks of a 68040 instruction cache line fill, a 2-1-1-1 burst.
+    pub fn line_fill_cycles(&self) -> i32 {
+        match self {
+            Self::M68040 => 5,
+            _ => 0,
+        }
+    }
+
+    /// Clocks a full-format extension word adds beyond the brief (d8,An,Xn) form: a word
+    /// or long base displacement, and a memory indirection with its outer displacement.
+    pub fn full_index_cycles(&self, ext: u16) -> i32 {
+        let base = match (ext >> 4) & 3 {
+            2 => self.pick(2, 0),
+            3 => self.pick(6, 1),
+            _ => 0,
+        };
+        let indirect = match ext & 3 {
+            0 if ext & 7 == 0 => 0,
+            0 | 1 => self.pick(5, 3),
+            _ => self.pick(7, 4),
+        };
+        base + indirect
+    }
+
+    /// The 68020/68030 or 68040 figure.
+    fn pick(&self, c020: i32, c040: i32) -> i32 {
+        if *self == Self::M68040 { c040 } else { c020 }
+    }
+
+    fn table(&self) -> usize {
+        (*self == Self::M68040) as usize
+    }
+
+    /// Clocks to fetch a `size` operand through `mode`.
+    fn fetch(&self, mode: AddressingMode, size: Size) -> i32 {
+        let long_immediate = mode == AddressingMode::Immediate && size == Size::Long;
+        FETCH_EA[self.table()][ea_index(mode)] + if long_immediate { self.pick(2, 0) } else { 0 }
+    }
+
+    /// Clocks to compute the address `mode` names without reading it.
+    fn calc(&self, mode: AddressingMode) -> i32 {
+        CALC_EA[self.table()][ea_index(mode)]
+    }
+
+    /// Clocks of `opcode`, just executed by `cpu`, or `None` to keep the handler's count:
+    /// always on the 68000 model, when the instruction took an exception, and for
+    /// coprocessor, MMU and cache instructions.
+    pub(crate) fn cycles(&self, cpu: &CpuCore, opcode: u16) -> Option<i32> {
+        if *self == Self::M68000 || cpu.timing_facts.vectored {
+            return None;
+        }
+        let t = |c020: i32, c040: i32| self.pick(c020, c040);
+        // Instructions without an <ea> field may have anything in its bits.
+        let mode = AddressingMode::decode(((opcode >> 3) & 7) as u8, (opcode & 7) as u8)
+            .unwrap_or(AddressingMode::DataDirect(0));
+        let in_register = mode.is_register_direct();
+        let opmode = (opcode >> 6) & 7;
+        // Read-modify-write or read of the <ea> operand: register and memory figures.
+        let operate = |reg: (i32, i32), mem: (i32, i32), size: Size| {
+            if in_register {
+                t(reg.0, reg.1)
+            } else {
+                t(mem.0, mem.1) + self.fetch(mode, size)
+            }
+        };
+
+        let cycles = match opcode >> 12 {
+            0x0 => {
+                if opcode & 0xF9FF == 0x08FC {
+                    // CAS2
+                    t(24, 20)
+                } else if opcode & 0xF9C0 == 0x08C0 && opcode & 0x0600 != 0 {
+                    // CAS
+                    t(12, 10) + self.fetch(mode, Size::Long)
+                } else if opcode & 0xFF00 == 0x0E00 {
+                    // MOVES
+                    t(5, 4) + self.calc(mode)
+                } else if opcode & 0xFFF0 == 0x06C0 {
+                    // RTM
+                    t(19, 19)
+                } else if opcode & 0xFFC0 == 0x06C0 {
+                    // CALLM
+                    t(60, 60) + self.calc(mode)
+                } else if opcode & 0x09C0 == 0x00C0 && (opcode >> 9) & 3 != 3 {
+                    // CMP2/CHK2
+                    t(18, 14) + self.calc(mode)
+                } else if opcode & 0xF138 == 0x0108 {
+                    // MOVEP: word or long, memory to register or register to memory
+                    match (opcode >> 6) & 3 {
+                        0 => t(12, 6),
+                        1 => t(18, 12),
+                        2 => t(11, 6),
+                        _ => t(17, 12),
+                    }
+                } else if opcode & 0x0100 != 0 || (opcode >> 8) & 0xF == 0x8 {
+                    // BTST/BCHG/BCLR/BSET, bit number in Dn or immediate
+                    let modify = (opcode >> 6) & 3 != 0;
+                    operate((4, 1), (4, if modify { 2 } else { 1 }), Size::Byte)
+                } else if mode == AddressingMode::Immediate {
+                    // ORI/ANDI/EORI to CCR or SR
+                    t(12, 9)
+                } else {
+                    // ORI/ANDI/SUBI/ADDI/EORI/CMPI
+                    let size = size_00(opcode);
+                    let immediate = self.fetch(AddressingMode::Immediate, size);
+                    let cmpi = (opcode >> 8) & 0xF == 0xC;
+                    immediate + operate((2, 1), (if cmpi { 2 } else { 4 }, 1), size)
+                }
+            }
+            0x1..=0x3 => {
+                let size = match opcode >> 12 {
+                    1 => Size::Byte,
+                    3 => Size::Word,
+                    _ => Size::Long,
+                };
+                let dst =
+                    AddressingMode::decode(((opcode >> 6) & 7) as u8, ((opcode >> 9) & 7) as u8)?;
+                t(2, 1) + self.fetch(mode, size) + self.calc(dst)
+            }
+            0x4 => match opcode {
+                0x4E70 => cpu.cyc_reset,
+                0x4E71 => t(2, 9),
+                0x4E72 => t(8, 13),
+                0x4E73 => t(20, 13),
+                0x4E74 => t(10, 8),
+                0x4E75 => t(10, 7),
+                0x4E76 => t(4, 1),
+                0x4E77 => t(14, 12),
+                0x4E7A => t(6, 3),
+                0x4E7B => t(12, 14),
+                _ if opcode & 0xFFF8 == 0x4808 => t(6, 2),
+                _ if opcode & 0xFFC0 == 0x4C00 => t(43, 20) + self.fetch(mode, Size::Long),
+                _ if opcode & 0xFFC0 == 0x4C40 => t(84, 44) + self.fetch(mode, Size::Long),
+                _ if opcode & 0xFFC0 == 0x40C0 => t(8, 2) + self.calc(mode),
+                _ if opcode & 0xFFC0 == 0x42C0 => t(4, 2) + self.calc(mode),
+                _ if opcode & 0xFFC0 == 0x44C0 => t(4, 2) + self.fetch(mode, Size::Word),
+                _ if opcode & 0xFFC0 == 0x46C0 => t(8, 9) + self.fetch(mode, Size::Word),
+                _ if opcode & 0xFFB8 == 0x4880 || opcode & 0xFFF8 == 0x49C0 => t(4, 1),
+                _ if opcode & 0xF1C0 == 0x41C0 => t(0, 1) + self.calc(mode),
+                _ if opcode & 0x0140 == 0x0100 => {
+                    // CHK.L (opmode 100) and CHK.W (opmode 110)
+                    let size = if opmode == 4 { Size::Long } else { Size::Word };
+                    t(8, 3) + self.fetch(mode, size)
+                }
+                _ if opcode & 0xFFF8 == 0x4840 => t(4, 1),
+                _ if opcode & 0xFFC0 == 0x4840 => t(3, 2) + self.calc(mode),
+                _ if opcode & 0xFFC0 == 0x4800 => operate((6, 6), (6, 6), Size::Byte),
+                _ if opcode & 0xFB80 == 0x4880 => {
+                    // MOVEM, register to memory or memory to register
+                    let per_register = if opcode & 0x0040 != 0 {
+                        cpu.cyc_movem_l
+                    } else {
+                        cpu.cyc_movem_w
+                    };
+                    let base = if opcode & 0x0400 != 0 {
+                        t(8, 3)
+                    } else {
+                        t(4, 2)
+                    };
+                    base + self.calc(mode)
+                        + ((cpu.timing_facts.movem_registers as i32) << per_register)
+                }
+                _ if opcode & 0xFFC0 == 0x4AC0 => {
+                    // TAS
+                    if in_register {
+                        t(4, 1)
+                    } else {
+                        t(12, 15) + self.calc(mode)
+                    }
+                }
+                _ if opcode & 0xFF00 == 0x4A00 => t(2, 1) + self.fetch(mode, size_00(opcode)),
+                _ if opcode & 0xFFF8 == 0x4E50 => t(5, 2),
+                _ if opcode & 0xFFF8 == 0x4E58 => t(6, 2),
+                _ if opcode & 0xFFF0 == 0x4E60 => t(2, 3),
+                _ if opcode & 0xFFC0 == 0x4E80 => t(2, 3) + self.calc(mode),
+                _ if opcode & 0xFFC0 == 0x4EC0 => t(2, 2) + self.calc(mode),
+                _ if opcode & 0xFF00 == 0x4200 => {
+                    // CLR
+                    if in_register {
+                        t(2, 1)
+                    } else {
+                        t(4, 1) + self.calc(mode)
+                    }
+                }
+                // NEGX/NEG/NOT
+                _ => operate((2, 1), (4, 1), size_00(opcode)),
+            },
+            0x5 if opmode & 3 == 3 => {
+                let condition = ((opcode >> 8) & 0xF) as u8;
+                let taken = cpu.test_condition(condition);
+                match mode {
+                    // TRAPcc, with a word, long or no operand
+                    AddressingMode::PcDisplacement => t(4, 1) + self.fetch(mode, Size::Word),
+                    AddressingMode::PcIndex => t(4, 1) + self.fetch(mode, Size::Long),
+                    AddressingMode::Immediate => t(4, 1),
+                    AddressingMode::AddressDirect(_) => {
+                        // DBcc: the condition ends the loop, else the counter does
+                        t(6, 3)
+                            + if taken {
+                                0
+                            } else if cpu.pc != cpu.ppc.wrapping_add(4) {
+                                cpu.cyc_dbcc_f_noexp
+                            } else {
+                                cpu.cyc_dbcc_f_exp
+                            }
+                    }
+                    _ if in_register => t(4, 1) + if taken { cpu.cyc_scc_r_true } else { 0 },
+                    _ => t(6, 1) + self.calc(mode),
+                }
+            }
+            // ADDQ/SUBQ
+            0x5 => operate((2, 1), (4, 1), size_00(opcode)),
+            0x6 => {
+                let condition = ((opcode >> 8) & 0xF) as u8;
+                match condition {
+                    0 => t(6, 2),
+                    1 => t(7, 3),
+                    _ if cpu.test_condition(condition) => t(6, 2),
+                    _ if matches!(opcode & 0xFF, 0x00 | 0xFF) => t(6, 2) + cpu.cyc_bcc_notake_w,
+                    _ => t(6, 2) + cpu.cyc_bcc_notake_b,
+                }
+            }
+            0x7 => t(2, 1),
+            0x8 | 0xC if opmode & 3 == 3 => {
+                // DIVU.W/DIVS.W, MULU.W/MULS.W
+                let (unsigned, signed) = if opcode >> 12 == 0x8 {
+                    (t(44, 27), t(56, 27))
+                } else {
+                    (t(27, 16), t(27, 16))
+                };
+                (if opmode == 3 { unsigned } else { signed }) + self.fetch(mode, Size::Word)
+            }
+            0x8 | 0xC if opcode & 0x01F0 == 0x0100 => {
+                // SBCD/ABCD, registers or -(Ax),-(Ay)
+                if opcode & 8 == 0 { t(4, 5) } else { t(16, 8) }
+            }
+            0x8 if matches!(opcode & 0x01F0, 0x0140 | 0x0180) => {
+                // PACK/UNPK
+                match (opcode & 0x0040 != 0, opcode & 8 != 0) {
+                    (true, false) => t(6, 3),
+                    (false, false) => t(8, 3),
+                    _ => t(13, 8),
+                }
+            }
+            0xC if opcode & 0x0130 == 0x0100 => t(2, 1), // EXG
+            0x9 | 0xD if opmode & 3 == 3 => {
+                // SUBA/ADDA
+                let size = if opmode == 3 { Size::Word } else { Size::Long };
+                t(2, 1) + self.fetch(mode, size)
+            }
+            0x9 | 0xD if opcode & 0x0130 == 0x0100 => {
+                // SUBX/ADDX, registers or -(Ay),-(Ax)
+                if opcode & 8 == 0 { t(2, 1) } else { t(12, 4) }
+            }
+            0xB if opmode & 3 == 3 => {
+                // CMPA
+                let size = if opmode == 3 { Size::Word } else { Size::Long };
+                t(4, 1) + self.fetch(mode, size)
+            }
+            0xB if opmode >= 4 && matches!(mode, AddressingMode::AddressDirect(_)) => t(9, 3),
+            0x8 | 0x9 | 0xB | 0xC | 0xD => {
+                // OR/SUB/CMP/EOR/AND/ADD: <ea> to Dn, or Dn to <ea>
+                let to_memory = opmode >= 4 && !in_register;
+                operate((2, 1), (if to_memory { 4 } else { 2 }, 1), size_00(opcode))
+            }
+            0xE if opcode & 0x08C0 == 0x08C0 => {
+                // BFTST/BFEXTU/BFCHG/BFEXTS/BFCLR/BFFFO/BFSET/BFINS
+                let (reg, mem) = match (opcode >> 8) & 7 {
+                    0 => (t(6, 3), t(13, 4)),
+                    1 | 3 => (t(5, 3), t(15, 5)),
+                    5 => (t(18, 6), t(28, 9)),
+                    7 => (t(10, 5), t(17, 9)),
+                    _ => (t(12, 6), t(20, 9)),
+                };
+                if in_register {
+                    reg
+                } else {
+                    mem + self.calc(mode)
+                }
+            }
+            0xE if opmode & 3 == 3 => {
+                // Memory shift or rotate by one: ASd, LSd, ROXd, ROd
+                let left = opcode & 0x0100 != 0;
+                let clocks = match (opcode >> 9) & 3 {
+                    0 if left => t(6, 2),
+                    3 => t(7, 2),
+                    _ => t(5, 2),
+                };
+                clocks + self.fetch(mode, Size::Word)
+            }
+            0xE => {
+                // Register shift or rotate: ASd, LSd, ROXd, ROd
+                let left = opcode & 0x0100 != 0;
+                let count_in_register = opcode & 0x0020 != 0;
+                match (opcode >> 3) & 3 {
+                    0 if left => t(8, 2),
+                    0 => t(6, 2),
+                    1 if count_in_register => t(6, 1),
+                    1 => t(4, 1),
+                    2 => t(12, 3),
+                    _ => t(8, 1),
+                }
+            }
+            _ => return None,
+        };
+        Some(cycles + cpu.timing_facts.index_cycles)
+    }
+}
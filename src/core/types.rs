@@ -19,6 +19,22 @@ pub enum CpuType {
     M68LC040 = 8,
     M68040 = 9,
     SCC68070 = 10,
+    M68EC060 = 11,
+    M68LC060 = 12,
+    M68060 = 13,
+    /// CPU32 core of the 68300 family (68330, 68332, 68340, ...).
+    CPU32 = 14,
+    /// ColdFire ISA_A (V2/V3 cores): one stack pointer for both modes.
+    ColdFireIsaA = 15,
+    /// ColdFire ISA_B (V4 cores): adds MOV3Q, MVS/MVZ, SATS, byte and word compares, TAS,
+    /// Bcc.L and a separate user stack pointer.
+    ColdFireIsaB = 16,
+    /// ColdFire ISA_C: ISA_B plus BITREV, BYTEREV, FF1 and STRLDSR.
+    ColdFireIsaC = 17,
+    /// 68000 with an 8-bit data bus and a 20-bit address bus (22-bit in the PLCC package).
+    M68008 = 18,
+    /// 68010 with a 31-bit address bus and an RMC pin.
+    M68012 = 19,
 }
 
 /// Trap handler with CPU and bus access for HLE.
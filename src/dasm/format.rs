@@ -66,7 +66,14 @@ pub fn disassemble(_pc: u32, opcode: u16, cpu_type: CpuType) -> (String, u32) {
         // 0x9: SUB/SUBA/SUBX
         0x9 => disasm_9xxx(opcode),
 
-        // 0xA: Line-A (A-line trap)
+        // 0xA: Line-A (A-line trap); MOV3Q and the EMAC on ColdFire
+        0xA if matches!(
+            cpu_type,
+            CpuType::ColdFireIsaA | CpuType::ColdFireIsaB | CpuType::ColdFireIsaC
+        ) =>
+        {
+            disasm_coldfire_aline(opcode)
+        }
         0xA => (format!("DC.W ${:04X}", opcode), 2), // A-line trap
 
         // 0xB: CMP/EOR
@@ -88,6 +95,24 @@ pub fn disassemble(_pc: u32, opcode: u16, cpu_type: CpuType) -> (String, u32) {
     }
 }
 
+/// Disassemble ColdFire Line-A opcodes: MOV3Q and the EMAC instructions.
+fn disasm_coldfire_aline(opcode: u16) -> (String, u32) {
+    match opcode & 0x01C0 {
+        0x0140 => {
+            let data = match (opcode >> 9) & 7 {
+                0 => -1,
+                d => d as i32,
+            };
+            (format!("MOV3Q #{},<ea>", data), 2)
+        }
+        0x0000..=0x00C0 => ("MAC/MSAC Ry,Rx,ACCn".to_string(), 4),
+        0x0100 => ("MOVE.L <ea>,ACC/MACSR/MASK".to_string(), 2),
+        0x0180 => ("MOVE.L ACC/MACSR/MASK,Rx".to_string(), 2),
+        _ if opcode == 0xA9C0 => ("MOVE.L MACSR,CCR".to_string(), 2),
+        _ => ("MOVCLR.L ACCn,Rx".to_string(), 2),
+    }
+}
+
 /// Disassemble 0xxx opcodes (ORI, ANDI, SUBI, etc.)
 fn disasm_0xxx(opcode: u16) -> (String, u32) {
     let size = ((opcode >> 6) & 3) as u8;
@@ -560,7 +585,10 @@ fn disasm_fline(opcode: u16, cpu_type: CpuType) -> (String, u32) {
             | CpuType::M68030
             | CpuType::M68EC040
             | CpuType::M68LC040
-            | CpuType::M68040 => {
+            | CpuType::M68040
+            | CpuType::M68EC060
+            | CpuType::M68LC060
+            | CpuType::M68060 => {
                 // This is an FPU instruction
                 let cmd = (opcode >> 6) & 7;
                 match cmd {
@@ -579,6 +607,11 @@ fn disasm_fline(opcode: u16, cpu_type: CpuType) -> (String, u32) {
         }
     }
 
+    // CPU32 LPSTOP and table lookups
+    if cpu_type == CpuType::CPU32 && (opcode & 0xFFC0) == 0xF800 {
+        return ("LPSTOP/TBLS/TBLU <ea>,Dx".to_string(), 4);
+    }
+
     // CP ID 0 = MMU
     if cp_id == 0 {
         return ("PMOVE/PTEST/...".to_string(), 4);
@@ -0,0 +1,334 @@
+//! Packed decimal (`.P`) conversions.
+//!
+//! A packed operand is 12 bytes: sign bits, a three-digit BCD exponent (plus
+//! a fourth digit used only on output), one integer digit and sixteen
+//! fraction digits. Both directions are computed exactly with a small
+//! arbitrary-precision integer and rounded once in the caller's environment.
+
+use std::cmp::Ordering;
+
+use super::softfloat::{FpEnv, RoundingMode, RoundingPrecision, round_pack};
+use super::types::FloatX80;
+
+/// Sign of the mantissa (SM) and of the exponent (SE).
+const PACKED_SM: u32 = 0x8000_0000;
+const PACKED_SE: u32 = 0x4000_0000;
+/// SE, both Y bits and an all-ones exponent mark an infinity or NaN.
+const PACKED_INF_NAN: u32 = 0x7FFF_0000;
+
+/// Little-endian arbitrary-precision unsigned integer.
+#[derive(Clone, PartialEq, Eq)]
+struct Big(Vec<u32>);
+
+impl Big {
+    fn from_u128(mut v: u128) -> Self {
+        let mut limbs = Vec::new();
+        while v != 0 {
+            limbs.push(v as u32);
+            v >>= 32;
+        }
+        Self(limbs)
+    }
+
+    fn pow10(n: u32) -> Self {
+        let mut r = Self::from_u128(1);
+        for _ in 0..n / 9 {
+            r.mul_small(1_000_000_000);
+        }
+        r.mul_small(10u32.pow(n % 9));
+        r
+    }
+
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn bits(&self) -> u32 {
+        match self.0.last() {
+            Some(&top) => (self.0.len() as u32) * 32 - top.leading_zeros(),
+            None => 0,
+        }
+    }
+
+    fn mul_small(&mut self, m: u32) {
+        let mut carry = 0u64;
+        for limb in &mut self.0 {
+            let v = (*limb as u64) * (m as u64) + carry;
+            *limb = v as u32;
+            carry = v >> 32;
+        }
+        if carry != 0 {
+            self.0.push(carry as u32);
+        }
+        self.trim();
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let mut r = vec![0u32; self.0.len() + other.0.len()];
+        for (i, &a) in self.0.iter().enumerate() {
+            let mut carry = 0u64;
+            for (j, &b) in other.0.iter().enumerate() {
+                let v = (a as u64) * (b as u64) + r[i + j] as u64 + carry;
+                r[i + j] = v as u32;
+                carry = v >> 32;
+            }
+            r[i + other.0.len()] = carry as u32;
+        }
+        let mut r = Self(r);
+        r.trim();
+        r
+    }
+
+    fn shl(&self, n: u32) -> Self {
+        if self.is_zero() {
+            return self.clone();
+        }
+        let (words, bits) = ((n / 32) as usize, n % 32);
+        let mut r = vec![0u32; words];
+        let mut carry = 0u32;
+        for &limb in &self.0 {
+            r.push((limb << bits) | carry);
+            carry = if bits == 0 { 0 } else { limb >> (32 - bits) };
+        }
+        r.push(carry);
+        let mut r = Self(r);
+        r.trim();
+        r
+    }
+
+    fn shr1(&mut self) {
+        let mut carry = 0u32;
+        for limb in self.0.iter_mut().rev() {
+            let next = *limb << 31;
+            *limb = (*limb >> 1) | carry;
+            carry = next;
+        }
+        self.trim();
+    }
+
+    fn sub_assign(&mut self, other: &Self) {
+        let mut borrow = 0i64;
+        for i in 0..self.0.len() {
+            let b = other.0.get(i).copied().unwrap_or(0) as i64;
+            let mut v = self.0[i] as i64 - b - borrow;
+            borrow = (v < 0) as i64;
+            if v < 0 {
+                v += 1 << 32;
+            }
+            self.0[i] = v as u32;
+        }
+        self.trim();
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+
+    /// `(self / den, self % den)`; the quotient must fit in 128 bits.
+    fn div_rem(&self, den: &Self) -> (u128, Self) {
+        let mut rem = self.clone();
+        let mut q = 0u128;
+        if self.bits() < den.bits() {
+            return (0, rem);
+        }
+        let shift = self.bits() - den.bits();
+        debug_assert!(shift < 128);
+        let mut d = den.shl(shift);
+        for i in (0..=shift).rev() {
+            if rem.cmp(&d) != Ordering::Less {
+                rem.sub_assign(&d);
+                q |= 1 << i;
+            }
+            d.shr1();
+        }
+        (q, rem)
+    }
+}
+
+/// `sig * 2^e2 * 10^e10` as a fraction `(num, den)`.
+fn fraction(sig: u64, e2: i32, e10: i32) -> (Big, Big) {
+    let mut num = Big::from_u128(sig as u128);
+    let mut den = Big::from_u128(1);
+    if e10 >= 0 {
+        num = num.mul(&Big::pow10(e10 as u32));
+    } else {
+        den = Big::pow10((-e10) as u32);
+    }
+    if e2 >= 0 {
+        num = num.shl(e2 as u32);
+    } else {
+        den = den.shl((-e2) as u32);
+    }
+    (num, den)
+}
+
+fn bcd_value(bcd: u32, digits: u32) -> u32 {
+    (0..digits)
+        .rev()
+        .fold(0, |v, i| v * 10 + ((bcd >> (i * 4)) & 0xF))
+}
+
+fn to_bcd(mut v: u64, digits: u32) -> u64 {
+    let mut bcd = 0;
+    for i in 0..digits {
+        bcd |= (v % 10) << (i * 4);
+        v /= 10;
+    }
+    bcd
+}
+
+impl FloatX80 {
+    /// Convert a packed decimal operand to extended precision.
+    ///
+    /// The conversion itself rounds in the environment's mode to extended
+    /// precision and reports inexactness as INEX1.
+    pub fn from_packed(words: [u32; 3], env: &mut FpEnv) -> Self {
+        let sign = words[0] & PACKED_SM != 0;
+        let fraction_digits = ((words[1] as u64) << 32) | words[2] as u64;
+        if words[0] & PACKED_INF_NAN == PACKED_INF_NAN {
+            return if fraction_digits == 0 {
+                Self::infinity(sign)
+            } else {
+                Self::new(0x7FFF | if sign { 0x8000 } else { 0 }, fraction_digits)
+            };
+        }
+
+        // Digits A-F are undefined on the 6888x; they simply weigh in at face value.
+        let mut mantissa = (words[0] & 0xF) as u64;
+        for i in (0..16).rev() {
+            mantissa = mantissa * 10 + ((fraction_digits >> (i * 4)) & 0xF);
+        }
+        if mantissa == 0 {
+            return Self::zero(sign);
+        }
+        let mut exp10 = bcd_value((words[0] >> 16) & 0xFFF, 3) as i32;
+        if words[0] & PACKED_SE != 0 {
+            exp10 = -exp10;
+        }
+
+        let mut conv = FpEnv {
+            precision: RoundingPrecision::Extended,
+            flags: 0,
+            ..*env
+        };
+        let (num, den) = fraction(mantissa, 0, exp10 - 16);
+        // Scale so the quotient carries 127-128 significant bits.
+        let scale = den.bits() as i32 + 127 - num.bits() as i32;
+        let (q, rem) = if scale >= 0 {
+            num.shl(scale as u32).div_rem(&den)
+        } else {
+            num.div_rem(&den.shl((-scale) as u32))
+        };
+        let lz = q.leading_zeros();
+        let sig = (q << lz) | (!rem.is_zero()) as u128;
+        let exp = Self::BIAS + (127 - lz as i32) - scale;
+        let result = round_pack(&mut conv, sign, exp, sig);
+
+        if conv.flags & FpEnv::INEX2 != 0 {
+            env.raise(FpEnv::INEX1);
+        }
+        env.raise(conv.flags & !FpEnv::INEX2);
+        result
+    }
+
+    /// Convert to a packed decimal operand with k-factor `k`.
+    ///
+    /// A positive `k` gives that many significant digits (OPERR above 17); zero
+    /// or negative `k` gives `-k` digits right of the decimal point. An
+    /// exponent needing a fourth digit also raises OPERR.
+    pub fn to_packed(self, k: i8, env: &mut FpEnv) -> [u32; 3] {
+        let sign_bit = if self.sign() { PACKED_SM } else { 0 };
+        if self.is_nan() {
+            if self.is_signaling_nan() {
+                env.raise(FpEnv::SNAN);
+            }
+            return [
+                sign_bit | PACKED_INF_NAN,
+                (self.mantissa >> 32) as u32,
+                self.mantissa as u32,
+            ];
+        }
+        if self.is_infinite() {
+            return [sign_bit | PACKED_INF_NAN, 0, 0];
+        }
+        if self.is_zero() {
+            return [sign_bit, 0, 0];
+        }
+
+        let (exp, sig) = self.normalized();
+        let e2 = exp - Self::BIAS - 63;
+
+        // ILOG = floor(log10(|x|)), estimated then corrected exactly.
+        let estimate = (exp - Self::BIAS) as f64 * std::f64::consts::LOG10_2
+            + (sig as f64 / (1u64 << 63) as f64).log10();
+        let mut ilog = estimate.floor() as i32;
+        let cmp_pow10 = |i: i32| {
+            let (num, den) = fraction(sig, e2, -i);
+            num.cmp(&den)
+        };
+        while cmp_pow10(ilog) == Ordering::Less {
+            ilog -= 1;
+        }
+        while cmp_pow10(ilog + 1) != Ordering::Less {
+            ilog += 1;
+        }
+
+        let len = if k > 0 {
+            if k > 17 {
+                env.raise(FpEnv::OPERR);
+            }
+            (k as i32).min(17)
+        } else {
+            (ilog + 1 - k as i32).clamp(1, 17)
+        };
+
+        let (num, den) = fraction(sig, e2, len - 1 - ilog);
+        let (mut digits, rem) = num.div_rem(&den);
+        if !rem.is_zero() {
+            env.raise(FpEnv::INEX2);
+            let round_up = match env.mode {
+                RoundingMode::Nearest => match rem.shl(1).cmp(&den) {
+                    Ordering::Greater => true,
+                    Ordering::Equal => digits & 1 != 0,
+                    Ordering::Less => false,
+                },
+                RoundingMode::Zero => false,
+                RoundingMode::MinusInf => self.sign(),
+                RoundingMode::PlusInf => !self.sign(),
+            };
+            if round_up {
+                digits += 1;
+            }
+        }
+        if digits == 10u128.pow(len as u32) {
+            digits /= 10;
+            ilog += 1;
+        }
+        let digits = (digits as u64) * 10u64.pow((17 - len) as u32);
+
+        let exp_bcd = to_bcd(ilog.unsigned_abs() as u64, 4) as u32;
+        if ilog.unsigned_abs() > 999 {
+            env.raise(FpEnv::OPERR);
+        }
+        let se = if ilog < 0 { PACKED_SE } else { 0 };
+        let fraction_digits = to_bcd(digits % 10u64.pow(16), 16);
+        [
+            sign_bit
+                | se
+                | ((exp_bcd & 0xFFF) << 16)
+                | (exp_bcd & 0xF000)
+                | (digits / 10u64.pow(16)) as u32,
+            (fraction_digits >> 32) as u32,
+            fraction_digits as u32,
+        ]
+    }
+}
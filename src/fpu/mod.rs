@@ -1,8 +1,12 @@
-//! FPU emulation (68881/68882/68040)
+//! FPU emulation (68881/68882/68040/68060)
 
+mod decimal;
 mod operations;
 mod registers;
+mod softfloat;
+mod transcendental;
 mod types;
 
 pub use registers::*;
+pub use softfloat::{FpEnv, RoundingMode, RoundingPrecision};
 pub use types::*;
@@ -1,20 +1,167 @@
 //! FPU operations (68040/68881-class).
 //!
-//! Note: This is currently a **minimal bring-up** focused on plumbing + a few
-//! OS-critical operations. Expect expansion over time.
+//! Arithmetic runs on the 80-bit [`FloatX80`] softfloat, rounded according to
+//! the FPCR mode and precision (or the precision forced by FSxxx/FDxxx).
 
+use super::softfloat::{FpEnv, RoundingPrecision};
+use super::types::{FloatX80, FpuFrame, FpuType};
 use crate::core::cpu::CpuCore;
+use crate::core::ea::{AddressingMode, EaResult};
+use crate::core::exceptions::vector;
 use crate::core::memory::AddressBus;
+use crate::core::types::Size;
+
+/// FMOVECR constant ROM (68881 offsets), as correctly rounded extended values.
+fn fmovecr_constant(offset: u16) -> FloatX80 {
+    let (sign_exp, mantissa) = match offset {
+        0x00 => (0x4000, 0xC90F_DAA2_2168_C235), // Pi
+        0x0B => (0x3FFD, 0x9A20_9A84_FBCF_F799), // log10(2)
+        0x0C => (0x4000, 0xADF8_5458_A2BB_4A9B), // e
+        0x0D => (0x3FFE, 0xB172_17F7_D1CF_79AC), // log_e(2) = ln(2)
+        0x0E => (0x4000, 0x935D_8DDD_AAA8_AC17), // log_e(10) = ln(10)
+        0x0F => (0x0000, 0),                     // Zero
+        0x30 => (0x3FFE, 0xB172_17F7_D1CF_79AC), // ln(2)
+        0x31 => (0x4000, 0x935D_8DDD_AAA8_AC17), // ln(10)
+        0x32 => (0x3FFF, 0x8000_0000_0000_0000), // 1.0
+        0x33 => (0x4002, 0xA000_0000_0000_0000), // 10.0
+        0x34 => (0x4005, 0xC800_0000_0000_0000), // 10^2
+        0x35 => (0x400C, 0x9C40_0000_0000_0000), // 10^4
+        0x36 => (0x4019, 0xBEBC_2000_0000_0000), // 10^8
+        0x37 => (0x4034, 0x8E1B_C9BF_0400_0000), // 10^16
+        0x38 => (0x4069, 0x9DC5_ADA8_2B70_B59E), // 10^32
+        0x39 => (0x40D3, 0xC278_1F49_FFCF_A6D5), // 10^64
+        0x3A => (0x41A8, 0x93BA_47C9_80E9_8CE0), // 10^128
+        0x3B => (0x4351, 0xAA7E_EBFB_9DF9_DE8E), // 10^256
+        0x3C => (0x46A3, 0xE319_A0AE_A60E_91C7), // 10^512
+        0x3D => (0x4D48, 0xC976_7586_8175_0C17), // 10^1024
+        0x3E => (0x5A92, 0x9E8B_3B5D_C53D_5DE5), // 10^2048
+        0x3F => (0x7525, 0xC460_5202_8A20_979B), // 10^4096
+        _ => (0x0000, 0),                        // Unknown constant, return 0
+    };
+    FloatX80::new(sign_exp, mantissa)
+}
+
+/// Split an arithmetic opmode into its base operation and any precision
+/// forced by the 68040 FSxxx/FDxxx forms. `None` for unassigned opmodes.
+fn decode_arith_opmode(opmode: u16) -> Option<(u16, Option<RoundingPrecision>)> {
+    if opmode & 0x40 != 0 {
+        let precision = if opmode & 0x04 != 0 {
+            RoundingPrecision::Double
+        } else {
+            RoundingPrecision::Single
+        };
+        let op = match opmode & !0x04 {
+            0x40 => 0x00, // FxMOVE
+            0x41 => 0x04, // FxSQRT
+            0x58 => 0x18, // FxABS
+            0x5A => 0x1A, // FxNEG
+            0x60 => 0x20, // FxDIV
+            0x62 => 0x22, // FxADD
+            0x63 => 0x23, // FxMUL
+            0x68 => 0x28, // FxSUB
+            _ => return None,
+        };
+        return Some((op, Some(precision)));
+    }
+    match opmode {
+        0x00..=0x04
+        | 0x06
+        | 0x08..=0x0A
+        | 0x0C..=0x12
+        | 0x14..=0x16
+        | 0x18..=0x1A
+        | 0x1C..=0x28
+        | 0x30..=0x38
+        | 0x3A => Some((opmode, None)),
+        _ => None,
+    }
+}
+
+/// Whether the 68060 leaves the general instruction with command word `w2` to
+/// software: FMOVECR and the transcendental, FGETEXP/FGETMAN, FMOD/FREM and FSCALE
+/// opmodes.
+fn unimplemented_on_060(w2: u16) -> bool {
+    let subop = (w2 >> 13) & 0x7;
+    let src_spec = (w2 >> 10) & 0x7;
+    if !matches!(subop, 0x0 | 0x2) {
+        return false;
+    }
+    if subop == 0x2 && src_spec == 7 {
+        return true;
+    }
+    matches!(
+        w2 & 0x7F,
+        0x02 | 0x06
+            | 0x08..=0x0A
+            | 0x0C..=0x12
+            | 0x14..=0x16
+            | 0x19
+            | 0x1C..=0x1F
+            | 0x21
+            | 0x25
+            | 0x26
+            | 0x30..=0x37
+    )
+}
+
+/// Whether `w2` moves a packed decimal operand, a data type the 68060 leaves to software.
+fn packed_decimal(w2: u16) -> bool {
+    let format = (w2 >> 10) & 0x7;
+    match (w2 >> 13) & 0x7 {
+        0x2 => format == 3,
+        0x3 => matches!(format, 3 | 7),
+        _ => false,
+    }
+}
+
+// FPSR condition code byte.
+const FPCC_N: u32 = 0x0800_0000;
+const FPCC_Z: u32 = 0x0400_0000;
+const FPCC_I: u32 = 0x0200_0000;
+const FPCC_NAN: u32 = 0x0100_0000;
+
+// FPSR exception status byte, laid out like the `FpEnv` flags.
+const FPSR_EXC_MASK: u32 = 0x0000_FF00;
+
+// FPSR accrued exception byte.
+const AEXC_IOP: u32 = 0x80;
+const AEXC_OVFL: u32 = 0x40;
+const AEXC_UNFL: u32 = 0x20;
+const AEXC_DZ: u32 = 0x10;
+const AEXC_INEX: u32 = 0x08;
+
+/// Default BIU flags in the last long word of a non-NULL FSAVE frame.
+const FSAVE_BIU_FLAGS: u32 = 0x7000_0000;
+/// BIU flag marking a pending exception.
+const FSAVE_BIU_EXCEPTION_PENDING: u32 = 0x0800_0000;
+
+/// Vector for the highest-priority exception in `enabled` (FPSR EXC layout).
+fn fpu_exception_vector(enabled: u8) -> u32 {
+    if enabled & FpEnv::BSUN != 0 {
+        vector::FP_BSUN
+    } else if enabled & FpEnv::SNAN != 0 {
+        vector::FP_SIGNALING_NAN
+    } else if enabled & FpEnv::OPERR != 0 {
+        vector::FP_OPERAND_ERROR
+    } else if enabled & FpEnv::OVFL != 0 {
+        vector::FP_OVERFLOW
+    } else if enabled & FpEnv::UNFL != 0 {
+        vector::FP_UNDERFLOW
+    } else if enabled & FpEnv::DZ != 0 {
+        vector::FP_DIVIDE_BY_ZERO
+    } else {
+        vector::FP_INEXACT
+    }
+}
 
 impl CpuCore {
     /// 68040 FPU "op0" entrypoint (opcode pattern 0xF2xx in Musashi: `040fpu0`).
     ///
-    /// For now this is a stub (future: ALU ops, FMOVE FP,<ea>, FMOVEM, FScc/FBcc, etc.).
+    /// Covers the arithmetic group (register and `<ea>` sources), FMOVE to
+    /// memory, FMOVECR, FMOVEM and control register moves.
     pub fn exec_fpu_op0<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
-        use crate::core::types::CpuType;
-
-        // LC040 and EC040 don't have integrated FPUs - must trap as Line-F
-        if matches!(self.cpu_type, CpuType::M68LC040 | CpuType::M68EC040) {
+        // Without an FPU (LC040/EC040 or an FPU-less board) this must trap as Line-F
+        if !self.fpu_present() {
             return 0;
         }
 
@@ -25,147 +172,61 @@ impl CpuCore {
         let w2 = self.read_16(bus, self.pc);
         let subop = (w2 >> 13) & 0x7;
 
+        // General instructions first take any exception left pending by the previous one.
+        if matches!(subop, 0x0 | 0x2 | 0x3)
+            && let Some(cycles) = self.fpu_take_pending_exception(bus)
+        {
+            return cycles;
+        }
+
+        // The 68060 traps these before reading any extension word, for its software
+        // package to emulate.
+        if self.fpu_type == FpuType::M68060 {
+            if unimplemented_on_060(w2) {
+                return 0;
+            }
+            if packed_decimal(w2) {
+                return self.take_exception(bus, vector::FP_UNIMPLEMENTED_DATA_TYPE);
+            }
+        }
+
         match subop {
-            0x2 => {
-                // FPU ALU <ea>, FPn - includes FMOVE, FADD, FSUB, FMUL, FDIV, FCMP from memory
-                let src_fmt = (w2 >> 10) & 0x7;
+            0x0 | 0x2 => {
+                // Arithmetic: FPm,FPn (subop 0) or <ea>,FPn (subop 2)
+                let src_spec = (w2 >> 10) & 0x7;
                 let dst = ((w2 >> 7) & 7) as usize;
-                let mut opmode = w2 & 0x7f;
-                // Handle Musashi-style rounding modifiers embedded in opmode.
-                if (opmode & 0x44) == 0x44 {
-                    opmode &= !0x44;
-                } else if (opmode & 0x40) != 0 {
-                    opmode &= !0x40;
+                let opmode = w2 & 0x7f;
+
+                if subop == 0x2 && src_spec == 7 {
+                    // FMOVECR - load constant from ROM; the opmode field holds the ROM offset
+                    let _w2 = self.read_imm_16(bus);
+                    let mut env = self.fpu_env();
+                    let value = fmovecr_constant(opmode).round(&mut env);
+                    if !self.fpu_update_exceptions(env.flags) {
+                        self.fpr[dst] = value;
+                        self.fpu_set_cc(value);
+                    }
+                    return 4;
                 }
 
+                let Some((op, precision)) = decode_arith_opmode(opmode) else {
+                    return 0; // Unimplemented opmode
+                };
+
                 // Consume w2 now that we're committed.
                 let _w2 = self.read_imm_16(bus);
 
-                // Read source value based on format
-                let src_value: Option<f64> = match src_fmt {
-                    0 => {
-                        // Long integer
-                        let v = self.exec_fpu_read_ea_long(bus, opcode);
-                        if v.is_none() {
-                            return 0;
-                        }
-                        Some(v.unwrap() as f64)
+                let mut env = self.fpu_env();
+                let src = if subop == 0x2 {
+                    match self.fpu_read_source(bus, opcode, src_spec, &mut env) {
+                        Some(v) => v,
+                        None => return 0,
                     }
-                    1 => {
-                        // Single precision float
-                        let v = self.exec_fpu_read_ea_single(bus, opcode);
-                        if v.is_none() {
-                            return 0;
-                        }
-                        Some(v.unwrap() as f64)
-                    }
-                    2 => {
-                        // Extended precision float (96-bit)
-                        let v = self.exec_fpu_read_ea_extended(bus, opcode);
-                        if v.is_none() {
-                            return 0;
-                        }
-                        Some(v.unwrap())
-                    }
-                    5 => {
-                        // Double precision float
-                        let v = self.exec_fpu_read_ea_double(bus, opcode);
-                        if v.is_none() {
-                            return 0;
-                        }
-                        Some(v.unwrap())
-                    }
-                    7 => {
-                        // FMOVECR - load constant from ROM
-                        // The opmode field contains the ROM offset
-                        let rom_offset = opmode as usize;
-                        let constant = match rom_offset {
-                            0x00 => std::f64::consts::PI,      // Pi
-                            0x0B => std::f64::consts::LOG10_2, // log10(2)
-                            0x0C => std::f64::consts::E,       // e
-                            0x0D => std::f64::consts::LN_2,    // log_e(2) = ln(2)
-                            0x0E => std::f64::consts::LN_10,   // log_e(10) = ln(10)
-                            0x0F => 0.0,                       // Zero
-                            0x30 => std::f64::consts::LN_2,    // ln(2)
-                            0x31 => std::f64::consts::LN_10,   // ln(10)
-                            0x32 => 1.0,                       // 1.0
-                            0x33 => 10.0,                      // 10.0
-                            0x34 => 100.0,                     // 10^2
-                            0x35 => 1.0e4,                     // 10^4
-                            0x36 => 1.0e8,                     // 10^8
-                            0x37 => 1.0e16,                    // 10^16
-                            0x38 => 1.0e32,                    // 10^32
-                            0x39 => 1.0e64,                    // 10^64
-                            0x3A => 1.0e128,                   // 10^128
-                            0x3B => 1.0e256,                   // 10^256
-                            // Higher powers would overflow, return infinity
-                            0x3C..=0x3F => f64::INFINITY,
-                            _ => 0.0, // Unknown constant, return 0
-                        };
-                        self.fpr[dst] = constant;
-                        self.fpu_set_cc(self.fpr[dst]);
-                        return 4;
-                    }
-                    _ => return 0, // Other formats not yet implemented
+                } else {
+                    self.fpr[src_spec as usize]
                 };
 
-                let src = src_value.unwrap();
-
-                match opmode {
-                    0x00 => {
-                        // FMOVE <ea>, FPn
-                        self.fpr[dst] = src;
-                        self.fpu_set_cc(src);
-                        4
-                    }
-                    0x20 => {
-                        // FDIV <ea>, FPn
-                        if src == 0.0 {
-                            if self.fpr[dst] == 0.0 {
-                                // 0/0 = NaN, set OPERR
-                                self.fpr[dst] = f64::NAN;
-                                self.fpsr |= 0x20; // OPERR
-                            } else {
-                                // x/0 = Inf, set DZ
-                                self.fpr[dst] = if self.fpr[dst] < 0.0 {
-                                    f64::NEG_INFINITY
-                                } else {
-                                    f64::INFINITY
-                                };
-                                self.fpsr |= 0x10; // DZ
-                            }
-                        } else {
-                            self.fpr[dst] /= src;
-                        }
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x22 => {
-                        // FADD <ea>, FPn
-                        self.fpr[dst] += src;
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x23 => {
-                        // FMUL <ea>, FPn
-                        self.fpr[dst] *= src;
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x28 => {
-                        // FSUB <ea>, FPn
-                        self.fpr[dst] -= src;
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x38 => {
-                        // FCMP <ea>, FPn
-                        let diff = self.fpr[dst] - src;
-                        self.fpu_set_cc(diff);
-                        4
-                    }
-                    _ => 0, // Unimplemented opmode
-                }
+                self.fpu_arith(op, precision, src, dst, env.flags)
             }
             0x3 => {
                 // FMOVE FP, <ea> - move FP register to memory/integer register
@@ -175,379 +236,69 @@ impl CpuCore {
                 // Consume w2 now that we're committed.
                 let _w2 = self.read_imm_16(bus);
 
-                let ea = (opcode & 0x3f) as u8;
-                let ea_mode = (ea >> 3) & 7;
-                let ea_reg = (ea & 7) as usize;
-
-                match dst_fmt {
-                    0 => {
-                        // Format 0: Long integer
-                        let int_val = self.fpr[src] as i32;
-                        if ea_mode == 0 {
-                            // Dn
-                            self.set_d(ea_reg, int_val as u32);
-                        } else {
-                            // Memory - get address and write
-                            let addr = self.get_fpu_ea_address(bus, ea_mode, ea_reg);
-                            if addr == 0 && ea_mode != 0 {
-                                return 0;
-                            }
-                            self.write_32(bus, addr, int_val as u32);
-                        }
-                        4
-                    }
-                    1 => {
-                        // Format 1: Single precision float
-                        let single_val = (self.fpr[src] as f32).to_bits();
-                        if ea_mode == 0 {
-                            // Dn
-                            self.set_d(ea_reg, single_val);
+                let ea_mode = ((opcode >> 3) & 7) as u8;
+                let ea_reg = (opcode & 7) as usize;
+                let value = self.fpr[src];
+                let mut env = self.fpu_env();
+
+                // Convert first: an enabled SNAN/OPERR leaves the destination untouched.
+                let image = match dst_fmt {
+                    0 => [value.to_i32(&mut env) as u32, 0, 0],
+                    1 => [value.to_f32_bits(&mut env), 0, 0],
+                    2 => value.to_memory(),
+                    3 | 7 => {
+                        // Packed decimal with a static k-factor, or dynamic from Dn
+                        let k = if dst_fmt == 3 {
+                            w2 & 0x7F
                         } else {
-                            let addr = self.get_fpu_ea_address(bus, ea_mode, ea_reg);
-                            if addr == 0 && ea_mode != 0 {
-                                return 0;
-                            }
-                            self.write_32(bus, addr, single_val);
-                        }
-                        4
+                            (self.d(((w2 >> 4) & 7) as usize) & 0x7F) as u16
+                        };
+                        // Sign-extend the 7-bit k-factor
+                        value.to_packed(((k as u8) << 1) as i8 >> 1, &mut env)
                     }
+                    4 => [value.to_int(16, &mut env) as u32, 0, 0],
                     5 => {
-                        // Format 5: Double precision float
-                        let double_bits = self.fpr[src].to_bits();
-                        let addr = self.get_fpu_ea_address(bus, ea_mode, ea_reg);
-                        if addr == 0 && ea_mode != 0 {
-                            return 0;
-                        }
-                        let hi = (double_bits >> 32) as u32;
-                        let lo = (double_bits & 0xFFFF_FFFF) as u32;
-                        self.write_32(bus, addr, hi);
-                        self.write_32(bus, addr.wrapping_add(4), lo);
-                        4
+                        let bits = value.to_f64_bits(&mut env);
+                        [(bits >> 32) as u32, bits as u32, 0]
                     }
-                    _ => 0, // Other formats not implemented
+                    _ => [value.to_int(8, &mut env) as u32, 0, 0],
+                };
+                if self.fpu_update_exceptions(env.flags) {
+                    return 4;
                 }
-            }
-            0x0 => {
-                // FP register-to-register operations (FMOVE FPm,FPn, FADD, FSUB, FMUL, FDIV, FCMP, etc.)
-                let src = ((w2 >> 10) & 7) as usize;
-                let dst = ((w2 >> 7) & 7) as usize;
-                let opmode = w2 & 0x7f;
 
-                // Consume w2
-                let _ = self.read_imm_16(bus);
-
-                match opmode {
-                    0x00 => {
-                        // FMOVE FPm, FPn
-                        self.fpr[dst] = self.fpr[src];
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x01 => {
-                        // FINT FPm, FPn - round to integer using FPCR rounding mode
-                        let rounding_mode = (self.fpcr >> 4) & 0x3;
-                        let val = self.fpr[src];
-                        self.fpr[dst] = match rounding_mode {
-                            0 => val.round(), // RN - Round to Nearest
-                            1 => val.trunc(), // RZ - Round toward Zero
-                            2 => val.floor(), // RM - Round toward Minus Infinity
-                            3 => val.ceil(),  // RP - Round toward Plus Infinity
-                            _ => val.round(),
-                        };
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x03 => {
-                        // FINTRZ FPm, FPn - round to integer toward zero
-                        self.fpr[dst] = self.fpr[src].trunc();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x04 | 0x44 | 0x45 => {
-                        // FSQRT FPm, FPn
-                        self.fpr[dst] = self.fpr[src].sqrt();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x18 | 0x58 | 0x5C => {
-                        // FABS FPm, FPn
-                        self.fpr[dst] = self.fpr[src].abs();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x1A | 0x5A | 0x5E => {
-                        // FNEG FPm, FPn
-                        self.fpr[dst] = -self.fpr[src];
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x20 | 0x60 | 0x64 => {
-                        // FDIV FPm, FPn (0x20), with rounding variants
-                        if self.fpr[src] == 0.0 {
-                            if self.fpr[dst] == 0.0 {
-                                // 0/0 = NaN, set OPERR
-                                self.fpr[dst] = f64::NAN;
-                                self.fpsr |= 0x20; // OPERR
-                            } else {
-                                // x/0 = Inf, set DZ
-                                self.fpr[dst] = if self.fpr[dst] < 0.0 {
-                                    f64::NEG_INFINITY
-                                } else {
-                                    f64::INFINITY
-                                };
-                                self.fpsr |= 0x10; // DZ
+                let written = match dst_fmt {
+                    0 | 1 => self.fpu_write_ea(bus, ea_mode, ea_reg, Size::Long, image[0]),
+                    4 => self.fpu_write_ea(bus, ea_mode, ea_reg, Size::Word, image[0]),
+                    6 => self.fpu_write_ea(bus, ea_mode, ea_reg, Size::Byte, image[0]),
+                    2 | 3 | 7 => {
+                        // Extended (stored exactly) and packed decimal: 12 bytes
+                        match self.fpu_operand_address(bus, ea_mode, ea_reg, 12) {
+                            Some(addr) => {
+                                self.write_32(bus, addr, image[0]);
+                                self.write_32(bus, addr.wrapping_add(4), image[1]);
+                                self.write_32(bus, addr.wrapping_add(8), image[2]);
+                                true
                             }
-                        } else {
-                            self.fpr[dst] /= self.fpr[src];
-                        }
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x22 | 0x62 | 0x66 => {
-                        // FADD FPm, FPn with rounding variants
-                        self.fpr[dst] += self.fpr[src];
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x23 | 0x63 | 0x67 => {
-                        // FMUL FPm, FPn with rounding variants
-                        self.fpr[dst] *= self.fpr[src];
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x28 | 0x68 | 0x6C => {
-                        // FSUB FPm, FPn with rounding variants
-                        self.fpr[dst] -= self.fpr[src];
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x38 => {
-                        // FCMP FPm, FPn
-                        let diff = self.fpr[dst] - self.fpr[src];
-                        self.fpu_set_cc(diff);
-                        4
-                    }
-                    0x3A => {
-                        // FTST FPm - test and set condition codes (doesn't write dst)
-                        self.fpu_set_cc(self.fpr[src]);
-                        4
-                    }
-                    0x17 => {
-                        // FMOVECR - load constant from ROM
-                        // The src field contains the ROM offset
-                        let rom_offset = src;
-                        let constant = match rom_offset {
-                            0x00 => std::f64::consts::PI,      // Pi
-                            0x0B => std::f64::consts::LOG10_2, // log10(2)
-                            0x0C => std::f64::consts::E,       // e
-                            0x0D => std::f64::consts::LN_2,    // log_e(2) = ln(2)
-                            0x0E => std::f64::consts::LN_10,   // log_e(10) = ln(10)
-                            0x0F => 0.0,                       // Zero
-                            0x30 => std::f64::consts::LN_2,    // ln(2)
-                            0x31 => std::f64::consts::LN_10,   // ln(10)
-                            0x32 => 1.0,                       // 1.0
-                            0x33 => 10.0,                      // 10.0
-                            0x34 => 100.0,                     // 10^2
-                            0x35 => 1.0e4,                     // 10^4
-                            0x36 => 1.0e8,                     // 10^8
-                            0x37 => 1.0e16,                    // 10^16
-                            0x38 => 1.0e32,                    // 10^32
-                            0x39 => 1.0e64,                    // 10^64
-                            0x3A => 1.0e128,                   // 10^128
-                            0x3B => 1.0e256,                   // 10^256
-                            // Higher powers would overflow, return infinity
-                            0x3C..=0x3F => f64::INFINITY,
-                            _ => 0.0, // Unknown constant, return 0
-                        };
-                        self.fpr[dst] = constant;
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    // ========== Transcendental Functions ==========
-                    0x0E => {
-                        // FSIN FPm, FPn
-                        self.fpr[dst] = self.fpr[src].sin();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x1D => {
-                        // FCOS FPm, FPn
-                        self.fpr[dst] = self.fpr[src].cos();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x0F => {
-                        // FTAN FPm, FPn
-                        self.fpr[dst] = self.fpr[src].tan();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x0C => {
-                        // FASIN FPm, FPn
-                        self.fpr[dst] = self.fpr[src].asin();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x1C => {
-                        // FACOS FPm, FPn
-                        self.fpr[dst] = self.fpr[src].acos();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x0A => {
-                        // FATAN FPm, FPn
-                        self.fpr[dst] = self.fpr[src].atan();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x02 => {
-                        // FSINH FPm, FPn
-                        self.fpr[dst] = self.fpr[src].sinh();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x19 => {
-                        // FCOSH FPm, FPn
-                        self.fpr[dst] = self.fpr[src].cosh();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x09 => {
-                        // FTANH FPm, FPn
-                        self.fpr[dst] = self.fpr[src].tanh();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x0D => {
-                        // FATANH FPm, FPn
-                        self.fpr[dst] = self.fpr[src].atanh();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x10 => {
-                        // FETOX FPm, FPn (e^x)
-                        self.fpr[dst] = self.fpr[src].exp();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x08 => {
-                        // FETOXM1 FPm, FPn (e^x - 1)
-                        self.fpr[dst] = self.fpr[src].exp_m1();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x11 => {
-                        // FTWOTOX FPm, FPn (2^x)
-                        self.fpr[dst] = (2.0_f64).powf(self.fpr[src]);
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x12 => {
-                        // FTENTOX FPm, FPn (10^x)
-                        self.fpr[dst] = (10.0_f64).powf(self.fpr[src]);
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x14 => {
-                        // FLOGN FPm, FPn (ln(x))
-                        self.fpr[dst] = self.fpr[src].ln();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x06 => {
-                        // FLOGNP1 FPm, FPn (ln(1+x))
-                        self.fpr[dst] = self.fpr[src].ln_1p();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x15 => {
-                        // FLOG10 FPm, FPn (log10(x))
-                        self.fpr[dst] = self.fpr[src].log10();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x16 => {
-                        // FLOG2 FPm, FPn (log2(x))
-                        self.fpr[dst] = self.fpr[src].log2();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x1E => {
-                        // FGETEXP FPm, FPn - extract exponent
-                        let val = self.fpr[src];
-                        if val == 0.0 || val.is_nan() || val.is_infinite() {
-                            self.fpr[dst] = if val.is_nan() || val.is_infinite() {
-                                f64::NAN
-                            } else {
-                                0.0
-                            };
-                        } else {
-                            // IEEE 754 double: sign (1 bit) | exponent (11 bits) | mantissa (52 bits)
-                            let bits = val.to_bits();
-                            let biased_exp = ((bits >> 52) & 0x7FF) as i32;
-                            let exp = biased_exp - 1023; // Remove bias
-                            self.fpr[dst] = exp as f64;
-                        }
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x1F => {
-                        // FGETMAN FPm, FPn - extract mantissa as 1.xxx
-                        let val = self.fpr[src];
-                        if val == 0.0 {
-                            self.fpr[dst] = 0.0;
-                        } else if val.is_nan() || val.is_infinite() {
-                            self.fpr[dst] = val; // Keep special values
-                        } else {
-                            // Extract mantissa and set exponent to 0 (bias 1023)
-                            let bits = val.to_bits();
-                            let sign = bits & (1 << 63);
-                            let mantissa_bits = bits & 0x000F_FFFF_FFFF_FFFF;
-                            // Construct 1.mantissa with exponent 0 (biased 1023)
-                            let result_bits = sign | (1023_u64 << 52) | mantissa_bits;
-                            self.fpr[dst] = f64::from_bits(result_bits);
+                            None => false,
                         }
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
                     }
-                    0x21 => {
-                        // FMOD FPm, FPn
-                        self.fpr[dst] %= self.fpr[src];
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x25 => {
-                        // FREM FPm, FPn (IEEE remainder)
-                        let src_val = self.fpr[src];
-                        let dst_val = self.fpr[dst];
-                        // IEEE remainder: r = x - y*round(x/y)
-                        if src_val != 0.0 {
-                            let n = (dst_val / src_val).round();
-                            self.fpr[dst] = dst_val - src_val * n;
+                    _ => {
+                        // Double precision float
+                        match self.fpu_operand_address(bus, ea_mode, ea_reg, 8) {
+                            Some(addr) => {
+                                self.write_32(bus, addr, image[0]);
+                                self.write_32(bus, addr.wrapping_add(4), image[1]);
+                                true
+                            }
+                            None => false,
                         }
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    0x26 => {
-                        // FSCALE FPm, FPn - multiply by power of 2
-                        // dst = dst * 2^src
-                        let scale = self.fpr[src] as i32;
-                        self.fpr[dst] *= (2.0_f64).powi(scale);
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
                     }
-                    0x30..=0x37 => {
-                        // FSINCOS FPm, FPc:FPs - compute sin and cos simultaneously
-                        // Bottom 3 bits (opmode & 7) = cos destination register
-                        let cos_dst = (opmode & 7) as usize;
-                        let val = self.fpr[src];
-                        self.fpr[dst] = val.sin();
-                        self.fpr[cos_dst] = val.cos();
-                        self.fpu_set_cc(self.fpr[dst]);
-                        4
-                    }
-                    _ => 0, // Unimplemented opmode
+                };
+                if !written {
+                    return 0;
                 }
+                4
             }
             0x6 | 0x7 => {
                 // FMOVEM - move multiple FP registers to/from memory
@@ -578,42 +329,30 @@ impl CpuCore {
                     self.set_a(ea_reg, addr);
                 }
 
-                // Extended precision is 12 bytes (96 bits): 16-bit exponent + 64-bit mantissa
-                // For simplicity, we store as: 4-byte zero padding + 8-byte double
-                if direction == 0x6 {
-                    // Memory to FP registers
-                    for i in 0..8 {
-                        let bit = if mode_bits & 0x1 != 0 {
-                            1 << i
-                        } else {
-                            1 << (7 - i)
-                        };
-                        if reg_list & bit != 0 {
-                            // Skip padding, read double
-                            let _pad = self.read_32(bus, addr);
-                            let hi = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                            let lo = self.read_32(bus, addr.wrapping_add(8)) as u64;
-                            self.fpr[i] = f64::from_bits((hi << 32) | lo);
-                            addr = addr.wrapping_add(12);
-                        }
+                // Each register occupies 12 bytes in extended-precision memory format.
+                for i in 0..8 {
+                    let bit = if mode_bits & 0x1 != 0 {
+                        1 << i
+                    } else {
+                        1 << (7 - i)
+                    };
+                    if reg_list & bit == 0 {
+                        continue;
                     }
-                } else {
-                    // FP registers to memory
-                    for i in 0..8 {
-                        let bit = if mode_bits & 0x1 != 0 {
-                            1 << i
-                        } else {
-                            1 << (7 - i)
-                        };
-                        if reg_list & bit != 0 {
-                            let val = self.fpr[i].to_bits();
-                            // Write as: 4-byte zero padding + 8-byte double
-                            self.write_32(bus, addr, 0);
-                            self.write_32(bus, addr.wrapping_add(4), (val >> 32) as u32);
-                            self.write_32(bus, addr.wrapping_add(8), (val & 0xFFFF_FFFF) as u32);
-                            addr = addr.wrapping_add(12);
-                        }
+                    if direction == 0x6 {
+                        // Memory to FP registers
+                        let w0 = self.read_32(bus, addr);
+                        let hi = self.read_32(bus, addr.wrapping_add(4));
+                        let lo = self.read_32(bus, addr.wrapping_add(8));
+                        self.fpr[i] = FloatX80::from_memory(w0, hi, lo);
+                    } else {
+                        // FP registers to memory
+                        let [w0, hi, lo] = self.fpr[i].to_memory();
+                        self.write_32(bus, addr, w0);
+                        self.write_32(bus, addr.wrapping_add(4), hi);
+                        self.write_32(bus, addr.wrapping_add(8), lo);
                     }
+                    addr = addr.wrapping_add(12);
                 }
 
                 // Handle post-increment
@@ -702,44 +441,125 @@ impl CpuCore {
         }
     }
 
-    /// FBcc - FPU conditional branch.
+    /// Execute an arithmetic opmode with `src` as the source operand and FPn as destination.
     ///
-    /// Note: The PC has already been advanced past the displacement when this is called.
-    pub fn exec_fbcc(&mut self, condition: u8, disp: i32) -> i32 {
-        // Extract FPSR condition codes
-        const FPCC_N: u32 = 0x0800_0000;
-        const FPCC_Z: u32 = 0x0400_0000;
-        const FPCC_I: u32 = 0x0200_0000;
-        const FPCC_NAN: u32 = 0x0100_0000;
+    /// `src_flags` carries any exceptions raised while converting the source operand.
+    fn fpu_arith(
+        &mut self,
+        op: u16,
+        precision: Option<RoundingPrecision>,
+        src: FloatX80,
+        dst: usize,
+        src_flags: u8,
+    ) -> i32 {
+        let mut env = self.fpu_env();
+        if let Some(p) = precision {
+            env = env.with_precision(p);
+        }
+        env.raise(src_flags);
+        let env = &mut env;
+        let d = self.fpr[dst];
+        let mut cos_result = None;
+
+        let result = match op {
+            0x00 => src.round(env),        // FMOVE
+            0x01 => src.round_to_int(env), // FINT (FPCR rounding mode)
+            0x02 => src.sinh(env),         // FSINH
+            0x03 => src.trunc(env),        // FINTRZ
+            0x04 => src.sqrt(env),         // FSQRT
+            0x06 => src.lognp1(env),       // FLOGNP1
+            0x08 => src.etoxm1(env),       // FETOXM1
+            0x09 => src.tanh(env),         // FTANH
+            0x0A => src.atan(env),         // FATAN
+            0x0C => src.asin(env),         // FASIN
+            0x0D => src.atanh(env),        // FATANH
+            0x0E => src.sin(env),          // FSIN
+            0x0F => src.tan(env),          // FTAN
+            0x10 => src.etox(env),         // FETOX
+            0x11 => src.twotox(env),       // FTWOTOX
+            0x12 => src.tentox(env),       // FTENTOX
+            0x14 => src.logn(env),         // FLOGN
+            0x15 => src.log10(env),        // FLOG10
+            0x16 => src.log2(env),         // FLOG2
+            0x18 => src.abs(env),          // FABS
+            0x19 => src.cosh(env),         // FCOSH
+            0x1A => src.neg(env),          // FNEG
+            0x1C => src.acos(env),         // FACOS
+            0x1D => src.cos(env),          // FCOS
+            0x1E => src.getexp(env),       // FGETEXP
+            0x1F => src.getman(env),       // FGETMAN
+            0x20 => d.div(src, env),       // FDIV
+            0x21 | 0x25 => {
+                // FMOD (truncated quotient) / FREM (IEEE quotient)
+                let (r, quotient) = if op == 0x21 {
+                    d.fmod(src, env)
+                } else {
+                    d.frem(src, env)
+                };
+                self.fpsr = (self.fpsr & !0x00FF_0000) | ((quotient as u32) << 16);
+                r
+            }
+            0x22 => d.add(src, env), // FADD
+            0x23 => d.mul(src, env), // FMUL
+            0x24 => {
+                // FSGLDIV - single-precision mantissa, extended exponent range
+                let mut sgl = env.with_precision(RoundingPrecision::Single);
+                let r = d.div(src, &mut sgl);
+                env.raise(sgl.flags);
+                r
+            }
+            0x26 => d.scale(src, env), // FSCALE
+            0x27 => {
+                // FSGLMUL - single-precision mantissa, extended exponent range
+                let mut sgl = env.with_precision(RoundingPrecision::Single);
+                let r = d.mul(src, &mut sgl);
+                env.raise(sgl.flags);
+                r
+            }
+            0x28 => d.sub(src, env), // FSUB
+            0x30..=0x37 => {
+                // FSINCOS FPm, FPc:FPs - bottom 3 bits of opmode = cos destination
+                let (sin, cos) = src.sincos(env);
+                cos_result = Some(cos);
+                sin
+            }
+            0x38 => {
+                // FCMP - condition codes only
+                let r = d.compare(src, env);
+                if !self.fpu_update_exceptions(env.flags) {
+                    self.fpu_set_cc(r);
+                }
+                return 4;
+            }
+            0x3A => {
+                // FTST - condition codes only
+                src.test(env);
+                if !self.fpu_update_exceptions(env.flags) {
+                    self.fpu_set_cc(src);
+                }
+                return 4;
+            }
+            _ => return 0,
+        };
 
-        let n = (self.fpsr & FPCC_N) != 0;
-        let z = (self.fpsr & FPCC_Z) != 0;
-        let _i = (self.fpsr & FPCC_I) != 0;
-        let nan = (self.fpsr & FPCC_NAN) != 0;
+        if self.fpu_update_exceptions(env.flags) {
+            return 4;
+        }
+        if let Some(cos) = cos_result {
+            self.fpr[(op & 7) as usize] = cos;
+        }
+        self.fpr[dst] = result;
+        self.fpu_set_cc(result);
+        4
+    }
 
-        // Evaluate FPU condition (simplified for common cases)
-        let take_branch = match condition {
-            0x00 => false,            // FBF - never
-            0x01 => z,                // FBEQ
-            0x0E => !nan && !z,       // FBNE (ordered not equal)
-            0x0F => true,             // FBT - always
-            0x10 => false,            // FBSF
-            0x11 => z,                // FBSEQ
-            0x12 => !(nan || z || n), // FBGT
-            0x13 => z || !(nan || n), // FBGE
-            0x14 => n && !(nan || z), // FBLT
-            0x15 => z || (n && !nan), // FBLE
-            0x16 => !(nan || z),      // FBGL
-            0x17 => !nan,             // FBGLE
-            0x18 => nan,              // FBNGLE
-            0x19 => nan || z,         // FBNGL
-            0x1A => nan || !(n || z), // FBNLE
-            0x1B => nan || z || !n,   // FBNLT
-            0x1C => nan || (n && !z), // FBNGE
-            0x1D => nan || n || z,    // FBNGT
-            0x1E => !z,               // FBSNE
-            0x1F => true,             // FBST
-            _ => false,
+    /// FBcc - FPU conditional branch.
+    ///
+    /// Note: The PC has already been advanced past the displacement when this is called.
+    pub fn exec_fbcc<B: AddressBus>(&mut self, bus: &mut B, condition: u8, disp: i32) -> i32 {
+        let take_branch = match self.fpu_test_condition(bus, condition) {
+            Ok(t) => t,
+            Err(cycles) => return cycles,
         };
 
         if take_branch {
@@ -763,40 +583,9 @@ impl CpuCore {
         ea_reg: usize,
         condition: u8,
     ) -> i32 {
-        // Extract FPSR condition codes
-        const FPCC_N: u32 = 0x0800_0000;
-        const FPCC_Z: u32 = 0x0400_0000;
-        const FPCC_I: u32 = 0x0200_0000;
-        const FPCC_NAN: u32 = 0x0100_0000;
-
-        let n = (self.fpsr & FPCC_N) != 0;
-        let z = (self.fpsr & FPCC_Z) != 0;
-        let _i = (self.fpsr & FPCC_I) != 0;
-        let nan = (self.fpsr & FPCC_NAN) != 0;
-
-        // Evaluate FPU condition
-        let cond_true = match condition {
-            0x00 => false,            // SF - never
-            0x01 => z,                // SEQ
-            0x0E => !nan && !z,       // SNE (ordered not equal)
-            0x0F => true,             // ST - always
-            0x10 => false,            // SF
-            0x11 => z,                // SEQ
-            0x12 => !(nan || z || n), // SGT
-            0x13 => z || !(nan || n), // SGE
-            0x14 => n && !(nan || z), // SLT
-            0x15 => z || (n && !nan), // SLE
-            0x16 => !(nan || z),      // SGL
-            0x17 => !nan,             // SGLE
-            0x18 => nan,              // SNGLE
-            0x19 => nan || z,         // SNGL
-            0x1A => nan || !(n || z), // SNLE
-            0x1B => nan || z || !n,   // SNLT
-            0x1C => nan || (n && !z), // SNGE
-            0x1D => nan || n || z,    // SNGT
-            0x1E => !z,               // SSNE
-            0x1F => true,             // SST
-            _ => false,
+        let cond_true = match self.fpu_test_condition(bus, condition) {
+            Ok(t) => t,
+            Err(cycles) => return cycles,
         };
 
         let value = if cond_true { 0xFFu8 } else { 0x00u8 };
@@ -813,14 +602,58 @@ impl CpuCore {
         4
     }
 
+    /// FDBcc - decrement and branch unless the FPU condition holds.
+    pub fn exec_fdbcc<B: AddressBus>(&mut self, bus: &mut B, reg: usize, condition: u8) -> i32 {
+        // The displacement is relative to its own extension word.
+        let base_pc = self.pc;
+        let disp = self.read_imm_16(bus) as i16 as i32;
+
+        let cond_true = match self.fpu_test_condition(bus, condition) {
+            Ok(t) => t,
+            Err(cycles) => return cycles,
+        };
+        if cond_true {
+            return 8;
+        }
+
+        let counter = (self.d(reg) as u16).wrapping_sub(1);
+        self.set_d(reg, (self.d(reg) & 0xFFFF0000) | counter as u32);
+        if counter != 0xFFFF {
+            self.change_of_flow = true;
+            self.pc = (base_pc as i32).wrapping_add(disp) as u32;
+        }
+        8
+    }
+
+    /// FTRAPcc - trap (vector 7) on FPU condition.
+    ///
+    /// `operand_words` is the size of the optional immediate operand (0, 1 or 2).
+    pub fn exec_ftrapcc<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        condition: u8,
+        operand_words: u32,
+    ) -> i32 {
+        self.pc = self.pc.wrapping_add(operand_words * 2);
+
+        match self.fpu_test_condition(bus, condition) {
+            Ok(true) => self.take_exception(bus, vector::TRAPV),
+            Ok(false) => 4,
+            Err(cycles) => cycles,
+        }
+    }
+
     /// 68040 FPU "op1" entrypoint (opcode pattern 0xF3xx in Musashi: `040fpu1`).
     ///
-    /// Implements a minimal subset: `FSAVE <ea>` and `FRESTORE <ea>` for a NULL/IDLE frame.
+    /// Implements `FSAVE <ea>` and `FRESTORE <ea>` with the frames of the fitted FPU model.
     pub fn exec_fpu_op1<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> i32 {
         let ea_mode = ((opcode >> 3) & 7) as u8;
         let ea_reg = (opcode & 7) as usize;
         let op = ((opcode >> 6) & 3) as u8;
 
+        if !self.fpu_present() {
+            return 0;
+        }
         match op {
             0 => self.exec_fsave(bus, ea_mode, ea_reg),
             1 => self.exec_frestore(bus, ea_mode, ea_reg),
@@ -829,78 +662,86 @@ impl CpuCore {
     }
 
     fn exec_fsave<B: AddressBus>(&mut self, bus: &mut B, ea_mode: u8, ea_reg: usize) -> i32 {
-        // Musashi supports only (An)+ and -(An) here for 68040.
+        // Musashi supports only (An)+ and -(An) here.
+        let (_, len) = self.fpu_type.fsave_frame(self.fsave_frame_kind());
         match ea_mode {
             3 => {
                 // (An)+
                 let addr = self.a(ea_reg);
-                self.set_a(ea_reg, addr.wrapping_add(4));
-
-                if self.fpu_just_reset {
-                    self.write_32(bus, addr, 0);
-                } else {
-                    // Total frame size is 7 longs (28 bytes). EA increment already did +4.
-                    self.set_a(ea_reg, self.a(ea_reg).wrapping_add(6 * 4));
-                    perform_fsave(bus, self, addr, true);
-                }
+                self.set_a(ea_reg, addr.wrapping_add(len));
+                perform_fsave(bus, self, addr);
                 8
             }
             4 => {
                 // -(An)
-                let addr_hi = self.a(ea_reg).wrapping_sub(4);
-                self.set_a(ea_reg, addr_hi);
-
-                if self.fpu_just_reset {
-                    self.write_32(bus, addr_hi, 0);
-                } else {
-                    // Total frame size is 28 bytes; one predecrement already happened (-4).
-                    self.set_a(ea_reg, self.a(ea_reg).wrapping_sub(6 * 4));
-                    perform_fsave(bus, self, addr_hi, false);
-                }
+                let addr = self.a(ea_reg).wrapping_sub(len);
+                self.set_a(ea_reg, addr);
+                perform_fsave(bus, self, addr);
                 8
             }
             _ => 0,
         }
     }
 
+    /// Frame FSAVE writes now: NULL after reset, BUSY (EXCP) while an exception is
+    /// pending on the 68040/68060, IDLE otherwise.
+    fn fsave_frame_kind(&self) -> FpuFrame {
+        if self.fpu_just_reset {
+            FpuFrame::Null
+        } else if self.fpu_pending_exception.is_some()
+            && matches!(self.fpu_type, FpuType::M68040 | FpuType::M68060)
+        {
+            FpuFrame::Busy
+        } else {
+            FpuFrame::Idle
+        }
+    }
+
     fn exec_frestore<B: AddressBus>(&mut self, bus: &mut B, ea_mode: u8, ea_reg: usize) -> i32 {
-        match ea_mode {
-            2 => {
-                // (An)
-                let addr = self.a(ea_reg);
-                let header = self.read_32(bus, addr);
-                if (header & 0xFF00_0000) == 0 {
-                    self.do_frestore_null();
-                } else {
-                    self.fpu_just_reset = false;
-                }
-                8
-            }
-            3 => {
-                // (An)+
-                let addr = self.a(ea_reg);
-                self.set_a(ea_reg, addr.wrapping_add(4));
-                let header = self.read_32(bus, addr);
+        // Musashi supports only (An) and (An)+ here.
+        if !matches!(ea_mode, 2 | 3) {
+            return 0;
+        }
+        let addr = self.a(ea_reg);
+        let header = self.read_32(bus, addr);
 
-                if (header & 0xFF00_0000) == 0 {
-                    self.do_frestore_null();
-                } else {
-                    self.fpu_just_reset = false;
-
-                    // Musashi adjusts A-reg by additional bytes based on frame type.
-                    // (EA macro already did +4.)
-                    let kind = header & 0x00FF_0000;
-                    let extra = match kind {
-                        0x0018_0000 => 6 * 4,  // IDLE
-                        0x0038_0000 => 14 * 4, // UNIMP
-                        0x00B4_0000 => 45 * 4, // BUSY
-                        _ => 0,
-                    };
-                    self.set_a(ea_reg, self.a(ea_reg).wrapping_add(extra));
-                }
-                8
-            }
-            _ => 0,
+        // A frame from another FPU model (or garbage) is refused with An unchanged.
+        let Some((frame, len)) = self.fpu_type.frestore_frame(header) else {
+            return self.take_exception(bus, vector::FORMAT_ERROR);
+        };
+        if frame == FpuFrame::Null {
+            self.do_frestore_null();
+        } else {
+            self.fpu_just_reset = false;
+            self.frestore_pending_exception(bus, addr, frame, len);
+        }
+        if ea_mode == 3 {
+            self.set_a(ea_reg, addr.wrapping_add(len));
+        }
+        8
+    }
+
+    /// Re-arm an exception recorded in a restored frame: by the BIU flags in its last long
+    /// word, or on the 68060 by the frame being EXCP.
+    ///
+    /// The vector is re-derived from the FPSR EXC byte and FPCR enables.
+    fn frestore_pending_exception<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        addr: u32,
+        frame: FpuFrame,
+        len: u32,
+    ) {
+        self.fpu_pending_exception = None;
+        let pending = if self.fpu_type == FpuType::M68060 {
+            frame == FpuFrame::Busy
+        } else {
+            len > 4
+                && self.read_32(bus, addr.wrapping_add(len - 4)) & FSAVE_BIU_EXCEPTION_PENDING != 0
+        };
+        let enabled = ((self.fpsr & self.fpcr) >> 8) as u8;
+        if pending && enabled != 0 {
+            self.fpu_pending_exception = Some(fpu_exception_vector(enabled));
         }
     }
 
@@ -908,686 +749,326 @@ impl CpuCore {
         self.fpcr = 0;
         self.fpsr = 0;
         self.fpiar = 0;
-        self.fpr = [f64::NAN; 8];
+        self.fpr = [FloatX80::DEFAULT_NAN; 8];
+        self.fpu_pending_exception = None;
         self.fpu_just_reset = true;
     }
 
-    /// Set FPU condition codes based on a floating point value.
-    fn fpu_set_cc(&mut self, value: f64) {
-        const FPCC_N: u32 = 0x0800_0000;
-        const FPCC_Z: u32 = 0x0400_0000;
-        const FPCC_I: u32 = 0x0200_0000;
-        const FPCC_NAN: u32 = 0x0100_0000;
+    /// Rounding environment selected by the current FPCR.
+    #[inline]
+    fn fpu_env(&self) -> FpEnv {
+        FpEnv::from_fpcr(self.fpcr)
+    }
 
+    /// Set FPU condition codes based on a floating point value.
+    fn fpu_set_cc(&mut self, value: FloatX80) {
         self.fpsr &= !(FPCC_N | FPCC_Z | FPCC_I | FPCC_NAN);
         if value.is_nan() {
             self.fpsr |= FPCC_NAN;
-        } else if value.is_infinite() {
+            return;
+        }
+        if value.is_infinite() {
             self.fpsr |= FPCC_I;
-            if value < 0.0 {
-                self.fpsr |= FPCC_N;
-            }
-        } else if value == 0.0 {
+        } else if value.is_zero() {
             self.fpsr |= FPCC_Z;
-            // Check for -0.0 by examining sign bit
-            if value.to_bits() >> 63 != 0 {
-                self.fpsr |= FPCC_N;
-            }
-        } else if value < 0.0 {
+        }
+        // -0.0 and -Inf report N as well
+        if value.sign() {
             self.fpsr |= FPCC_N;
         }
     }
 
-    /// Get effective address for FPU memory operations.
-    fn get_fpu_ea_address<B: AddressBus>(
+    /// Evaluate an FPU conditional predicate against the FPSR condition codes.
+    fn fpu_condition(&self, condition: u8) -> bool {
+        let n = (self.fpsr & FPCC_N) != 0;
+        let z = (self.fpsr & FPCC_Z) != 0;
+        let nan = (self.fpsr & FPCC_NAN) != 0;
+
+        // 0x00-0x0F are the IEEE-aware predicates; 0x10-0x1F test the same
+        // relations but raise BSUN when the operands were unordered.
+        match condition {
+            0x00 | 0x10 => false,            // F / SF
+            0x01 | 0x11 => z,                // EQ / SEQ
+            0x02 | 0x12 => !(nan || z || n), // OGT / GT
+            0x03 | 0x13 => z || !(nan || n), // OGE / GE
+            0x04 | 0x14 => n && !(nan || z), // OLT / LT
+            0x05 | 0x15 => z || (n && !nan), // OLE / LE
+            0x06 | 0x16 => !(nan || z),      // OGL / GL
+            0x07 | 0x17 => !nan,             // OR / GLE
+            0x08 | 0x18 => nan,              // UN / NGLE
+            0x09 | 0x19 => nan || z,         // UEQ / NGL
+            0x0A | 0x1A => nan || !(n || z), // UGT / NLE
+            0x0B | 0x1B => nan || z || !n,   // UGE / NLT
+            0x0C | 0x1C => nan || (n && !z), // ULT / NGE
+            0x0D | 0x1D => nan || z || n,    // ULE / NGT
+            0x0E | 0x1E => !z,               // NE / SNE
+            0x0F | 0x1F => true,             // T / ST
+            _ => false,
+        }
+    }
+
+    /// Evaluate a conditional instruction's predicate, handling BSUN.
+    ///
+    /// `Err` carries the cycle count when an exception was taken instead.
+    fn fpu_test_condition<B: AddressBus>(
         &mut self,
         bus: &mut B,
-        ea_mode: u8,
-        ea_reg: usize,
-    ) -> u32 {
-        match ea_mode {
-            2 => self.a(ea_reg),
-            3 => {
-                // Note: caller must handle post-increment based on data size
-                self.a(ea_reg)
-            }
-            4 => {
-                // Note: caller must handle pre-decrement based on data size
-                self.a(ea_reg)
+        condition: u8,
+    ) -> Result<bool, i32> {
+        if let Some(cycles) = self.fpu_take_pending_exception(bus) {
+            return Err(cycles);
+        }
+
+        if condition & 0x10 != 0 && self.fpsr & FPCC_NAN != 0 {
+            self.fpsr |= ((FpEnv::BSUN as u32) << 8) | AEXC_IOP;
+            if self.fpcr & ((FpEnv::BSUN as u32) << 8) != 0 {
+                // Taken before the instruction completes; the handler must clear
+                // the NaN bit or BSUN enable before returning.
+                return Err(self.take_exception(bus, vector::FP_BSUN));
             }
-            5 => {
-                let disp = self.read_imm_16(bus) as i16 as i32;
-                (self.a(ea_reg) as i32).wrapping_add(disp) as u32
-            }
-            7 => match ea_reg {
-                0 => self.read_imm_16(bus) as i16 as i32 as u32,
-                1 => self.read_imm_32(bus),
-                2 => {
-                    let pc = self.pc;
-                    let disp = self.read_imm_16(bus) as i16 as i32;
-                    (pc as i32).wrapping_add(disp) as u32
-                }
-                _ => 0,
-            },
-            _ => 0,
         }
+        Ok(self.fpu_condition(condition))
     }
 
-    /// Read a 32-bit integer from FPU EA (for format 0).
-    fn exec_fpu_read_ea_long<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> Option<i32> {
-        let ea = (opcode & 0x3f) as u8;
-        let ea_mode = (ea >> 3) & 7;
-        let ea_reg = (ea & 7) as usize;
+    /// Latch the exceptions raised by an FPU operation into FPSR.
+    ///
+    /// EXC is replaced and AEXC accumulates. An exception enabled in FPCR is
+    /// left pending for the next FPU instruction. Returns true if that exception
+    /// also suppresses the destination write (SNAN, OPERR and DZ).
+    fn fpu_update_exceptions(&mut self, flags: u8) -> bool {
+        let mut aexc = 0;
+        if flags & (FpEnv::BSUN | FpEnv::SNAN | FpEnv::OPERR) != 0 {
+            aexc |= AEXC_IOP;
+        }
+        if flags & FpEnv::OVFL != 0 {
+            aexc |= AEXC_OVFL;
+        }
+        if flags & FpEnv::UNFL != 0 && flags & FpEnv::INEX2 != 0 {
+            aexc |= AEXC_UNFL;
+        }
+        if flags & FpEnv::DZ != 0 {
+            aexc |= AEXC_DZ;
+        }
+        if flags & (FpEnv::OVFL | FpEnv::INEX2 | FpEnv::INEX1) != 0 {
+            aexc |= AEXC_INEX;
+        }
+        self.fpsr = (self.fpsr & !FPSR_EXC_MASK) | ((flags as u32) << 8) | aexc;
 
-        match ea_mode {
-            0 => Some(self.d(ea_reg) as i32),
-            2 => Some(self.read_32(bus, self.a(ea_reg)) as i32),
-            3 => {
-                let a = self.a(ea_reg);
-                self.set_a(ea_reg, a.wrapping_add(4));
-                Some(self.read_32(bus, a) as i32)
-            }
-            4 => {
-                let a = self.a(ea_reg).wrapping_sub(4);
-                self.set_a(ea_reg, a);
-                Some(self.read_32(bus, a) as i32)
-            }
-            5 => {
-                let disp = self.read_imm_16(bus) as i16 as i32;
-                let addr = (self.a(ea_reg) as i32).wrapping_add(disp) as u32;
-                Some(self.read_32(bus, addr) as i32)
-            }
-            7 => match ea_reg {
-                0 => {
-                    let addr = self.read_imm_16(bus) as i16 as i32 as u32;
-                    Some(self.read_32(bus, addr) as i32)
-                }
-                1 => {
-                    let addr = self.read_imm_32(bus);
-                    Some(self.read_32(bus, addr) as i32)
-                }
-                2 => {
-                    let pc = self.pc;
-                    let disp = self.read_imm_16(bus) as i16 as i32;
-                    Some(self.read_32(bus, (pc as i32).wrapping_add(disp) as u32) as i32)
-                }
-                4 => Some(self.read_imm_32(bus) as i32),
-                _ => None,
-            },
-            _ => None,
+        let enabled = flags & (self.fpcr >> 8) as u8;
+        if enabled == 0 {
+            return false;
         }
+        self.fpu_pending_exception = Some(fpu_exception_vector(enabled));
+        enabled & (FpEnv::SNAN | FpEnv::OPERR | FpEnv::DZ) != 0
+    }
+
+    /// Take the exception left pending by an earlier FPU instruction, if any.
+    ///
+    /// The frame points at the current instruction, which is re-executed on RTE.
+    fn fpu_take_pending_exception<B: AddressBus>(&mut self, bus: &mut B) -> Option<i32> {
+        let vector = self.fpu_pending_exception.take()?;
+        Some(self.take_exception(bus, vector))
     }
 
-    /// Read a 96-bit extended precision float from FPU EA (for format 2).
-    /// Extended format: 16-bit sign+exponent, 16-bit padding, 64-bit mantissa = 12 bytes
-    fn exec_fpu_read_ea_extended<B: AddressBus>(
+    /// Get effective address for FPU memory operations.
+    fn get_fpu_ea_address<B: AddressBus>(
         &mut self,
         bus: &mut B,
-        opcode: u16,
-    ) -> Option<f64> {
-        let ea = (opcode & 0x3f) as u8;
-        let ea_mode = (ea >> 3) & 7;
-        let ea_reg = (ea & 7) as usize;
-
-        // Helper to convert 80-bit extended to f64
-        fn extended_to_f64(exp_word: u16, mantissa: u64) -> f64 {
-            let sign = (exp_word >> 15) & 1;
-            let exp = (exp_word & 0x7FFF) as i32;
-
-            if exp == 0 && mantissa == 0 {
-                return if sign != 0 { -0.0 } else { 0.0 };
-            }
-            if exp == 0x7FFF {
-                return if mantissa == 0 {
-                    if sign != 0 {
-                        f64::NEG_INFINITY
-                    } else {
-                        f64::INFINITY
-                    }
-                } else {
-                    f64::NAN
-                };
-            }
-
-            // Bias for 80-bit: 16383, for 64-bit: 1023
-            let biased_exp = exp - 16383 + 1023;
-            if biased_exp <= 0 || biased_exp >= 2047 {
-                // Overflow/underflow - simplified handling
-                return if biased_exp >= 2047 {
-                    if sign != 0 {
-                        f64::NEG_INFINITY
-                    } else {
-                        f64::INFINITY
-                    }
-                } else {
-                    0.0
-                };
-            }
-
-            // Extended has explicit integer bit, double doesn't
-            // Take top 52 bits of mantissa (after the explicit 1)
-            let frac = (mantissa << 1) >> 12;
-            let bits = ((sign as u64) << 63) | ((biased_exp as u64) << 52) | frac;
-            f64::from_bits(bits)
-        }
-
+        ea_mode: u8,
+        ea_reg: usize,
+    ) -> u32 {
         match ea_mode {
-            2 => {
-                let addr = self.a(ea_reg);
-                let exp_word = self.read_16(bus, addr);
-                // Skip 16-bit padding
-                let hi = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                let lo = self.read_32(bus, addr.wrapping_add(8)) as u64;
-                Some(extended_to_f64(exp_word, (hi << 32) | lo))
-            }
+            2 => self.a(ea_reg),
             3 => {
-                let a = self.a(ea_reg);
-                self.set_a(ea_reg, a.wrapping_add(12));
-                let exp_word = self.read_16(bus, a);
-                let hi = self.read_32(bus, a.wrapping_add(4)) as u64;
-                let lo = self.read_32(bus, a.wrapping_add(8)) as u64;
-                Some(extended_to_f64(exp_word, (hi << 32) | lo))
+                // Note: caller must handle post-increment based on data size
+                self.a(ea_reg)
             }
             4 => {
-                let a = self.a(ea_reg).wrapping_sub(12);
-                self.set_a(ea_reg, a);
-                let exp_word = self.read_16(bus, a);
-                let hi = self.read_32(bus, a.wrapping_add(4)) as u64;
-                let lo = self.read_32(bus, a.wrapping_add(8)) as u64;
-                Some(extended_to_f64(exp_word, (hi << 32) | lo))
+                // Note: caller must handle pre-decrement based on data size
+                self.a(ea_reg)
             }
             5 => {
                 let disp = self.read_imm_16(bus) as i16 as i32;
-                let addr = (self.a(ea_reg) as i32).wrapping_add(disp) as u32;
-                let exp_word = self.read_16(bus, addr);
-                let hi = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                let lo = self.read_32(bus, addr.wrapping_add(8)) as u64;
-                Some(extended_to_f64(exp_word, (hi << 32) | lo))
+                (self.a(ea_reg) as i32).wrapping_add(disp) as u32
             }
             7 => match ea_reg {
-                0 => {
-                    let addr = self.read_imm_16(bus) as i16 as i32 as u32;
-                    let exp_word = self.read_16(bus, addr);
-                    let hi = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                    let lo = self.read_32(bus, addr.wrapping_add(8)) as u64;
-                    Some(extended_to_f64(exp_word, (hi << 32) | lo))
-                }
-                1 => {
-                    let addr = self.read_imm_32(bus);
-                    let exp_word = self.read_16(bus, addr);
-                    let hi = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                    let lo = self.read_32(bus, addr.wrapping_add(8)) as u64;
-                    Some(extended_to_f64(exp_word, (hi << 32) | lo))
-                }
+                0 => self.read_imm_16(bus) as i16 as i32 as u32,
+                1 => self.read_imm_32(bus),
                 2 => {
                     let pc = self.pc;
                     let disp = self.read_imm_16(bus) as i16 as i32;
-                    let addr = (pc as i32).wrapping_add(disp) as u32;
-                    let exp_word = self.read_16(bus, addr);
-                    let hi = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                    let lo = self.read_32(bus, addr.wrapping_add(8)) as u64;
-                    Some(extended_to_f64(exp_word, (hi << 32) | lo))
+                    (pc as i32).wrapping_add(disp) as u32
                 }
-                _ => None,
+                _ => 0,
             },
-            _ => None,
+            _ => 0,
         }
     }
 
-    /// Read a 32-bit single precision float from FPU EA (for format 1).
-    fn exec_fpu_read_ea_single<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> Option<f32> {
-        let ea = (opcode & 0x3f) as u8;
-        let ea_mode = (ea >> 3) & 7;
-        let ea_reg = (ea & 7) as usize;
-
-        let bits: Option<u32> = match ea_mode {
-            0 => Some(self.d(ea_reg)),
-            2 => Some(self.read_32(bus, self.a(ea_reg))),
-            3 => {
+    /// Resolve the address of a `size`-byte FPU memory operand, applying the
+    /// (An)+ / -(An) adjustment for the full operand size.
+    ///
+    /// Returns `None` for register-direct modes.
+    fn fpu_operand_address<B: AddressBus>(
+        &mut self,
+        bus: &mut B,
+        ea_mode: u8,
+        ea_reg: usize,
+        size: u32,
+    ) -> Option<u32> {
+        match ea_mode {
+            0 | 1 => None,
+            3 | 4 => {
+                // A7 stays word aligned for byte operands.
+                let step = if size == 1 && ea_reg == 7 { 2 } else { size };
                 let a = self.a(ea_reg);
-                self.set_a(ea_reg, a.wrapping_add(4));
-                Some(self.read_32(bus, a))
-            }
-            4 => {
-                let a = self.a(ea_reg).wrapping_sub(4);
-                self.set_a(ea_reg, a);
-                Some(self.read_32(bus, a))
-            }
-            5 => {
-                let disp = self.read_imm_16(bus) as i16 as i32;
-                let addr = (self.a(ea_reg) as i32).wrapping_add(disp) as u32;
-                Some(self.read_32(bus, addr))
-            }
-            7 => match ea_reg {
-                0 => {
-                    let addr = self.read_imm_16(bus) as i16 as i32 as u32;
-                    Some(self.read_32(bus, addr))
-                }
-                1 => {
-                    let addr = self.read_imm_32(bus);
-                    Some(self.read_32(bus, addr))
-                }
-                2 => {
-                    let pc = self.pc;
-                    let disp = self.read_imm_16(bus) as i16 as i32;
-                    Some(self.read_32(bus, (pc as i32).wrapping_add(disp) as u32))
+                if ea_mode == 3 {
+                    self.set_a(ea_reg, a.wrapping_add(step));
+                    Some(a)
+                } else {
+                    let a = a.wrapping_sub(step);
+                    self.set_a(ea_reg, a);
+                    Some(a)
                 }
-                4 => Some(self.read_imm_32(bus)),
-                _ => None,
-            },
-            _ => None,
-        };
-
-        bits.map(f32::from_bits)
-    }
-
-    /// Read a 64-bit double precision float from FPU EA (for format 5).
-    fn exec_fpu_read_ea_double<B: AddressBus>(&mut self, bus: &mut B, opcode: u16) -> Option<f64> {
-        let ea = (opcode & 0x3f) as u8;
-        let ea_mode = (ea >> 3) & 7;
-        let ea_reg = (ea & 7) as usize;
-
-        let bits: Option<u64> = match ea_mode {
-            2 => {
-                let addr = self.a(ea_reg);
-                let hi = self.read_32(bus, addr) as u64;
-                let lo = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                Some((hi << 32) | lo)
-            }
-            3 => {
-                let a = self.a(ea_reg);
-                self.set_a(ea_reg, a.wrapping_add(8));
-                let hi = self.read_32(bus, a) as u64;
-                let lo = self.read_32(bus, a.wrapping_add(4)) as u64;
-                Some((hi << 32) | lo)
-            }
-            4 => {
-                let a = self.a(ea_reg).wrapping_sub(8);
-                self.set_a(ea_reg, a);
-                let hi = self.read_32(bus, a) as u64;
-                let lo = self.read_32(bus, a.wrapping_add(4)) as u64;
-                Some((hi << 32) | lo)
             }
-            5 => {
-                let disp = self.read_imm_16(bus) as i16 as i32;
-                let addr = (self.a(ea_reg) as i32).wrapping_add(disp) as u32;
-                let hi = self.read_32(bus, addr) as u64;
-                let lo = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                Some((hi << 32) | lo)
+            7 if ea_reg == 4 => {
+                // #<data>: the operand is inline; a byte sits in the low half of its word.
+                let addr = self.pc;
+                self.pc = self.pc.wrapping_add(size.max(2));
+                Some(if size == 1 {
+                    addr.wrapping_add(1)
+                } else {
+                    addr
+                })
             }
-            7 => match ea_reg {
-                0 => {
-                    let addr = self.read_imm_16(bus) as i16 as i32 as u32;
-                    let hi = self.read_32(bus, addr) as u64;
-                    let lo = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                    Some((hi << 32) | lo)
+            _ => {
+                let mode = AddressingMode::decode(ea_mode, ea_reg as u8)?;
+                match self.resolve_ea(bus, mode, Size::Long) {
+                    EaResult::Memory(addr) => Some(addr),
+                    _ => None,
                 }
-                1 => {
-                    let addr = self.read_imm_32(bus);
-                    let hi = self.read_32(bus, addr) as u64;
-                    let lo = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                    Some((hi << 32) | lo)
-                }
-                2 => {
-                    let pc = self.pc;
-                    let disp = self.read_imm_16(bus) as i16 as i32;
-                    let addr = (pc as i32).wrapping_add(disp) as u32;
-                    let hi = self.read_32(bus, addr) as u64;
-                    let lo = self.read_32(bus, addr.wrapping_add(4)) as u64;
-                    Some((hi << 32) | lo)
-                }
-                4 => {
-                    let hi = self.read_imm_32(bus) as u64;
-                    let lo = self.read_imm_32(bus) as u64;
-                    Some((hi << 32) | lo)
-                }
-                _ => None,
-            },
-            _ => None,
-        };
-
-        bits.map(f64::from_bits)
-    }
-}
-
-fn perform_fsave<B: AddressBus>(bus: &mut B, cpu: &mut CpuCore, addr: u32, inc: bool) {
-    // Generate a 68881-style "IDLE" frame as Musashi does for 68040 FSAVE.
-    // This is sufficient for many OSes that only probe save/restore behavior.
-    if inc {
-        cpu.write_32(bus, addr, 0x1F18_0000);
-        cpu.write_32(bus, addr.wrapping_add(4), 0);
-        cpu.write_32(bus, addr.wrapping_add(8), 0);
-        cpu.write_32(bus, addr.wrapping_add(12), 0);
-        cpu.write_32(bus, addr.wrapping_add(16), 0);
-        cpu.write_32(bus, addr.wrapping_add(20), 0);
-        cpu.write_32(bus, addr.wrapping_add(24), 0x7000_0000);
-    } else {
-        cpu.write_32(bus, addr, 0x7000_0000);
-        cpu.write_32(bus, addr.wrapping_sub(4), 0);
-        cpu.write_32(bus, addr.wrapping_sub(8), 0);
-        cpu.write_32(bus, addr.wrapping_sub(12), 0);
-        cpu.write_32(bus, addr.wrapping_sub(16), 0);
-        cpu.write_32(bus, addr.wrapping_sub(20), 0);
-        cpu.write_32(bus, addr.wrapping_sub(24), 0x1F18_0000);
+            }
+        }
     }
-}
 
-// TODO: These functions are scaffolding for full FPU FMOVE implementation.
-// They will be wired up when we complete FPU support.
-#[allow(dead_code)]
-impl CpuCore {
-    /// FMOVE.L <ea>, FPn - move 32-bit integer to FP register
-    fn exec_fmove_ea_long_to_fp<B: AddressBus>(
+    /// Read a byte, word or long source operand (Dn or memory), zero-extended.
+    fn fpu_read_ea<B: AddressBus>(
         &mut self,
         bus: &mut B,
-        opcode: u16,
-        w2: u16,
-    ) -> i32 {
-        let ea = (opcode & 0x3f) as u8;
-        let ea_mode = (ea >> 3) & 7;
-        let ea_reg = (ea & 7) as usize;
-
-        // dst fp reg: bits 9..7 of w2
-        let dst = ((w2 >> 7) & 7) as usize;
-
-        // Read 32-bit integer from source EA
-        let int_value: i32 = match ea_mode {
-            0 => {
-                // Dn - data register direct
-                self.d(ea_reg) as i32
-            }
-            2 => {
-                // (An)
-                let addr = self.a(ea_reg);
-                self.read_32(bus, addr) as i32
-            }
-            3 => {
-                // (An)+
-                let a = self.a(ea_reg);
-                self.set_a(ea_reg, a.wrapping_add(4));
-                self.read_32(bus, a) as i32
-            }
-            4 => {
-                // -(An)
-                let a = self.a(ea_reg).wrapping_sub(4);
-                self.set_a(ea_reg, a);
-                self.read_32(bus, a) as i32
-            }
-            5 => {
-                // (d16,An)
-                let disp = self.read_imm_16(bus) as i16 as i32;
-                let addr = (self.a(ea_reg) as i32).wrapping_add(disp) as u32;
-                self.read_32(bus, addr) as i32
-            }
-            7 => match ea_reg {
-                0 => {
-                    // (xxx).W
-                    let addr = self.read_imm_16(bus) as i16 as i32 as u32;
-                    self.read_32(bus, addr) as i32
-                }
-                1 => {
-                    // (xxx).L
-                    let addr = self.read_imm_32(bus);
-                    self.read_32(bus, addr) as i32
-                }
-                4 => {
-                    // #<data> - immediate
-                    self.read_imm_32(bus) as i32
-                }
-                _ => return 0,
-            },
-            _ => return 0,
-        };
-
-        // Convert integer to f64 and store
-        let value = int_value as f64;
-        self.fpr[dst] = value;
-
-        // Update FPSR condition codes
-        const FPCC_N: u32 = 0x0800_0000;
-        const FPCC_Z: u32 = 0x0400_0000;
-        const FPCC_NAN: u32 = 0x0100_0000;
-        self.fpsr &= !(FPCC_N | FPCC_Z | FPCC_NAN);
-        if value == 0.0 {
-            self.fpsr |= FPCC_Z;
-        }
-        if value < 0.0 {
-            self.fpsr |= FPCC_N;
+        ea_mode: u8,
+        ea_reg: usize,
+        size: Size,
+    ) -> Option<u32> {
+        if ea_mode == 0 {
+            return Some(self.d(ea_reg) & size.mask());
         }
-
-        4
+        let addr = self.fpu_operand_address(bus, ea_mode, ea_reg, size.bytes())?;
+        Some(match size {
+            Size::Byte => self.read_8(bus, addr) as u32,
+            Size::Word => self.read_16(bus, addr) as u32,
+            Size::Long => self.read_32(bus, addr),
+        })
     }
 
-    /// FMOVE.S <ea>, FPn - move 32-bit single precision float to FP register
-    fn exec_fmove_ea_single_to_fp<B: AddressBus>(
+    /// Write a byte, word or long destination operand (Dn or memory).
+    /// Returns false for an invalid EA.
+    fn fpu_write_ea<B: AddressBus>(
         &mut self,
         bus: &mut B,
-        opcode: u16,
-        w2: u16,
-    ) -> i32 {
-        let ea = (opcode & 0x3f) as u8;
-        let ea_mode = (ea >> 3) & 7;
-        let ea_reg = (ea & 7) as usize;
-
-        // dst fp reg: bits 9..7 of w2
-        let dst = ((w2 >> 7) & 7) as usize;
-
-        // Read 32-bit single precision float from source EA
-        let raw_bits: u32 = match ea_mode {
-            0 => {
-                // Dn - data register direct
-                self.d(ea_reg)
-            }
-            2 => {
-                // (An)
-                let addr = self.a(ea_reg);
-                self.read_32(bus, addr)
-            }
-            3 => {
-                // (An)+
-                let a = self.a(ea_reg);
-                self.set_a(ea_reg, a.wrapping_add(4));
-                self.read_32(bus, a)
-            }
-            4 => {
-                // -(An)
-                let a = self.a(ea_reg).wrapping_sub(4);
-                self.set_a(ea_reg, a);
-                self.read_32(bus, a)
-            }
-            5 => {
-                // (d16,An)
-                let disp = self.read_imm_16(bus) as i16 as i32;
-                let addr = (self.a(ea_reg) as i32).wrapping_add(disp) as u32;
-                self.read_32(bus, addr)
-            }
-            7 => match ea_reg {
-                0 => {
-                    // (xxx).W
-                    let addr = self.read_imm_16(bus) as i16 as i32 as u32;
-                    self.read_32(bus, addr)
-                }
-                1 => {
-                    // (xxx).L
-                    let addr = self.read_imm_32(bus);
-                    self.read_32(bus, addr)
-                }
-                2 => {
-                    // (d16,PC)
-                    let pc = self.pc;
-                    let disp = self.read_imm_16(bus) as i16 as i32;
-                    let addr = (pc as i32).wrapping_add(disp) as u32;
-                    self.read_32(bus, addr)
-                }
-                4 => {
-                    // #<data> - immediate
-                    self.read_imm_32(bus)
-                }
-                _ => return 0,
-            },
-            _ => return 0,
-        };
-
-        // Convert single precision to f64 and store
-        let single_value = f32::from_bits(raw_bits);
-        let value = single_value as f64;
-        self.fpr[dst] = value;
-
-        // Update FPSR condition codes
-        const FPCC_N: u32 = 0x0800_0000;
-        const FPCC_Z: u32 = 0x0400_0000;
-        const FPCC_NAN: u32 = 0x0100_0000;
-        self.fpsr &= !(FPCC_N | FPCC_Z | FPCC_NAN);
-        if value == 0.0 {
-            self.fpsr |= FPCC_Z;
-        }
-        if value < 0.0 {
-            self.fpsr |= FPCC_N;
+        ea_mode: u8,
+        ea_reg: usize,
+        size: Size,
+        value: u32,
+    ) -> bool {
+        if ea_mode == 0 {
+            let d = self.d(ea_reg);
+            self.set_d(ea_reg, (d & !size.mask()) | (value & size.mask()));
+            return true;
         }
-        if single_value.is_nan() {
-            self.fpsr |= FPCC_NAN;
+        let Some(addr) = self.fpu_operand_address(bus, ea_mode, ea_reg, size.bytes()) else {
+            return false;
+        };
+        match size {
+            Size::Byte => self.write_8(bus, addr, value as u8),
+            Size::Word => self.write_16(bus, addr, value as u16),
+            Size::Long => self.write_32(bus, addr, value),
         }
-
-        4
+        true
     }
 
-    fn exec_fmove_ea_double_to_fp<B: AddressBus>(
+    /// Fetch an `<ea>` source operand in the given data format, converted to extended.
+    ///
+    /// Conversion exceptions (INEX1 for packed decimal) are raised in `env`.
+    fn fpu_read_source<B: AddressBus>(
         &mut self,
         bus: &mut B,
         opcode: u16,
-        w2: u16,
-    ) -> i32 {
-        let ea = (opcode & 0x3f) as u8;
-        let ea_mode = (ea >> 3) & 7;
-        let ea_reg = (ea & 7) as usize;
-
-        // Musashi fpgen_rm_reg fields:
-        // - dst fp reg: bits 9..7
-        let dst = ((w2 >> 7) & 7) as usize;
+        format: u16,
+        env: &mut FpEnv,
+    ) -> Option<FloatX80> {
+        let ea_mode = ((opcode >> 3) & 7) as u8;
+        let ea_reg = (opcode & 7) as usize;
 
-        let addr = match ea_mode {
-            2 => self.a(ea_reg), // (An)
-            3 => {
-                // (An)+
-                let a = self.a(ea_reg);
-                self.set_a(ea_reg, a.wrapping_add(8));
-                a
+        match format {
+            0 => {
+                // Long integer
+                let v = self.fpu_read_ea(bus, ea_mode, ea_reg, Size::Long)?;
+                Some(FloatX80::from_i32(v as i32))
+            }
+            1 => {
+                // Single precision float
+                let v = self.fpu_read_ea(bus, ea_mode, ea_reg, Size::Long)?;
+                Some(FloatX80::from_f32_bits(v))
+            }
+            2 | 3 => {
+                // Extended precision float or packed decimal (96-bit memory image)
+                let addr = self.fpu_operand_address(bus, ea_mode, ea_reg, 12)?;
+                let w0 = self.read_32(bus, addr);
+                let hi = self.read_32(bus, addr.wrapping_add(4));
+                let lo = self.read_32(bus, addr.wrapping_add(8));
+                Some(if format == 2 {
+                    FloatX80::from_memory(w0, hi, lo)
+                } else {
+                    FloatX80::from_packed([w0, hi, lo], env)
+                })
             }
             4 => {
-                // -(An)
-                let a = self.a(ea_reg).wrapping_sub(8);
-                self.set_a(ea_reg, a);
-                a
+                // Word integer
+                let v = self.fpu_read_ea(bus, ea_mode, ea_reg, Size::Word)?;
+                Some(FloatX80::from_i32(v as i16 as i32))
             }
             5 => {
-                // (d16,An)
-                let disp = self.read_imm_16(bus) as i16 as i32;
-                (self.a(ea_reg) as i32).wrapping_add(disp) as u32
-            }
-            7 => match ea_reg {
-                0 => {
-                    // (xxx).W
-                    self.read_imm_16(bus) as i16 as i32 as u32
-                }
-                1 => {
-                    // (xxx).L
-                    self.read_imm_32(bus)
-                }
-                _ => return 0,
-            },
-            _ => return 0,
-        };
-
-        let bits = read_u64_be(self, bus, addr);
-        let value = f64::from_bits(bits);
-        self.fpr[dst] = value;
-
-        // Minimal FPSR condition code updates (not yet a full IEEE model):
-        // mirror Musashi's FPCC flags layout (top nybble of FPSR).
-        const FPCC_N: u32 = 0x0800_0000;
-        const FPCC_Z: u32 = 0x0400_0000;
-        const FPCC_NAN: u32 = 0x0100_0000;
-        self.fpsr &= !(FPCC_N | FPCC_Z | FPCC_NAN);
-        if value.is_nan() {
-            self.fpsr |= FPCC_NAN;
-        } else {
-            if value == 0.0 {
-                self.fpsr |= FPCC_Z;
+                // Double precision float
+                let addr = self.fpu_operand_address(bus, ea_mode, ea_reg, 8)?;
+                let hi = self.read_32(bus, addr) as u64;
+                let lo = self.read_32(bus, addr.wrapping_add(4)) as u64;
+                Some(FloatX80::from_f64_bits((hi << 32) | lo))
             }
-            if value.is_sign_negative() && value != 0.0 {
-                self.fpsr |= FPCC_N;
+            6 => {
+                // Byte integer
+                let v = self.fpu_read_ea(bus, ea_mode, ea_reg, Size::Byte)?;
+                Some(FloatX80::from_i32(v as i8 as i32))
             }
+            _ => None,
         }
-
-        4
     }
+}
 
-    fn exec_fmove_fp_to_ea_double<B: AddressBus>(
-        &mut self,
-        bus: &mut B,
-        opcode: u16,
-        w2: u16,
-    ) -> i32 {
-        let ea = (opcode & 0x3f) as u8;
-        let ea_mode = (ea >> 3) & 7;
-        let ea_reg = (ea & 7) as usize;
-
-        // w2 fields (Musashi):
-        // - src fp reg: bits 9..7
-        let src = ((w2 >> 7) & 7) as usize;
-        let value = self.fpr[src];
-        let bits = value.to_bits();
-
-        // Resolve destination address with correct 64-bit (8 byte) (An)+/-(An) semantics.
-        let addr = match ea_mode {
-            2 => self.a(ea_reg), // (An)
-            3 => {
-                // (An)+
-                let a = self.a(ea_reg);
-                self.set_a(ea_reg, a.wrapping_add(8));
-                a
-            }
-            4 => {
-                // -(An)
-                let a = self.a(ea_reg).wrapping_sub(8);
-                self.set_a(ea_reg, a);
-                a
-            }
-            5 => {
-                // (d16,An)
-                let disp = self.read_imm_16(bus) as i16 as i32;
-                (self.a(ea_reg) as i32).wrapping_add(disp) as u32
-            }
-            7 => match ea_reg {
-                0 => {
-                    // (xxx).W
-                    self.read_imm_16(bus) as i16 as i32 as u32
-                }
-                1 => {
-                    // (xxx).L
-                    self.read_imm_32(bus)
-                }
-                _ => return 0,
-            },
-            _ => return 0,
+fn perform_fsave<B: AddressBus>(bus: &mut B, cpu: &mut CpuCore, addr: u32) {
+    // Format long word and zero fill. Non-NULL 68881/68882/68040 frames end with the BIU
+    // flags, whose bit 27 records an exception still waiting to be taken.
+    let frame = cpu.fsave_frame_kind();
+    let (format, len) = cpu.fpu_type.fsave_frame(frame);
+    cpu.write_32(bus, addr, format);
+    for offset in (4..len).step_by(4) {
+        cpu.write_32(bus, addr.wrapping_add(offset), 0);
+    }
+    if frame != FpuFrame::Null && len > 4 && cpu.fpu_type != FpuType::M68060 {
+        let biu_flags = if cpu.fpu_pending_exception.is_some() {
+            FSAVE_BIU_FLAGS | FSAVE_BIU_EXCEPTION_PENDING
+        } else {
+            FSAVE_BIU_FLAGS
         };
-
-        write_u64_be(self, bus, addr, bits);
-        12
+        cpu.write_32(bus, addr.wrapping_add(len - 4), biu_flags);
     }
 }
-
-#[allow(dead_code)]
-fn write_u64_be<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, addr: u32, value: u64) {
-    let hi = (value >> 32) as u32;
-    let lo = (value & 0xFFFF_FFFF) as u32;
-    cpu.write_32(bus, addr, hi);
-    cpu.write_32(bus, addr.wrapping_add(4), lo);
-}
-
-#[allow(dead_code)]
-fn read_u64_be<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, addr: u32) -> u64 {
-    let hi = cpu.read_32(bus, addr) as u64;
-    let lo = cpu.read_32(bus, addr.wrapping_add(4)) as u64;
-    (hi << 32) | lo
-}
@@ -0,0 +1,898 @@
+//! Extended-precision (80-bit) software floating point.
+//!
+//! Closely follows Berkeley SoftFloat's `floatx80` routines (the same basis
+//! Musashi and MAME use for the 6888x), but leans on `u128` for the wide
+//! intermediate significands. Every result is rounded through an [`FpEnv`],
+//! so the FPCR rounding mode and rounding precision apply uniformly.
+
+use super::types::FloatX80;
+
+/// FPCR rounding mode (FPCR bits 5-4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    #[default]
+    Nearest,
+    Zero,
+    MinusInf,
+    PlusInf,
+}
+
+/// FPCR rounding precision (FPCR bits 7-6).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingPrecision {
+    #[default]
+    Extended,
+    Single,
+    Double,
+}
+
+/// Rounding controls plus the exception flags accumulated by an operation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FpEnv {
+    pub mode: RoundingMode,
+    pub precision: RoundingPrecision,
+    /// Raised exceptions, laid out like the FPSR EXC byte.
+    pub flags: u8,
+}
+
+impl FpEnv {
+    pub const BSUN: u8 = 0x80;
+    pub const SNAN: u8 = 0x40;
+    pub const OPERR: u8 = 0x20;
+    pub const OVFL: u8 = 0x10;
+    pub const UNFL: u8 = 0x08;
+    pub const DZ: u8 = 0x04;
+    pub const INEX2: u8 = 0x02;
+    pub const INEX1: u8 = 0x01;
+
+    /// Environment described by an FPCR value.
+    pub fn from_fpcr(fpcr: u32) -> Self {
+        let mode = match (fpcr >> 4) & 3 {
+            0 => RoundingMode::Nearest,
+            1 => RoundingMode::Zero,
+            2 => RoundingMode::MinusInf,
+            _ => RoundingMode::PlusInf,
+        };
+        let precision = match (fpcr >> 6) & 3 {
+            1 => RoundingPrecision::Single,
+            2 => RoundingPrecision::Double,
+            _ => RoundingPrecision::Extended,
+        };
+        Self {
+            mode,
+            precision,
+            flags: 0,
+        }
+    }
+
+    /// Same rounding mode, but with the precision forced (FSxxx/FDxxx opcodes).
+    pub fn with_precision(self, precision: RoundingPrecision) -> Self {
+        Self { precision, ..self }
+    }
+
+    #[inline]
+    pub fn raise(&mut self, flags: u8) {
+        self.flags |= flags;
+    }
+}
+
+/// Shift right, OR-ing every bit shifted out into bit 0.
+#[inline]
+fn shift_right_jam(a: u128, count: i32) -> u128 {
+    if count <= 0 {
+        a
+    } else if count < 128 {
+        (a >> count) | ((a << (128 - count)) != 0) as u128
+    } else {
+        (a != 0) as u128
+    }
+}
+
+/// Integer square root of a 128-bit value.
+fn isqrt128(n: u128) -> u128 {
+    if n == 0 {
+        return 0;
+    }
+    let mut x = ((n as f64).sqrt() as u128).max(1);
+    // One Newton step takes the f64 estimate to within a unit; then settle.
+    x = (x + n / x) / 2;
+    while x.checked_mul(x).is_none_or(|sq| sq > n) {
+        x -= 1;
+    }
+    while (x + 1).checked_mul(x + 1).is_some_and(|sq| sq <= n) {
+        x += 1;
+    }
+    x
+}
+
+/// Round a wide significand and pack it (SoftFloat `roundAndPackFloatx80`).
+///
+/// `sig` carries the integer bit at bit 127 (or is zero); the low 64 bits
+/// are extra precision used for rounding. `exp` is biased and may be out of
+/// range, in which case overflow or gradual underflow is applied.
+pub(crate) fn round_pack(env: &mut FpEnv, sign: bool, mut exp: i32, sig: u128) -> FloatX80 {
+    let mut sig0 = (sig >> 64) as u64;
+    let mut sig1 = sig as u64;
+    let nearest = env.mode == RoundingMode::Nearest;
+    let pack = |exp: i32, mant: u64| {
+        FloatX80::new(
+            ((exp as u16) & 0x7FFF) | if sign { 0x8000 } else { 0 },
+            mant,
+        )
+    };
+    let overflow = |env: &mut FpEnv, round_mask: u64| {
+        env.raise(FpEnv::OVFL | FpEnv::INEX2);
+        let to_max = match env.mode {
+            RoundingMode::Zero => true,
+            RoundingMode::MinusInf => !sign,
+            RoundingMode::PlusInf => sign,
+            RoundingMode::Nearest => false,
+        };
+        if to_max {
+            pack(0x7FFE, !round_mask)
+        } else {
+            FloatX80::infinity(sign)
+        }
+    };
+
+    if env.precision != RoundingPrecision::Extended {
+        let (mut increment, mut round_mask) = match env.precision {
+            RoundingPrecision::Double => (0x400u64, 0x7FFu64),
+            _ => (0x80_0000_0000u64, 0xFF_FFFF_FFFFu64),
+        };
+        sig0 |= (sig1 != 0) as u64;
+        if !nearest {
+            increment = match env.mode {
+                RoundingMode::Zero => 0,
+                RoundingMode::MinusInf if !sign => 0,
+                RoundingMode::PlusInf if sign => 0,
+                _ => round_mask,
+            };
+        }
+        let mut round_bits = sig0 & round_mask;
+        if !(1..0x7FFE).contains(&exp) {
+            if exp > 0x7FFE || (exp == 0x7FFE && sig0.checked_add(increment).is_none()) {
+                return overflow(env, round_mask);
+            }
+            if exp <= 0 {
+                sig0 = shift_right_jam(sig0 as u128, 1 - exp) as u64;
+                exp = 0;
+                round_bits = sig0 & round_mask;
+                if round_bits != 0 {
+                    env.raise(FpEnv::UNFL | FpEnv::INEX2);
+                }
+                sig0 = sig0.wrapping_add(increment);
+                if sig0 & 0x8000_0000_0000_0000 != 0 {
+                    exp = 1;
+                }
+                let inc = round_mask + 1;
+                if nearest && round_bits << 1 == inc {
+                    round_mask |= inc;
+                }
+                return pack(exp, sig0 & !round_mask);
+            }
+        }
+        if round_bits != 0 {
+            env.raise(FpEnv::INEX2);
+        }
+        let (sum, carry) = sig0.overflowing_add(increment);
+        sig0 = sum;
+        if carry {
+            exp += 1;
+            sig0 = 0x8000_0000_0000_0000;
+        }
+        let inc = round_mask + 1;
+        if nearest && round_bits << 1 == inc {
+            round_mask |= inc;
+        }
+        sig0 &= !round_mask;
+        if sig0 == 0 {
+            exp = 0;
+        }
+        return pack(exp, sig0);
+    }
+
+    let increment_for = |env: &FpEnv, sig1: u64| match env.mode {
+        RoundingMode::Nearest => sig1 & 0x8000_0000_0000_0000 != 0,
+        RoundingMode::Zero => false,
+        RoundingMode::MinusInf => sign && sig1 != 0,
+        RoundingMode::PlusInf => !sign && sig1 != 0,
+    };
+    let mut increment = increment_for(env, sig1);
+    if !(1..0x7FFE).contains(&exp) {
+        if exp > 0x7FFE || (exp == 0x7FFE && sig0 == u64::MAX && increment) {
+            return overflow(env, 0);
+        }
+        if exp <= 0 {
+            let shifted = shift_right_jam(sig, 1 - exp);
+            sig0 = (shifted >> 64) as u64;
+            sig1 = shifted as u64;
+            exp = 0;
+            if sig1 != 0 {
+                env.raise(FpEnv::UNFL | FpEnv::INEX2);
+            }
+            increment = increment_for(env, sig1);
+            if increment {
+                sig0 = sig0.wrapping_add(1);
+                if nearest && sig1 << 1 == 0 {
+                    sig0 &= !1;
+                }
+                if sig0 & 0x8000_0000_0000_0000 != 0 {
+                    exp = 1;
+                }
+            }
+            return pack(exp, sig0);
+        }
+    }
+    if sig1 != 0 {
+        env.raise(FpEnv::INEX2);
+    }
+    if increment {
+        sig0 = sig0.wrapping_add(1);
+        if sig0 == 0 {
+            exp += 1;
+            sig0 = 0x8000_0000_0000_0000;
+        } else if nearest && sig1 << 1 == 0 {
+            sig0 &= !1;
+        }
+    } else if sig0 == 0 {
+        exp = 0;
+    }
+    pack(exp, sig0)
+}
+
+impl FloatX80 {
+    /// Biased exponent and significand with the integer bit in bit 63.
+    ///
+    /// Denormals and unnormals are normalized, so the exponent may drop
+    /// below 1. Only meaningful for finite non-zero values.
+    pub(crate) fn normalized(self) -> (i32, u64) {
+        let exp = self.biased_exp().max(1);
+        let shift = self.mantissa.leading_zeros();
+        (exp - shift as i32, self.mantissa << shift)
+    }
+
+    /// Quiet a NaN by setting the quiet bit.
+    #[inline]
+    fn quieted(self) -> Self {
+        Self::new(self.sign_exp, self.mantissa | 0x4000_0000_0000_0000)
+    }
+
+    /// NaN result of a dyadic operation: the destination NaN wins, as on the 68881.
+    fn propagate_nan(dst: Self, src: Self, env: &mut FpEnv) -> Self {
+        if dst.is_signaling_nan() || src.is_signaling_nan() {
+            env.raise(FpEnv::SNAN);
+        }
+        if dst.is_nan() {
+            dst.quieted()
+        } else {
+            src.quieted()
+        }
+    }
+
+    fn invalid(env: &mut FpEnv) -> Self {
+        env.raise(FpEnv::OPERR);
+        Self::DEFAULT_NAN
+    }
+
+    /// Round a finite non-zero value to the environment's precision.
+    fn repack(self, env: &mut FpEnv) -> Self {
+        let (exp, sig) = self.normalized();
+        round_pack(env, self.sign(), exp, (sig as u128) << 64)
+    }
+
+    /// Round to the environment's precision (FMOVE semantics).
+    ///
+    /// Signaling NaNs are quieted and raise SNAN; zeros and infinities pass
+    /// through with a canonical encoding.
+    pub fn round(self, env: &mut FpEnv) -> Self {
+        if self.is_nan() {
+            return Self::propagate_nan(self, self, env);
+        }
+        if self.is_infinite() {
+            return Self::infinity(self.sign());
+        }
+        if self.is_zero() {
+            return Self::zero(self.sign());
+        }
+        self.repack(env)
+    }
+
+    /// FABS: magnitude, rounded to the environment's precision.
+    pub fn abs(self, env: &mut FpEnv) -> Self {
+        if self.is_nan() {
+            return Self::propagate_nan(self, self, env);
+        }
+        self.with_sign(false).round(env)
+    }
+
+    /// FNEG: negation, rounded to the environment's precision.
+    pub fn neg(self, env: &mut FpEnv) -> Self {
+        if self.is_nan() {
+            return Self::propagate_nan(self, self, env);
+        }
+        self.negate().round(env)
+    }
+
+    pub fn add(self, src: Self, env: &mut FpEnv) -> Self {
+        if self.is_nan() || src.is_nan() {
+            return Self::propagate_nan(self, src, env);
+        }
+        if self.sign() == src.sign() {
+            Self::add_mags(self, src, self.sign(), env)
+        } else {
+            Self::sub_mags(self, src, self.sign(), env)
+        }
+    }
+
+    pub fn sub(self, src: Self, env: &mut FpEnv) -> Self {
+        if self.is_nan() || src.is_nan() {
+            return Self::propagate_nan(self, src, env);
+        }
+        if self.sign() == src.sign() {
+            Self::sub_mags(self, src, self.sign(), env)
+        } else {
+            Self::add_mags(self, src, self.sign(), env)
+        }
+    }
+
+    /// |a| + |b| with the given sign.
+    fn add_mags(a: Self, b: Self, sign: bool, env: &mut FpEnv) -> Self {
+        if a.is_infinite() || b.is_infinite() {
+            return Self::infinity(sign);
+        }
+        if b.is_zero() {
+            return if a.is_zero() {
+                Self::zero(sign)
+            } else {
+                a.repack(env)
+            };
+        }
+        if a.is_zero() {
+            return b.with_sign(sign).repack(env);
+        }
+        let (a_exp, a_sig) = a.normalized();
+        let (b_exp, b_sig) = b.normalized();
+        let (mut exp, big, small, diff) = if a_exp >= b_exp {
+            (a_exp, a_sig, b_sig, a_exp - b_exp)
+        } else {
+            (b_exp, b_sig, a_sig, b_exp - a_exp)
+        };
+        // One bit of headroom for the carry out of the integer bit.
+        let mut sum = ((big as u128) << 63) + shift_right_jam((small as u128) << 63, diff);
+        if sum >> 127 != 0 {
+            exp += 1;
+        } else {
+            sum <<= 1;
+        }
+        round_pack(env, sign, exp, sum)
+    }
+
+    /// |a| - |b|; the result carries `sign` when |a| >= |b| and the opposite otherwise.
+    fn sub_mags(a: Self, b: Self, sign: bool, env: &mut FpEnv) -> Self {
+        if a.is_infinite() && b.is_infinite() {
+            return Self::invalid(env);
+        }
+        if a.is_infinite() {
+            return Self::infinity(sign);
+        }
+        if b.is_infinite() {
+            return Self::infinity(!sign);
+        }
+        let exact_zero_sign = env.mode == RoundingMode::MinusInf;
+        if b.is_zero() {
+            return if a.is_zero() {
+                Self::zero(exact_zero_sign)
+            } else {
+                a.repack(env)
+            };
+        }
+        if a.is_zero() {
+            return b.with_sign(!sign).repack(env);
+        }
+        let (a_exp, a_sig) = a.normalized();
+        let (b_exp, b_sig) = b.normalized();
+        let (exp, big, small, diff, sign) = match (a_exp, a_sig).cmp(&(b_exp, b_sig)) {
+            std::cmp::Ordering::Greater => (a_exp, a_sig, b_sig, a_exp - b_exp, sign),
+            std::cmp::Ordering::Less => (b_exp, b_sig, a_sig, b_exp - a_exp, !sign),
+            std::cmp::Ordering::Equal => return Self::zero(exact_zero_sign),
+        };
+        let diffv = ((big as u128) << 64) - shift_right_jam((small as u128) << 64, diff);
+        let shift = diffv.leading_zeros();
+        round_pack(env, sign, exp - shift as i32, diffv << shift)
+    }
+
+    pub fn mul(self, src: Self, env: &mut FpEnv) -> Self {
+        if self.is_nan() || src.is_nan() {
+            return Self::propagate_nan(self, src, env);
+        }
+        let sign = self.sign() != src.sign();
+        if self.is_infinite() || src.is_infinite() {
+            if self.is_zero() || src.is_zero() {
+                return Self::invalid(env);
+            }
+            return Self::infinity(sign);
+        }
+        if self.is_zero() || src.is_zero() {
+            return Self::zero(sign);
+        }
+        let (a_exp, a_sig) = self.normalized();
+        let (b_exp, b_sig) = src.normalized();
+        let mut exp = a_exp + b_exp - 0x3FFE;
+        let mut prod = a_sig as u128 * b_sig as u128;
+        if prod >> 127 == 0 {
+            prod <<= 1;
+            exp -= 1;
+        }
+        round_pack(env, sign, exp, prod)
+    }
+
+    /// `self / src`.
+    pub fn div(self, src: Self, env: &mut FpEnv) -> Self {
+        if self.is_nan() || src.is_nan() {
+            return Self::propagate_nan(self, src, env);
+        }
+        let sign = self.sign() != src.sign();
+        if self.is_infinite() {
+            if src.is_infinite() {
+                return Self::invalid(env);
+            }
+            return Self::infinity(sign);
+        }
+        if src.is_infinite() {
+            return Self::zero(sign);
+        }
+        if src.is_zero() {
+            if self.is_zero() {
+                return Self::invalid(env);
+            }
+            env.raise(FpEnv::DZ);
+            return Self::infinity(sign);
+        }
+        if self.is_zero() {
+            return Self::zero(sign);
+        }
+        let (a_exp, a_sig) = self.normalized();
+        let (b_exp, b_sig) = src.normalized();
+        let mut exp = a_exp - b_exp + 0x3FFE;
+        let num = if a_sig < b_sig {
+            (a_sig as u128) << 64
+        } else {
+            exp += 1;
+            (a_sig as u128) << 63
+        };
+        let d = b_sig as u128;
+        let (q0, r0) = (num / d, num % d);
+        let (q1, r1) = ((r0 << 64) / d, (r0 << 64) % d);
+        let sig = (q0 << 64) | q1 | (r1 != 0) as u128;
+        round_pack(env, sign, exp, sig)
+    }
+
+    pub fn sqrt(self, env: &mut FpEnv) -> Self {
+        if self.is_nan() {
+            return Self::propagate_nan(self, self, env);
+        }
+        if self.is_zero() {
+            return Self::zero(self.sign());
+        }
+        if self.sign() {
+            return Self::invalid(env);
+        }
+        if self.is_infinite() {
+            return self;
+        }
+        let (exp, sig) = self.normalized();
+        let unbiased = exp - Self::BIAS;
+        let n = if unbiased & 1 == 0 {
+            (sig as u128) << 63
+        } else {
+            (sig as u128) << 64
+        };
+        let root = isqrt128(n);
+        let rem = n - root * root;
+        // Exactly-half cannot occur for a square root, so guard + sticky suffice.
+        let extra: u128 = if rem == 0 {
+            0
+        } else if rem > root {
+            0xC000_0000_0000_0000
+        } else {
+            0x4000_0000_0000_0000
+        };
+        round_pack(
+            env,
+            false,
+            unbiased.div_euclid(2) + Self::BIAS,
+            (root << 64) | extra,
+        )
+    }
+
+    /// Round to an integral value using the environment's rounding mode (FINT).
+    pub fn round_to_int(self, env: &mut FpEnv) -> Self {
+        if self.is_nan() {
+            return Self::propagate_nan(self, self, env);
+        }
+        if self.is_infinite() || self.is_zero() {
+            return self.round(env);
+        }
+        let sign = self.sign();
+        let (exp, sig) = self.normalized();
+        if exp >= Self::BIAS + 63 {
+            return self.repack(env);
+        }
+        if exp < Self::BIAS {
+            env.raise(FpEnv::INEX2);
+            let to_one = match env.mode {
+                RoundingMode::Nearest => exp == Self::BIAS - 1 && (sig << 1) != 0,
+                RoundingMode::Zero => false,
+                RoundingMode::MinusInf => sign,
+                RoundingMode::PlusInf => !sign,
+            };
+            return if to_one {
+                Self::ONE.with_sign(sign)
+            } else {
+                Self::zero(sign)
+            };
+        }
+        let frac_bits = (Self::BIAS + 63 - exp) as u32;
+        let mask = (1u64 << frac_bits) - 1;
+        let frac = sig & mask;
+        if frac == 0 {
+            return self.repack(env);
+        }
+        env.raise(FpEnv::INEX2);
+        let half = 1u64 << (frac_bits - 1);
+        let int = (sig & !mask) as u128;
+        let increment = match env.mode {
+            RoundingMode::Nearest => frac > half || (frac == half && (int >> frac_bits) & 1 != 0),
+            RoundingMode::Zero => false,
+            RoundingMode::MinusInf => sign,
+            RoundingMode::PlusInf => !sign,
+        };
+        let int = if increment {
+            int + (1u128 << frac_bits)
+        } else {
+            int
+        };
+        let (exp, int) = if int >> 64 != 0 {
+            (exp + 1, int >> 1)
+        } else {
+            (exp, int)
+        };
+        Self::new(exp as u16 | if sign { 0x8000 } else { 0 }, int as u64).round(env)
+    }
+
+    /// Round to an integral value toward zero (FINTRZ).
+    pub fn trunc(self, env: &mut FpEnv) -> Self {
+        let mode = env.mode;
+        env.mode = RoundingMode::Zero;
+        let result = self.round_to_int(env);
+        env.mode = mode;
+        result
+    }
+
+    /// Exact conversion from a signed integer.
+    pub fn from_i64(value: i64) -> Self {
+        if value == 0 {
+            return Self::ZERO;
+        }
+        let mag = value.unsigned_abs();
+        let shift = mag.leading_zeros();
+        Self::new(
+            (Self::BIAS + 63 - shift as i32) as u16 | if value < 0 { 0x8000 } else { 0 },
+            mag << shift,
+        )
+    }
+
+    pub fn from_i32(value: i32) -> Self {
+        Self::from_i64(value as i64)
+    }
+
+    /// Convert to a signed integer of `bits` width using the environment's
+    /// rounding mode. Out-of-range values and NaNs raise OPERR and saturate.
+    pub fn to_int(self, bits: u32, env: &mut FpEnv) -> i64 {
+        let max = (1i64 << (bits - 1)) - 1;
+        let min = -(1i64 << (bits - 1));
+        if self.is_nan() {
+            if self.is_signaling_nan() {
+                env.raise(FpEnv::SNAN);
+            }
+            env.raise(FpEnv::OPERR);
+            return if self.sign() { min } else { max };
+        }
+        let saturate = |env: &mut FpEnv, sign: bool| {
+            env.raise(FpEnv::OPERR);
+            if sign { min } else { max }
+        };
+        if self.is_infinite() {
+            return saturate(env, self.sign());
+        }
+        let mut inner = FpEnv {
+            precision: RoundingPrecision::Extended,
+            ..*env
+        };
+        let int = self.round_to_int(&mut inner);
+        if int.is_zero() {
+            env.raise(inner.flags);
+            return 0;
+        }
+        let (exp, sig) = int.normalized();
+        if exp > Self::BIAS + bits as i32 {
+            return saturate(env, int.sign());
+        }
+        let mag = (sig >> (Self::BIAS + 63 - exp)) as i128;
+        let value = if int.sign() { -mag } else { mag };
+        if value > max as i128 || value < min as i128 {
+            return saturate(env, int.sign());
+        }
+        env.raise(inner.flags);
+        value as i64
+    }
+
+    pub fn to_i32(self, env: &mut FpEnv) -> i32 {
+        self.to_int(32, env) as i32
+    }
+
+    /// Exact conversion from IEEE single-precision bits.
+    pub fn from_f32_bits(bits: u32) -> Self {
+        Self::from_ieee(bits as u64, 23, 8)
+    }
+
+    /// Exact conversion from IEEE double-precision bits.
+    pub fn from_f64_bits(bits: u64) -> Self {
+        Self::from_ieee(bits, 52, 11)
+    }
+
+    fn from_ieee(bits: u64, frac_bits: u32, exp_bits: u32) -> Self {
+        let sign = (bits >> (frac_bits + exp_bits)) & 1 != 0;
+        let exp_max = (1u64 << exp_bits) - 1;
+        let bias = (exp_max >> 1) as i32;
+        let exp = ((bits >> frac_bits) & exp_max) as i32;
+        let frac = bits & ((1u64 << frac_bits) - 1);
+        let sign_bit = if sign { 0x8000 } else { 0 };
+        if exp as u64 == exp_max {
+            // Infinities and NaNs keep their payload bits, left-justified.
+            return Self::new(0x7FFF | sign_bit, frac << (63 - frac_bits));
+        }
+        if exp == 0 {
+            if frac == 0 {
+                return Self::zero(sign);
+            }
+            let shift = frac.leading_zeros();
+            let e = Self::BIAS - bias + 1 - (shift as i32 - (63 - frac_bits as i32));
+            return Self::new(e as u16 | sign_bit, frac << shift);
+        }
+        Self::new(
+            (exp - bias + Self::BIAS) as u16 | sign_bit,
+            (1u64 << 63) | (frac << (63 - frac_bits)),
+        )
+    }
+
+    /// Round to IEEE single-precision bits using the environment's rounding mode.
+    pub fn to_f32_bits(self, env: &mut FpEnv) -> u32 {
+        self.to_ieee(23, 8, env) as u32
+    }
+
+    /// Round to IEEE double-precision bits using the environment's rounding mode.
+    pub fn to_f64_bits(self, env: &mut FpEnv) -> u64 {
+        self.to_ieee(52, 11, env)
+    }
+
+    fn to_ieee(self, frac_bits: u32, exp_bits: u32, env: &mut FpEnv) -> u64 {
+        let sign = self.sign();
+        let sign_bit = (sign as u64) << (frac_bits + exp_bits);
+        let exp_max = (1i32 << exp_bits) - 1;
+        let bias = exp_max >> 1;
+        let frac_mask = (1u64 << frac_bits) - 1;
+        if self.is_nan() {
+            if self.is_signaling_nan() {
+                env.raise(FpEnv::SNAN);
+            }
+            let payload = (self.quieted().mantissa << 1) >> (64 - frac_bits);
+            return sign_bit | ((exp_max as u64) << frac_bits) | payload;
+        }
+        if self.is_infinite() {
+            return sign_bit | ((exp_max as u64) << frac_bits);
+        }
+        if self.is_zero() {
+            return sign_bit;
+        }
+        let (exp, sig) = self.normalized();
+        let mut target_exp = exp - Self::BIAS + bias;
+        let shift = (63 - frac_bits) as i32 + (1 - target_exp).max(0);
+        let scaled = shift_right_jam((sig as u128) << 64, shift);
+        let mut int = (scaled >> 64) as u64;
+        let rem = scaled as u64;
+        if rem != 0 {
+            env.raise(FpEnv::INEX2);
+            if target_exp < 1 {
+                env.raise(FpEnv::UNFL);
+            }
+        }
+        let increment = match env.mode {
+            RoundingMode::Nearest => {
+                rem > 0x8000_0000_0000_0000 || (rem == 0x8000_0000_0000_0000 && int & 1 != 0)
+            }
+            RoundingMode::Zero => false,
+            RoundingMode::MinusInf => sign && rem != 0,
+            RoundingMode::PlusInf => !sign && rem != 0,
+        };
+        if increment {
+            int += 1;
+        }
+        if target_exp < 1 {
+            // Denormal; a carry into the hidden bit yields the smallest normal.
+            return sign_bit | int;
+        }
+        if int >> (frac_bits + 1) != 0 {
+            int >>= 1;
+            target_exp += 1;
+        }
+        if target_exp >= exp_max {
+            env.raise(FpEnv::OVFL | FpEnv::INEX2);
+            let to_max = match env.mode {
+                RoundingMode::Zero => true,
+                RoundingMode::MinusInf => !sign,
+                RoundingMode::PlusInf => sign,
+                RoundingMode::Nearest => false,
+            };
+            return if to_max {
+                sign_bit | (((exp_max - 1) as u64) << frac_bits) | frac_mask
+            } else {
+                sign_bit | ((exp_max as u64) << frac_bits)
+            };
+        }
+        sign_bit | ((target_exp as u64) << frac_bits) | (int & frac_mask)
+    }
+
+    /// Convenience conversion from a host double (exact).
+    pub fn from_f64(value: f64) -> Self {
+        Self::from_f64_bits(value.to_bits())
+    }
+
+    /// Convenience conversion to a host double (round to nearest).
+    pub fn to_f64(self) -> f64 {
+        f64::from_bits(self.to_f64_bits(&mut FpEnv::default()))
+    }
+
+    /// Partial remainder shared by FMOD and FREM.
+    ///
+    /// Returns the remainder and the FPSR quotient byte (sign of the
+    /// quotient in bit 7, its seven least significant bits below).
+    /// With `ieee` the quotient is rounded to nearest (FREM); otherwise it is
+    /// truncated (FMOD).
+    fn partial_rem(self, src: Self, ieee: bool, env: &mut FpEnv) -> (Self, u8) {
+        if self.is_nan() || src.is_nan() {
+            return (Self::propagate_nan(self, src, env), 0);
+        }
+        if self.is_infinite() || src.is_zero() {
+            return (Self::invalid(env), 0);
+        }
+        let q_sign = ((self.sign() != src.sign()) as u8) << 7;
+        if self.is_zero() || src.is_infinite() {
+            return (self.round(env), q_sign);
+        }
+        let sign = self.sign();
+        let (a_exp, a_sig) = self.normalized();
+        let (b_exp, b_sig) = src.normalized();
+        let mut diff = a_exp - b_exp;
+        if diff < 0 {
+            if ieee && diff == -1 && a_sig > b_sig {
+                // |dst| lies strictly between |src|/2 and |src|: quotient rounds to 1.
+                let r = ((b_sig as u128) << 1) - a_sig as u128;
+                let shift = r.leading_zeros() - 64;
+                let sig = (r << shift) << 64;
+                return (
+                    round_pack(env, !sign, a_exp - shift as i32, sig),
+                    q_sign | 1,
+                );
+            }
+            return (self.repack(env), q_sign);
+        }
+        let d = b_sig as u128;
+        let mut r = a_sig as u128;
+        let mut q = r / d;
+        r %= d;
+        while diff > 0 {
+            let k = diff.min(64);
+            r <<= k;
+            q = ((q << k) | (r / d)) & 0xFF;
+            r %= d;
+            diff -= k;
+        }
+        let mut sign = sign;
+        if ieee && (2 * r > d || (2 * r == d && q & 1 != 0)) {
+            r = d - r;
+            q += 1;
+            sign = !sign;
+        }
+        let q_byte = q_sign | (q & 0x7F) as u8;
+        if r == 0 {
+            return (Self::zero(self.sign()), q_byte);
+        }
+        let shift = r.leading_zeros() - 64;
+        let sig = (r << shift) << 64;
+        (round_pack(env, sign, b_exp - shift as i32, sig), q_byte)
+    }
+
+    /// FMOD: remainder with a truncated quotient. Returns the result and the FPSR quotient byte.
+    pub fn fmod(self, src: Self, env: &mut FpEnv) -> (Self, u8) {
+        self.partial_rem(src, false, env)
+    }
+
+    /// FREM: IEEE remainder. Returns the result and the FPSR quotient byte.
+    pub fn frem(self, src: Self, env: &mut FpEnv) -> (Self, u8) {
+        self.partial_rem(src, true, env)
+    }
+
+    /// FSCALE: `self * 2^int(src)`, with the scale factor truncated toward zero.
+    pub fn scale(self, src: Self, env: &mut FpEnv) -> Self {
+        if self.is_nan() || src.is_nan() {
+            return Self::propagate_nan(self, src, env);
+        }
+        if src.is_infinite() {
+            return Self::invalid(env);
+        }
+        if self.is_zero() || self.is_infinite() {
+            return self.round(env);
+        }
+        let mut inner = FpEnv::default();
+        // Anything beyond +/-2^16 over- or underflows regardless.
+        let n = src.to_int(32, &mut inner).clamp(-0x10000, 0x10000) as i32;
+        let (exp, sig) = self.normalized();
+        round_pack(env, self.sign(), exp + n, (sig as u128) << 64)
+    }
+
+    /// FGETEXP: unbiased exponent as a floating-point integer.
+    pub fn getexp(self, env: &mut FpEnv) -> Self {
+        if self.is_nan() {
+            return Self::propagate_nan(self, self, env);
+        }
+        if self.is_infinite() {
+            return Self::invalid(env);
+        }
+        if self.is_zero() {
+            return self;
+        }
+        Self::from_i32(self.normalized().0 - Self::BIAS)
+    }
+
+    /// FGETMAN: mantissa scaled into [1.0, 2.0), keeping the sign.
+    pub fn getman(self, env: &mut FpEnv) -> Self {
+        if self.is_nan() {
+            return Self::propagate_nan(self, self, env);
+        }
+        if self.is_infinite() {
+            return Self::invalid(env);
+        }
+        if self.is_zero() {
+            return self;
+        }
+        let (_, sig) = self.normalized();
+        Self::new(Self::BIAS as u16 | (self.sign_exp & 0x8000), sig).round(env)
+    }
+
+    /// FCMP: a value whose condition codes describe `self - src`.
+    ///
+    /// Only SNAN is reported; the subtraction never signals overflow,
+    /// underflow or inexact. Equal infinities compare as a zero carrying
+    /// their sign.
+    pub fn compare(self, src: Self, env: &mut FpEnv) -> Self {
+        if self.is_infinite() && src.is_infinite() && self.sign() == src.sign() {
+            return Self::zero(self.sign());
+        }
+        let mut inner = FpEnv {
+            precision: RoundingPrecision::Extended,
+            ..*env
+        };
+        let result = self.sub(src, &mut inner);
+        env.raise(inner.flags & FpEnv::SNAN);
+        result
+    }
+
+    /// FTST / condition-code helper: report a signaling NaN operand.
+    pub fn test(self, env: &mut FpEnv) {
+        if self.is_signaling_nan() {
+            env.raise(FpEnv::SNAN);
+        }
+    }
+}
@@ -0,0 +1,651 @@
+//! Transcendental functions on [`FloatX80`].
+//!
+//! The 6888x evaluates these in microcode. Here each function reduces its
+//! argument with split constants (Cody-Waite style), sums a Taylor or atanh
+//! series in extended precision and rounds once into the caller's
+//! environment, which keeps results within an ulp or two of the true
+//! extended-precision value.
+
+use super::softfloat::{FpEnv, round_pack};
+use super::types::FloatX80;
+
+const PI_2: FloatX80 = FloatX80::new(0x3FFF, 0xC90F_DAA2_2168_C235);
+const PI_4: FloatX80 = FloatX80::new(0x3FFE, 0xC90F_DAA2_2168_C235);
+const TWO: FloatX80 = FloatX80::new(0x4000, 0x8000_0000_0000_0000);
+const HALF: FloatX80 = FloatX80::new(0x3FFE, 0x8000_0000_0000_0000);
+const LN2: FloatX80 = FloatX80::new(0x3FFE, 0xB172_17F7_D1CF_79AC);
+const LN10: FloatX80 = FloatX80::new(0x4000, 0x935D_8DDD_AAA8_AC17);
+const LOG2E: FloatX80 = FloatX80::new(0x3FFF, 0xB8AA_3B29_5C17_F0BC);
+const LOG10E: FloatX80 = FloatX80::new(0x3FFD, 0xDE5B_D8A9_3728_7195);
+const LOG10_2: FloatX80 = FloatX80::new(0x3FFD, 0x9A20_9A84_FBCF_F799);
+/// Mantissa of sqrt(2); log arguments above it are halved.
+const SQRT2_MANT: u64 = 0xB504_F333_F9DE_6484;
+
+// Split constants: the high parts carry 32 significant bits so that products
+// with small integers are exact.
+const PI_2_P1: FloatX80 = FloatX80::new(0x3FFF, 0xC90F_DAA2_0000_0000);
+const PI_2_P2: FloatX80 = FloatX80::new(0x3FDD, 0x85A3_08D3_0000_0000);
+const PI_2_P3: FloatX80 = FloatX80::new(0x3FBA, 0x98CC_5170_1B83_9A25);
+const LN2_HI: FloatX80 = FloatX80::new(0x3FFE, 0xB172_17F7_0000_0000);
+const LN2_LO: FloatX80 = FloatX80::new(0x3FDE, 0xD1CF_79AB_C9E3_B398);
+const LN10_HI: FloatX80 = FloatX80::new(0x4000, 0x935D_8DDD_0000_0000);
+const LN10_LO: FloatX80 = FloatX80::new(0x3FE0, 0xAAA8_AC16_EA56_D62C);
+
+// Internal arithmetic: round-to-nearest extended, flags discarded.
+fn add(a: FloatX80, b: FloatX80) -> FloatX80 {
+    a.add(b, &mut FpEnv::default())
+}
+
+fn sub(a: FloatX80, b: FloatX80) -> FloatX80 {
+    a.sub(b, &mut FpEnv::default())
+}
+
+fn mul(a: FloatX80, b: FloatX80) -> FloatX80 {
+    a.mul(b, &mut FpEnv::default())
+}
+
+fn div(a: FloatX80, b: FloatX80) -> FloatX80 {
+    a.div(b, &mut FpEnv::default())
+}
+
+fn int(n: i64) -> FloatX80 {
+    FloatX80::from_i64(n)
+}
+
+/// Round an (inexact) extended result into the caller's environment.
+fn finish(v: FloatX80, env: &mut FpEnv) -> FloatX80 {
+    env.raise(FpEnv::INEX2);
+    v.round(env)
+}
+
+/// Round `p * 2^k` into the caller's environment, with over/underflow.
+fn finish_scaled(p: FloatX80, k: i32, sign: bool, env: &mut FpEnv) -> FloatX80 {
+    env.raise(FpEnv::INEX2);
+    let (exp, sig) = p.normalized();
+    round_pack(env, sign, exp + k, (sig as u128) << 64)
+}
+
+/// Magnitude too large for any finite result: overflow (or max finite).
+fn overflowed(sign: bool, env: &mut FpEnv) -> FloatX80 {
+    round_pack(env, sign, 0x8000, 1 << 127)
+}
+
+/// Magnitude far below the smallest denormal: underflow to zero (or min denormal).
+fn underflowed(sign: bool, env: &mut FpEnv) -> FloatX80 {
+    round_pack(env, sign, -0x100, 1 << 127)
+}
+
+/// exp(r) for |r| <= ~0.35.
+fn exp_series(r: FloatX80) -> FloatX80 {
+    let mut p = FloatX80::ONE;
+    for n in (1..=24).rev() {
+        p = add(FloatX80::ONE, div(mul(r, p), int(n)));
+    }
+    p
+}
+
+/// Split exp(x) into `p * 2^k` for finite |x| below ~11500.
+fn exp_parts(x: FloatX80) -> (FloatX80, i32) {
+    let k = (x.to_f64() * std::f64::consts::LOG2_E).round() as i32;
+    let kx = int(k as i64);
+    let r = sub(sub(x, mul(kx, LN2_HI)), mul(kx, LN2_LO));
+    (exp_series(r), k)
+}
+
+/// exp(x) - 1 in extended precision, for finite x below ~11350.
+fn expm1_core(x: FloatX80) -> FloatX80 {
+    if x.to_f64().abs() < 0.5 {
+        let mut p = FloatX80::ONE;
+        for n in (2..=26).rev() {
+            p = add(FloatX80::ONE, div(mul(x, p), int(n)));
+        }
+        return mul(x, p);
+    }
+    let (p, k) = exp_parts(x);
+    let (exp, sig) = p.normalized();
+    sub(FloatX80::new((exp + k) as u16, sig), FloatX80::ONE)
+}
+
+/// atanh(f) = f + f^3/3 + f^5/5 + ... for |f| <= ~0.18.
+fn atanh_series(f: FloatX80) -> FloatX80 {
+    let z = mul(f, f);
+    let mut p = FloatX80::ZERO;
+    for n in (0..=22).rev() {
+        p = add(div(FloatX80::ONE, int(2 * n + 1)), mul(z, p));
+    }
+    mul(f, p)
+}
+
+/// Split ln(x) for positive finite x into `k` and ln(m), with
+/// x = m * 2^k and m in [sqrt(2)/2, sqrt(2)].
+fn ln_parts(x: FloatX80) -> (i32, FloatX80) {
+    let (exp, sig) = x.normalized();
+    let (k, m) = if sig > SQRT2_MANT {
+        (
+            exp - FloatX80::BIAS + 1,
+            FloatX80::new((FloatX80::BIAS - 1) as u16, sig),
+        )
+    } else {
+        (
+            exp - FloatX80::BIAS,
+            FloatX80::new(FloatX80::BIAS as u16, sig),
+        )
+    };
+    let f = div(sub(m, FloatX80::ONE), add(m, FloatX80::ONE));
+    (k, mul(TWO, atanh_series(f)))
+}
+
+fn ln_core(x: FloatX80) -> FloatX80 {
+    let (k, ln_m) = ln_parts(x);
+    let kx = int(k as i64);
+    add(mul(kx, LN2_HI), add(mul(kx, LN2_LO), ln_m))
+}
+
+/// ln(1 + x) for finite x > -1.
+fn lnp1_core(x: FloatX80) -> FloatX80 {
+    if x.to_f64().abs() < 0.25 {
+        let f = div(x, add(TWO, x));
+        return mul(TWO, atanh_series(f));
+    }
+    ln_core(add(FloatX80::ONE, x))
+}
+
+/// atan(t) = t - t^3/3 + t^5/5 - ... for |t| <= tan(pi/8).
+fn atan_series(t: FloatX80) -> FloatX80 {
+    let z = mul(t, t);
+    let mut p = FloatX80::ZERO;
+    for n in (0..=40).rev() {
+        let term = div(FloatX80::ONE, int(2 * n + 1));
+        p = add(if n & 1 != 0 { term.negate() } else { term }, mul(z, p));
+    }
+    mul(t, p)
+}
+
+/// atan(a) for positive finite a.
+fn atan_core(a: FloatX80) -> FloatX80 {
+    let (t, offset) = if a.to_f64() > 1.0 {
+        (div(FloatX80::ONE, a), Some(PI_2))
+    } else {
+        (a, None)
+    };
+    let base = if t.to_f64() > 0.414_213_562_373_095 {
+        add(
+            PI_4,
+            atan_series(div(sub(t, FloatX80::ONE), add(t, FloatX80::ONE))),
+        )
+    } else {
+        atan_series(t)
+    };
+    match offset {
+        Some(o) => sub(o, base),
+        None => base,
+    }
+}
+
+/// Reduce x modulo pi/2: returns r in [-pi/4, pi/4] and the quadrant (0-3).
+fn reduce_pi_2(x: FloatX80) -> (FloatX80, i64) {
+    let xf = x.to_f64();
+    if xf.abs() <= std::f64::consts::FRAC_PI_4 {
+        return (x, 0);
+    }
+    if xf.abs() < (1u64 << 30) as f64 {
+        let n = (xf * std::f64::consts::FRAC_2_PI).round() as i64;
+        let nx = int(n);
+        let r = sub(
+            sub(sub(x, mul(nx, PI_2_P1)), mul(nx, PI_2_P2)),
+            mul(nx, PI_2_P3),
+        );
+        return (r, n & 3);
+    }
+    let (r, q) = x.frem(PI_2, &mut FpEnv::default());
+    let n = (q & 0x7F) as i64;
+    (r, (if q & 0x80 != 0 { -n } else { n }) & 3)
+}
+
+fn sin_series(r: FloatX80) -> FloatX80 {
+    let z = mul(r, r);
+    let mut p = FloatX80::ONE;
+    for k in (1..=14).rev() {
+        p = sub(FloatX80::ONE, div(mul(z, p), int(2 * k * (2 * k + 1))));
+    }
+    mul(r, p)
+}
+
+fn cos_series(r: FloatX80) -> FloatX80 {
+    let z = mul(r, r);
+    let mut p = FloatX80::ONE;
+    for k in (1..=14).rev() {
+        p = sub(FloatX80::ONE, div(mul(z, p), int((2 * k - 1) * (2 * k))));
+    }
+    p
+}
+
+fn sin_cos_core(x: FloatX80) -> (FloatX80, FloatX80) {
+    let (r, q) = reduce_pi_2(x);
+    let (s, c) = (sin_series(r), cos_series(r));
+    match q {
+        0 => (s, c),
+        1 => (c, s.negate()),
+        2 => (s.negate(), c.negate()),
+        _ => (c.negate(), s),
+    }
+}
+
+impl FloatX80 {
+    /// Shared NaN/infinity/zero screening for monadic functions. Returns the
+    /// result when the operand is a NaN.
+    fn nan_operand(self, env: &mut FpEnv) -> Option<Self> {
+        self.is_nan().then(|| self.round(env))
+    }
+
+    fn operr(env: &mut FpEnv) -> Self {
+        env.raise(FpEnv::OPERR);
+        Self::DEFAULT_NAN
+    }
+
+    /// FSIN
+    pub fn sin(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_infinite() {
+            return Self::operr(env);
+        }
+        if self.is_zero() {
+            return self;
+        }
+        finish(sin_cos_core(self).0, env)
+    }
+
+    /// FCOS
+    pub fn cos(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_infinite() {
+            return Self::operr(env);
+        }
+        if self.is_zero() {
+            return Self::ONE.round(env);
+        }
+        finish(sin_cos_core(self).1, env)
+    }
+
+    /// FSINCOS: returns `(sin, cos)`.
+    pub fn sincos(self, env: &mut FpEnv) -> (Self, Self) {
+        if let Some(n) = self.nan_operand(env) {
+            return (n, n);
+        }
+        if self.is_infinite() {
+            let n = Self::operr(env);
+            return (n, n);
+        }
+        if self.is_zero() {
+            return (self, Self::ONE.round(env));
+        }
+        let (s, c) = sin_cos_core(self);
+        (finish(s, env), finish(c, env))
+    }
+
+    /// FTAN
+    pub fn tan(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_infinite() {
+            return Self::operr(env);
+        }
+        if self.is_zero() {
+            return self;
+        }
+        let (s, c) = sin_cos_core(self);
+        finish(div(s, c), env)
+    }
+
+    /// FATAN
+    pub fn atan(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_zero() {
+            return self;
+        }
+        if self.is_infinite() {
+            return finish(PI_2.with_sign(self.sign()), env);
+        }
+        finish(atan_core(self.with_sign(false)).with_sign(self.sign()), env)
+    }
+
+    /// FASIN
+    pub fn asin(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_zero() {
+            return self;
+        }
+        let a = self.with_sign(false);
+        if self.is_infinite() || a.to_f64() > 1.0 {
+            return Self::operr(env);
+        }
+        if a == Self::ONE {
+            return finish(PI_2.with_sign(self.sign()), env);
+        }
+        // 1 - a^2 computed as (1 - a)(1 + a) to avoid cancellation near 1.
+        let root = mul(sub(Self::ONE, a), add(Self::ONE, a)).sqrt(&mut FpEnv::default());
+        finish(atan_core(div(a, root)).with_sign(self.sign()), env)
+    }
+
+    /// FACOS
+    pub fn acos(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_infinite() || self.with_sign(false).to_f64() > 1.0 {
+            return Self::operr(env);
+        }
+        if self == Self::ONE {
+            return Self::ZERO;
+        }
+        // acos(x) = 2 * atan(sqrt((1 - x) / (1 + x)))
+        let q = div(sub(Self::ONE, self), add(Self::ONE, self));
+        if q.is_infinite() {
+            return finish(add(PI_2, PI_2), env);
+        }
+        let t = q.sqrt(&mut FpEnv::default());
+        let half_angle = if t.is_zero() { t } else { atan_core(t) };
+        finish(mul(TWO, half_angle), env)
+    }
+
+    /// FSINH
+    pub fn sinh(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_zero() || self.is_infinite() {
+            return self;
+        }
+        let sign = self.sign();
+        let a = self.with_sign(false);
+        let af = a.to_f64();
+        if af < 1.0 {
+            let z = mul(a, a);
+            let mut p = Self::ONE;
+            for k in (1..=12).rev() {
+                p = add(Self::ONE, div(mul(z, p), int(2 * k * (2 * k + 1))));
+            }
+            return finish(mul(a, p).with_sign(sign), env);
+        }
+        if af > 12000.0 {
+            return overflowed(sign, env);
+        }
+        if af < 40.0 {
+            let (p, k) = exp_parts(a);
+            let (exp, sig) = p.normalized();
+            let e = Self::new((exp + k) as u16, sig);
+            let v = mul(HALF, sub(e, div(Self::ONE, e)));
+            return finish(v.with_sign(sign), env);
+        }
+        let (p, k) = exp_parts(a);
+        finish_scaled(p, k - 1, sign, env)
+    }
+
+    /// FCOSH
+    pub fn cosh(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_infinite() {
+            return Self::INFINITY;
+        }
+        if self.is_zero() {
+            return Self::ONE.round(env);
+        }
+        let a = self.with_sign(false);
+        let af = a.to_f64();
+        if af > 12000.0 {
+            return overflowed(false, env);
+        }
+        let (p, k) = exp_parts(a);
+        if af < 40.0 {
+            let (exp, sig) = p.normalized();
+            let e = Self::new((exp + k) as u16, sig);
+            return finish(mul(HALF, add(e, div(Self::ONE, e))), env);
+        }
+        finish_scaled(p, k - 1, false, env)
+    }
+
+    /// FTANH
+    pub fn tanh(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_zero() {
+            return self;
+        }
+        let sign = self.sign();
+        if self.is_infinite() {
+            return Self::ONE.with_sign(sign);
+        }
+        let a = self.with_sign(false);
+        if a.to_f64() > 23.0 {
+            return finish(Self::ONE.with_sign(sign), env);
+        }
+        let t = expm1_core(mul(TWO, a));
+        finish(div(t, add(t, TWO)).with_sign(sign), env)
+    }
+
+    /// FATANH
+    pub fn atanh(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_zero() {
+            return self;
+        }
+        let sign = self.sign();
+        let a = self.with_sign(false);
+        if self.is_infinite() || a.to_f64() > 1.0 {
+            return Self::operr(env);
+        }
+        if a == Self::ONE {
+            env.raise(FpEnv::DZ);
+            return Self::infinity(sign);
+        }
+        let v = mul(HALF, lnp1_core(div(mul(TWO, a), sub(Self::ONE, a))));
+        finish(v.with_sign(sign), env)
+    }
+
+    /// FETOX
+    pub fn etox(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_infinite() {
+            return if self.sign() {
+                Self::ZERO
+            } else {
+                Self::INFINITY
+            };
+        }
+        if self.is_zero() {
+            return Self::ONE.round(env);
+        }
+        let xf = self.to_f64();
+        if xf > 11400.0 {
+            return overflowed(false, env);
+        }
+        if xf < -11500.0 {
+            return underflowed(false, env);
+        }
+        let (p, k) = exp_parts(self);
+        finish_scaled(p, k, false, env)
+    }
+
+    /// FETOXM1
+    pub fn etoxm1(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_infinite() {
+            return if self.sign() {
+                Self::ONE.negate()
+            } else {
+                Self::INFINITY
+            };
+        }
+        if self.is_zero() {
+            return self;
+        }
+        if self.to_f64() > 11350.0 {
+            return self.etox(env);
+        }
+        if self.to_f64() < -100.0 {
+            return finish(Self::ONE.negate(), env);
+        }
+        finish(expm1_core(self), env)
+    }
+
+    /// FTWOTOX
+    pub fn twotox(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_infinite() {
+            return if self.sign() {
+                Self::ZERO
+            } else {
+                Self::INFINITY
+            };
+        }
+        if self.is_zero() {
+            return Self::ONE.round(env);
+        }
+        let xf = self.to_f64();
+        if xf > 16500.0 {
+            return overflowed(false, env);
+        }
+        if xf < -16500.0 {
+            return underflowed(false, env);
+        }
+        let n = xf.round() as i32;
+        let f = sub(self, int(n as i64));
+        if f.is_zero() {
+            return round_pack(env, false, Self::BIAS + n, 1 << 127);
+        }
+        finish_scaled(exp_series(mul(f, LN2)), n, false, env)
+    }
+
+    /// FTENTOX
+    pub fn tentox(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_infinite() {
+            return if self.sign() {
+                Self::ZERO
+            } else {
+                Self::INFINITY
+            };
+        }
+        if self.is_zero() {
+            return Self::ONE.round(env);
+        }
+        let xf = self.to_f64();
+        if xf > 5000.0 {
+            return overflowed(false, env);
+        }
+        if xf < -5000.0 {
+            return underflowed(false, env);
+        }
+        // Powers of ten up to 10^27 are exact in a 64-bit mantissa.
+        if (1.0..=27.0).contains(&xf) && xf.fract() == 0.0 {
+            let mut v = Self::ONE;
+            for _ in 0..xf as i32 {
+                v = mul(v, int(10));
+            }
+            return v.round(env);
+        }
+        let k = (xf * std::f64::consts::LOG2_10).round() as i64;
+        // x * ln(10) - k * ln(2), with x split so the leading product is exact.
+        let x_hi = Self::new(self.sign_exp, self.mantissa & 0xFFFF_FFFF_0000_0000);
+        let x_lo = sub(self, x_hi);
+        let kx = int(k);
+        let lead = sub(mul(x_hi, LN10_HI), mul(kx, LN2_HI));
+        let tail = sub(add(mul(x_hi, LN10_LO), mul(x_lo, LN10)), mul(kx, LN2_LO));
+        finish_scaled(exp_series(add(lead, tail)), k as i32, false, env)
+    }
+
+    /// Screening shared by the logarithms. `Err` carries the final result.
+    fn log_operand(self, env: &mut FpEnv) -> Result<(), Self> {
+        if let Some(n) = self.nan_operand(env) {
+            return Err(n);
+        }
+        if self.is_zero() {
+            env.raise(FpEnv::DZ);
+            return Err(Self::infinity(true));
+        }
+        if self.sign() {
+            return Err(Self::operr(env));
+        }
+        if self.is_infinite() {
+            return Err(self);
+        }
+        if self == Self::ONE {
+            return Err(Self::ZERO);
+        }
+        Ok(())
+    }
+
+    /// FLOGN
+    pub fn logn(self, env: &mut FpEnv) -> Self {
+        if let Err(v) = self.log_operand(env) {
+            return v;
+        }
+        finish(ln_core(self), env)
+    }
+
+    /// FLOGNP1
+    pub fn lognp1(self, env: &mut FpEnv) -> Self {
+        if let Some(n) = self.nan_operand(env) {
+            return n;
+        }
+        if self.is_zero() {
+            return self;
+        }
+        if self.is_infinite() {
+            return if self.sign() { Self::operr(env) } else { self };
+        }
+        let minus_one = Self::ONE.negate();
+        if self == minus_one {
+            env.raise(FpEnv::DZ);
+            return Self::infinity(true);
+        }
+        if self.sign() && self.to_f64() < -1.0 {
+            return Self::operr(env);
+        }
+        finish(lnp1_core(self), env)
+    }
+
+    /// FLOG2
+    pub fn log2(self, env: &mut FpEnv) -> Self {
+        if let Err(v) = self.log_operand(env) {
+            return v;
+        }
+        let (exp, sig) = self.normalized();
+        if sig == 1 << 63 {
+            return int((exp - Self::BIAS) as i64).round(env);
+        }
+        let (k, ln_m) = ln_parts(self);
+        finish(add(int(k as i64), mul(ln_m, LOG2E)), env)
+    }
+
+    /// FLOG10
+    pub fn log10(self, env: &mut FpEnv) -> Self {
+        if let Err(v) = self.log_operand(env) {
+            return v;
+        }
+        let (k, ln_m) = ln_parts(self);
+        finish(add(mul(int(k as i64), LOG10_2), mul(ln_m, LOG10E)), env)
+    }
+}
@@ -1,7 +1,210 @@
 //! FPU types
 
-#[derive(Debug, Clone, Copy, Default)]
+use crate::core::types::CpuType;
+
+/// Floating-point unit fitted to the CPU.
+///
+/// [`CpuCore::set_cpu_type`](crate::CpuCore::set_cpu_type) picks the usual one for the
+/// CPU; [`CpuCore::set_fpu_type`](crate::CpuCore::set_fpu_type) overrides it. The model
+/// decides the FSAVE/FRESTORE frame formats, which guest software reads to tell them apart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FpuType {
+    /// No FPU: every FPU instruction takes the Line-F exception.
+    #[default]
+    None,
+    /// External 68881 coprocessor (frame version $1F).
+    M68881,
+    /// External 68882 coprocessor (frame version $20).
+    M68882,
+    /// The 68040's on-chip FPU (frame version $41).
+    M68040,
+    /// The 68060's on-chip FPU (12-byte frames, format byte at offset 2).
+    M68060,
+}
+
+/// Kind of FSAVE frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FpuFrame {
+    /// FPU in its reset state.
+    Null,
+    /// No operation in progress.
+    Idle,
+    /// Operation or exception in progress; the 68060 calls this frame EXCP.
+    Busy,
+    /// 68040 only: an instruction left to software.
+    Unimp,
+}
+
+impl FpuType {
+    /// FPU a system built around `cpu_type` usually has.
+    pub fn for_cpu(cpu_type: CpuType) -> Self {
+        match cpu_type {
+            CpuType::M68EC020 | CpuType::M68020 | CpuType::M68EC030 | CpuType::M68030 => {
+                Self::M68881
+            }
+            CpuType::M68040 => Self::M68040,
+            CpuType::M68060 => Self::M68060,
+            _ => Self::None,
+        }
+    }
+
+    /// Format long word and total length in bytes of this model's `frame`.
+    pub(crate) fn fsave_frame(self, frame: FpuFrame) -> (u32, u32) {
+        if self == Self::M68060 {
+            let format = match frame {
+                FpuFrame::Null => 0x00,
+                FpuFrame::Idle => 0x60,
+                FpuFrame::Busy | FpuFrame::Unimp => 0xE0,
+            };
+            return (format << 8, 12);
+        }
+        let (version, size) = match (self, frame) {
+            (_, FpuFrame::Null) | (Self::None, _) => return (0, 4),
+            (Self::M68881, FpuFrame::Idle) => (0x1F, 0x18),
+            (Self::M68881, _) => (0x1F, 0xB4),
+            (Self::M68882, FpuFrame::Idle) => (0x20, 0x38),
+            (Self::M68882, _) => (0x20, 0xD4),
+            (_, FpuFrame::Idle) => (0x41, 0x00),
+            (_, FpuFrame::Unimp) => (0x41, 0x30),
+            (_, FpuFrame::Busy) => (0x41, 0x60),
+        };
+        (version << 24 | size << 16, 4 + size)
+    }
+
+    /// Kind and length of the frame that starts with `header`, or `None` if this model
+    /// does not accept it.
+    pub(crate) fn frestore_frame(self, header: u32) -> Option<(FpuFrame, u32)> {
+        let frames: &[FpuFrame] = match self {
+            Self::None => &[],
+            Self::M68881 | Self::M68882 | Self::M68060 => {
+                &[FpuFrame::Null, FpuFrame::Idle, FpuFrame::Busy]
+            }
+            Self::M68040 => &[
+                FpuFrame::Null,
+                FpuFrame::Idle,
+                FpuFrame::Busy,
+                FpuFrame::Unimp,
+            ],
+        };
+        let mask = match (self, header >> 24) {
+            (Self::M68060, _) => 0x0000_FF00,
+            (_, 0) => 0xFF00_0000,
+            _ => 0xFFFF_0000,
+        };
+        frames.iter().find_map(|&frame| {
+            let (format, len) = self.fsave_frame(frame);
+            (header & mask == format).then_some((frame, len))
+        })
+    }
+}
+
+/// 80-bit extended-precision value as held in the FP0-FP7 data registers.
+///
+/// Layout matches the 68881 register format: 1 sign bit, 15-bit biased
+/// exponent (bias 16383) and a 64-bit mantissa with an explicit integer bit.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct FloatX80 {
     pub mantissa: u64,
     pub sign_exp: u16,
 }
+
+impl FloatX80 {
+    /// Exponent bias of the extended format.
+    pub const BIAS: i32 = 0x3FFF;
+
+    pub const ZERO: Self = Self::new(0x0000, 0);
+    pub const ONE: Self = Self::new(0x3FFF, 0x8000_0000_0000_0000);
+    pub const INFINITY: Self = Self::new(0x7FFF, 0);
+    /// Default quiet NaN produced by invalid operations (68881 "all ones" NaN).
+    pub const DEFAULT_NAN: Self = Self::new(0x7FFF, 0xFFFF_FFFF_FFFF_FFFF);
+
+    /// Build a value from its raw sign/exponent word and mantissa.
+    pub const fn new(sign_exp: u16, mantissa: u64) -> Self {
+        Self { mantissa, sign_exp }
+    }
+
+    /// Sign bit.
+    #[inline]
+    pub fn sign(self) -> bool {
+        self.sign_exp & 0x8000 != 0
+    }
+
+    /// Raw biased exponent field.
+    #[inline]
+    pub fn biased_exp(self) -> i32 {
+        (self.sign_exp & 0x7FFF) as i32
+    }
+
+    /// Copy of `self` with the sign bit replaced.
+    #[inline]
+    pub fn with_sign(self, sign: bool) -> Self {
+        Self::new(
+            (self.sign_exp & 0x7FFF) | if sign { 0x8000 } else { 0 },
+            self.mantissa,
+        )
+    }
+
+    /// Copy of `self` with the sign bit inverted.
+    #[inline]
+    pub fn negate(self) -> Self {
+        Self::new(self.sign_exp ^ 0x8000, self.mantissa)
+    }
+
+    #[inline]
+    pub fn is_nan(self) -> bool {
+        self.biased_exp() == 0x7FFF && (self.mantissa << 1) != 0
+    }
+
+    /// NaN with the quiet bit (mantissa bit 62) clear.
+    #[inline]
+    pub fn is_signaling_nan(self) -> bool {
+        self.is_nan() && self.mantissa & 0x4000_0000_0000_0000 == 0
+    }
+
+    /// Infinity. The integer bit is ignored, as on the 68881.
+    #[inline]
+    pub fn is_infinite(self) -> bool {
+        self.biased_exp() == 0x7FFF && (self.mantissa << 1) == 0
+    }
+
+    /// Zero of either sign, including unnormalized zeros.
+    #[inline]
+    pub fn is_zero(self) -> bool {
+        self.biased_exp() != 0x7FFF && self.mantissa == 0
+    }
+
+    #[inline]
+    pub fn is_denormal(self) -> bool {
+        self.biased_exp() == 0 && self.mantissa != 0
+    }
+
+    /// Signed zero.
+    #[inline]
+    pub fn zero(sign: bool) -> Self {
+        Self::ZERO.with_sign(sign)
+    }
+
+    /// Signed infinity.
+    #[inline]
+    pub fn infinity(sign: bool) -> Self {
+        Self::INFINITY.with_sign(sign)
+    }
+
+    /// Decode the 12-byte memory image used by FMOVE.X and FMOVEM
+    /// (sign/exponent word, 16 bits of padding, 64-bit mantissa).
+    pub fn from_memory(sign_exp_word: u32, mant_hi: u32, mant_lo: u32) -> Self {
+        Self::new(
+            (sign_exp_word >> 16) as u16,
+            ((mant_hi as u64) << 32) | mant_lo as u64,
+        )
+    }
+
+    /// Encode as the three longwords of the 12-byte memory image.
+    pub fn to_memory(self) -> [u32; 3] {
+        [
+            (self.sign_exp as u32) << 16,
+            (self.mantissa >> 32) as u32,
+            self.mantissa as u32,
+        ]
+    }
+}
@@ -2,14 +2,17 @@
 //!
 //! A safe Rust M68000 family CPU emulator.
 //!
-//! Supports: M68000, M68010, M68EC020, M68020, M68EC030, M68030, M68EC040, M68LC040, M68040, SCC68070
+//! Supports: M68000, M68008, M68010, M68012, M68EC020, M68020, M68EC030, M68030, M68EC040, M68LC040, M68040, M68EC060, M68LC060, M68060, SCC68070, CPU32, ColdFire ISA_A/ISA_B/ISA_C
 
 pub mod core;
 pub mod dasm;
 pub mod fpu;
 pub mod mmu;
+pub mod scc68070;
 
 // Re-export commonly used types from core
+pub use core::coprocessor::Coprocessor;
 pub use core::cpu::CpuCore;
 pub use core::memory::AddressBus;
-pub use core::types::{CpuType, HleHandler, NoOpHleHandler, Size, StepResult};
\ No newline at end of file
+pub use core::types::{CpuType, HleHandler, NoOpHleHandler, Size, StepResult};
+pub use scc68070::Scc68070;
@@ -0,0 +1,161 @@
+//! Address translation cache (ATC).
+//!
+//! The 68030 has a single 22-entry fully associative ATC shared by instruction and data
+//! accesses. The 68040 has separate 64-entry instruction and data ATCs. Entries are
+//! replaced round-robin; only what PFLUSH can observe (which translations are cached) is
+//! modelled, not the 68040's set-associative indexing.
+//!
+//! Table edits do not take effect for cached pages until the guest flushes them with
+//! PFLUSH, or on the 68030 reloads TC/CRP/SRP with PMOVE.
+
+use crate::core::cache::CacheMode;
+use crate::core::types::CpuType;
+
+/// A cached translation for one page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AtcEntry {
+    /// Function code of the access that loaded the entry. On the 68040 only the
+    /// supervisor bit (FC2) is significant, so this is 0 or 4.
+    pub fc: u8,
+    /// Page-aligned logical address.
+    pub logical: u32,
+    /// Page-aligned physical address.
+    pub physical: u32,
+    /// Mask of the page offset bits.
+    pub page_mask: u32,
+    pub write_protected: bool,
+    pub supervisor_only: bool,
+    /// The page descriptor's M bit was set when the entry was loaded.
+    pub modified: bool,
+    /// 68040 G bit: the entry survives PFLUSHN/PFLUSHAN.
+    pub global: bool,
+    /// How the on-chip caches treat the page (68030 CI bit, 68040 CM field).
+    pub cache_mode: CacheMode,
+}
+
+impl AtcEntry {
+    /// True if this entry translates `logical` for function code `fc`.
+    pub fn matches(&self, fc: u8, logical: u32) -> bool {
+        self.fc == fc && self.logical == logical & !self.page_mask
+    }
+
+    /// Physical address for a logical address inside this entry's page.
+    pub fn translate(&self, logical: u32) -> u32 {
+        self.physical | (logical & self.page_mask)
+    }
+}
+
+/// One fully associative bank of ATC entries.
+#[derive(Debug, Clone, Default)]
+pub struct AtcBank {
+    entries: Vec<AtcEntry>,
+    capacity: usize,
+    next_victim: usize,
+}
+
+impl AtcBank {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            capacity,
+            next_victim: 0,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Currently valid entries.
+    pub fn entries(&self) -> &[AtcEntry] {
+        &self.entries
+    }
+
+    pub fn lookup(&self, fc: u8, logical: u32) -> Option<AtcEntry> {
+        self.entries
+            .iter()
+            .copied()
+            .find(|e| e.matches(fc, logical))
+    }
+
+    /// Add an entry, replacing any stale entry for the same page or, when full, the
+    /// next round-robin victim.
+    pub fn insert(&mut self, entry: AtcEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        if let Some(slot) = self
+            .entries
+            .iter_mut()
+            .find(|e| e.fc == entry.fc && e.logical == entry.logical)
+        {
+            *slot = entry;
+        } else if self.entries.len() < self.capacity {
+            self.entries.push(entry);
+        } else {
+            self.entries[self.next_victim] = entry;
+            self.next_victim = (self.next_victim + 1) % self.capacity;
+        }
+    }
+
+    /// Invalidate every entry for which `flush` returns true.
+    pub fn flush(&mut self, flush: impl Fn(&AtcEntry) -> bool) {
+        self.entries.retain(|e| !flush(e));
+        if self.next_victim >= self.entries.len() {
+            self.next_victim = 0;
+        }
+    }
+}
+
+/// The ATC(s) of one CPU model.
+#[derive(Debug, Clone, Default)]
+pub struct Atc {
+    /// Data ATC, or the unified ATC when there is no separate instruction ATC.
+    pub data: AtcBank,
+    /// Instruction ATC (68040/68060 only).
+    pub instruction: Option<AtcBank>,
+}
+
+impl Atc {
+    /// ATC geometry for `cpu_type`; CPUs without a PMMU get an empty ATC.
+    pub fn for_cpu(cpu_type: CpuType) -> Self {
+        match cpu_type {
+            CpuType::M68030 => Self {
+                data: AtcBank::new(22),
+                instruction: None,
+            },
+            CpuType::M68040 | CpuType::M68LC040 | CpuType::M68060 | CpuType::M68LC060 => Self {
+                data: AtcBank::new(64),
+                instruction: Some(AtcBank::new(64)),
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// The bank that caches instruction (`true`) or data (`false`) accesses.
+    pub fn bank(&self, instruction: bool) -> &AtcBank {
+        match &self.instruction {
+            Some(bank) if instruction => bank,
+            _ => &self.data,
+        }
+    }
+
+    pub fn bank_mut(&mut self, instruction: bool) -> &mut AtcBank {
+        match &mut self.instruction {
+            Some(bank) if instruction => bank,
+            _ => &mut self.data,
+        }
+    }
+
+    /// Invalidate matching entries in every bank.
+    pub fn flush(&mut self, flush: impl Fn(&AtcEntry) -> bool) {
+        self.data.flush(&flush);
+        if let Some(bank) = &mut self.instruction {
+            bank.flush(&flush);
+        }
+    }
+
+    pub fn flush_all(&mut self) {
+        self.flush(|_| true);
+    }
+}
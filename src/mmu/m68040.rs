@@ -0,0 +1,231 @@
+//! 68040 (and 68LC040) native table walk.
+//!
+//! Unlike the 68030, the 68040 has a fixed three-level tree selected by URP/SRP:
+//! ```text
+//! 4K pages: [31:25] root index | [24:18] pointer index | [17:12] page index | [11:0] offset
+//! 8K pages: [31:25] root index | [24:18] pointer index | [17:13] page index | [12:0] offset
+//! ```
+//! The only TC bits are E (bit 15, enable) and P (bit 14, 8K pages).
+
+use crate::core::cache::CacheMode;
+use crate::core::cpu::CpuCore;
+use crate::core::memory::AddressBus;
+
+use super::atc::AtcEntry;
+use super::translation::{access_fault, read_u32_phys, write_u32_phys};
+use super::ttr::{TTR040_W, ttr040_matches};
+use super::{HistoryUpdate, MmuFaultKind, MmuResult};
+
+/// TC: enable translation.
+pub const TC_ENABLE: u32 = 0x8000;
+/// TC: 8K pages (4K when clear).
+pub const TC_PAGE_8K: u32 = 0x4000;
+
+/// Root/pointer descriptors: bits 1-0 (UDT) are resident when >= 2.
+const UDT_RESIDENT: u32 = 0x2;
+/// Page descriptor type (PDT) for an indirect descriptor.
+const PDT_INDIRECT: u32 = 0x2;
+/// Write-protect bit, present in root, pointer and page descriptors.
+const DESC_WP: u32 = 0x0004;
+/// Used bit, present in root, pointer and page descriptors.
+const DESC_USED: u32 = 0x0008;
+
+/// Page descriptor: modified.
+pub const PD_MODIFIED: u32 = 0x0010;
+/// Page descriptor: supervisor only.
+pub const PD_SUPERVISOR: u32 = 0x0080;
+/// Page descriptor: global (survives PFLUSHN).
+pub const PD_GLOBAL: u32 = 0x0400;
+
+/// MMUSR: resident (translation valid).
+pub const MMUSR_RESIDENT: u32 = 0x0001;
+/// MMUSR: matched a transparent translation register.
+pub const MMUSR_TRANSPARENT: u32 = 0x0002;
+/// MMUSR: write protected.
+pub const MMUSR_WRITE_PROTECTED: u32 = 0x0004;
+/// MMUSR: bus error during the table search.
+pub const MMUSR_BUS_ERROR: u32 = 0x0800;
+
+/// Result of a successful 68040 table walk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageWalk {
+    /// Physical address for the logical address walked.
+    pub physical: u32,
+    /// The page descriptor, after following an indirect descriptor.
+    pub descriptor: u32,
+    /// Mask of the page offset bits (0xFFF or 0x1FFF).
+    pub page_mask: u32,
+    /// Write protection accumulated over the root, pointer and page levels.
+    pub write_protected: bool,
+}
+
+impl PageWalk {
+    pub fn global(&self) -> bool {
+        self.descriptor & PD_GLOBAL != 0
+    }
+
+    pub fn supervisor_only(&self) -> bool {
+        self.descriptor & PD_SUPERVISOR != 0
+    }
+
+    /// CM field: 0 = cachable write-through, 1 = copyback, 2/3 = noncachable.
+    pub fn cache_mode(&self) -> u8 {
+        ((self.descriptor >> 5) & 3) as u8
+    }
+
+    /// ATC entry for this page, cached under `fc` (0 for user, 4 for supervisor).
+    pub fn atc_entry(&self, fc: u8, logical: u32) -> AtcEntry {
+        AtcEntry {
+            fc,
+            logical: logical & !self.page_mask,
+            physical: self.physical & !self.page_mask,
+            page_mask: self.page_mask,
+            write_protected: self.write_protected,
+            supervisor_only: self.supervisor_only(),
+            modified: self.descriptor & PD_MODIFIED != 0,
+            global: self.global(),
+            cache_mode: CacheMode::from_cm_040(self.cache_mode()),
+        }
+    }
+}
+
+/// Set `bits` in the descriptor at `addr` unless they are already set.
+fn update_descriptor<B: AddressBus>(
+    bus: &mut B,
+    addr: u32,
+    desc: u32,
+    bits: u32,
+) -> MmuResult<u32> {
+    if desc & bits != bits {
+        write_u32_phys(bus, addr, desc | bits)?;
+    }
+    Ok(desc | bits)
+}
+
+/// Walk the 68040 tables for `logical` without checking access rights.
+///
+/// Unless `history` is [`HistoryUpdate::None`], U is set in each descriptor fetched and a
+/// permitted write sets M in the page descriptor.
+pub fn walk<B: AddressBus>(
+    cpu: &CpuCore,
+    bus: &mut B,
+    logical: u32,
+    supervisor: bool,
+    history: HistoryUpdate,
+) -> MmuResult<PageWalk> {
+    let root = if supervisor { cpu.srp } else { cpu.urp } & 0xFFFF_FE00;
+    let page_8k = cpu.tc & TC_PAGE_8K != 0;
+    let used = if history == HistoryUpdate::None {
+        0
+    } else {
+        DESC_USED
+    };
+
+    let root_addr = root + ((logical >> 25) << 2);
+    let mut root_desc = read_u32_phys(bus, root_addr)?;
+    if root_desc & UDT_RESIDENT == 0 {
+        return Err(access_fault(logical));
+    }
+    root_desc = update_descriptor(bus, root_addr, root_desc, used)?;
+
+    let pointer_table = root_desc & 0xFFFF_FE00;
+    let pointer_addr = pointer_table + (((logical >> 18) & 0x7F) << 2);
+    let mut pointer_desc = read_u32_phys(bus, pointer_addr)?;
+    if pointer_desc & UDT_RESIDENT == 0 {
+        return Err(access_fault(logical));
+    }
+    pointer_desc = update_descriptor(bus, pointer_addr, pointer_desc, used)?;
+
+    let (page_table, page_index, frame_mask) = if page_8k {
+        (
+            pointer_desc & 0xFFFF_FF80,
+            (logical >> 13) & 0x1F,
+            0xFFFF_E000,
+        )
+    } else {
+        (
+            pointer_desc & 0xFFFF_FF00,
+            (logical >> 12) & 0x3F,
+            0xFFFF_F000,
+        )
+    };
+    let mut page_addr = page_table + (page_index << 2);
+    let mut page_desc = read_u32_phys(bus, page_addr)?;
+    match page_desc & 3 {
+        0 => return Err(access_fault(logical)),
+        PDT_INDIRECT => {
+            page_addr = page_desc & 0xFFFF_FFFC;
+            page_desc = read_u32_phys(bus, page_addr)?;
+            // An indirect descriptor must point at a resident page descriptor.
+            if page_desc & 3 == 0 || page_desc & 3 == PDT_INDIRECT {
+                return Err(access_fault(logical));
+            }
+        }
+        _ => {}
+    }
+
+    let write_protected = (root_desc | pointer_desc | page_desc) & DESC_WP != 0;
+    let refused = write_protected || (page_desc & PD_SUPERVISOR != 0 && !supervisor);
+    let modified = if history == HistoryUpdate::Write && !refused {
+        PD_MODIFIED
+    } else {
+        0
+    };
+    let page_desc = update_descriptor(bus, page_addr, page_desc, used | modified)?;
+
+    Ok(PageWalk {
+        physical: (page_desc & frame_mask) | (logical & !frame_mask),
+        descriptor: page_desc,
+        page_mask: !frame_mask,
+        write_protected,
+    })
+}
+
+/// 68040 PTEST: search for `logical` under function code `fc` and return the MMUSR value.
+///
+/// A successful search also loads the translation into the ATC for `instruction` (FC 2/6)
+/// or data accesses. The search updates U bits like a normal access, and PTESTW (`write`)
+/// also sets M.
+pub fn ptest<B: AddressBus>(
+    cpu: &mut CpuCore,
+    bus: &mut B,
+    logical: u32,
+    fc: u8,
+    write: bool,
+) -> u32 {
+    let instruction = fc & 3 == 2;
+    let ttrs = if instruction {
+        [cpu.itt0, cpu.itt1]
+    } else {
+        [cpu.dtt0, cpu.dtt1]
+    };
+    if let Some(&ttr) = ttrs.iter().find(|&&tt| ttr040_matches(tt, logical, fc)) {
+        let mut mmusr = (logical & 0xFFFF_F000) | MMUSR_TRANSPARENT | MMUSR_RESIDENT;
+        if ttr & TTR040_W != 0 {
+            mmusr |= MMUSR_WRITE_PROTECTED;
+        }
+        return mmusr;
+    }
+
+    match walk(
+        cpu,
+        bus,
+        logical,
+        fc & 4 != 0,
+        HistoryUpdate::for_access(write),
+    ) {
+        Ok(page) => {
+            cpu.atc
+                .bank_mut(instruction)
+                .insert(page.atc_entry(fc & 4, logical));
+            // G, U1/U0, S, CM and M come straight from the page descriptor.
+            let mut mmusr = (page.physical & 0xFFFF_F000) | (page.descriptor & 0x07F0);
+            if page.write_protected {
+                mmusr |= MMUSR_WRITE_PROTECTED;
+            }
+            mmusr | MMUSR_RESIDENT
+        }
+        Err(f) if f.kind == MmuFaultKind::BusError => MMUSR_BUS_ERROR,
+        Err(_) => 0,
+    }
+}
@@ -1,18 +1,26 @@
 //! MMU emulation (68030/68040 PMMU)
 
+pub mod atc;
+pub mod m68040;
 mod translation;
 pub mod ttr;
 
+use crate::core::cache::CacheMode;
 use crate::core::cpu::CpuCore;
 use crate::core::memory::AddressBus;
 
-pub use translation::translate;
+pub use translation::{
+    TableSearch, pload_030, probe_instruction, ptest_030, search_030, translate,
+    validate_root_pointer_030, validate_tc_030,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MmuFaultKind {
     ConfigurationError,
     IllegalOperation,
     AccessLevelViolation,
+    /// A table index fell outside the limit of a 68030 root pointer or long descriptor.
+    LimitViolation,
     /// A physical bus error occurred while walking tables / fetching descriptors.
     BusError,
 }
@@ -25,10 +33,29 @@ pub struct MmuFault {
 
 pub type MmuResult<T> = Result<T, MmuFault>;
 
+/// How a table search maintains the used (U) and modified (M) bits of the descriptors
+/// it fetches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryUpdate {
+    /// Leave descriptor memory untouched (PTEST).
+    None,
+    /// Set U in every descriptor on the path.
+    Read,
+    /// Set U, and M in the page descriptor unless the write is refused.
+    Write,
+}
+
+impl HistoryUpdate {
+    pub fn for_access(write: bool) -> Self {
+        if write { Self::Write } else { Self::Read }
+    }
+}
+
 /// Translate a logical address using the CPU's PMMU state (68030/68040 style).
 ///
-/// This is currently based on the (vendored) Musashi PMMU algorithm and focuses on the common
-/// CRP/SRP + TC table-walk behavior. Access permission checks and detailed MMUSR bits are TODO.
+/// The 68030 path is based on the (vendored) Musashi PMMU algorithm; the 68040/68LC040 use
+/// the fixed URP/SRP walk in [`m68040`]. Both enforce write protection and supervisor-only
+/// pages at every table level and write the U/M bits back to descriptor memory.
 ///
 /// The `instruction` parameter indicates whether this is an instruction fetch (true) or
 /// data access (false), used for ITT/DTT selection on 68040.
@@ -42,3 +69,22 @@ pub fn translate_address<B: AddressBus>(
 ) -> MmuResult<u32> {
     translate(cpu, bus, logical, write, supervisor, instruction)
 }
+
+/// How the on-chip caches treat an access to `logical`.
+///
+/// A matching TTR decides first; otherwise a translated page uses the mode of its ATC entry,
+/// which the translation for the access has just loaded. Untranslated accesses are cachable
+/// write-through.
+pub fn cache_mode(cpu: &CpuCore, logical: u32, supervisor: bool, instruction: bool) -> CacheMode {
+    if let Some(mode) = ttr::ttr_cache_mode(cpu, logical, instruction) {
+        return mode;
+    }
+    if cpu.has_pmmu && cpu.pmmu_enabled {
+        let is_040 = !matches!(cpu.cpu_type, crate::core::types::CpuType::M68030);
+        let fc = translation::atc_function_code(is_040, supervisor, instruction);
+        if let Some(entry) = cpu.atc.bank(instruction).lookup(fc, logical) {
+            return entry.cache_mode;
+        }
+    }
+    CacheMode::WriteThrough
+}
@@ -1,9 +1,12 @@
 //! Address translation (PMMU table walk)
 
-use crate::core::cpu::CpuCore;
+use crate::core::cache::CacheMode;
+use crate::core::cpu::{CpuCore, FC_SUPERVISOR_DATA};
 use crate::core::memory::{AddressBus, BusFaultKind};
+use crate::core::types::CpuType;
 
-use super::{MmuFault, MmuFaultKind, MmuResult};
+use super::atc::AtcEntry;
+use super::{HistoryUpdate, MmuFault, MmuFaultKind, MmuResult};
 
 fn buserr(address: u32) -> MmuFault {
     MmuFault {
@@ -12,7 +15,7 @@ fn buserr(address: u32) -> MmuFault {
     }
 }
 
-fn access_fault(address: u32) -> MmuFault {
+pub(super) fn access_fault(address: u32) -> MmuFault {
     MmuFault {
         kind: MmuFaultKind::AccessLevelViolation,
         address,
@@ -26,28 +29,49 @@ fn config_fault(address: u32) -> MmuFault {
     }
 }
 
-fn read_u32_phys<B: AddressBus>(bus: &mut B, addr: u32) -> MmuResult<u32> {
-    bus.try_read_long(addr).map_err(|f| {
-        if matches!(f.kind, BusFaultKind::BusError) {
-            buserr(f.address)
-        } else {
-            buserr(addr)
-        }
-    })
+fn limit_fault(address: u32) -> MmuFault {
+    MmuFault {
+        kind: MmuFaultKind::LimitViolation,
+        address,
+    }
+}
+
+/// Read a descriptor. Table searches run in supervisor data space.
+pub(super) fn read_u32_phys<B: AddressBus>(bus: &mut B, addr: u32) -> MmuResult<u32> {
+    bus.try_read_long_fc(FC_SUPERVISOR_DATA as u8, addr)
+        .map_err(|f| {
+            if matches!(f.kind, BusFaultKind::BusError) {
+                buserr(f.address)
+            } else {
+                buserr(addr)
+            }
+        })
+}
+
+/// Write a descriptor back after setting its U/M bits.
+pub(super) fn write_u32_phys<B: AddressBus>(bus: &mut B, addr: u32, value: u32) -> MmuResult<()> {
+    bus.try_write_long_fc(FC_SUPERVISOR_DATA as u8, addr, value)
+        .map_err(|f| {
+            if matches!(f.kind, BusFaultKind::BusError) {
+                buserr(f.address)
+            } else {
+                buserr(addr)
+            }
+        })
 }
 
 /// Perform 68030/68040 PMMU translation.
 ///
-/// This implementation follows the structure of Musashi's `pmmu_translate_addr()` algorithm.
-/// It currently supports:
-/// - CRP/SRP selection via TC bit 25 (0x0200_0000)
-/// - Root/table modes 2 (4-byte descriptors) and 3 (8-byte descriptors)
-/// - Early-termination descriptors (mode 1) at table A/B/C
+/// Translations are looked up in the CPU's ATC first; a miss walks the tables and loads
+/// the result into the ATC. Supports:
 /// - Transparent Translation Registers (TTRs) for 68030/68040
+/// - The native 68040 walk (URP/SRP, 4K/8K pages) for `M68040`/`M68LC040`, which the
+///   68060 shares
+/// - The 68030 walk, see [`search_030`]
 ///
-/// TODO:
-/// - Access permission checks and precise MMUSR (`mmu_sr`) bits
-/// - Page descriptor root mode (root_limit & 3 == 1)
+/// Write-protected pages refuse writes and supervisor-only pages refuse user accesses,
+/// whichever table level set the bit. A write through an ATC entry whose page is not yet
+/// marked modified searches the tables again so that M reaches the page descriptor.
 pub fn translate<B: AddressBus>(
     cpu: &mut CpuCore,
     bus: &mut B,
@@ -68,28 +92,170 @@ pub fn translate<B: AddressBus>(
     }
 
     // Check Transparent Translation Registers first - they bypass page table walk.
-    if let Some(phys) = super::ttr::check_transparent_translation(cpu, logical, write, instruction)
+    if let Some(result) =
+        super::ttr::check_transparent_translation(cpu, logical, write, instruction)
     {
-        return Ok(phys);
+        return result;
+    }
+
+    let is_040 = matches!(
+        cpu.cpu_type,
+        CpuType::M68040 | CpuType::M68LC040 | CpuType::M68060 | CpuType::M68LC060
+    );
+    let fc = atc_function_code(is_040, supervisor, instruction);
+    let history = HistoryUpdate::for_access(write);
+    let cached = cpu
+        .atc
+        .bank(instruction)
+        .lookup(fc, logical)
+        .filter(|e| !write || e.modified || e.write_protected);
+    let entry = match cached {
+        Some(entry) => entry,
+        None => {
+            let entry = if is_040 {
+                super::m68040::walk(cpu, bus, logical, supervisor, history)?.atc_entry(fc, logical)
+            } else {
+                let search = search_030(cpu, bus, logical, fc, 7, history);
+                if let Some(fault) = search.fault {
+                    return Err(fault);
+                }
+                atc_entry_030(cpu, &search, logical, fc)
+            };
+            cpu.atc.bank_mut(instruction).insert(entry);
+            entry
+        }
+    };
+
+    if (entry.supervisor_only && !supervisor) || (write && entry.write_protected) {
+        return Err(access_fault(logical));
+    }
+    Ok(entry.translate(logical))
+}
+
+/// Translate an instruction fetch from `logical` as [`translate`] would, but without
+/// loading the ATC or setting U bits, to look at the instruction stream ahead of the fetch
+/// proper. `None` if the fetch would fault.
+pub fn probe_instruction<B: AddressBus>(cpu: &CpuCore, bus: &mut B, logical: u32) -> Option<u32> {
+    if !cpu.pmmu_enabled || !cpu.has_pmmu || cpu.exception_processing {
+        return Some(logical);
+    }
+    if let Some(result) = super::ttr::check_transparent_translation(cpu, logical, false, true) {
+        return result.ok();
+    }
+
+    let is_040 = matches!(
+        cpu.cpu_type,
+        CpuType::M68040 | CpuType::M68LC040 | CpuType::M68060 | CpuType::M68LC060
+    );
+    let supervisor = cpu.is_supervisor();
+    let fc = atc_function_code(is_040, supervisor, true);
+    let entry = match cpu.atc.bank(true).lookup(fc, logical) {
+        Some(entry) => entry,
+        None if is_040 => super::m68040::walk(cpu, bus, logical, supervisor, HistoryUpdate::None)
+            .ok()?
+            .atc_entry(fc, logical),
+        None => {
+            let search = search_030(cpu, bus, logical, fc, 7, HistoryUpdate::None);
+            if search.fault.is_some() {
+                return None;
+            }
+            atc_entry_030(cpu, &search, logical, fc)
+        }
+    };
+    if entry.supervisor_only && !supervisor {
+        return None;
+    }
+    Some(entry.translate(logical))
+}
+
+/// Function code an access is cached under. The 68040 ATCs only distinguish user from
+/// supervisor (and keep instruction and data entries in separate banks).
+pub(super) fn atc_function_code(is_040: bool, supervisor: bool, instruction: bool) -> u8 {
+    let s = if supervisor { 4 } else { 0 };
+    if is_040 {
+        s
+    } else {
+        s | if instruction { 2 } else { 1 }
     }
+}
+
+/// 68030 page offset mask from TC.PS (bits 23-20); sizes below 256 bytes are invalid.
+fn page_mask_030(tc: u32) -> u32 {
+    let ps = ((tc >> 20) & 0xF).max(8);
+    (1u32 << ps) - 1
+}
+
+/// 68030 TC bits.
+const TC_ENABLE_030: u32 = 0x8000_0000;
+const TC_SRE: u32 = 0x0200_0000;
+const TC_FCL: u32 = 0x0100_0000;
+
+/// 68030 descriptor bits. S and L/U only exist in the status long of 8-byte descriptors.
+const DESC_WP: u32 = 0x0004;
+const DESC_USED: u32 = 0x0008;
+const DESC_MODIFIED: u32 = 0x0010;
+const DESC_CACHE_INHIBIT: u32 = 0x0040;
+const DESC_SUPERVISOR: u32 = 0x0100;
+const DESC_LOWER_LIMIT: u32 = 0x8000_0000;
+
+/// MMUSR (68030 PSR) bits reported by PTEST.
+pub const MMUSR_BUS_ERROR: u16 = 0x8000;
+pub const MMUSR_LIMIT: u16 = 0x4000;
+pub const MMUSR_SUPERVISOR: u16 = 0x2000;
+pub const MMUSR_WRITE_PROTECTED: u16 = 0x0800;
+pub const MMUSR_INVALID: u16 = 0x0400;
+pub const MMUSR_MODIFIED: u16 = 0x0200;
+pub const MMUSR_TRANSPARENT: u16 = 0x0040;
+
+/// Outcome of a 68030 table search.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TableSearch {
+    /// Physical address; only meaningful when the search reached a page descriptor.
+    pub physical: u32,
+    /// Number of table levels accessed (MMUSR N field).
+    pub levels: u8,
+    /// Physical address of the last descriptor fetched.
+    pub descriptor_address: u32,
+    pub write_protected: bool,
+    pub supervisor_only: bool,
+    pub modified: bool,
+    /// CI bit of the page descriptor.
+    pub cache_inhibit: bool,
+    /// True once a page descriptor was reached.
+    pub complete: bool,
+    pub fault: Option<MmuFault>,
+}
+
+/// Search the 68030 tables for `logical`, stopping after `max_levels` descriptor fetches.
+///
+/// This implementation follows the structure of Musashi's `pmmu_translate_addr()` algorithm,
+/// extended to the full 68030 tree:
+/// - CRP/SRP selection via TC.SRE, and a function-code level ahead of table A via TC.FCL
+/// - Table A-D as given by TIA-TID, ending at the first zero field
+/// - 4-byte and 8-byte descriptors, with L/U limit checks from the root pointer and long
+///   table descriptors
+/// - Early-termination page descriptors at any level, a page descriptor in the root
+///   pointer, and indirect descriptors in the last table
+/// - WP and S accumulated over every level, and U/M maintenance as selected by `history`
+pub fn search_030<B: AddressBus>(
+    cpu: &CpuCore,
+    bus: &mut B,
+    logical: u32,
+    fc: u8,
+    max_levels: u8,
+    history: HistoryUpdate,
+) -> TableSearch {
+    let mut search = TableSearch::default();
+    let supervisor = fc & 4 != 0;
 
     // Root pointer selection: if SRP enabled and supervisor, use SRP; else CRP.
-    let use_srp = (cpu.mmu_tc & 0x0200_0000) != 0 && supervisor;
+    let use_srp = (cpu.mmu_tc & TC_SRE) != 0 && supervisor;
     let (root_aptr, root_limit) = if use_srp {
         (cpu.mmu_srp_aptr, cpu.mmu_srp_limit)
     } else {
         (cpu.mmu_crp_aptr, cpu.mmu_crp_limit)
     };
 
-    // Initial shift / table bits (Musashi):
-    // is = tc[19:16], abits=tc[15:12], bbits=tc[11:8], cbits=tc[7:4]
-    let is = (cpu.mmu_tc >> 16) & 0xF;
-    let abits = (cpu.mmu_tc >> 12) & 0xF;
-    let bbits = (cpu.mmu_tc >> 8) & 0xF;
-    let cbits = (cpu.mmu_tc >> 4) & 0xF;
-
-    let addr_in = logical;
-
     #[inline]
     fn top_index(addr: u32, left_shift: u32, bits: u32) -> u32 {
         if bits == 0 {
@@ -109,100 +275,264 @@ pub fn translate<B: AddressBus>(
         }
     }
 
-    // Table A offset.
-    let mut tofs = top_index(addr_in, is, abits);
-
-    let mut tbl_entry: u32;
-    let tamode: u32;
+    // Table index for every level, paired with the address bits consumed once it is done.
+    let is = (cpu.mmu_tc >> 16) & 0xF;
+    let mut shift = is;
+    let mut indices = Vec::with_capacity(5);
+    if cpu.mmu_tc & TC_FCL != 0 {
+        indices.push((fc as u32, shift));
+    }
+    for bits in table_index_bits(cpu.mmu_tc) {
+        indices.push((top_index(logical, shift, bits), shift + bits));
+        shift += bits;
+    }
 
-    match root_limit & 3 {
-        0 => return Err(config_fault(logical)),
-        1 => return Err(config_fault(logical)), // page descriptor root mode not implemented yet
-        2 => {
-            // 4-byte descriptors
-            tofs = tofs.wrapping_mul(4);
-            let e = read_u32_phys(bus, tofs.wrapping_add(root_aptr & 0xFFFF_FFFC))?;
-            tbl_entry = e;
-            tamode = e & 3;
+    // Descriptor type of the table about to be read: 2 = 4-byte, 3 = 8-byte descriptors.
+    let mut dt = root_limit & 3;
+    match dt {
+        0 => {
+            search.fault = Some(config_fault(logical));
+            return search;
         }
-        3 => {
-            // 8-byte descriptors: mode in high long, pointer/base in low long
-            tofs = tofs.wrapping_mul(8);
-            let hi = read_u32_phys(bus, tofs.wrapping_add(root_aptr & 0xFFFF_FFFC))?;
-            let lo = read_u32_phys(
-                bus,
-                tofs.wrapping_add(root_aptr & 0xFFFF_FFFC).wrapping_add(4),
-            )?;
-            tamode = hi & 3;
-            tbl_entry = lo;
+        1 => {
+            // The root pointer is itself a page descriptor: one linear mapping.
+            search.physical = (root_aptr & 0xFFFF_FF00).wrapping_add(low_bits(logical, is));
+            search.complete = true;
+            return search;
         }
-        _ => unreachable!(),
+        _ => {}
     }
+    let mut table = root_aptr & 0xFFFF_FFF0;
+    // Status long of the root pointer or long table descriptor limiting the next index.
+    let mut limit = Some(root_limit);
 
-    // Table B offset and pointer from A entry.
-    tofs = top_index(addr_in, is + abits, bbits);
-    let mut tptr = tbl_entry & 0xFFFF_FFF0;
-    let tbmode: u32;
+    for (level, &(index, shift)) in indices.iter().enumerate() {
+        if search.levels >= max_levels {
+            return search;
+        }
+        if let Some(status) = limit
+            && !index_within_limit(status, index)
+        {
+            search.fault = Some(limit_fault(logical));
+            return search;
+        }
+        let mut addr = table.wrapping_add(index.wrapping_mul(4 << (dt - 2)));
+        let (mut status, mut entry) = match fetch_descriptor(bus, &mut search, addr, dt == 3) {
+            Ok(v) => v,
+            Err(f) => {
+                search.fault = Some(f);
+                return search;
+            }
+        };
 
-    match tamode {
-        0 => return Err(access_fault(logical)),
-        1 => {
-            // Early termination descriptor (Musashi uses &0xffffff00).
-            let base = tbl_entry & 0xFFFF_FF00;
-            let shift = is + abits;
-            let addr_out = low_bits(addr_in, shift).wrapping_add(base);
-            return Ok(addr_out);
-        }
-        2 => {
-            tofs = tofs.wrapping_mul(4);
-            tbl_entry = read_u32_phys(bus, tofs.wrapping_add(tptr))?;
-            tbmode = tbl_entry & 3;
-        }
-        3 => {
-            tofs = tofs.wrapping_mul(8);
-            let hi = read_u32_phys(bus, tofs.wrapping_add(tptr))?;
-            let lo = read_u32_phys(bus, tofs.wrapping_add(tptr).wrapping_add(4))?;
-            tbmode = hi & 3;
-            tbl_entry = lo;
-        }
-        _ => return Err(access_fault(logical)),
-    }
-
-    // Table C
-    tofs = top_index(addr_in, is + abits + bbits, cbits);
-    tptr = tbl_entry & 0xFFFF_FFF0;
-    let tcmode: u32;
-
-    match tbmode {
-        0 => return Err(access_fault(logical)),
-        1 => {
-            let base = tbl_entry & 0xFFFF_FF00;
-            let shift = is + abits + bbits;
-            let addr_out = low_bits(addr_in, shift).wrapping_add(base);
-            return Ok(addr_out);
+        let mut mode = status & 3;
+        if mode == 0 {
+            search.fault = Some(access_fault(logical));
+            return search;
+        }
+        let mut long = dt == 3;
+        if mode != 1 && level + 1 == indices.len() {
+            // A table descriptor in the last table is indirect: it points at the page
+            // descriptor, which is 4 or 8 bytes as its own type says.
+            if search.levels >= max_levels {
+                return search;
+            }
+            addr = entry & 0xFFFF_FFFC;
+            long = mode == 3;
+            (status, entry) = match fetch_descriptor(bus, &mut search, addr, long) {
+                Ok(v) => v,
+                Err(f) => {
+                    search.fault = Some(f);
+                    return search;
+                }
+            };
+            mode = status & 3;
+            if mode != 1 {
+                search.fault = Some(access_fault(logical));
+                return search;
+            }
+        }
+
+        search.write_protected |= status & DESC_WP != 0;
+        if long {
+            search.supervisor_only |= status & DESC_SUPERVISOR != 0;
+        }
+
+        // Valid descriptors are marked used; a page descriptor is marked modified by a
+        // write that is going to be allowed.
+        let mut updated = status;
+        if history != HistoryUpdate::None {
+            updated |= DESC_USED;
+        }
+        if mode == 1
+            && history == HistoryUpdate::Write
+            && !search.write_protected
+            && (supervisor || !search.supervisor_only)
+        {
+            updated |= DESC_MODIFIED;
         }
-        2 => {
-            tofs = tofs.wrapping_mul(4);
-            tbl_entry = read_u32_phys(bus, tofs.wrapping_add(tptr))?;
-            tcmode = tbl_entry & 3;
+        if updated != status
+            && let Err(f) = write_u32_phys(bus, addr, updated)
+        {
+            search.fault = Some(f);
+            return search;
         }
-        3 => {
-            tofs = tofs.wrapping_mul(8);
-            let hi = read_u32_phys(bus, tofs.wrapping_add(tptr))?;
-            let lo = read_u32_phys(bus, tofs.wrapping_add(tptr).wrapping_add(4))?;
-            tcmode = hi & 3;
-            tbl_entry = lo;
+
+        if mode == 1 {
+            // Page descriptor, possibly an early-termination one (Musashi uses &0xffffff00).
+            search.modified = updated & DESC_MODIFIED != 0;
+            search.cache_inhibit = status & DESC_CACHE_INHIBIT != 0;
+            search.physical = low_bits(logical, shift).wrapping_add(entry & 0xFFFF_FF00);
+            search.complete = true;
+            return search;
         }
-        _ => return Err(access_fault(logical)),
+        limit = long.then_some(status);
+        dt = mode;
+        table = entry & 0xFFFF_FFF0;
     }
 
-    // Final termination at table C.
-    match tcmode {
-        1 => {
-            let base = tbl_entry & 0xFFFF_FF00;
-            let shift = is + abits + bbits + cbits;
-            Ok(low_bits(addr_in, shift).wrapping_add(base))
+    // TIA = 0 leaves no tables to search.
+    search.fault = Some(config_fault(logical));
+    search
+}
+
+/// Table index widths TIA-TID from a 68030 TC; the first zero field ends the tree.
+fn table_index_bits(tc: u32) -> impl Iterator<Item = u32> {
+    [12, 8, 4, 0]
+        .into_iter()
+        .map(move |shift| (tc >> shift) & 0xF)
+        .take_while(|&bits| bits != 0)
+}
+
+/// Check `index` against the L/U bit and LIMIT field of a root pointer or long table
+/// descriptor status long.
+fn index_within_limit(status: u32, index: u32) -> bool {
+    let limit = (status >> 16) & 0x7FFF;
+    if status & DESC_LOWER_LIMIT != 0 {
+        index >= limit
+    } else {
+        index <= limit
+    }
+}
+
+/// Fetch the 4- or 8-byte descriptor at `addr` as `(status, address)` and count the level.
+/// 8-byte descriptors keep the status in the high long and the address in the low long.
+fn fetch_descriptor<B: AddressBus>(
+    bus: &mut B,
+    search: &mut TableSearch,
+    addr: u32,
+    long: bool,
+) -> MmuResult<(u32, u32)> {
+    search.levels += 1;
+    search.descriptor_address = addr;
+    let status = read_u32_phys(bus, addr)?;
+    let entry = if long {
+        read_u32_phys(bus, addr.wrapping_add(4))?
+    } else {
+        status
+    };
+    Ok((status, entry))
+}
+
+/// Check a TC value being loaded into a 68030. With E set, the page size must be at least
+/// 256 bytes and IS, the table index fields and PS must add up to 32 bits.
+pub fn validate_tc_030(tc: u32) -> MmuResult<()> {
+    if tc & TC_ENABLE_030 == 0 {
+        return Ok(());
+    }
+    let ps = (tc >> 20) & 0xF;
+    let is = (tc >> 16) & 0xF;
+    if ps < 8 || is + table_index_bits(tc).sum::<u32>() + ps != 32 {
+        return Err(config_fault(0));
+    }
+    Ok(())
+}
+
+/// Check the limit long of a CRP/SRP value being loaded: DT = 0 is invalid.
+pub fn validate_root_pointer_030(limit: u32) -> MmuResult<()> {
+    if limit & 3 == 0 {
+        return Err(config_fault(0));
+    }
+    Ok(())
+}
+
+/// 68030 PTEST: search the ATC (`level` 0) or the tables (`level` 1-7) for `logical`
+/// under function code `fc`, returning the MMUSR value and the last descriptor address.
+/// `write` is the direction of PTESTW, which decides which TTRs match.
+pub fn ptest_030<B: AddressBus>(
+    cpu: &CpuCore,
+    bus: &mut B,
+    logical: u32,
+    fc: u8,
+    level: u8,
+    write: bool,
+) -> (u16, u32) {
+    if level == 0 {
+        if [cpu.mmu_tt0, cpu.mmu_tt1]
+            .iter()
+            .any(|&tt| super::ttr::ttr_matches(tt, logical, fc, write))
+        {
+            return (MMUSR_TRANSPARENT, 0);
         }
-        _ => Err(access_fault(logical)),
+        let Some(entry) = cpu.atc.data.lookup(fc, logical) else {
+            return (MMUSR_INVALID, 0);
+        };
+        let mut mmusr = 0;
+        if entry.write_protected {
+            mmusr |= MMUSR_WRITE_PROTECTED;
+        }
+        if entry.modified {
+            mmusr |= MMUSR_MODIFIED;
+        }
+        return (mmusr, 0);
+    }
+
+    let search = search_030(cpu, bus, logical, fc, level, HistoryUpdate::None);
+    let mut mmusr = search.levels as u16;
+    match search.fault.map(|f| f.kind) {
+        Some(MmuFaultKind::BusError) => mmusr |= MMUSR_BUS_ERROR | MMUSR_INVALID,
+        Some(MmuFaultKind::LimitViolation) => mmusr |= MMUSR_LIMIT | MMUSR_INVALID,
+        Some(_) => mmusr |= MMUSR_INVALID,
+        None => {}
+    }
+    if search.supervisor_only {
+        mmusr |= MMUSR_SUPERVISOR;
+    }
+    if search.write_protected {
+        mmusr |= MMUSR_WRITE_PROTECTED;
+    }
+    if search.modified {
+        mmusr |= MMUSR_MODIFIED;
+    }
+    (mmusr, search.descriptor_address)
+}
+
+/// 68030 PLOAD: walk the tables for `logical` under `fc` and load the result into the
+/// ATC, updating U (and for PLOADW, M) as the access would. Invalid translations are not
+/// loaded.
+pub fn pload_030<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, logical: u32, fc: u8, write: bool) {
+    let search = search_030(cpu, bus, logical, fc, 7, HistoryUpdate::for_access(write));
+    if search.fault.is_none() && search.complete {
+        let entry = atc_entry_030(cpu, &search, logical, fc);
+        cpu.atc.data.insert(entry);
+    }
+}
+
+fn atc_entry_030(cpu: &CpuCore, search: &TableSearch, logical: u32, fc: u8) -> AtcEntry {
+    let page_mask = page_mask_030(cpu.mmu_tc);
+    AtcEntry {
+        fc,
+        logical: logical & !page_mask,
+        physical: search.physical.wrapping_sub(logical & page_mask),
+        page_mask,
+        write_protected: search.write_protected,
+        supervisor_only: search.supervisor_only,
+        modified: search.modified,
+        global: false,
+        cache_mode: if search.cache_inhibit {
+            CacheMode::Inhibited
+        } else {
+            CacheMode::WriteThrough
+        },
     }
 }
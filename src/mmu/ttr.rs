@@ -3,102 +3,177 @@
 //! Implements TTR matching for 68030 (TT0/TT1) and 68040 (ITT0/ITT1, DTT0/DTT1).
 //! TTRs allow certain address ranges to bypass page table translation.
 
+use crate::core::cache::CacheMode;
 use crate::core::cpu::CpuCore;
 use crate::core::types::CpuType;
 
-/// TTR register format (68030/68040):
+use super::MmuResult;
+use super::translation::access_fault;
+
+/// 68030 TTR register format:
 /// ```text
 /// [31:24] Base Address (compared against address[31:24])
 /// [23:16] Address Mask (1 = ignore bit during comparison)
 /// [15]    E: Enable
-/// [14]    CI: Cache Inhibit (ignored by us)
-/// [13]    R/W: 0=read-only, 1=read/write (68030) or W (68040)
-/// [12]    RWM: R/W Mask (68030) or 0 (68040)
+/// [14]    CI: cache inhibit
+/// [13]    R/W: with RWM clear, 0 = writes only, 1 = reads only
+/// [12]    RWM: R/W Mask, 1 = ignore R/W
 /// [10:8]  FC Base (function code to match)
 /// [4:2]   FC Mask (1 = ignore FC bit)
 /// ```
+///
+/// 68040 TTR register format:
+/// ```text
+/// [31:24] Base Address
+/// [23:16] Address Mask
+/// [15]    E: Enable
+/// [14:13] S: 00 = user accesses only, 01 = supervisor only, 1x = either
+/// [9:8]   U1/U0: user page attributes (ignored by us)
+/// [6:5]   CM: cache mode, as in a page descriptor
+/// [2]     W: write protect
+/// ```
 const TTR_ENABLE: u32 = 0x8000;
+const TTR_RW: u32 = 0x2000;
+const TTR_RWM: u32 = 0x1000;
+const TTR_CACHE_INHIBIT: u32 = 0x4000;
+const TTR_CM_SHIFT: u32 = 5;
 const TTR_BASE_MASK: u32 = 0xFF00_0000;
 const TTR_ADDR_MASK_SHIFT: u32 = 16;
 const TTR_FC_BASE_SHIFT: u32 = 8;
 const TTR_FC_MASK_SHIFT: u32 = 2;
+const TTR040_S_SHIFT: u32 = 13;
 
-/// Check if a single TTR matches the given address and function code.
-///
-/// Returns `true` if the TTR is enabled and matches.
-pub fn ttr_matches(ttr: u32, addr: u32, fc: u8, _write: bool) -> bool {
+/// 68040 W bit: writes through the TTR take an access error.
+pub const TTR040_W: u32 = 0x0004;
+
+/// Check the enable bit and address of a TTR.
+fn ttr_address_matches(ttr: u32, addr: u32) -> bool {
     // Check enable bit
     if (ttr & TTR_ENABLE) == 0 {
         return false;
     }
 
-    // Extract fields
     let base = (ttr & TTR_BASE_MASK) >> 24;
     let addr_mask = (ttr >> TTR_ADDR_MASK_SHIFT) & 0xFF;
-    let fc_base = ((ttr >> TTR_FC_BASE_SHIFT) & 0x07) as u8;
-    let fc_mask = ((ttr >> TTR_FC_MASK_SHIFT) & 0x07) as u8;
 
     // Compare address (masked)
     let addr_high = (addr >> 24) & 0xFF;
-    let addr_match = (addr_high & !addr_mask) == (base & !addr_mask);
+    (addr_high & !addr_mask) == (base & !addr_mask)
+}
+
+/// Check the enable bit, address and function code of a 68030 TTR, ignoring the access
+/// direction.
+fn ttr_selects(ttr: u32, addr: u32, fc: u8) -> bool {
+    let fc_base = ((ttr >> TTR_FC_BASE_SHIFT) & 0x07) as u8;
+    let fc_mask = ((ttr >> TTR_FC_MASK_SHIFT) & 0x07) as u8;
 
     // Compare function code (masked)
-    let fc_match = (fc & !fc_mask) == (fc_base & !fc_mask);
+    ttr_address_matches(ttr, addr) && (fc & !fc_mask) == (fc_base & !fc_mask)
+}
 
-    addr_match && fc_match
+/// Check if a single 68030 TTR matches the given access.
+///
+/// Returns `true` if the TTR is enabled and matches. Unless RWM is set, a TTR only matches
+/// reads (R/W = 1) or only writes (R/W = 0).
+pub fn ttr_matches(ttr: u32, addr: u32, fc: u8, write: bool) -> bool {
+    ttr_selects(ttr, addr, fc) && ((ttr & TTR_RWM) != 0 || ((ttr & TTR_RW) != 0) != write)
+}
+
+/// Check if a single 68040 TTR matches an access to `addr` under function code `fc`.
+///
+/// The S field selects user accesses, supervisor accesses or both; the access direction
+/// does not take part, W only refuses writes once the TTR has matched.
+pub(super) fn ttr040_matches(ttr: u32, addr: u32, fc: u8) -> bool {
+    let supervisor = fc & 4 != 0;
+    let s_match = match (ttr >> TTR040_S_SHIFT) & 3 {
+        0 => !supervisor,
+        1 => supervisor,
+        _ => true,
+    };
+    ttr_address_matches(ttr, addr) && s_match
 }
 
 /// Check if transparent translation applies for the given access.
 ///
 /// For 68030: Checks TT0 and TT1.
-/// For 68040: Checks ITT0/ITT1 for instruction accesses, DTT0/DTT1 for data.
+/// For 68040: Checks ITT0/ITT1 for instruction accesses, DTT0/DTT1 for data. These match
+/// either direction; a write through a write-protected TTR is an access fault.
 ///
-/// Returns `Some(physical_addr)` if transparent translation applies (identity mapping),
-/// or `None` if normal page table translation should be used.
+/// Returns `Some(Ok(physical_addr))` if transparent translation applies (identity mapping),
+/// `Some(Err(_))` if it applies but refuses the access, or `None` if normal page table
+/// translation should be used.
 pub fn check_transparent_translation(
     cpu: &CpuCore,
     addr: u32,
     write: bool,
     instruction: bool,
-) -> Option<u32> {
+) -> Option<MmuResult<u32>> {
     // Determine function code based on access type and privilege level
     let fc = compute_function_code(cpu, instruction);
 
     match cpu.cpu_type {
-        CpuType::M68030 => {
-            // 68030 has two shared TTRs for both instruction and data
-            if ttr_matches(cpu.mmu_tt0, addr, fc, write) {
-                return Some(addr);
-            }
-            if ttr_matches(cpu.mmu_tt1, addr, fc, write) {
-                return Some(addr);
+        // 68030 has two shared TTRs for both instruction and data
+        CpuType::M68030 => (ttr_matches(cpu.mmu_tt0, addr, fc, write)
+            || ttr_matches(cpu.mmu_tt1, addr, fc, write))
+        .then_some(Ok(addr)),
+        CpuType::M68EC040
+        | CpuType::M68LC040
+        | CpuType::M68040
+        | CpuType::M68EC060
+        | CpuType::M68LC060
+        | CpuType::M68060 => {
+            // Instruction accesses check ITT0/ITT1, data accesses DTT0/DTT1.
+            let ttrs = if instruction {
+                [cpu.itt0, cpu.itt1]
+            } else {
+                [cpu.dtt0, cpu.dtt1]
+            };
+            let ttr = ttrs
+                .into_iter()
+                .find(|&ttr| ttr040_matches(ttr, addr, fc))?;
+            if write && ttr & TTR040_W != 0 {
+                return Some(Err(access_fault(addr)));
             }
+            Some(Ok(addr))
         }
-        CpuType::M68EC040 | CpuType::M68LC040 | CpuType::M68040 => {
-            if instruction {
-                // Instruction access: check ITT0, ITT1
-                if ttr_matches(cpu.itt0, addr, fc, write) {
-                    return Some(addr);
-                }
-                if ttr_matches(cpu.itt1, addr, fc, write) {
-                    return Some(addr);
+        // Other CPUs don't have TTRs
+        _ => None,
+    }
+}
+
+/// Cache mode set by the TTR that transparently translates `addr`, if any.
+///
+/// Cache lookups and line fills are reads, so a 68030 TTR only counts if it matches reads.
+pub fn ttr_cache_mode(cpu: &CpuCore, addr: u32, instruction: bool) -> Option<CacheMode> {
+    let fc = compute_function_code(cpu, instruction);
+    match cpu.cpu_type {
+        CpuType::M68030 => [cpu.mmu_tt0, cpu.mmu_tt1]
+            .into_iter()
+            .find(|&ttr| ttr_matches(ttr, addr, fc, false))
+            .map(|ttr| {
+                if ttr & TTR_CACHE_INHIBIT != 0 {
+                    CacheMode::Inhibited
+                } else {
+                    CacheMode::WriteThrough
                 }
+            }),
+        CpuType::M68EC040
+        | CpuType::M68LC040
+        | CpuType::M68040
+        | CpuType::M68EC060
+        | CpuType::M68LC060
+        | CpuType::M68060 => {
+            let ttrs = if instruction {
+                [cpu.itt0, cpu.itt1]
             } else {
-                // Data access: check DTT0, DTT1
-                if ttr_matches(cpu.dtt0, addr, fc, write) {
-                    return Some(addr);
-                }
-                if ttr_matches(cpu.dtt1, addr, fc, write) {
-                    return Some(addr);
-                }
-            }
-        }
-        _ => {
-            // Other CPUs don't have TTRs
+                [cpu.dtt0, cpu.dtt1]
+            };
+            ttrs.into_iter()
+                .find(|&ttr| ttr040_matches(ttr, addr, fc))
+                .map(|ttr| CacheMode::from_cm_040(((ttr >> TTR_CM_SHIFT) & 3) as u8))
         }
+        _ => None,
     }
-
-    None
 }
 
 /// Compute function code for the current access.
@@ -113,12 +188,10 @@ pub fn check_transparent_translation(
 /// - 6: Supervisor Program (instruction)
 /// - 7: CPU Space (interrupt acknowledge, etc.)
 fn compute_function_code(cpu: &CpuCore, instruction: bool) -> u8 {
-    let is_supervisor = cpu.is_supervisor();
-    match (is_supervisor, instruction) {
-        (false, false) => 1, // User Data
-        (false, true) => 2,  // User Program
-        (true, false) => 5,  // Supervisor Data
-        (true, true) => 6,   // Supervisor Program
+    if instruction {
+        cpu.program_fc()
+    } else {
+        cpu.data_fc()
     }
 }
 
@@ -137,7 +210,7 @@ mod tests {
     fn test_ttr_address_match() {
         // TTR matching addresses 0x40xxxxxx (base=0x40, mask=0x00)
         // FCMask=7 (bits 4:2) to match any FC
-        let ttr = 0x4000_801C; // Base=0x40, Mask=0x00, E=1, FCMask=7
+        let ttr = 0x4000_901C; // Base=0x40, Mask=0x00, E=1, RWM=1, FCMask=7
         assert!(ttr_matches(ttr, 0x4000_0000, 5, false));
         assert!(ttr_matches(ttr, 0x40FF_FFFF, 5, false));
         assert!(!ttr_matches(ttr, 0x4100_0000, 5, false));
@@ -148,7 +221,7 @@ mod tests {
     fn test_ttr_address_mask() {
         // TTR matching addresses 0x40-0x4F (base=0x40, mask=0x0F)
         // FCMask=7 (bits 4:2) to match any FC
-        let ttr = 0x400F_801C; // Base=0x40, Mask=0x0F, E=1, FCMask=7
+        let ttr = 0x400F_901C; // Base=0x40, Mask=0x0F, E=1, RWM=1, FCMask=7
         assert!(ttr_matches(ttr, 0x4000_0000, 5, false));
         assert!(ttr_matches(ttr, 0x4F00_0000, 5, false));
         assert!(!ttr_matches(ttr, 0x5000_0000, 5, false));
@@ -157,7 +230,7 @@ mod tests {
     #[test]
     fn test_ttr_fc_match() {
         // TTR matching FC=5 (supervisor data) only
-        let ttr = 0x4000_8500; // Base=0x40, E=1, FC=5, FCMask=0
+        let ttr = 0x4000_9500; // Base=0x40, E=1, RWM=1, FC=5, FCMask=0
         assert!(ttr_matches(ttr, 0x4000_0000, 5, false));
         assert!(!ttr_matches(ttr, 0x4000_0000, 1, false)); // User data
         assert!(!ttr_matches(ttr, 0x4000_0000, 6, false)); // Supervisor program
@@ -166,11 +239,40 @@ mod tests {
     #[test]
     fn test_ttr_fc_mask() {
         // TTR matching any supervisor access (FC=4-7, FCMask=3)
-        let ttr = 0x4000_840C; // Base=0x40, E=1, FC=4, FCMask=3
+        let ttr = 0x4000_940C; // Base=0x40, E=1, RWM=1, FC=4, FCMask=3
         assert!(ttr_matches(ttr, 0x4000_0000, 4, false));
         assert!(ttr_matches(ttr, 0x4000_0000, 5, false));
         assert!(ttr_matches(ttr, 0x4000_0000, 6, false));
         assert!(ttr_matches(ttr, 0x4000_0000, 7, false));
         assert!(!ttr_matches(ttr, 0x4000_0000, 1, false)); // User data
     }
+
+    #[test]
+    fn test_ttr_rw_fields() {
+        // R/W=0, RWM=0: writes only
+        let ttr = 0x4000_801C;
+        assert!(!ttr_matches(ttr, 0x4000_0000, 5, false));
+        assert!(ttr_matches(ttr, 0x4000_0000, 5, true));
+        // R/W=1: reads only
+        assert!(ttr_matches(ttr | TTR_RW, 0x4000_0000, 5, false));
+        assert!(!ttr_matches(ttr | TTR_RW, 0x4000_0000, 5, true));
+        // RWM=1: direction ignored
+        assert!(ttr_matches(ttr | TTR_RWM, 0x4000_0000, 5, false));
+        assert!(ttr_matches(ttr | TTR_RWM, 0x4000_0000, 5, true));
+    }
+
+    #[test]
+    fn test_ttr040_s_field() {
+        // S=00: user only; S=01: supervisor only; S=1x: either
+        let user = 0x4000_8000;
+        assert!(ttr040_matches(user, 0x4000_0000, 1));
+        assert!(!ttr040_matches(user, 0x4000_0000, 5));
+        let supervisor = 0x4000_A000;
+        assert!(!ttr040_matches(supervisor, 0x4000_0000, 2));
+        assert!(ttr040_matches(supervisor, 0x4000_0000, 6));
+        let either = 0x4000_C000;
+        assert!(ttr040_matches(either, 0x4000_0000, 1));
+        assert!(ttr040_matches(either, 0x4000_0000, 5));
+        assert!(!ttr040_matches(either, 0x4100_0000, 5));
+    }
 }
@@ -0,0 +1,182 @@
+//! SCC68070 DMA channels.
+//!
+//! Each channel moves bytes or words between a device and memory, one unit per request.
+//! The host raises a device request with [`DmaChannel::request`], passing the data for a
+//! device-to-memory transfer or getting it back for memory-to-device. The channel steps
+//! MAR as SCR says and counts MTC down; at zero the operation completes (COC). A bus
+//! error stops the channel with ERR set. Either requests the channel's interrupt at the
+//! level in CCR, if CCR enables it.
+
+use crate::core::memory::AddressBus;
+
+/// Register offsets within a channel's block.
+const CSR: u32 = 0x00;
+const CER: u32 = 0x01;
+const DCR: u32 = 0x04;
+const OCR: u32 = 0x05;
+const SCR: u32 = 0x06;
+const CCR: u32 = 0x07;
+const MTC: u32 = 0x0A;
+const MTC_END: u32 = 0x0B;
+const MAR: u32 = 0x0C;
+const MAR_END: u32 = 0x0F;
+const DAR: u32 = 0x14;
+const DAR_END: u32 = 0x17;
+const CPR: u32 = 0x2D;
+
+/// CSR bits. COC and ERR are cleared by writing ones to them.
+pub mod csr {
+    /// Channel operation complete.
+    pub const COC: u8 = 0x80;
+    /// The operation stopped with an error (see CER).
+    pub const ERR: u8 = 0x10;
+    /// Channel active.
+    pub const CA: u8 = 0x08;
+}
+
+/// CER error codes.
+pub mod cer {
+    pub const NONE: u8 = 0x00;
+    pub const BUS_ERROR: u8 = 0x09;
+    pub const SOFTWARE_ABORT: u8 = 0x11;
+}
+
+/// OCR bits.
+pub mod ocr {
+    /// Direction: memory to device when set.
+    pub const D: u8 = 0x80;
+    /// Operand size: byte (0x00) or word (0x10).
+    pub const OS: u8 = 0x30;
+    pub const WORD: u8 = 0x10;
+}
+
+/// SCR memory address count field.
+pub mod scr {
+    pub const MAC: u8 = 0x0C;
+    pub const INCREMENT: u8 = 0x04;
+    pub const DECREMENT: u8 = 0x08;
+}
+
+/// CCR bits.
+pub mod ccr {
+    /// Start operation (reads as 0).
+    pub const SO: u8 = 0x80;
+    /// Software abort (reads as 0).
+    pub const SA: u8 = 0x10;
+    /// Interrupt enable.
+    pub const INE: u8 = 0x08;
+    /// Interrupt priority level.
+    pub const IPL: u8 = 0x07;
+}
+
+/// One DMA channel's registers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DmaChannel {
+    pub csr: u8,
+    pub cer: u8,
+    pub dcr: u8,
+    pub ocr: u8,
+    pub scr: u8,
+    pub ccr: u8,
+    pub cpr: u8,
+    /// Memory transfer counter.
+    pub mtc: u16,
+    /// Memory address register.
+    pub mar: u32,
+    /// Device address register.
+    pub dar: u32,
+}
+
+impl DmaChannel {
+    /// A device request: move one unit between `data` and memory at MAR. Returns `false`
+    /// if the channel is not active.
+    pub fn request<B: AddressBus>(&mut self, bus: &mut B, data: &mut u16) -> bool {
+        if self.csr & csr::CA == 0 {
+            return false;
+        }
+        let word = self.ocr & ocr::OS == ocr::WORD;
+        let result = match (self.ocr & ocr::D != 0, word) {
+            (true, true) => bus.try_read_word(self.mar).map(|v| *data = v),
+            (true, false) => bus.try_read_byte(self.mar).map(|v| *data = v as u16),
+            (false, true) => bus.try_write_word(self.mar, *data),
+            (false, false) => bus.try_write_byte(self.mar, *data as u8),
+        };
+        if result.is_err() {
+            self.csr = (self.csr & !csr::CA) | csr::ERR;
+            self.cer = cer::BUS_ERROR;
+            return true;
+        }
+
+        let step = if word { 2 } else { 1 };
+        match self.scr & scr::MAC {
+            scr::INCREMENT => self.mar = self.mar.wrapping_add(step),
+            scr::DECREMENT => self.mar = self.mar.wrapping_sub(step),
+            _ => {}
+        }
+        self.mtc = self.mtc.wrapping_sub(1);
+        if self.mtc == 0 {
+            self.csr = (self.csr & !csr::CA) | csr::COC;
+        }
+        true
+    }
+
+    /// Interrupt level the channel requests, or 0.
+    pub(crate) fn irq_level(&self) -> u8 {
+        if self.ccr & ccr::INE != 0 && self.csr & (csr::COC | csr::ERR) != 0 {
+            self.ccr & ccr::IPL
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn read(&self, offset: u32) -> u8 {
+        match offset {
+            CSR => self.csr,
+            CER => self.cer,
+            DCR => self.dcr,
+            OCR => self.ocr,
+            SCR => self.scr,
+            CCR => self.ccr,
+            CPR => self.cpr,
+            MTC..=MTC_END => (self.mtc >> (8 * (MTC_END - offset))) as u8,
+            MAR..=MAR_END => (self.mar >> (8 * (MAR_END - offset))) as u8,
+            DAR..=DAR_END => (self.dar >> (8 * (DAR_END - offset))) as u8,
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn write(&mut self, offset: u32, value: u8) {
+        match offset {
+            CSR => self.csr &= !(value & (csr::COC | csr::ERR)),
+            DCR => self.dcr = value,
+            OCR => self.ocr = value,
+            SCR => self.scr = value,
+            CCR => self.control(value),
+            CPR => self.cpr = value,
+            MTC..=MTC_END => {
+                self.mtc = set_byte(self.mtc as u32, MTC_END - offset, value) as u16;
+            }
+            MAR..=MAR_END => self.mar = set_byte(self.mar, MAR_END - offset, value),
+            DAR..=DAR_END => self.dar = set_byte(self.dar, DAR_END - offset, value),
+            _ => {}
+        }
+    }
+
+    /// A write to CCR: start or abort the operation.
+    fn control(&mut self, value: u8) {
+        self.ccr = value & (ccr::INE | ccr::IPL);
+        if value & ccr::SA != 0 && self.csr & csr::CA != 0 {
+            self.csr = (self.csr & !csr::CA) | csr::ERR;
+            self.cer = cer::SOFTWARE_ABORT;
+        } else if value & ccr::SO != 0 {
+            self.csr = (self.csr & !(csr::COC | csr::ERR)) | csr::CA;
+            self.cer = cer::NONE;
+        }
+    }
+}
+
+/// `value` with byte `index` (0 = least significant) replaced by `byte`.
+fn set_byte(value: u32, index: u32, byte: u8) -> u32 {
+    let shift = 8 * index;
+    (value & !(0xFF << shift)) | (byte as u32) << shift
+}
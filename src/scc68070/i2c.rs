@@ -0,0 +1,150 @@
+//! SCC68070 I²C bus interface.
+//!
+//! Only master operation is modelled. The slaves on the bus are an [`I2cBus`] attached
+//! with [`I2c::attach`], and every transfer completes at once, setting PIN:
+//!
+//!   START  with the bus free, write ISR with MST, TRX and BB set: IDR holds the slave
+//!          address and direction, and LRB the slave's acknowledge
+//!   byte   with the bus busy, write ISR with MST and BB set and PIN clear: TRX set sends
+//!          IDR, TRX clear receives into IDR, acknowledging it if ICR ACK is set
+//!   STOP   write ISR with MST set and BB clear
+//!
+//! Without an attached bus nothing answers, so LRB reads 1 (no acknowledge).
+
+use std::fmt;
+
+/// Register offsets from [`super::BASE`].
+const IDR: u32 = 0x2001;
+const IAR: u32 = 0x2003;
+const ISR: u32 = 0x2005;
+const ICR: u32 = 0x2007;
+const ICCR: u32 = 0x2009;
+
+/// ISR bits.
+pub mod isr {
+    /// Master mode.
+    pub const MST: u8 = 0x80;
+    /// Transmitter.
+    pub const TRX: u8 = 0x40;
+    /// Bus busy (between START and STOP).
+    pub const BB: u8 = 0x20;
+    /// A transfer has finished; requests the I²C interrupt.
+    pub const PIN: u8 = 0x10;
+    /// Last received bit: the acknowledge of the last byte sent (0 = acknowledged).
+    pub const LRB: u8 = 0x01;
+}
+
+/// ICR bits.
+pub mod icr {
+    /// Enable the interface.
+    pub const ESO: u8 = 0x08;
+    /// Acknowledge received bytes.
+    pub const ACK: u8 = 0x04;
+}
+
+/// The slave devices on the I²C bus, as seen by the master.
+pub trait I2cBus {
+    /// START followed by the address byte (slave address and R/W bit). Returns whether a
+    /// slave acknowledged.
+    fn start(&mut self, address: u8) -> bool;
+    /// Send a byte to the addressed slave. Returns whether it acknowledged.
+    fn write(&mut self, byte: u8) -> bool;
+    /// Receive a byte from the addressed slave; `ack` is the master's acknowledge.
+    fn read(&mut self, ack: bool) -> u8;
+    fn stop(&mut self);
+}
+
+/// I²C registers and the attached bus.
+#[derive(Default)]
+pub struct I2c {
+    pub idr: u8,
+    pub iar: u8,
+    pub isr: u8,
+    pub icr: u8,
+    pub iccr: u8,
+    bus: Option<Box<dyn I2cBus>>,
+}
+
+impl fmt::Debug for I2c {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("I2c")
+            .field("idr", &self.idr)
+            .field("iar", &self.iar)
+            .field("isr", &self.isr)
+            .field("icr", &self.icr)
+            .field("iccr", &self.iccr)
+            .field("bus", &self.bus.is_some())
+            .finish()
+    }
+}
+
+impl I2c {
+    /// Connect the slave devices.
+    pub fn attach(&mut self, bus: Box<dyn I2cBus>) {
+        self.bus = Some(bus);
+    }
+
+    pub(crate) fn pending(&self) -> bool {
+        self.icr & icr::ESO != 0 && self.isr & isr::PIN != 0
+    }
+
+    pub(crate) fn read(&self, offset: u32) -> u8 {
+        match offset {
+            IDR => self.idr,
+            IAR => self.iar,
+            ISR => self.isr,
+            ICR => self.icr,
+            ICCR => self.iccr,
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn write(&mut self, offset: u32, value: u8) {
+        match offset {
+            IDR => self.idr = value,
+            IAR => self.iar = value,
+            ISR => self.control(value),
+            ICR => self.icr = value,
+            ICCR => self.iccr = value,
+            _ => {}
+        }
+    }
+
+    /// A write to ISR: start, stop or move a byte.
+    fn control(&mut self, value: u8) {
+        if self.icr & icr::ESO == 0 || value & isr::MST == 0 {
+            self.isr = (self.isr & !(isr::MST | isr::TRX)) | (value & (isr::MST | isr::TRX));
+            return;
+        }
+        let busy = self.isr & isr::BB != 0;
+        let mode = value & (isr::MST | isr::TRX);
+        match (busy, value & isr::BB != 0) {
+            (false, true) => {
+                let ack = self.bus.as_mut().is_some_and(|bus| bus.start(self.idr));
+                self.finish(mode | isr::BB, ack);
+            }
+            (true, false) => {
+                if let Some(bus) = self.bus.as_mut() {
+                    bus.stop();
+                }
+                self.isr = mode;
+            }
+            (true, true) if value & isr::PIN == 0 => {
+                let ack = if value & isr::TRX != 0 {
+                    self.bus.as_mut().is_some_and(|bus| bus.write(self.idr))
+                } else {
+                    let ack = self.icr & icr::ACK != 0;
+                    self.idr = self.bus.as_mut().map_or(0xFF, |bus| bus.read(ack));
+                    ack
+                };
+                self.finish(mode | isr::BB, ack);
+            }
+            _ => {}
+        }
+    }
+
+    /// End a transfer: PIN set, LRB from the acknowledge.
+    fn finish(&mut self, state: u8, ack: bool) {
+        self.isr = state | isr::PIN | if ack { 0 } else { isr::LRB };
+    }
+}
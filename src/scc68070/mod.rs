@@ -0,0 +1,170 @@
+//! # SCC68070
+//!
+//! On-chip peripherals of the Philips SCC68070.
+//!
+//! Besides its 68010-compatible core ([`CpuType::SCC68070`](crate::CpuType::SCC68070)),
+//! the SCC68070 integrates a UART, three 16-bit timers, an I²C bus interface, two DMA
+//! channels and an interrupt priority controller, with their registers from
+//! [`BASE`]. [`Scc68070`] models them as a block that is not wired to the CPU by itself;
+//! the host connects it:
+//!
+//! - the host bus forwards accesses to addresses [`Scc68070::contains`] to
+//!   [`Scc68070::read_byte`] and friends;
+//! - before each step, [`Scc68070::update_irq`] drives the CPU's interrupt level from the
+//!   on-chip sources and the host's own;
+//! - [`AddressBus::interrupt_acknowledge`](crate::AddressBus::interrupt_acknowledge)
+//!   asks [`Scc68070::interrupt_acknowledge`] first, which supplies the on-chip vector
+//!   (57-63 for levels 1-7) when an on-chip source is at that level;
+//! - after each step, [`Scc68070::tick`] advances the timers by the cycles it took.
+//!
+//! The priority controller gives each source its level: PICR1 the timers (bits 2-0) and
+//! I²C (bits 6-4), PICR2 the UART transmitter (bits 2-0) and receiver (bits 6-4), LIR the
+//! external INT2 (bits 2-0) and INT1 (bits 6-4) inputs, and each DMA channel's CCR its own.
+//! Level 0 disables a source.
+
+pub mod dma;
+pub mod i2c;
+pub mod timer;
+pub mod uart;
+
+use crate::core::cpu::CpuCore;
+use dma::DmaChannel;
+use i2c::I2c;
+use timer::Timers;
+use uart::Uart;
+
+/// Address of the on-chip register area.
+pub const BASE: u32 = 0x8000_0000;
+
+/// Register offsets from [`BASE`].
+const LIR: u32 = 0x1001;
+const PICR1: u32 = 0x2045;
+const PICR2: u32 = 0x2047;
+const DMA: u32 = 0x4000;
+/// Size of each DMA channel's register block.
+const DMA_CHANNEL: u32 = 0x40;
+
+/// First of the vectors on-chip interrupts are acknowledged with; level n uses
+/// `ON_CHIP_VECTOR + n`.
+pub const ON_CHIP_VECTOR: u32 = 56;
+
+/// The SCC68070 peripheral block.
+#[derive(Debug, Default)]
+pub struct Scc68070 {
+    pub uart: Uart,
+    pub timers: Timers,
+    pub i2c: I2c,
+    pub dma: [DmaChannel; 2],
+    pub picr1: u8,
+    pub picr2: u8,
+    pub lir: u8,
+    /// External INT1 and INT2 inputs.
+    int_pins: [bool; 2],
+}
+
+impl Scc68070 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether `address` is in the on-chip register area.
+    pub fn contains(&self, address: u32) -> bool {
+        address & 0xFFFF_0000 == BASE
+    }
+
+    pub fn read_byte(&mut self, address: u32) -> u8 {
+        let offset = address.wrapping_sub(BASE);
+        match offset {
+            LIR => self.lir,
+            0x2000..=0x200F => self.i2c.read(offset),
+            0x2010..=0x201F => self.uart.read(offset),
+            0x2020..=0x202F => self.timers.read(offset),
+            PICR1 => self.picr1,
+            PICR2 => self.picr2,
+            0x4000..=0x407F => {
+                let channel = ((offset - DMA) / DMA_CHANNEL) as usize;
+                self.dma[channel].read(offset % DMA_CHANNEL)
+            }
+            _ => 0,
+        }
+    }
+
+    pub fn read_word(&mut self, address: u32) -> u16 {
+        let high = self.read_byte(address) as u16;
+        (high << 8) | self.read_byte(address.wrapping_add(1)) as u16
+    }
+
+    pub fn read_long(&mut self, address: u32) -> u32 {
+        let high = self.read_word(address) as u32;
+        (high << 16) | self.read_word(address.wrapping_add(2)) as u32
+    }
+
+    pub fn write_byte(&mut self, address: u32, value: u8) {
+        let offset = address.wrapping_sub(BASE);
+        match offset {
+            LIR => self.lir = value,
+            0x2000..=0x200F => self.i2c.write(offset, value),
+            0x2010..=0x201F => self.uart.write(offset, value),
+            0x2020..=0x202F => self.timers.write(offset, value),
+            PICR1 => self.picr1 = value,
+            PICR2 => self.picr2 = value,
+            0x4000..=0x407F => {
+                let channel = ((offset - DMA) / DMA_CHANNEL) as usize;
+                self.dma[channel].write(offset % DMA_CHANNEL, value);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn write_word(&mut self, address: u32, value: u16) {
+        self.write_byte(address, (value >> 8) as u8);
+        self.write_byte(address.wrapping_add(1), value as u8);
+    }
+
+    pub fn write_long(&mut self, address: u32, value: u32) {
+        self.write_word(address, (value >> 16) as u16);
+        self.write_word(address.wrapping_add(2), value as u16);
+    }
+
+    /// Set the external INT1 (`pin` 1) or INT2 (`pin` 2) input.
+    pub fn set_int_pin(&mut self, pin: usize, active: bool) {
+        self.int_pins[pin - 1] = active;
+    }
+
+    /// Advance the timers by `cycles` CPU clocks.
+    pub fn tick(&mut self, cycles: i32) {
+        self.timers.tick(cycles);
+    }
+
+    /// Levels of the sources requesting an interrupt.
+    fn requests(&self) -> [u8; 8] {
+        let level = |active: bool, level: u8| if active { level & 7 } else { 0 };
+        [
+            level(self.timers.pending(), self.picr1),
+            level(self.i2c.pending(), self.picr1 >> 4),
+            level(self.uart.tx_pending(), self.picr2),
+            level(self.uart.rx_pending(), self.picr2 >> 4),
+            level(self.int_pins[0], self.lir >> 4),
+            level(self.int_pins[1], self.lir),
+            self.dma[0].irq_level(),
+            self.dma[1].irq_level(),
+        ]
+    }
+
+    /// Highest level an on-chip source is requesting, or 0.
+    pub fn irq_level(&self) -> u8 {
+        self.requests().into_iter().max().unwrap_or(0)
+    }
+
+    /// Drive the CPU's interrupt level with the higher of the on-chip request and
+    /// `external`, the level off-chip devices request.
+    pub fn update_irq(&self, cpu: &mut CpuCore, external: u8) {
+        cpu.set_irq(self.irq_level().max(external));
+    }
+
+    /// Vector for an interrupt acknowledge at `level`, if an on-chip source is requesting
+    /// that level.
+    pub fn interrupt_acknowledge(&self, level: u8) -> Option<u32> {
+        (level != 0 && self.requests().contains(&level)).then_some(ON_CHIP_VECTOR + level as u32)
+    }
+}
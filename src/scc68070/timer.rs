@@ -0,0 +1,153 @@
+//! SCC68070 timers.
+//!
+//! Timer 0 counts up from the reload register (RRR) at the CPU clock divided by 96 and
+//! reloads when it overflows. Timers 1 and 2 each run in the mode TCR selects for them:
+//!
+//!   match          set MA when the timer equals timer 0
+//!   capture        copy timer 0 on an input event and set CA
+//!   event counter  count input events and set OV on overflow
+//!
+//! Input events come from the host through [`Timers::input`]. Any TSR flag requests the
+//! timer interrupt; software clears flags by writing ones to them.
+
+/// Register offsets from [`super::BASE`].
+const TSR: u32 = 0x2020;
+const TCR: u32 = 0x2021;
+const RRR: u32 = 0x2022;
+const RRR_END: u32 = 0x2023;
+const T0: u32 = 0x2024;
+/// Last byte of T2; T1 and T2 follow T0.
+const T2_END: u32 = 0x2029;
+
+/// CPU clocks per timer count.
+pub const PRESCALE: i32 = 96;
+
+/// TSR bits.
+pub mod tsr {
+    pub const OV0: u8 = 0x80;
+    pub const MA1: u8 = 0x40;
+    pub const CA1: u8 = 0x20;
+    pub const OV1: u8 = 0x10;
+    pub const MA2: u8 = 0x08;
+    pub const CA2: u8 = 0x04;
+    pub const OV2: u8 = 0x02;
+}
+
+/// Timer 1/2 modes in TCR (timer 1 in bits 5-4, timer 2 in bits 1-0).
+pub mod mode {
+    pub const INHIBIT: u8 = 0;
+    pub const MATCH: u8 = 1;
+    pub const CAPTURE: u8 = 2;
+    pub const EVENT: u8 = 3;
+}
+
+/// Timer registers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Timers {
+    pub tsr: u8,
+    pub tcr: u8,
+    pub reload: u16,
+    /// T0, T1 and T2.
+    pub counter: [u16; 3],
+    /// CPU clocks towards the next count.
+    prescale: i32,
+}
+
+impl Timers {
+    /// Mode of timer 1 or 2.
+    fn mode(&self, timer: usize) -> u8 {
+        match timer {
+            1 => (self.tcr >> 4) & 3,
+            _ => self.tcr & 3,
+        }
+    }
+
+    /// TSR bits of timer 1 or 2: (match, capture, overflow).
+    fn flags(timer: usize) -> (u8, u8, u8) {
+        match timer {
+            1 => (tsr::MA1, tsr::CA1, tsr::OV1),
+            _ => (tsr::MA2, tsr::CA2, tsr::OV2),
+        }
+    }
+
+    /// Advance by `cycles` CPU clocks.
+    pub fn tick(&mut self, cycles: i32) {
+        self.prescale += cycles;
+        while self.prescale >= PRESCALE {
+            self.prescale -= PRESCALE;
+            self.count();
+        }
+    }
+
+    fn count(&mut self) {
+        self.counter[0] = match self.counter[0].checked_add(1) {
+            Some(t0) => t0,
+            None => {
+                self.tsr |= tsr::OV0;
+                self.reload
+            }
+        };
+        for timer in 1..=2 {
+            if self.mode(timer) == mode::MATCH && self.counter[timer] == self.counter[0] {
+                self.tsr |= Self::flags(timer).0;
+            }
+        }
+    }
+
+    /// An event on the input of timer 1 or 2.
+    pub fn input(&mut self, timer: usize) {
+        let (_, capture, overflow) = Self::flags(timer);
+        match self.mode(timer) {
+            mode::CAPTURE => {
+                self.counter[timer] = self.counter[0];
+                self.tsr |= capture;
+            }
+            mode::EVENT => {
+                let (count, wrapped) = self.counter[timer].overflowing_add(1);
+                self.counter[timer] = count;
+                if wrapped {
+                    self.tsr |= overflow;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub(crate) fn pending(&self) -> bool {
+        self.tsr != 0
+    }
+
+    pub(crate) fn read(&self, offset: u32) -> u8 {
+        let byte = |word: u16| {
+            if offset & 1 == 0 {
+                (word >> 8) as u8
+            } else {
+                word as u8
+            }
+        };
+        match offset {
+            TSR => self.tsr,
+            TCR => self.tcr,
+            RRR..=RRR_END => byte(self.reload),
+            T0..=T2_END => byte(self.counter[((offset - T0) / 2) as usize]),
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn write(&mut self, offset: u32, value: u8) {
+        let set_byte = |word: &mut u16| {
+            *word = if offset & 1 == 0 {
+                (*word & 0x00FF) | (value as u16) << 8
+            } else {
+                (*word & 0xFF00) | value as u16
+            }
+        };
+        match offset {
+            TSR => self.tsr &= !value,
+            TCR => self.tcr = value,
+            RRR..=RRR_END => set_byte(&mut self.reload),
+            T0..=T2_END => set_byte(&mut self.counter[((offset - T0) / 2) as usize]),
+            _ => {}
+        }
+    }
+}
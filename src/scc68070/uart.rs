@@ -0,0 +1,145 @@
+//! SCC68070 UART.
+//!
+//! One asynchronous channel. Characters the CPU writes to UTHR are handed to the host
+//! through [`Uart::take_transmitted`], and the host delivers incoming characters with
+//! [`Uart::receive`]. Both complete at once: the baud rates UCSR selects are not modelled,
+//! so the transmitter is ready again as soon as it is enabled.
+
+use std::collections::VecDeque;
+
+/// Register offsets from [`super::BASE`].
+const UMR: u32 = 0x2011;
+const USR: u32 = 0x2013;
+const UCSR: u32 = 0x2015;
+const UCR: u32 = 0x2017;
+const UTHR: u32 = 0x2019;
+const URHR: u32 = 0x201B;
+
+/// USR bits.
+pub mod usr {
+    /// A received character is waiting in URHR.
+    pub const RXRDY: u8 = 0x01;
+    /// The receive holding register is full.
+    pub const FFULL: u8 = 0x02;
+    /// UTHR can take a character.
+    pub const TXRDY: u8 = 0x04;
+    /// The transmitter has nothing left to send.
+    pub const TXEMT: u8 = 0x08;
+    /// Overrun: a character arrived while URHR was still full, and was lost.
+    pub const OE: u8 = 0x10;
+    /// Errors reset by the reset-error-status command.
+    pub const ERRORS: u8 = 0xF0;
+}
+
+/// UCR commands.
+pub mod ucr {
+    pub const RX_ENABLE: u8 = 0x01;
+    pub const RX_DISABLE: u8 = 0x02;
+    pub const TX_ENABLE: u8 = 0x04;
+    pub const TX_DISABLE: u8 = 0x08;
+    /// Miscellaneous command field.
+    pub const MISC: u8 = 0x70;
+    pub const RESET_RECEIVER: u8 = 0x20;
+    pub const RESET_TRANSMITTER: u8 = 0x30;
+    pub const RESET_ERROR_STATUS: u8 = 0x40;
+}
+
+/// UART registers and the characters in flight.
+#[derive(Debug, Clone, Default)]
+pub struct Uart {
+    pub umr: u8,
+    pub ucsr: u8,
+    /// RXRDY, FFULL and the error bits; TXRDY and TXEMT follow the transmitter enable.
+    usr: u8,
+    urhr: u8,
+    rx_enabled: bool,
+    tx_enabled: bool,
+    transmitted: VecDeque<u8>,
+}
+
+impl Uart {
+    /// The status register as the CPU reads it.
+    pub fn status(&self) -> u8 {
+        if self.tx_enabled {
+            self.usr | usr::TXRDY | usr::TXEMT
+        } else {
+            self.usr
+        }
+    }
+
+    /// Deliver a character from the line. It is dropped, with an overrun, if the last one
+    /// has not been read yet, and ignored while the receiver is disabled.
+    pub fn receive(&mut self, byte: u8) {
+        if !self.rx_enabled {
+            return;
+        }
+        if self.usr & usr::RXRDY != 0 {
+            self.usr |= usr::OE;
+        } else {
+            self.urhr = byte;
+            self.usr |= usr::RXRDY | usr::FFULL;
+        }
+    }
+
+    /// Next character the CPU has sent, oldest first.
+    pub fn take_transmitted(&mut self) -> Option<u8> {
+        self.transmitted.pop_front()
+    }
+
+    /// Receiver interrupt condition: a character is waiting.
+    pub(crate) fn rx_pending(&self) -> bool {
+        self.rx_enabled && self.usr & usr::RXRDY != 0
+    }
+
+    /// Transmitter interrupt condition: UTHR is empty.
+    pub(crate) fn tx_pending(&self) -> bool {
+        self.tx_enabled
+    }
+
+    pub(crate) fn read(&mut self, offset: u32) -> u8 {
+        match offset {
+            UMR => self.umr,
+            USR => self.status(),
+            UCSR => self.ucsr,
+            URHR => {
+                self.usr &= !(usr::RXRDY | usr::FFULL);
+                self.urhr
+            }
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn write(&mut self, offset: u32, value: u8) {
+        match offset {
+            UMR => self.umr = value,
+            UCSR => self.ucsr = value,
+            UCR => self.command(value),
+            UTHR if self.tx_enabled => self.transmitted.push_back(value),
+            _ => {}
+        }
+    }
+
+    fn command(&mut self, value: u8) {
+        match value & ucr::MISC {
+            ucr::RESET_RECEIVER => {
+                self.rx_enabled = false;
+                self.usr &= !(usr::RXRDY | usr::FFULL);
+            }
+            ucr::RESET_TRANSMITTER => self.tx_enabled = false,
+            ucr::RESET_ERROR_STATUS => self.usr &= !usr::ERRORS,
+            _ => {}
+        }
+        if value & ucr::RX_ENABLE != 0 {
+            self.rx_enabled = true;
+        }
+        if value & ucr::RX_DISABLE != 0 {
+            self.rx_enabled = false;
+        }
+        if value & ucr::TX_ENABLE != 0 {
+            self.tx_enabled = true;
+        }
+        if value & ucr::TX_DISABLE != 0 {
+            self.tx_enabled = false;
+        }
+    }
+}
@@ -0,0 +1,149 @@
+//! Bus-cycle timing: per-cycle offsets reported to the bus and DTACK wait states.
+
+mod common;
+
+use common::flat::{CODE, FlatBus, step_cycles, supervisor_cpu};
+use m68k::core::memory::{AddressBus, BusCycle};
+use m68k::{CpuCore, CpuType};
+
+/// Memory that logs every reported bus cycle and delays DTACK for `slow` addresses.
+struct TestBus {
+    flat: FlatBus,
+    cycles: Vec<BusCycle>,
+    slow: std::ops::Range<u32>,
+    wait_states: u32,
+}
+
+impl TestBus {
+    fn new() -> Self {
+        Self {
+            flat: FlatBus::new(),
+            cycles: Vec::new(),
+            slow: 0..0,
+            wait_states: 0,
+        }
+    }
+}
+
+impl AddressBus for TestBus {
+    fn read_byte(&mut self, address: u32) -> u8 {
+        self.flat.read_byte(address)
+    }
+
+    fn read_word(&mut self, address: u32) -> u16 {
+        self.flat.read_word(address)
+    }
+
+    fn read_long(&mut self, address: u32) -> u32 {
+        self.flat.read_long(address)
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) {
+        self.flat.write_byte(address, value);
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) {
+        self.flat.write_word(address, value);
+    }
+
+    fn write_long(&mut self, address: u32, value: u32) {
+        self.flat.write_long(address, value);
+    }
+
+    fn bus_cycle(&mut self, cycle: BusCycle) -> u32 {
+        self.cycles.push(cycle);
+        if self.slow.contains(&cycle.address) {
+            self.wait_states
+        } else {
+            0
+        }
+    }
+}
+
+/// `cpu_type` with bus-cycle timing set to `timing` and `code` at 0x1000.
+fn setup(cpu_type: CpuType, timing: bool, code: &[u16]) -> (CpuCore, TestBus) {
+    let mut cpu = supervisor_cpu(cpu_type);
+    cpu.set_bus_cycle_timing(timing);
+    let mut bus = TestBus::new();
+    bus.flat.write_words(CODE, code);
+    (cpu, bus)
+}
+
+/// (address, fc, offset, size, write) of each logged cycle.
+fn summary(bus: &TestBus) -> Vec<(u32, u8, i32, u8, bool)> {
+    bus.cycles
+        .iter()
+        .map(|c| (c.address, c.fc, c.offset, c.size, c.write))
+        .collect()
+}
+
+// MOVE.W $4000,D0
+const READ_4000: [u16; 2] = [0x3038, 0x4000];
+
+#[test]
+fn test_cycles_reported_with_offsets() {
+    let (mut cpu, mut bus) = setup(CpuType::M68000, true, &READ_4000);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(
+        summary(&bus),
+        vec![
+            (0x1000, 6, 0, 2, false),
+            (0x1002, 6, 4, 2, false),
+            (0x4000, 5, 8, 2, false),
+        ]
+    );
+
+    // Without bus-cycle timing the bus hears nothing.
+    let (mut cpu, mut bus) = setup(CpuType::M68000, false, &READ_4000);
+    step_cycles(&mut cpu, &mut bus);
+    assert!(bus.cycles.is_empty());
+}
+
+#[test]
+fn test_wait_states_stall_the_instruction() {
+    let (mut cpu, mut bus) = setup(CpuType::M68000, false, &READ_4000);
+    let base = step_cycles(&mut cpu, &mut bus);
+
+    // Slow program memory pushes the data cycle back; slow data only adds its own waits.
+    let (mut cpu, mut bus) = setup(CpuType::M68000, true, &READ_4000);
+    bus.slow = 0x1000..0x1004;
+    bus.wait_states = 3;
+    assert_eq!(step_cycles(&mut cpu, &mut bus), base + 6);
+    assert_eq!(bus.cycles[2].offset, 14);
+
+    let (mut cpu, mut bus) = setup(CpuType::M68000, true, &READ_4000);
+    bus.slow = 0x4000..0x4002;
+    bus.wait_states = 2;
+    assert_eq!(step_cycles(&mut cpu, &mut bus), base + 2);
+}
+
+#[test]
+fn test_execute_charges_wait_states_to_the_budget() {
+    // MOVE.W $4000,D0 ; MOVE.W $4000,D1
+    let code = [0x3038, 0x4000, 0x3238, 0x4000];
+    let (mut cpu, mut bus) = setup(CpuType::M68000, false, &code);
+    let base = cpu.execute(&mut bus, 1);
+
+    let (mut cpu, mut bus) = setup(CpuType::M68000, true, &code);
+    bus.slow = 0x4000..0x4002;
+    bus.wait_states = 4;
+    assert_eq!(cpu.execute(&mut bus, 1), base + 4);
+    assert_eq!(cpu.cycles_remaining, 1 - (base + 4));
+    assert_eq!(cpu.pc, 0x1004, "one instruction ran");
+}
+
+#[test]
+fn test_long_access_width_follows_the_data_bus() {
+    // MOVE.L D0,$4000
+    let code = [0x21C0, 0x4000];
+    let (mut cpu, mut bus) = setup(CpuType::M68000, true, &code);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(
+        summary(&bus)[2..],
+        [(0x4000, 5, 8, 2, true), (0x4002, 5, 12, 2, true)]
+    );
+
+    let (mut cpu, mut bus) = setup(CpuType::M68020, true, &code);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(summary(&bus)[2..], [(0x4000, 5, 6, 4, true)]);
+}
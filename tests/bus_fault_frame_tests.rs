@@ -0,0 +1,405 @@
+//! Bus fault stack frames with RTE continuation: 68010 format $8, 68020/68030
+//! format $A and $B, 68040 format $7, 68060 format $4 and CPU32 format $C.
+
+mod common;
+
+use common::flat::{FlatBus, STACK};
+use m68k::core::memory::{AddressBus, BusFault, BusFaultKind};
+use m68k::{CpuCore, CpuType};
+
+const HANDLER: u32 = 0x3000;
+/// Accesses to this long word raise a bus error while `faulting` is set.
+const FAULT_ADDR: u32 = 0x6000;
+
+struct TestBus {
+    flat: FlatBus,
+    faulting: bool,
+}
+
+impl TestBus {
+    fn check(&self, address: u32) -> Result<(), BusFault> {
+        if self.faulting && address & !3 == FAULT_ADDR {
+            return Err(BusFault {
+                kind: BusFaultKind::BusError,
+                address,
+            });
+        }
+        Ok(())
+    }
+}
+
+impl AddressBus for TestBus {
+    fn read_byte(&mut self, address: u32) -> u8 {
+        self.flat.read_byte(address)
+    }
+
+    fn read_word(&mut self, address: u32) -> u16 {
+        self.flat.read_word(address)
+    }
+
+    fn read_long(&mut self, address: u32) -> u32 {
+        self.flat.read_long(address)
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) {
+        self.flat.write_byte(address, value);
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) {
+        self.flat.write_word(address, value);
+    }
+
+    fn write_long(&mut self, address: u32, value: u32) {
+        self.flat.write_long(address, value);
+    }
+
+    fn try_read_byte(&mut self, address: u32) -> Result<u8, BusFault> {
+        self.check(address)?;
+        Ok(self.read_byte(address))
+    }
+
+    fn try_read_word(&mut self, address: u32) -> Result<u16, BusFault> {
+        self.check(address)?;
+        Ok(self.read_word(address))
+    }
+
+    fn try_read_long(&mut self, address: u32) -> Result<u32, BusFault> {
+        self.check(address)?;
+        Ok(self.read_long(address))
+    }
+
+    fn try_write_byte(&mut self, address: u32, value: u8) -> Result<(), BusFault> {
+        self.check(address)?;
+        self.write_byte(address, value);
+        Ok(())
+    }
+
+    fn try_write_word(&mut self, address: u32, value: u16) -> Result<(), BusFault> {
+        self.check(address)?;
+        self.write_word(address, value);
+        Ok(())
+    }
+
+    fn try_write_long(&mut self, address: u32, value: u32) -> Result<(), BusFault> {
+        self.check(address)?;
+        self.write_long(address, value);
+        Ok(())
+    }
+}
+
+/// `cpu_type` with `code` at 0x1000, an RTE at the bus/address error handler and the
+/// bus faulting.
+fn setup(cpu_type: CpuType, code: &[u16]) -> (CpuCore, TestBus) {
+    let (cpu, mut flat) = common::flat::setup(cpu_type, code);
+    flat.write_long(2 * 4, HANDLER);
+    flat.write_long(3 * 4, HANDLER);
+    flat.write_words(HANDLER, &[0x4E73]);
+    let bus = TestBus {
+        flat,
+        faulting: true,
+    };
+    (cpu, bus)
+}
+
+#[test]
+fn test_write_fault_short_frame_and_rerun() {
+    // MOVE.W D1,$6000 ; NOP
+    let (mut cpu, mut bus) = setup(CpuType::M68030, &[0x31C1, 0x6000, 0x4E71]);
+    cpu.set_d(1, 0x1234_BEEF);
+
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, HANDLER);
+    let sp = cpu.a(7);
+    assert_eq!(sp, STACK - 32);
+    assert_eq!(bus.read_word(sp), 0x2700, "SR");
+    assert_eq!(
+        bus.read_long(sp + 2),
+        0x1000,
+        "PC of the faulted instruction"
+    );
+    assert_eq!(bus.read_word(sp + 6), 0xA008);
+    assert_eq!(bus.read_word(sp + 0x0A), 0x0125, "DF, word, FC 5");
+    assert_eq!(bus.read_word(sp + 0x0C), 0x31C1, "pipe stage C");
+    assert_eq!(bus.read_long(sp + 0x10), FAULT_ADDR);
+    assert_eq!(bus.read_long(sp + 0x18), 0xBEEF, "data output buffer");
+
+    // With DF still set, RTE reruns the write.
+    bus.faulting = false;
+    cpu.step(&mut bus);
+    assert_eq!((cpu.pc, cpu.a(7)), (0x1000, STACK));
+    cpu.step(&mut bus);
+    assert_eq!(bus.read_word(FAULT_ADDR), 0xBEEF);
+    assert_eq!(cpu.pc, 0x1004);
+}
+
+#[test]
+fn test_read_fault_long_frame_completed_by_handler() {
+    // MOVE.L $6000,D2 ; MOVE.L $6000,D3
+    let (mut cpu, mut bus) = setup(CpuType::M68020, &[0x2438, 0x6000, 0x2638, 0x6000]);
+    bus.write_long(FAULT_ADDR, 0x6666_6666);
+
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(sp, STACK - 92);
+    assert_eq!(bus.read_word(sp + 6), 0xB008);
+    assert_eq!(bus.read_word(sp + 0x0A), 0x0145, "DF, read, long, FC 5");
+    assert_eq!(bus.read_long(sp + 0x10), FAULT_ADDR);
+    assert_eq!(bus.read_long(sp + 0x24), 0x1004, "stage B address");
+
+    // The handler supplies the data and clears DF; the bus keeps faulting.
+    bus.write_word(sp + 0x0A, 0x0045);
+    bus.write_long(sp + 0x2C, 0xC0DE_CAFE);
+    cpu.step(&mut bus);
+    assert_eq!((cpu.pc, cpu.a(7)), (0x1000, STACK));
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(2), 0xC0DE_CAFE);
+    assert_eq!(cpu.pc, 0x1004);
+
+    // The completed cycle is used once: the next read faults again.
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, HANDLER);
+}
+
+#[test]
+fn test_write_completed_by_handler_is_not_repeated() {
+    // MOVE.L D1,$6000 ; NOP
+    let (mut cpu, mut bus) = setup(CpuType::M68030, &[0x21C1, 0x6000, 0x4E71]);
+    cpu.set_d(1, 0x1111_2222);
+
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(bus.read_word(sp + 0x0A), 0x0105, "DF, write, long, FC 5");
+    assert_eq!(bus.read_long(sp + 0x18), 0x1111_2222);
+
+    bus.write_word(sp + 0x0A, 0x0005);
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x1004, "the write is skipped, not faulted again");
+    assert_eq!(bus.read_long(FAULT_ADDR), 0);
+}
+
+#[test]
+fn test_instruction_fetch_fault_and_address_error() {
+    let (mut cpu, mut bus) = setup(CpuType::M68030, &[]);
+    cpu.pc = FAULT_ADDR;
+
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(bus.read_word(sp + 6), 0xB008);
+    assert_eq!(bus.read_word(sp + 0x0A), 0x5006, "FB, RB, FC 6");
+    assert_eq!(bus.read_long(sp + 0x24), FAULT_ADDR, "stage B address");
+
+    // Odd instruction fetches take an address error with the same long frame.
+    let (mut cpu, mut bus) = setup(CpuType::M68020, &[]);
+    cpu.pc = 0x1001;
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(cpu.pc, HANDLER);
+    assert_eq!(bus.read_word(sp + 6), 0xB00C);
+    assert_eq!(bus.read_long(sp + 0x24), 0x1001);
+}
+
+#[test]
+fn test_68010_write_fault_frame_and_rerun() {
+    // MOVE.W D1,$6000 ; NOP
+    let (mut cpu, mut bus) = setup(CpuType::M68010, &[0x31C1, 0x6000, 0x4E71]);
+    cpu.set_d(1, 0x1234_BEEF);
+
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, HANDLER);
+    let sp = cpu.a(7);
+    assert_eq!(sp, STACK - 58);
+    assert_eq!(bus.read_word(sp), 0x2700);
+    assert_eq!(bus.read_long(sp + 2), 0x1000);
+    assert_eq!(bus.read_word(sp + 6), 0x8008);
+    assert_eq!(bus.read_word(sp + 0x08), 0x0005, "write, word, FC 5");
+    assert_eq!(bus.read_long(sp + 0x0A), FAULT_ADDR);
+    assert_eq!(bus.read_word(sp + 0x10), 0xBEEF, "data output buffer");
+
+    // RR clear: RTE reruns the write.
+    bus.faulting = false;
+    cpu.step(&mut bus);
+    assert_eq!((cpu.pc, cpu.a(7)), (0x1000, STACK));
+    cpu.step(&mut bus);
+    assert_eq!(bus.read_word(FAULT_ADDR), 0xBEEF);
+    assert_eq!(cpu.pc, 0x1004);
+}
+
+#[test]
+fn test_68010_long_read_completed_by_handler() {
+    // MOVE.L $6000,D2
+    let (mut cpu, mut bus) = setup(CpuType::M68010, &[0x2438, 0x6000]);
+    bus.write_long(FAULT_ADDR, 0x6666_7777);
+
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(bus.read_word(sp + 0x08), 0x1105, "DF, read, FC 5");
+
+    // The handler supplies the high word and sets RR; the low word cycle still runs.
+    bus.write_word(sp + 0x08, 0x9105);
+    bus.write_word(sp + 0x14, 0xC0DE);
+    bus.faulting = false;
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(2), 0xC0DE_7777);
+    assert_eq!(cpu.pc, 0x1004);
+}
+
+#[test]
+fn test_68010_byte_write_completed_and_address_error() {
+    // MOVE.B D1,$6001 ; NOP
+    let (mut cpu, mut bus) = setup(CpuType::SCC68070, &[0x11C1, 0x6001, 0x4E71]);
+    cpu.set_d(1, 0x55);
+
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(bus.read_word(sp + 0x08), 0x0205, "BY, odd byte lane, write");
+    assert_eq!(bus.read_word(sp + 0x10), 0x0055);
+
+    bus.write_word(sp + 0x08, 0x8205);
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x1004, "the write is skipped, not faulted again");
+    assert_eq!(bus.read_byte(FAULT_ADDR + 1), 0);
+
+    // MOVE.W $4001,D0 takes an address error with the same frame.
+    let (mut cpu, mut bus) = setup(CpuType::M68010, &[0x3038, 0x4001]);
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(cpu.pc, HANDLER);
+    assert_eq!(bus.read_word(sp + 6), 0x800C);
+    assert_eq!(bus.read_word(sp + 0x08), 0x1105);
+    assert_eq!(bus.read_long(sp + 0x0A), 0x4001);
+}
+
+#[test]
+fn test_68040_write_fault_reported_as_write_back() {
+    // MOVE.W D1,$6000 ; NOP
+    let (mut cpu, mut bus) = setup(CpuType::M68040, &[0x31C1, 0x6000, 0x4E71]);
+    cpu.set_d(1, 0x1234_BEEF);
+
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, HANDLER);
+    let sp = cpu.a(7);
+    assert_eq!(sp, STACK - 60);
+    assert_eq!(bus.read_long(sp + 2), 0x1000);
+    assert_eq!(bus.read_word(sp + 6), 0x7008);
+    assert_eq!(bus.read_long(sp + 0x08), FAULT_ADDR, "effective address");
+    assert_eq!(bus.read_word(sp + 0x0C), 0x0045, "write, word, TM 5");
+    assert_eq!(bus.read_word(sp + 0x0E), 0x00C5, "WB3 valid");
+    assert_eq!(bus.read_long(sp + 0x14), FAULT_ADDR);
+    assert_eq!(
+        (bus.read_long(sp + 0x18), bus.read_long(sp + 0x1C)),
+        (FAULT_ADDR, 0xBEEF)
+    );
+
+    // The handler performs the write-back itself and clears WB3's valid bit.
+    bus.write_word(sp + 0x0E, 0x0045);
+    cpu.step(&mut bus);
+    assert_eq!((cpu.pc, cpu.a(7)), (0x1000, STACK));
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x1004, "the write is not repeated");
+}
+
+#[test]
+fn test_68040_read_fault_restarts_and_address_error() {
+    // MOVE.L $6000,D2
+    let (mut cpu, mut bus) = setup(CpuType::M68LC040, &[0x2438, 0x6000]);
+    bus.write_long(FAULT_ADDR, 0x6666_7777);
+
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(bus.read_word(sp + 0x0C), 0x0105, "read, long, TM 5");
+    assert_eq!(bus.read_word(sp + 0x0E), 0, "no write-back");
+
+    bus.faulting = false;
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(2), 0x6666_7777);
+    assert_eq!((cpu.pc, cpu.a(7)), (0x1004, STACK));
+
+    // Odd instruction fetches stack a format $2 frame with the address.
+    let (mut cpu, mut bus) = setup(CpuType::M68040, &[]);
+    cpu.pc = 0x1001;
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(cpu.pc, HANDLER);
+    assert_eq!(bus.read_word(sp + 6), 0x200C);
+    assert_eq!(bus.read_long(sp + 8), 0x1001);
+}
+
+#[test]
+fn test_68060_access_fault_frame_restarts_instruction() {
+    // MOVE.W D1,$6000
+    let (mut cpu, mut bus) = setup(CpuType::M68060, &[0x31C1, 0x6000]);
+    cpu.set_d(1, 0x1234_BEEF);
+
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, HANDLER);
+    let sp = cpu.a(7);
+    assert_eq!(sp, STACK - 16);
+    assert_eq!(bus.read_long(sp + 2), 0x1000);
+    assert_eq!(bus.read_word(sp + 6), 0x4008);
+    assert_eq!(bus.read_long(sp + 8), FAULT_ADDR);
+    assert_eq!(bus.read_long(sp + 12), 0x00C5_0010, "write, word, TM 5, WE");
+
+    bus.faulting = false;
+    cpu.step(&mut bus);
+    assert_eq!((cpu.pc, cpu.a(7)), (0x1000, STACK));
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x1004);
+    assert_eq!(bus.read_word(FAULT_ADDR), 0xBEEF);
+
+    // MOVE.L $6000,D2
+    let (mut cpu, mut bus) = setup(CpuType::M68LC060, &[0x2438, 0x6000]);
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(bus.read_long(sp + 12), 0x0105_0020, "read, long, TM 5, RE");
+}
+
+#[test]
+fn test_cpu32_format_c_frame_rerun_and_completed_write() {
+    // MOVE.W D1,$6000 ; NOP
+    let (mut cpu, mut bus) = setup(CpuType::CPU32, &[0x31C1, 0x6000, 0x4E71]);
+    cpu.set_d(1, 0x1234_BEEF);
+
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, HANDLER);
+    let sp = cpu.a(7);
+    assert_eq!(sp, STACK - 24);
+    assert_eq!(bus.read_word(sp), 0x2700);
+    assert_eq!(bus.read_long(sp + 2), 0x1000);
+    assert_eq!(bus.read_word(sp + 6), 0xC008);
+    assert_eq!(bus.read_long(sp + 0x08), FAULT_ADDR);
+    assert_eq!(bus.read_long(sp + 0x0C), 0xBEEF, "data output buffer");
+    assert_eq!(bus.read_long(sp + 0x10), 0x1000, "current instruction PC");
+    assert_eq!(bus.read_word(sp + 0x16), 0x0015, "write, word, FC 5");
+
+    // RR clear: RTE reruns the write.
+    bus.faulting = false;
+    cpu.step(&mut bus);
+    assert_eq!((cpu.pc, cpu.a(7)), (0x1000, STACK));
+    cpu.step(&mut bus);
+    assert_eq!(bus.read_word(FAULT_ADDR), 0xBEEF);
+    assert_eq!(cpu.pc, 0x1004);
+
+    // RR set: the handler completed the write and the instruction skips it.
+    let (mut cpu, mut bus) = setup(CpuType::CPU32, &[0x31C1, 0x6000, 0x4E71]);
+    cpu.set_d(1, 0x1234_BEEF);
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    bus.write_word(sp + 0x16, 0x0215);
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x1004, "the write is skipped, not faulted again");
+    assert_eq!(bus.read_word(FAULT_ADDR), 0);
+
+    // MOVE.L $4001,D0 takes an address error with the same frame.
+    let (mut cpu, mut bus) = setup(CpuType::CPU32, &[0x2038, 0x4001]);
+    cpu.step(&mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(cpu.pc, HANDLER);
+    assert_eq!(bus.read_word(sp + 6), 0xC00C);
+    assert_eq!(bus.read_long(sp + 0x08), 0x4001);
+    assert_eq!(bus.read_word(sp + 0x16), 0x0065, "read, long, FC 5");
+}
@@ -0,0 +1,272 @@
+//! ColdFire cores: the reduced 68000 instruction set, the ISA_B/ISA_C additions, the
+//! format/vector/fault status exception frame, and the EMAC unit.
+
+mod common;
+
+use common::flat::{FlatBus, STACK, step_n};
+use m68k::core::emac::macsr;
+use m68k::core::memory::AddressBus;
+use m68k::{CpuCore, CpuType, StepResult};
+
+const HANDLER: u32 = 0x3000;
+
+/// `cpu_type` with `code` at 0x1000 and vectors 2-15 pointing at `HANDLER`, which holds
+/// an RTE.
+fn setup(cpu_type: CpuType, code: &[u16]) -> (CpuCore, FlatBus) {
+    let (cpu, mut bus) = common::flat::setup(cpu_type, code);
+    for vector in 2..16 {
+        bus.write_long(vector * 4, HANDLER);
+    }
+    bus.write_word(HANDLER, 0x4E73);
+    (cpu, bus)
+}
+
+fn assert_illegal(cpu_type: CpuType, code: &[u16]) {
+    let (mut cpu, mut bus) = setup(cpu_type, code);
+    assert!(
+        matches!(
+            cpu.step(&mut bus),
+            StepResult::IllegalInstruction { opcode } if opcode == code[0]
+        ),
+        "{cpu_type:?} {:04X}",
+        code[0]
+    );
+}
+
+#[test]
+fn test_exception_frame_aligns_stack_and_rte_restores_it() {
+    // DIVU.W D1,D0 by zero with SP two bytes off a long word.
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &[0x80C1]);
+    cpu.set_a(7, STACK + 2);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, HANDLER);
+    assert_eq!(cpu.a(7), STACK - 8);
+    assert_eq!(bus.read_long(STACK - 8), 0x6000_0000 | (5 << 18) | 0x2700);
+    assert_eq!(bus.read_long(STACK - 4), 0x1000, "faulting PC");
+
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x1000);
+    assert_eq!(cpu.a(7), STACK + 2);
+}
+
+#[test]
+fn test_rte_format_error() {
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &[0x4E73]);
+    bus.write_long(14 * 4, 0x3100);
+    // A 68000-style format 0 frame.
+    bus.write_long(STACK, 0x0000_2700);
+    bus.write_long(STACK + 4, 0x2000);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x3100);
+    assert_eq!(bus.read_long(STACK - 8) >> 18, (4 << 10) | 14);
+}
+
+#[test]
+fn test_index_word_takes_address_error_with_fault_status() {
+    // MOVE.L (0,A0,D0.W),D1 ; MOVE.L (0,A0,D0.L*8),D1
+    for ext in [0x0000, 0x0E00] {
+        let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &[0x2230, ext]);
+        cpu.step(&mut bus);
+        assert_eq!(cpu.pc, HANDLER);
+        assert_eq!(
+            bus.read_long(STACK - 8),
+            0x4000_0000 | (0b01 << 26) | (3 << 18) | 0x2700
+        );
+    }
+
+    // MOVE.L (4,A0,D0.L*4),D1
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &[0x2230, 0x0C04]);
+    cpu.set_a(0, 0x2000);
+    cpu.set_d(0, 1);
+    bus.write_long(0x2008, 0x1234_5678);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(1), 0x1234_5678);
+}
+
+#[test]
+fn test_68000_forms_left_out_are_illegal() {
+    for code in [
+        &[0xD200][..],          // ADD.B D0,D1
+        &[0x4440][..],          // NEG.W D0
+        &[0xE398][..],          // ROL.L #1,D0
+        &[0x51C8, 0xFFFE][..],  // DBRA D0
+        &[0x0640, 0x0001][..],  // ADDI.W #1,D0
+        &[0x0690, 0, 1][..],    // ADDI.L #1,(A0)
+        &[0x4E7A, 0x0002][..],  // MOVEC CACR,D0
+        &[0xC141][..],          // EXG D0,D1
+        &[0x2379, 0, 0, 0][..], // MOVE.L (xxx).L,(d16,A1)
+        &[0x337C, 1, 0][..],    // MOVE.W #1,(d16,A1)
+        &[0x00C0][..],          // BITREV D0
+        &[0x4C80][..],          // SATS D0
+    ] {
+        assert_illegal(CpuType::ColdFireIsaA, code);
+    }
+
+    // ISA_B allows the immediate-to-displacement move.
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaB, &[0x337C, 0x1234, 0x0002]);
+    cpu.set_a(1, 0x2000);
+    cpu.step(&mut bus);
+    assert_eq!(bus.read_word(0x2002), 0x1234);
+}
+
+#[test]
+fn test_multiply_and_shift_never_set_v() {
+    // MULS.L D1,D0 ; ASL.L #1,D2
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &[0x4C01, 0x0800, 0xE382]);
+    cpu.set_d(0, 0x1000_0000);
+    cpu.set_d(1, 0x100);
+    cpu.set_d(2, 0x4000_0000);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(0), 0);
+    assert!(!cpu.flag_v());
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(2), 0x8000_0000);
+    assert!(!cpu.flag_v());
+}
+
+#[test]
+fn test_mov3q_mvs_mvz_and_sats() {
+    // MOV3Q #-1,D0 ; MVS.B D1,D2 ; MVZ.W D1,D3 ; ADD.L D5,D4 ; SATS D4
+    let code = [0xA140, 0x7501, 0x77C1, 0xD885, 0x4C84];
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaB, &code);
+    cpu.set_d(1, 0x1234_8080);
+    cpu.set_d(4, 0x7FFF_FFFF);
+    cpu.set_d(5, 1);
+    step_n(&mut cpu, &mut bus, 5);
+    assert_eq!(cpu.d(0), 0xFFFF_FFFF);
+    assert_eq!(cpu.d(2), 0xFFFF_FF80);
+    assert_eq!(cpu.d(3), 0x8080);
+    assert_eq!(cpu.d(4), 0x7FFF_FFFF);
+    assert!(!cpu.flag_v());
+
+    // Line-A on ISA_A.
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &[0xA140]);
+    cpu.set_emac(false);
+    assert!(matches!(
+        cpu.step(&mut bus),
+        StepResult::AlineTrap { opcode: 0xA140 }
+    ));
+    assert_illegal(CpuType::ColdFireIsaA, &[0x7501]);
+}
+
+#[test]
+fn test_rems_remu() {
+    // REMU.L D1,D2:D0 ; REMS.L D1,D3:D4 ; DIVU.L D1,D5
+    let code = [0x4C41, 0x0002, 0x4C41, 0x4803, 0x4C41, 0x5005];
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &code);
+    cpu.set_d(0, 17);
+    cpu.set_d(1, 5);
+    cpu.set_d(4, (-17i32) as u32);
+    cpu.set_d(5, 17);
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!((cpu.d(0), cpu.d(2)), (17, 2));
+    assert_eq!(cpu.d(3), (-2i32) as u32);
+    assert_eq!(cpu.d(5), 3);
+}
+
+#[test]
+fn test_isa_c_bit_operations() {
+    // BITREV D0 ; BYTEREV D1 ; FF1 D2
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaC, &[0x00C0, 0x02C1, 0x04C2]);
+    cpu.set_d(0, 0x0000_0001);
+    cpu.set_d(1, 0x1234_5678);
+    cpu.set_d(2, 0x0010_0000);
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(cpu.d(0), 0x8000_0000);
+    assert_eq!(cpu.d(1), 0x7856_3412);
+    assert_eq!(cpu.d(2), 11);
+
+    assert_illegal(CpuType::ColdFireIsaB, &[0x02C1]);
+}
+
+#[test]
+fn test_isa_a_shares_one_stack_pointer() {
+    let (mut cpu, _) = setup(CpuType::ColdFireIsaA, &[]);
+    cpu.set_sr(0x0000);
+    assert_eq!(cpu.a(7), STACK);
+
+    let (mut cpu, _) = setup(CpuType::ColdFireIsaB, &[]);
+    cpu.set_usp(0x4000);
+    cpu.set_sr(0x0000);
+    assert_eq!(cpu.a(7), 0x4000);
+}
+
+#[test]
+fn test_movec_coldfire_registers() {
+    // MOVEC D0,ACR1 ; MOVEC D0,RAMBAR ; MOVEC D0,MBAR
+    let code = [0x4E7B, 0x0005, 0x4E7B, 0x0C05, 0x4E7B, 0x0C0F];
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &code);
+    cpu.set_d(0, 0x2000_0021);
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(cpu.acr[1], 0x2000_0021);
+    assert_eq!(cpu.rambar[1], 0x2000_0021);
+    assert_eq!(cpu.mbar, 0x2000_0021);
+
+    // The other stack pointer is ISA_B's.
+    assert_illegal(CpuType::ColdFireIsaA, &[0x4E7B, 0x0800]);
+}
+
+#[test]
+fn test_emac_multiply_accumulate() {
+    // MAC.W D1.L,D2.L,ACC0 ; MSAC.W D1.U,D2.L,ACC1 ; MOVE.L ACC0,D3 ; MOVE.L MACSR,CCR ;
+    // MOVE.L ACC1,D4
+    let code = [0xA401, 0x0000, 0xA481, 0x0140, 0xA183, 0xA9C0, 0xA384];
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &code);
+    cpu.set_d(1, 0x0002_0003);
+    cpu.set_d(2, 0xFFFC);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.emac.acc[0], -12);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.emac.acc[1], 8);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(3), (-12i32) as u32);
+    cpu.step(&mut bus);
+    assert!(!cpu.flag_n(), "MACSR follows the last MAC");
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(4), 8);
+}
+
+#[test]
+fn test_emac_accumulator_extensions() {
+    // MOVE.L D1,ACCEXT01 ; MOVE.L ACCEXT01,D2 ; MOVE.L D3,ACCEXT23 ; MOVE.L ACCEXT23,D4
+    let code = [0xAB01, 0xAB82, 0xAF03, 0xAF84];
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &code);
+    cpu.emac.acc[0] = 0x1234_5678;
+    cpu.emac.acc[1] = 5;
+    cpu.set_d(1, 0x0001_FFFF);
+    cpu.set_d(3, 0x0080_0000);
+    step_n(&mut cpu, &mut bus, 4);
+    assert_eq!(cpu.emac.acc[0], 0x0001_1234_5678);
+    assert_eq!(cpu.emac.acc[1], -0xFFFF_FFFB, "extension $FFFF is negative");
+    assert_eq!(cpu.d(2), 0x0001_FFFF);
+    assert_eq!(cpu.emac.acc[2], 0x0080_0000_0000);
+    assert_eq!(cpu.emac.acc[3], 0);
+    assert_eq!(cpu.d(4), 0x0080_0000);
+    assert_eq!(cpu.emac.macsr, 0, "MACSR is not affected");
+}
+
+#[test]
+fn test_emac_saturates_in_overflow_mode() {
+    // MOVE.L #OMC,MACSR ; MOVE.L #$7FFFFFFF,ACC0 ; MAC.W D1,D1,ACC0 ; MOVCLR.L ACC0,D4
+    let code = [
+        0xA93C, 0x0000, 0x0080, 0xA13C, 0x7FFF, 0xFFFF, 0xA201, 0x0000, 0xA1C4,
+    ];
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &code);
+    cpu.set_d(1, 2);
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(cpu.emac.acc[0], 0x7FFF_FFFF);
+    assert_ne!(cpu.emac.macsr & macsr::V, 0);
+    assert_ne!(cpu.emac.macsr & macsr::PAV0, 0);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(4), 0x7FFF_FFFF);
+    assert_eq!(cpu.emac.acc[0], 0);
+    assert_eq!(cpu.emac.macsr & macsr::PAV0, 0);
+
+    // Without the EMAC the same opcodes are Line-A.
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &code[6..]);
+    cpu.set_emac(false);
+    assert!(matches!(
+        cpu.step(&mut bus),
+        StepResult::AlineTrap { opcode: 0xA201 }
+    ));
+}
@@ -0,0 +1,101 @@
+//! Flat test memory and the CPU state most unit tests start from.
+//!
+//! Tests that only need somewhere to put code and data use [`setup`]: 64K of RAM mirrored
+//! over the whole address space, with the code at [`CODE`]. Buses that log or fault keep
+//! their memory in a [`FlatBus`] and start the CPU with [`supervisor_cpu`].
+
+use m68k::core::memory::AddressBus;
+use m68k::{CpuCore, CpuType, StepResult};
+
+/// Where [`setup`] loads the code.
+pub const CODE: u32 = 0x1000;
+/// Initial supervisor stack pointer.
+pub const STACK: u32 = 0x8000;
+
+/// 64K of RAM, mirrored over the whole address space.
+pub struct FlatBus {
+    pub memory: [u8; 0x10000],
+}
+
+impl FlatBus {
+    pub fn new() -> Self {
+        Self {
+            memory: [0; 0x10000],
+        }
+    }
+
+    pub fn write_words(&mut self, addr: u32, words: &[u16]) {
+        for (i, w) in words.iter().enumerate() {
+            self.write_word(addr + (i as u32) * 2, *w);
+        }
+    }
+}
+
+impl Default for FlatBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AddressBus for FlatBus {
+    fn read_byte(&mut self, address: u32) -> u8 {
+        self.memory[(address as usize) & 0xFFFF]
+    }
+
+    fn read_word(&mut self, address: u32) -> u16 {
+        let addr = (address as usize) & 0xFFFF;
+        u16::from_be_bytes([self.memory[addr], self.memory[(addr + 1) & 0xFFFF]])
+    }
+
+    fn read_long(&mut self, address: u32) -> u32 {
+        ((self.read_word(address) as u32) << 16) | self.read_word(address + 2) as u32
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) {
+        self.memory[(address as usize) & 0xFFFF] = value;
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) {
+        let addr = (address as usize) & 0xFFFF;
+        let bytes = value.to_be_bytes();
+        self.memory[addr] = bytes[0];
+        self.memory[(addr + 1) & 0xFFFF] = bytes[1];
+    }
+
+    fn write_long(&mut self, address: u32, value: u32) {
+        self.write_word(address, (value >> 16) as u16);
+        self.write_word(address + 2, value as u16);
+    }
+}
+
+/// `cpu_type` about to run from [`CODE`] in supervisor mode with interrupts masked and
+/// A7 = [`STACK`].
+pub fn supervisor_cpu(cpu_type: CpuType) -> CpuCore {
+    let mut cpu = CpuCore::new();
+    cpu.set_cpu_type(cpu_type);
+    cpu.pc = CODE;
+    cpu.set_sr(0x2700);
+    cpu.set_a(7, STACK);
+    cpu
+}
+
+/// [`supervisor_cpu`] with `code` loaded at [`CODE`] in a [`FlatBus`].
+pub fn setup(cpu_type: CpuType, code: &[u16]) -> (CpuCore, FlatBus) {
+    let mut bus = FlatBus::new();
+    bus.write_words(CODE, code);
+    (supervisor_cpu(cpu_type), bus)
+}
+
+pub fn step_n<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B, n: usize) {
+    for _ in 0..n {
+        cpu.step(bus);
+    }
+}
+
+/// Step one instruction, which must complete, and return its cycles.
+pub fn step_cycles<B: AddressBus>(cpu: &mut CpuCore, bus: &mut B) -> i32 {
+    match cpu.step(bus) {
+        StepResult::Ok { cycles } => cycles,
+        other => panic!("unexpected {other:?}"),
+    }
+}
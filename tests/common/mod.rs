@@ -1,5 +1,9 @@
-//! Test harness for Musashi integration tests.
+//! Test harness for Musashi integration tests, and the flat memory unit tests share.
 
+// Each test binary uses only part of the harness.
+#![allow(dead_code)]
+
+pub mod flat;
 mod memory;
 mod test_device;
 
@@ -0,0 +1,415 @@
+//! External coprocessor interface: the CPU side of the 68020/68030 CIR protocol.
+
+mod common;
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+use common::flat::{CODE, FlatBus, STACK, step_cycles};
+use m68k::core::coprocessor::{Coprocessor, cir, cir_address, control, ea_class, response};
+use m68k::core::memory::{AddressBus, BusCycle};
+use m68k::{CpuCore, CpuType, StepResult};
+
+/// What the coprocessor answers, and what the CPU wrote to it.
+#[derive(Default)]
+struct Script {
+    responses: VecDeque<u16>,
+    operands: VecDeque<u32>,
+    select: VecDeque<u16>,
+    save: VecDeque<u16>,
+    writes: Vec<(u8, u8, u32)>,
+}
+
+impl Script {
+    fn written(&self, offset: u8) -> Vec<u32> {
+        self.writes
+            .iter()
+            .filter(|w| w.0 == offset)
+            .map(|w| w.2)
+            .collect()
+    }
+}
+
+struct Scripted(Rc<RefCell<Script>>);
+
+impl Coprocessor for Scripted {
+    fn read_cir(&mut self, offset: u8, _size: u8) -> u32 {
+        let mut s = self.0.borrow_mut();
+        match offset {
+            cir::RESPONSE => s.responses.pop_front().unwrap_or(response::NULL) as u32,
+            cir::OPERAND => s.operands.pop_front().unwrap_or(0),
+            cir::REGISTER_SELECT => s.select.pop_front().unwrap_or(0) as u32,
+            cir::SAVE => s.save.pop_front().unwrap_or(0) as u32,
+            // Accept whatever frame was restored.
+            cir::RESTORE => s.written(cir::RESTORE).last().copied().unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn write_cir(&mut self, offset: u8, size: u8, value: u32) {
+        self.0.borrow_mut().writes.push((offset, size, value));
+    }
+}
+
+/// Memory that logs every reported bus cycle.
+struct TestBus {
+    flat: FlatBus,
+    cycles: Vec<BusCycle>,
+}
+
+impl AddressBus for TestBus {
+    fn read_byte(&mut self, address: u32) -> u8 {
+        self.flat.read_byte(address)
+    }
+
+    fn read_word(&mut self, address: u32) -> u16 {
+        self.flat.read_word(address)
+    }
+
+    fn read_long(&mut self, address: u32) -> u32 {
+        self.flat.read_long(address)
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) {
+        self.flat.write_byte(address, value);
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) {
+        self.flat.write_word(address, value);
+    }
+
+    fn write_long(&mut self, address: u32, value: u32) {
+        self.flat.write_long(address, value);
+    }
+
+    fn bus_cycle(&mut self, cycle: BusCycle) -> u32 {
+        self.cycles.push(cycle);
+        0
+    }
+}
+
+/// Coprocessor ID used by the tests; its opcodes are $FCxx-$FDxx.
+const CPID: u8 = 6;
+
+/// Handler address of exception `vector`.
+fn handler(vector: u32) -> u32 {
+    0x4000 + vector * 4
+}
+
+/// `cpu_type` running `code` at 0x1000 with A0 = 0x2000, every vector pointing at its
+/// [`handler`] and a scripted coprocessor on [`CPID`].
+fn setup(cpu_type: CpuType, code: &[u16]) -> (CpuCore, TestBus, Rc<RefCell<Script>>) {
+    let (mut cpu, mut flat) = common::flat::setup(cpu_type, code);
+    for vector in 2..128 {
+        flat.write_long(vector * 4, handler(vector));
+    }
+    cpu.set_a(0, 0x2000);
+
+    let script = Rc::new(RefCell::new(Script::default()));
+    cpu.attach_coprocessor(CPID, Box::new(Scripted(script.clone())));
+    let bus = TestBus {
+        flat,
+        cycles: Vec::new(),
+    };
+    (cpu, bus, script)
+}
+
+#[test]
+fn test_cpgen_writes_command_and_finishes_on_null() {
+    // cpGEN with command $1234.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC00, 0x1234]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, 0x1004);
+    assert_eq!(script.borrow().written(cir::COMMAND), vec![0x1234]);
+
+    // Null with CA and IA restarts the instruction, so a pending interrupt is taken
+    // before the response CIR is read again.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68030, &[0xFC00, 0x0001]);
+    bus.flat.write_words(handler(27), &[0x4E73]);
+    script.borrow_mut().responses =
+        VecDeque::from([response::CA | response::NULL | response::IA, response::NULL]);
+    cpu.set_sr(0x2000);
+    cpu.set_irq(3);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, handler(27));
+    assert_eq!(bus.read_long(cpu.a(7) + 2), CODE, "restarted instruction");
+    assert_eq!(script.borrow().responses.len(), 1);
+
+    cpu.set_irq(0);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, CODE);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, 0x1004);
+    assert!(script.borrow().responses.is_empty());
+}
+
+#[test]
+fn test_unattached_ids_and_68040_take_fline() {
+    let (mut cpu, mut bus, _) = setup(CpuType::M68020, &[0xFA00, 0x0000]);
+    assert!(matches!(
+        cpu.step(&mut bus),
+        StepResult::FlineTrap { opcode: 0xFA00 }
+    ));
+
+    let (mut cpu, mut bus, script) = setup(CpuType::M68040, &[0xFC00, 0x0000]);
+    assert!(matches!(
+        cpu.step(&mut bus),
+        StepResult::FlineTrap { opcode: 0xFC00 }
+    ));
+    assert!(script.borrow().writes.is_empty());
+}
+
+#[test]
+fn test_attached_id_1_replaces_fpu() {
+    // FMOVE.X FP0,FP1 goes to the coprocessor instead of the built-in FPU.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xF200, 0x0080]);
+    let coprocessor = cpu.detach_coprocessor(CPID).unwrap();
+    cpu.attach_coprocessor(1, coprocessor);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(script.borrow().written(cir::COMMAND), vec![0x0080]);
+}
+
+#[test]
+fn test_evaluate_ea_and_transfer_data() {
+    // cpGEN (d16,A0): the operand goes to the coprocessor, then a result comes back to
+    // the same address through write-to-previously-evaluated-EA.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC28, 0x0000, 0x0010]);
+    bus.write_long(0x2010, 0xCAFE_F00D);
+    script.borrow_mut().responses = VecDeque::from([
+        response::CA | response::EVALUATE_EA_AND_TRANSFER_DATA | (ea_class::CONTROL << 8) | 4,
+        response::WRITE_TO_PREVIOUSLY_EVALUATED_EA | 4,
+    ]);
+    script.borrow_mut().operands = VecDeque::from([0x1234_5678]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(script.borrow().written(cir::OPERAND), vec![0xCAFE_F00D]);
+    assert_eq!(bus.read_long(0x2010), 0x1234_5678);
+    assert_eq!(cpu.pc, 0x1006);
+
+    // cpGEN #imm.W: an immediate operand comes from the instruction stream.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC3C, 0x0000, 0xBEEF]);
+    script.borrow_mut().responses =
+        VecDeque::from([response::EVALUATE_EA_AND_TRANSFER_DATA | (ea_class::ANY << 8) | 2]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(script.borrow().written(cir::OPERAND), vec![0xBEEF]);
+    assert_eq!(cpu.pc, 0x1006);
+
+    // cpGEN D3 with DR: the coprocessor writes a data register.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC03, 0x0000]);
+    script.borrow_mut().responses = VecDeque::from([response::DR
+        | response::EVALUATE_EA_AND_TRANSFER_DATA
+        | (ea_class::DATA_ALTERABLE << 8)
+        | 4]);
+    script.borrow_mut().operands = VecDeque::from([0x0BAD_BEEF]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.d(3), 0x0BAD_BEEF);
+
+    // An address register is not in the data alterable class: F-line.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC0B, 0x0000]);
+    script.borrow_mut().responses = VecDeque::from([response::DR
+        | response::EVALUATE_EA_AND_TRANSFER_DATA
+        | (ea_class::DATA_ALTERABLE << 8)
+        | 4]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, handler(11));
+    assert_eq!(
+        script.borrow().written(cir::CONTROL),
+        vec![control::AB as u32]
+    );
+}
+
+#[test]
+fn test_register_transfers() {
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC00, 0x0000]);
+    cpu.set_d(2, 0x2222_2222);
+    cpu.set_a(1, 0x1111_1111);
+    cpu.vbr = 0x0000_0000;
+    script.borrow_mut().responses = VecDeque::from([
+        // D2 and A1 to the coprocessor, VBR from it, then D5 from it.
+        response::CA | response::TRANSFER_MULTIPLE_REGISTERS,
+        response::CA | response::DR | response::TRANSFER_CONTROL_REGISTER,
+        response::CA | response::DR | response::TRANSFER_SINGLE_REGISTER | 5,
+        response::CA | response::PC | response::TRANSFER_OPERATION_WORD,
+        response::NULL,
+    ]);
+    script.borrow_mut().select = VecDeque::from([0x0204, 0x0801]);
+    script.borrow_mut().operands = VecDeque::from([0x0001_0000, 0x5555_5555]);
+    step_cycles(&mut cpu, &mut bus);
+
+    let s = script.borrow();
+    assert_eq!(s.written(cir::OPERAND), vec![0x2222_2222, 0x1111_1111]);
+    assert_eq!(cpu.vbr, 0x0001_0000);
+    assert_eq!(cpu.d(5), 0x5555_5555);
+    assert_eq!(s.written(cir::OPERATION_WORD), vec![0xFC00]);
+    assert_eq!(s.written(cir::INSTRUCTION_ADDRESS), vec![CODE]);
+}
+
+#[test]
+fn test_conditional_instructions() {
+    // cpBcc.W taken when the coprocessor answers TF.
+    for (tf, target) in [(response::TF, 0x1012), (0, 0x1004)] {
+        let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC85, 0x0010]);
+        script.borrow_mut().responses = VecDeque::from([response::NULL | tf]);
+        step_cycles(&mut cpu, &mut bus);
+        assert_eq!(cpu.pc, target);
+        assert_eq!(script.borrow().written(cir::CONDITION), vec![0x05]);
+    }
+
+    // cpScc D1 and cpDBcc D0.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC41, 0x000A]);
+    script.borrow_mut().responses = VecDeque::from([response::NULL | response::TF]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.d(1) & 0xFF, 0xFF);
+    assert_eq!(script.borrow().written(cir::CONDITION), vec![0x0A]);
+
+    let (mut cpu, mut bus, _) = setup(CpuType::M68020, &[0xFC48, 0x0000, 0xFFFC]);
+    cpu.set_d(0, 1);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!((cpu.pc, cpu.d(0)), (CODE, 0));
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!((cpu.pc, cpu.d(0) & 0xFFFF), (0x1006, 0xFFFF));
+
+    // cpTRAPcc.W traps on TF.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC7A, 0x0001, 0x1234]);
+    script.borrow_mut().responses = VecDeque::from([response::NULL | response::TF]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, handler(7));
+}
+
+#[test]
+fn test_protocol_violation_resumes_on_rte() {
+    // A register transfer is not allowed in cpBcc. The handler returns with RTE and the
+    // CPU reads the response CIR again.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC81, 0x0020]);
+    bus.flat.write_words(handler(13), &[0x4E73]);
+    script.borrow_mut().responses = VecDeque::from([
+        response::CA | response::TRANSFER_SINGLE_REGISTER,
+        response::NULL | response::TF,
+    ]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, handler(13));
+    let sp = cpu.a(7);
+    assert_eq!(bus.read_word(sp + 6), 0x9000 | (13 << 2));
+    assert_eq!(bus.read_long(sp + 8), CODE);
+
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.a(7), STACK);
+    assert_eq!(cpu.pc, 0x1022);
+}
+
+#[test]
+fn test_exception_primitives() {
+    // Pre-instruction: the frame returns to the instruction.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC00, 0x0000]);
+    script.borrow_mut().responses = VecDeque::from([response::PRE_INSTRUCTION_EXCEPTION | 64]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, handler(64));
+    assert_eq!(bus.read_long(cpu.a(7) + 2), CODE);
+    assert_eq!(
+        script.borrow().written(cir::CONTROL),
+        vec![control::XA as u32]
+    );
+
+    // Post-instruction: format $2 with the next instruction.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC00, 0x0000]);
+    script.borrow_mut().responses = VecDeque::from([response::POST_INSTRUCTION_EXCEPTION | 65]);
+    step_cycles(&mut cpu, &mut bus);
+    let sp = cpu.a(7);
+    assert_eq!(cpu.pc, handler(65));
+    assert_eq!(bus.read_long(sp + 2), 0x1004);
+    assert_eq!(bus.read_word(sp + 6), 0x2000 | (65 << 2));
+    assert_eq!(bus.read_long(sp + 8), CODE);
+
+    // Supervisor check in user mode.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC00, 0x0000]);
+    cpu.set_sr(0x0000);
+    script.borrow_mut().responses =
+        VecDeque::from([response::CA | response::SUPERVISOR_CHECK, response::NULL]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, handler(8));
+
+    // Busy restarts the instruction.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFC00, 0x0000]);
+    script.borrow_mut().responses = VecDeque::from([response::BUSY]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, CODE);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, 0x1004);
+    assert_eq!(script.borrow().written(cir::COMMAND).len(), 2);
+}
+
+#[test]
+fn test_save_and_restore() {
+    // cpSAVE -(A1), then cpRESTORE (A1)+.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68030, &[0xFD21, 0xFD59]);
+    cpu.set_a(1, 0x3000);
+    script.borrow_mut().save = VecDeque::from([0x4108]);
+    script.borrow_mut().operands = VecDeque::from([0xAAAA_AAAA, 0xBBBB_BBBB]);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.a(1), 0x3000 - 12);
+    assert_eq!(bus.read_long(0x3000 - 12), 0x4108_0000);
+    assert_eq!(bus.read_long(0x3000 - 8), 0xAAAA_AAAA);
+    assert_eq!(bus.read_long(0x3000 - 4), 0xBBBB_BBBB);
+
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.a(1), 0x3000);
+    let s = script.borrow();
+    assert_eq!(s.written(cir::RESTORE), vec![0x4108]);
+    assert_eq!(s.written(cir::OPERAND), vec![0xAAAA_AAAA, 0xBBBB_BBBB]);
+    drop(s);
+
+    // Both are privileged and check before talking to the coprocessor.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFD21]);
+    cpu.set_sr(0x0000);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, handler(8));
+    assert!(script.borrow().writes.is_empty());
+}
+
+#[test]
+fn test_save_not_ready_restarts_instruction() {
+    // cpSAVE -(A1): not ready once, so a pending interrupt is taken and the save runs
+    // when the instruction is restarted.
+    let (mut cpu, mut bus, script) = setup(CpuType::M68020, &[0xFD21]);
+    bus.flat.write_words(handler(27), &[0x4E73]);
+    cpu.set_a(1, 0x3000);
+    script.borrow_mut().save = VecDeque::from([0x0100, 0x4104]);
+    script.borrow_mut().operands = VecDeque::from([0xAAAA_AAAA]);
+    cpu.set_sr(0x2000);
+    cpu.set_irq(3);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, handler(27));
+    assert_eq!(bus.read_long(cpu.a(7) + 2), CODE, "restarted instruction");
+    assert_eq!(cpu.a(1), 0x3000);
+    assert_eq!(script.borrow().save.len(), 1);
+
+    cpu.set_irq(0);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, CODE);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.pc, 0x1002);
+    assert_eq!(cpu.a(1), 0x3000 - 8);
+    assert_eq!(bus.read_long(0x3000 - 8), 0x4104_0000);
+    assert_eq!(bus.read_long(0x3000 - 4), 0xAAAA_AAAA);
+}
+
+#[test]
+fn test_cir_accesses_are_cpu_space_bus_cycles() {
+    let (mut cpu, mut bus, _) = setup(CpuType::M68020, &[0xFC00, 0x0000]);
+    cpu.set_bus_cycle_timing(true);
+    step_cycles(&mut cpu, &mut bus);
+    let cpu_space: Vec<(u32, bool)> = bus
+        .cycles
+        .iter()
+        .filter(|c| c.fc == 7)
+        .map(|c| (c.address, c.write))
+        .collect();
+    assert_eq!(
+        cpu_space,
+        vec![
+            (cir_address(CPID, cir::COMMAND), true),
+            (cir_address(CPID, cir::RESPONSE), false),
+        ]
+    );
+    assert_eq!(cir_address(CPID, cir::COMMAND), 0x0002_C00A);
+}
@@ -0,0 +1,259 @@
+//! CPU32 core: the 68010 programming model with the 68020 integer extensions, no
+//! memory indirect addressing, LPSTOP, the table lookup instructions, BGND and
+//! format $2 frames for the post-instruction traps.
+
+mod common;
+
+use common::flat::{FlatBus, STACK, step_n};
+use m68k::core::memory::AddressBus;
+use m68k::{CpuCore, CpuType, StepResult};
+
+const HANDLER: u32 = 0x3000;
+
+struct TestBus {
+    flat: FlatBus,
+    /// Interrupt mask of the last LPSTOP broadcast.
+    low_power_mask: Option<u8>,
+}
+
+impl AddressBus for TestBus {
+    fn read_byte(&mut self, address: u32) -> u8 {
+        self.flat.read_byte(address)
+    }
+
+    fn read_word(&mut self, address: u32) -> u16 {
+        self.flat.read_word(address)
+    }
+
+    fn read_long(&mut self, address: u32) -> u32 {
+        self.flat.read_long(address)
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) {
+        self.flat.write_byte(address, value);
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) {
+        self.flat.write_word(address, value);
+    }
+
+    fn write_long(&mut self, address: u32, value: u32) {
+        self.flat.write_long(address, value);
+    }
+
+    fn low_power_stop(&mut self, int_mask: u8) {
+        self.low_power_mask = Some(int_mask);
+    }
+}
+
+/// A CPU32 with `code` at 0x1000, A0 = 0x2000 and vectors 4, 5 and 8 pointing at
+/// `HANDLER`.
+fn setup(code: &[u16]) -> (CpuCore, TestBus) {
+    let (mut cpu, mut flat) = common::flat::setup(CpuType::CPU32, code);
+    for vector in [4, 5, 8] {
+        flat.write_long(vector * 4, HANDLER);
+    }
+    cpu.set_a(0, 0x2000);
+    let bus = TestBus {
+        flat,
+        low_power_mask: None,
+    };
+    (cpu, bus)
+}
+
+#[test]
+fn test_sr_keeps_t0_and_drops_master() {
+    let (mut cpu, _) = setup(&[]);
+    cpu.set_sr(0x5700);
+    assert_eq!(cpu.get_sr(), 0x4700);
+}
+
+#[test]
+fn test_movec_register_set() {
+    // MOVEC D0,VBR ; MOVEC VBR,D1
+    let (mut cpu, mut bus) = setup(&[0x4E7B, 0x0801, 0x4E7A, 0x1801]);
+    cpu.set_d(0, 0x0000_4000);
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.vbr, 0x4000);
+    assert_eq!(cpu.d(1), 0x4000);
+
+    // No CACR, CAAR or MSP.
+    for reg in [0x002, 0x802, 0x803] {
+        let (mut cpu, mut bus) = setup(&[0x4E7A, reg]);
+        assert!(
+            matches!(
+                cpu.step(&mut bus),
+                StepResult::IllegalInstruction { opcode: 0x4E7A }
+            ),
+            "{reg:#05X}"
+        );
+    }
+}
+
+#[test]
+fn test_68020_instructions_not_on_cpu32() {
+    for code in [
+        &[0x0AD0, 0x0040][..],         // CAS.B D0,D1,(A0)
+        &[0xE8C0, 0x0008][..],         // BFTST D0{0:8}
+        &[0x8149, 0x0000][..],         // PACK -(A1),-(A0),#0
+        &[0x0CFC, 0x0000, 0x0000][..], // CAS2.W
+    ] {
+        let (mut cpu, mut bus) = setup(code);
+        assert!(
+            matches!(
+                cpu.step(&mut bus),
+                StepResult::IllegalInstruction { opcode } if opcode == code[0]
+            ),
+            "{:04X}",
+            code[0]
+        );
+    }
+
+    // EXTB.L D0 ; MULU.L D1,D3:D2 ; CHK2.B (A0),D4
+    let (mut cpu, mut bus) = setup(&[0x49C0, 0x4C01, 0x2403, 0x00D0, 0x4800]);
+    cpu.set_d(0, 0x80);
+    cpu.set_d(1, 0x1_0000);
+    cpu.set_d(2, 0x1_0000);
+    cpu.set_d(4, 5);
+    bus.flat.write_words(0x2000, &[0x0010]);
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(cpu.d(0), 0xFFFF_FF80);
+    assert_eq!((cpu.d(3), cpu.d(2)), (1, 0));
+    assert_eq!(cpu.pc, 0x100A);
+}
+
+#[test]
+fn test_memory_indirect_modes_are_illegal() {
+    // MOVE.L ([A0]),D0
+    let (mut cpu, mut bus) = setup(&[0x2030, 0x0151]);
+    bus.write_long(0x2000, 0x2100);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, HANDLER);
+    assert_eq!(cpu.a(7), STACK - 8);
+    assert_eq!(bus.read_long(STACK - 6), 0x1000);
+    assert_eq!(bus.read_word(STACK - 2), 4 << 2);
+    assert_eq!(cpu.d(0), 0);
+
+    // Scaled indexes and the full format without indirection still work:
+    // MOVE.L (4,A0,D1.L*4),D0 ; MOVE.L (A0,D1.L),D2 with a null base displacement
+    let (mut cpu, mut bus) = setup(&[0x2030, 0x1C04, 0x2430, 0x1910]);
+    cpu.set_d(1, 2);
+    bus.write_long(0x200C, 0xCAFE_F00D);
+    bus.write_long(0x2002, 0x1234_5678);
+    cpu.step(&mut bus);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(0), 0xCAFE_F00D);
+    assert_eq!(cpu.d(2), 0x1234_5678);
+}
+
+#[test]
+fn test_lpstop_loads_sr_and_broadcasts_mask() {
+    // LPSTOP #$2100
+    let (mut cpu, mut bus) = setup(&[0xF800, 0x01C0, 0x2100]);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.get_sr(), 0x2100);
+    assert_eq!(bus.low_power_mask, Some(1));
+    assert!(matches!(cpu.step(&mut bus), StepResult::Stopped));
+
+    // Privileged in user mode.
+    let (mut cpu, mut bus) = setup(&[0xF800, 0x01C0, 0x2100]);
+    cpu.set_sr(0x0000);
+    cpu.set_a(7, 0x7000);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, HANDLER);
+    assert_eq!(bus.read_word(STACK - 2), 8 << 2);
+    assert_eq!(bus.low_power_mask, None);
+}
+
+#[test]
+fn test_table_lookup_from_memory() {
+    // TBLU.B (A0),D1 ; TBLUN.B (A0),D2 ; TBLU.W (A0),D3
+    let code = [0xF810, 0x1100, 0xF810, 0x2500, 0xF810, 0x3140];
+    let (mut cpu, mut bus) = setup(&code);
+    bus.flat.write_words(0x2000, &[0x0A14, 0x1EFF]);
+    // Entry 1, halfway to entry 2.
+    cpu.set_d(1, 0xAAAA_0180);
+    cpu.set_d(2, 0xAAAA_0180);
+    // Word entry 0, a quarter of the way to entry 1.
+    cpu.set_d(3, 0x0000_0040);
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(cpu.d(1), 0xAAAA_0119, "20 + (30 - 20) / 2, rounded");
+    assert_eq!(cpu.d(2), 0xAAAA_1900, "25.0 with the fraction kept");
+    assert_eq!(cpu.d(3), 0x0F4F, "$0A14 + ($1EFF - $0A14) / 4, rounded");
+    assert_eq!(cpu.pc, 0x100C);
+}
+
+#[test]
+fn test_signed_table_lookup_from_memory() {
+    // TBLS.B (A0),D1 ; TBLU.B (A0),D2
+    let (mut cpu, mut bus) = setup(&[0xF810, 0x1900, 0xF810, 0x2100]);
+    bus.flat.write_words(0x2000, &[0xF010]);
+    // Entry 0, a quarter of the way to entry 1.
+    cpu.set_d(1, 0x0000_0040);
+    cpu.set_d(2, 0x0000_0040);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(1), 0x0000_00F8, "-16 + 32 / 4");
+    assert_eq!(cpu.get_sr() & 0x0F, 0x08, "N");
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(2), 0x0000_00B8, "240 - 224 / 4");
+}
+
+#[test]
+fn test_table_lookup_between_registers() {
+    // TBLS.W D2:D3,D1 ; TBLU.W D2:D3,D4
+    let (mut cpu, mut bus) = setup(&[0xF802, 0x1843, 0xF802, 0x4043]);
+    cpu.set_d(1, 0x0000_0040);
+    cpu.set_d(4, 0x0000_0040);
+    cpu.set_d(2, 0xFFF0);
+    cpu.set_d(3, 0x0010);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(1), 0x0000_FFF8, "-16 + 32 / 4");
+    assert_eq!(cpu.get_sr() & 0x0F, 0x08, "N");
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(4), 0x0000_BFF8, "65520 - 65504 / 4");
+    assert_eq!(cpu.pc, 0x1008);
+
+    // The register form with a memory operand, and the memory form with a data
+    // register, are not table lookups.
+    for code in [[0xF810, 0x1800], [0xF802, 0x1943]] {
+        let (mut cpu, mut bus) = setup(&code);
+        assert!(matches!(cpu.step(&mut bus), StepResult::FlineTrap { .. }));
+    }
+}
+
+#[test]
+fn test_bgnd_enters_background_mode_when_enabled() {
+    let (mut cpu, mut bus) = setup(&[0x4AFA, 0x4E71]);
+    assert!(matches!(
+        cpu.step(&mut bus),
+        StepResult::IllegalInstruction { opcode: 0x4AFA }
+    ));
+
+    let (mut cpu, mut bus) = setup(&[0x4AFA, 0x4E71]);
+    cpu.set_background_debug(true);
+    cpu.step(&mut bus);
+    assert!(cpu.is_in_background());
+    cpu.set_irq(7);
+    assert!(matches!(cpu.step(&mut bus), StepResult::Stopped));
+    assert_eq!(cpu.pc, 0x1002);
+
+    cpu.set_irq(0);
+    cpu.leave_background();
+    assert!(!cpu.is_in_background());
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x1004);
+}
+
+#[test]
+fn test_zero_divide_stacks_format_2_frame() {
+    // DIVU.W D1,D0
+    let (mut cpu, mut bus) = setup(&[0x80C1]);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, HANDLER);
+    let sp = cpu.a(7);
+    assert_eq!(sp, STACK - 12);
+    assert_eq!(bus.read_long(sp + 2), 0x1002);
+    assert_eq!(bus.read_word(sp + 6), 0x2014);
+    assert_eq!(bus.read_long(sp + 8), 0x1000, "address of the instruction");
+}
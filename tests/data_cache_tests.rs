@@ -0,0 +1,158 @@
+//! 68030 data cache and 68040 caches: stale data, copyback lines that only reach the bus
+//! when pushed or evicted, CINV/CPUSH and cache-inhibited TTRs.
+
+mod common;
+
+use common::flat::{FlatBus, step_n};
+use m68k::core::cache::cacr;
+use m68k::core::memory::AddressBus;
+use m68k::{CpuCore, CpuType};
+
+/// `cpu_type` with cache emulation on, `cacr` loaded and `code` at 0x1000.
+fn setup(cpu_type: CpuType, cacr: u32, code: &[u16]) -> (CpuCore, FlatBus) {
+    let (mut cpu, bus) = common::flat::setup(cpu_type, code);
+    cpu.set_cache_emulation(true);
+    cpu.write_control_register(0x002, cacr);
+    (cpu, bus)
+}
+
+/// DTT0 for every address, user and supervisor, with the given 68040 CM field.
+fn dtt0_with_cm(cm: u32) -> u32 {
+    0x00FF_C000 | (cm << 5)
+}
+
+#[test]
+fn test_030_data_cache_serves_stale_data_until_cleared() {
+    // MOVE.L $5000,D0 ; MOVE.L $5000,D1 ; MOVE.L $5000,D2
+    let code = [0x2038, 0x5000, 0x2238, 0x5000, 0x2438, 0x5000];
+    let (mut cpu, mut bus) = setup(CpuType::M68030, cacr::ENABLE_D, &code);
+    bus.write_long(0x5000, 0x1111_1111);
+
+    step_n(&mut cpu, &mut bus, 1);
+    bus.write_long(0x5000, 0x2222_2222);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(1), 0x1111_1111, "the cache does not snoop");
+
+    cpu.write_control_register(0x002, cacr::ENABLE_D | cacr::CLEAR_D);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(2), 0x2222_2222);
+}
+
+#[test]
+fn test_030_writes_go_through_and_ci_ttr_bypasses() {
+    // MOVE.L $5000,D0 ; MOVE.L D1,$5000 ; MOVE.L $5000,D2
+    let code = [0x2038, 0x5000, 0x21C1, 0x5000, 0x2438, 0x5000];
+    let (mut cpu, mut bus) = setup(CpuType::M68030, cacr::ENABLE_D, &code);
+    cpu.set_d(1, 0x3333_3333);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(bus.read_long(0x5000), 0x3333_3333, "write-through");
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(
+        cpu.d(2),
+        0x3333_3333,
+        "the write hit updates the cached copy"
+    );
+
+    // TT0 with CI covering all of 0x00xxxxxx keeps reads off the cache.
+    let code = [0x2038, 0x5000, 0x2238, 0x5000];
+    let (mut cpu, mut bus) = setup(CpuType::M68030, cacr::ENABLE_D, &code);
+    cpu.mmu_tt0 = 0x0000_D01C;
+    bus.write_long(0x5000, 0x1111_1111);
+    step_n(&mut cpu, &mut bus, 1);
+    bus.write_long(0x5000, 0x2222_2222);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(1), 0x2222_2222);
+}
+
+#[test]
+fn test_040_copyback_reaches_bus_only_on_cpush() {
+    // MOVE.L D1,$5000 ; MOVE.L $5000,D2 ; CPUSHA DC
+    let code = [0x21C1, 0x5000, 0x2438, 0x5000, 0xF478];
+    let (mut cpu, mut bus) = setup(CpuType::M68040, cacr::ENABLE_D_040, &code);
+    cpu.dtt0 = dtt0_with_cm(1);
+    cpu.set_d(1, 0xCAFE_F00D);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(2), 0xCAFE_F00D);
+    assert_eq!(
+        bus.read_long(0x5000),
+        0,
+        "the dirty line is still in the cache"
+    );
+    assert_eq!(cpu.dcache.dirty_lines(), vec![0x5000]);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(bus.read_long(0x5000), 0xCAFE_F00D);
+    assert!(cpu.dcache.dirty_lines().is_empty());
+}
+
+#[test]
+fn test_040_cinv_discards_dirty_data() {
+    // MOVE.L D1,$5000 ; CINVL DC,(A0) ; MOVE.L $5000,D2
+    let code = [0x21C1, 0x5000, 0xF448, 0x2438, 0x5000];
+    let (mut cpu, mut bus) = setup(CpuType::M68040, cacr::ENABLE_D_040, &code);
+    cpu.dtt0 = dtt0_with_cm(1);
+    cpu.set_a(0, 0x5004);
+    cpu.set_d(1, 0xCAFE_F00D);
+    bus.write_long(0x5000, 0x1234_5678);
+
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(cpu.d(2), 0x1234_5678, "the write was lost");
+    assert_eq!(bus.read_long(0x5000), 0x1234_5678);
+}
+
+#[test]
+fn test_040_evicted_copyback_line_is_pushed() {
+    // MOVE.L D1,(A0) ; ADDA.L A1,A0, five times: every line maps to the same set.
+    let code = [0x2081, 0xD1C9].repeat(5);
+    let (mut cpu, mut bus) = setup(CpuType::M68040, cacr::ENABLE_D_040, &code);
+    cpu.dtt0 = dtt0_with_cm(1);
+    cpu.set_a(0, 0x4000);
+    cpu.set_a(1, 0x400);
+    cpu.set_d(1, 0x5555_AAAA);
+
+    step_n(&mut cpu, &mut bus, 8);
+    assert_eq!(bus.read_long(0x4000), 0, "four ways hold four lines");
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(
+        bus.read_long(0x4000),
+        0x5555_AAAA,
+        "the fifth evicts the first"
+    );
+    assert_eq!(bus.read_long(0x4400), 0);
+}
+
+#[test]
+fn test_040_inhibited_and_disabled_accesses_use_the_bus() {
+    // MOVE.L D1,$5000 ; MOVE.L $5000,D2
+    let code = [0x21C1, 0x5000, 0x2438, 0x5000];
+    for (cacr, cm) in [(cacr::ENABLE_D_040, 2), (0, 1)] {
+        let (mut cpu, mut bus) = setup(CpuType::M68040, cacr, &code);
+        cpu.dtt0 = dtt0_with_cm(cm);
+        cpu.set_d(1, 0xCAFE_F00D);
+
+        step_n(&mut cpu, &mut bus, 1);
+        assert_eq!(bus.read_long(0x5000), 0xCAFE_F00D, "CACR {cacr:#x} CM {cm}");
+        bus.write_long(0x5000, 0x1234_5678);
+        step_n(&mut cpu, &mut bus, 1);
+        assert_eq!(cpu.d(2), 0x1234_5678, "CACR {cacr:#x} CM {cm}");
+    }
+}
+
+#[test]
+fn test_040_instruction_cache_and_cinv() {
+    // MOVEQ #1,D0 ; CINVA IC: run the MOVEQ, patch it, run it again, then invalidate.
+    let (mut cpu, mut bus) = setup(CpuType::M68040, cacr::ENABLE_I_040, &[0x7001, 0xF498]);
+    step_n(&mut cpu, &mut bus, 1);
+    bus.write_words(0x1000, &[0x7002]);
+
+    cpu.pc = 0x1000;
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(0), 1, "the cached MOVEQ #1 runs");
+
+    step_n(&mut cpu, &mut bus, 1);
+    cpu.pc = 0x1000;
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(0), 2);
+}
@@ -0,0 +1,155 @@
+//! Exception, interrupt, trace, bus-error and reset timing for each CPU model.
+
+mod common;
+
+use common::flat::{FlatBus, step_cycles};
+use m68k::core::memory::{AddressBus, BusFault, BusFaultKind};
+use m68k::{CpuCore, CpuType};
+
+const HANDLER: u32 = 0x3000;
+/// Accesses to this word raise a bus error.
+const FAULT_ADDR: u32 = 0x6000;
+
+struct TestBus {
+    flat: FlatBus,
+}
+
+impl AddressBus for TestBus {
+    fn read_byte(&mut self, address: u32) -> u8 {
+        self.flat.read_byte(address)
+    }
+
+    fn read_word(&mut self, address: u32) -> u16 {
+        self.flat.read_word(address)
+    }
+
+    fn read_long(&mut self, address: u32) -> u32 {
+        self.flat.read_long(address)
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) {
+        self.flat.write_byte(address, value);
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) {
+        self.flat.write_word(address, value);
+    }
+
+    fn write_long(&mut self, address: u32, value: u32) {
+        self.flat.write_long(address, value);
+    }
+
+    fn try_read_word(&mut self, address: u32) -> Result<u16, BusFault> {
+        if address == FAULT_ADDR {
+            return Err(BusFault {
+                kind: BusFaultKind::BusError,
+                address,
+            });
+        }
+        Ok(self.read_word(address))
+    }
+}
+
+/// Expected cycles per model: (trap, trace, bus error, address error, interrupt, reset).
+const MODELS: [(CpuType, [i32; 6]); 7] = [
+    (CpuType::M68000, [34, 34, 50, 50, 44, 40]),
+    (CpuType::M68010, [38, 38, 126, 126, 46, 40]),
+    (CpuType::SCC68070, [38, 38, 126, 126, 46, 40]),
+    (CpuType::M68020, [20, 25, 50, 50, 30, 4]),
+    (CpuType::M68EC030, [20, 25, 50, 50, 30, 4]),
+    (CpuType::M68030, [20, 25, 50, 50, 30, 4]),
+    (CpuType::M68040, [20, 25, 50, 50, 30, 4]),
+];
+
+/// `cpu_type` with `code` at 0x1000 and every vector pointing at `HANDLER`.
+fn setup(cpu_type: CpuType, code: &[u16]) -> (CpuCore, TestBus) {
+    let (cpu, mut flat) = common::flat::setup(cpu_type, code);
+    for vector in 2..64 {
+        flat.write_long(vector * 4, HANDLER);
+    }
+    flat.write_words(HANDLER, &[0x4E71]);
+    (cpu, TestBus { flat })
+}
+
+const NOP: [u16; 1] = [0x4E71];
+
+#[test]
+fn test_trap_cycles() {
+    for (cpu_type, [trap, ..]) in MODELS {
+        // TRAP #0, taken by execute() rather than handed back to the caller.
+        let (mut cpu, mut bus) = setup(cpu_type, &[0x4E40]);
+        assert_eq!(cpu.execute(&mut bus, 1), trap, "{cpu_type:?}");
+        assert_eq!(cpu.pc, HANDLER, "{cpu_type:?}");
+    }
+}
+
+#[test]
+fn test_trace_cycles() {
+    for (cpu_type, [_, trace, ..]) in MODELS {
+        let (mut cpu, mut bus) = setup(cpu_type, &NOP);
+        let nop = step_cycles(&mut cpu, &mut bus);
+
+        let (mut cpu, mut bus) = setup(cpu_type, &NOP);
+        cpu.set_sr(0xA700);
+        assert_eq!(step_cycles(&mut cpu, &mut bus), nop + trace, "{cpu_type:?}");
+        assert_eq!(cpu.pc, HANDLER, "{cpu_type:?}");
+    }
+}
+
+#[test]
+fn test_bus_and_address_error_cycles() {
+    for (cpu_type, [_, _, bus_error, address_error, ..]) in MODELS {
+        // MOVE.W $6000,D0
+        let (mut cpu, mut bus) = setup(cpu_type, &[0x3038, FAULT_ADDR as u16]);
+        assert_eq!(step_cycles(&mut cpu, &mut bus), bus_error, "{cpu_type:?}");
+        assert_eq!(cpu.pc, HANDLER, "{cpu_type:?}");
+
+        // JMP $2001: the fetch from the odd address faults before the next instruction.
+        let (mut cpu, mut bus) = setup(cpu_type, &[0x4EF8, 0x2001]);
+        step_cycles(&mut cpu, &mut bus);
+        assert_eq!(
+            step_cycles(&mut cpu, &mut bus),
+            address_error,
+            "{cpu_type:?}"
+        );
+        assert_eq!(cpu.pc, HANDLER, "{cpu_type:?}");
+    }
+}
+
+#[test]
+fn test_interrupt_cycles() {
+    for (cpu_type, [.., interrupt, _]) in MODELS {
+        let (mut cpu, mut bus) = setup(cpu_type, &NOP);
+        let nop = step_cycles(&mut cpu, &mut bus);
+
+        let (mut cpu, mut bus) = setup(cpu_type, &NOP);
+        cpu.set_sr(0x2000);
+        cpu.int_level = 3;
+        assert_eq!(
+            step_cycles(&mut cpu, &mut bus),
+            nop + interrupt,
+            "{cpu_type:?}"
+        );
+        assert_eq!(cpu.pc, HANDLER, "{cpu_type:?}");
+    }
+}
+
+#[test]
+fn test_reset_cycles() {
+    for (cpu_type, [.., reset]) in MODELS {
+        let (mut cpu, mut bus) = setup(cpu_type, &NOP);
+        bus.write_long(0, 0x8000);
+        bus.write_long(4, 0x1000);
+        cpu.reset(&mut bus);
+        assert_eq!(cpu.reset_cycles, reset as u32, "{cpu_type:?}");
+
+        // The first execute() charges the reset before running anything.
+        let (mut nop_cpu, mut nop_bus) = setup(cpu_type, &NOP);
+        let nop = step_cycles(&mut nop_cpu, &mut nop_bus);
+        assert_eq!(
+            cpu.execute(&mut bus, reset + 1),
+            reset + nop,
+            "{cpu_type:?}"
+        );
+    }
+}
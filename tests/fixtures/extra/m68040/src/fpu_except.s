@@ -16,7 +16,7 @@ run_test:
     fdiv.x %fp1, %fp0           | 1.0 / 0.0 = Infinity, sets DZ
     
     fmove.l %fpsr, %d0
-    btst #4, %d0                | DZ bit in FPSR exception byte
+    btst #4, %d0                | DZ bit in FPSR accrued exception byte
     beq TEST_FAIL               | DZ should be set
     
     /* =================================================================== */
@@ -39,8 +39,8 @@ run_test:
     fdiv.x %fp1, %fp0           | 0/0 = NaN, sets OPERR
     
     fmove.l %fpsr, %d0
-    btst #5, %d0                | OPERR bit
-    beq TEST_FAIL               | OPERR should be set
+    btst #7, %d0                | IOP bit in FPSR accrued exception byte
+    beq TEST_FAIL               | OPERR should accrue IOP
     
     /* =================================================================== */
     /* Test 4: NaN propagation */
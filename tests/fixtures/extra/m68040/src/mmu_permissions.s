@@ -11,8 +11,9 @@ run_test:
     /* Test 1: Enable MMU via TC register */
     /* =================================================================== */
     /* Set up a simple translation control register */
-    /* For 68040: TC bit 15 (E) enables translation */
-    move.l #0x00008000, %d0     | E=1 (enable), other bits default
+    /* For 68040: TC bit 15 (E) enables translation; leave it clear */
+    /* here since no page tables are set up */
+    move.l #0x00004000, %d0     | E=0, P=1 (8K pages)
     movec %d0, %tc              | Write to TC
     
     /* Read back and verify */
@@ -12,8 +12,9 @@ run_test:
     /* Read current TC value */
     movec %tc, %d0
     
-    /* Set TC to enable translation (bit 15 = E bit) */
-    move.l #0x8000, %d0     | E=1, other bits 0
+    /* Set TC page size (bit 14 = P bit). E (bit 15) stays clear */
+    /* because no page tables are set up. */
+    move.l #0x4000, %d0     | E=0, P=1
     movec %d0, %tc
     
     /* Read back and verify */
@@ -82,7 +83,7 @@ run_test:
     /* =================================================================== */
     
     /* Set TC with page size = 4K (PS=0) */
-    move.l #0x8000, %d0     | E=1, PS=0 (4K pages)
+    move.l #0x0000, %d0     | E=0, PS=0 (4K pages)
     movec %d0, %tc
     
     movec %tc, %d1
@@ -90,7 +91,7 @@ run_test:
     bne TEST_FAIL
     
     /* Set TC with page size = 8K (PS=1) */
-    move.l #0xC000, %d0     | E=1, PS=1 (8K pages)
+    move.l #0x4000, %d0     | E=0, PS=1 (8K pages)
     movec %d0, %tc
     
     movec %tc, %d1
@@ -30,7 +30,8 @@ run_test:
     /* =================================================================== */
     /* Test 4: TC - Translation Control */
     /* =================================================================== */
-    move.l #0x00008000, %d0     | Enable bit set
+    /* E stays clear: enabling translation needs page tables */
+    move.l #0x00004000, %d0     | P bit set (8K pages)
     movec %d0, %tc              | Write TC
     movec %tc, %d1              | Read back
     cmp.l %d0, %d1
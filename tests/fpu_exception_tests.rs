@@ -0,0 +1,116 @@
+//! FPU IEEE exception model: FPSR EXC/AEXC latching, FPCR enables and the
+//! pre-instruction exception vectors (48-54).
+
+mod common;
+
+use common::flat::{FlatBus, step_n};
+use m68k::core::memory::AddressBus;
+use m68k::fpu::FloatX80;
+use m68k::{CpuCore, CpuType};
+
+const HANDLER: u32 = 0x3000;
+
+/// 68040 with `code` at 0x1000, FPCR = `fpcr` and every FPU vector pointing at `HANDLER`.
+fn setup(code: &[u16], fpcr: u32) -> (CpuCore, FlatBus) {
+    let (mut cpu, mut bus) = common::flat::setup(CpuType::M68040, code);
+    for vector in 48..=54 {
+        bus.write_long(vector * 4, HANDLER);
+    }
+    cpu.fpcr = fpcr;
+    (cpu, bus)
+}
+
+/// FMOVE.L #a,FP0 ; FMOVE.L #b,FP1 ; FDIV FP1,FP0
+fn divide(a: u16, b: u16) -> Vec<u16> {
+    vec![
+        0xF23C, 0x4000, 0x0000, a, 0xF23C, 0x4080, 0x0000, b, 0xF200, 0x0420,
+    ]
+}
+
+#[test]
+fn test_divide_by_zero_latches_exc_and_aexc() {
+    let (mut cpu, mut bus) = setup(&divide(1, 0), 0);
+    step_n(&mut cpu, &mut bus, 3);
+
+    assert_eq!(cpu.fpr[0], FloatX80::INFINITY);
+    assert_eq!(cpu.fpsr & 0xFF00, 0x0400, "EXC should hold DZ only");
+    assert_eq!(cpu.fpsr & 0x00F8, 0x0010, "AEXC should hold DZ only");
+    assert_eq!(cpu.fpu_pending_exception, None);
+
+    // 1/3 is inexact: EXC is replaced, AEXC keeps accumulating.
+    cpu.pc = 0x1000;
+    bus.write_word(0x100E, 3);
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(cpu.fpsr & 0xFF00, 0x0200, "EXC should hold INEX2 only");
+    assert_eq!(cpu.fpsr & 0x00F8, 0x0018, "AEXC should hold DZ and INEX");
+}
+
+#[test]
+fn test_enabled_divide_by_zero_traps_on_next_fpu_instruction() {
+    let mut code = divide(1, 0);
+    code.extend_from_slice(&[0x4E71, 0xF200, 0x003A]); // NOP ; FTST FP0
+    let (mut cpu, mut bus) = setup(&code, 0x0400);
+
+    step_n(&mut cpu, &mut bus, 4);
+    assert_eq!(cpu.fpu_pending_exception, Some(50));
+    assert_eq!(cpu.pc, 0x1016, "the NOP must run before the trap");
+    assert_eq!(
+        cpu.fpr[0],
+        FloatX80::ONE,
+        "an enabled DZ leaves the destination untouched"
+    );
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, HANDLER);
+    assert_eq!(cpu.fpu_pending_exception, None);
+    let sp = cpu.a(7);
+    assert_eq!(bus.read_long(sp + 2), 0x1016, "stacked PC is the FTST");
+    assert_eq!(bus.read_word(sp + 6), 50 << 2);
+}
+
+#[test]
+fn test_bsun_on_unordered_condition() {
+    // 0/0 = NaN ; FBGT.W *+8
+    let mut code = divide(0, 0);
+    code.extend_from_slice(&[0xF292, 0x0006]);
+
+    let (mut cpu, mut bus) = setup(&code, 0);
+    step_n(&mut cpu, &mut bus, 4);
+    assert_eq!(cpu.pc, 0x1018, "GT is false for unordered operands");
+    assert_ne!(cpu.fpsr & 0x8000, 0, "BSUN should be set in EXC");
+    assert_ne!(cpu.fpsr & 0x0080, 0, "IOP should be set in AEXC");
+
+    // With BSUN enabled the branch traps instead, stacking the FBcc itself.
+    let (mut cpu, mut bus) = setup(&code, 0x8000);
+    step_n(&mut cpu, &mut bus, 4);
+    assert_eq!(cpu.pc, HANDLER);
+    assert_eq!(bus.read_long(cpu.a(7) + 2), 0x1014);
+    assert_eq!(bus.read_word(cpu.a(7) + 6), 48 << 2);
+
+    // The IEEE-aware OGT never signals.
+    code[10] = 0xF282;
+    let (mut cpu, mut bus) = setup(&code, 0x8000);
+    step_n(&mut cpu, &mut bus, 4);
+    assert_eq!(cpu.pc, 0x1018);
+    assert_eq!(cpu.fpsr & 0x8000, 0);
+}
+
+#[test]
+fn test_fsave_frestore_carry_pending_exception() {
+    // 1/0 with DZ enabled ; FSAVE -(A0) ; FRESTORE (A0)+
+    let mut code = divide(1, 0);
+    code.extend_from_slice(&[0xF320, 0xF358]);
+    let (mut cpu, mut bus) = setup(&code, 0x0400);
+    cpu.set_a(0, 0x2000);
+
+    // The pending exception makes the 68040 save a 100-byte BUSY frame.
+    step_n(&mut cpu, &mut bus, 4);
+    assert_eq!(cpu.a(0), 0x2000 - 100);
+    assert_eq!(bus.read_long(0x2000 - 100), 0x4160_0000);
+    assert_ne!(bus.read_long(0x2000 - 4) & 0x0800_0000, 0);
+
+    cpu.fpu_pending_exception = None;
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.a(0), 0x2000);
+    assert_eq!(cpu.fpu_pending_exception, Some(50));
+}
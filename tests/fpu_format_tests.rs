@@ -0,0 +1,132 @@
+//! FPU data formats: packed decimal with static and dynamic k-factors, and the
+//! word and byte integer formats.
+
+mod common;
+
+use common::flat::{FlatBus, step_n};
+use m68k::core::memory::AddressBus;
+use m68k::fpu::FloatX80;
+use m68k::{CpuCore, CpuType};
+
+/// 68040 with `code` at 0x1000, A0 = 0x2000 (source) and A1 = 0x3000 (destination).
+fn setup(code: &[u16]) -> (CpuCore, FlatBus) {
+    let (mut cpu, bus) = common::flat::setup(CpuType::M68040, code);
+    cpu.set_a(0, 0x2000);
+    cpu.set_a(1, 0x3000);
+    (cpu, bus)
+}
+
+fn read_packed(bus: &mut FlatBus, addr: u32) -> [u32; 3] {
+    [
+        bus.read_long(addr),
+        bus.read_long(addr + 4),
+        bus.read_long(addr + 8),
+    ]
+}
+
+#[test]
+fn test_fmove_p_out_static_k_factor() {
+    // FMOVECR #0,FP0 (pi) ; FMOVE.P FP0,(A1){#17} ; FMOVE.P FP0,-(A1){#3}
+    let (mut cpu, mut bus) = setup(&[0xF200, 0x5C00, 0xF211, 0x6C11, 0xF221, 0x6C03]);
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(
+        read_packed(&mut bus, 0x3000),
+        [0x0000_0003, 0x1415_9265, 0x3589_7932]
+    );
+    assert_ne!(cpu.fpsr & 0x0200, 0, "17 digits of pi are inexact");
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.a(1), 0x3000 - 12);
+    assert_eq!(
+        read_packed(&mut bus, 0x3000 - 12),
+        [0x0000_0003, 0x1400_0000, 0]
+    );
+
+    // k > 17 still writes 17 digits but signals an operand error.
+    bus.write_words(0x1006, &[0xF211, 0x6C12]);
+    cpu.pc = 0x1006;
+    cpu.set_a(1, 0x3000);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_ne!(cpu.fpsr & 0x2000, 0, "OPERR expected for k = 18");
+    assert_eq!(bus.read_long(0x3008), 0x3589_7932);
+}
+
+#[test]
+fn test_fmove_p_out_dynamic_k_factor() {
+    // FMOVE.X (A0),FP0 ; FMOVE.P FP0,(A1){D2}
+    let (mut cpu, mut bus) = setup(&[0xF210, 0x4800, 0xF211, 0x7C20]);
+    // 12345.678
+    bus.write_long(0x2000, 0x400C_0000);
+    bus.write_long(0x2004, 0xC0E6_B645);
+    bus.write_long(0x2008, 0xA1CA_C083);
+    cpu.set_d(2, (-2i32) as u32);
+
+    step_n(&mut cpu, &mut bus, 2);
+    // Two digits right of the decimal point: 1.234568E+4
+    assert_eq!(read_packed(&mut bus, 0x3000), [0x0004_0001, 0x2345_6800, 0]);
+}
+
+#[test]
+fn test_fmove_p_in_and_round_trip() {
+    // FMOVE.P (A0),FP0 ; FMOVE.P FP0,(A1){#17}
+    let (mut cpu, mut bus) = setup(&[0xF210, 0x4C00, 0xF211, 0x6C11]);
+    // -1.5E-3
+    bus.write_long(0x2000, 0xC003_0001);
+    bus.write_long(0x2004, 0x5000_0000);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.fpr[0].sign_exp, 0xBFF5);
+    assert_eq!(cpu.fpr[0].mantissa, 0xC49B_A5E3_53F7_CED9);
+    assert_ne!(cpu.fpsr & 0x0100, 0, "decimal input rounding is INEX1");
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(read_packed(&mut bus, 0x3000), [0xC003_0001, 0x5000_0000, 0]);
+
+    // 9.8765432109876543E+321 survives a round trip.
+    let (mut cpu, mut bus) = setup(&[0xF210, 0x4C00, 0xF211, 0x6C11]);
+    let packed = [0x0321_0009, 0x8765_4321, 0x0987_6543];
+    bus.write_long(0x2000, packed[0]);
+    bus.write_long(0x2004, packed[1]);
+    bus.write_long(0x2008, packed[2]);
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(read_packed(&mut bus, 0x3000), packed);
+}
+
+#[test]
+fn test_fmove_p_special_values() {
+    // FMOVE.P (A0),FP0
+    let (mut cpu, mut bus) = setup(&[0xF210, 0x4C00]);
+    bus.write_long(0x2000, 0xFFFF_0000);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.fpr[0], FloatX80::infinity(true));
+
+    // FMOVE.P FP1,(A1){#5} with FP1 = +0
+    let (mut cpu, mut bus) = setup(&[0xF211, 0x6C85]);
+    bus.write_long(0x3000, 0xDEAD_BEEF);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(read_packed(&mut bus, 0x3000), [0, 0, 0]);
+}
+
+#[test]
+fn test_word_and_byte_formats() {
+    // FMOVE.W #-2,FP0 ; FMOVE.B FP0,D1 ; FMOVE.B (A0)+,FP1 ; FADD.W D3,FP1
+    let (mut cpu, mut bus) = setup(&[
+        0xF23C, 0x5000, 0xFFFE, 0xF201, 0x7800, 0xF218, 0x5880, 0xF203, 0x50A2,
+    ]);
+    cpu.set_d(1, 0x1234_5678);
+    cpu.set_d(3, 0xFFFF_0100);
+    bus.write_byte(0x2000, 0x85);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.fpr[0], FloatX80::from_i32(-2));
+    assert_eq!(cpu.d(1), 0x1234_56FE);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.a(0), 0x2001);
+    assert_eq!(cpu.fpr[1], FloatX80::from_i32(-123 + 256));
+
+    // FMOVE.W FP0,D1 saturates out-of-range values with OPERR.
+    let (mut cpu, mut bus) = setup(&[0xF23C, 0x4000, 0x0001, 0x0000, 0xF201, 0x7000]);
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(1) & 0xFFFF, 0x7FFF);
+    assert_ne!(cpu.fpsr & 0x2000, 0);
+}
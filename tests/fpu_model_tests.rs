@@ -0,0 +1,135 @@
+//! FPU model selection: Line-F without an FPU and the per-model FSAVE/FRESTORE
+//! frames (68881 $1F, 68882 $20, 68040 $41).
+
+mod common;
+
+use common::flat::FlatBus;
+use m68k::core::memory::AddressBus;
+use m68k::fpu::FpuType;
+use m68k::{CpuCore, CpuType, StepResult};
+
+const HANDLER: u32 = 0x3000;
+
+/// FSAVE -(A0)
+const FSAVE_PREDEC_A0: u16 = 0xF320;
+/// FRESTORE (A0)
+const FRESTORE_A0: u16 = 0xF350;
+/// FRESTORE (A0)+
+const FRESTORE_POSTINC_A0: u16 = 0xF358;
+
+/// `cpu_type` fitted with `fpu_type`, `code` at 0x1000, A0 = 0x2000 and the format
+/// error vector pointing at `HANDLER`.
+fn setup(cpu_type: CpuType, fpu_type: FpuType, code: &[u16]) -> (CpuCore, FlatBus) {
+    let (mut cpu, mut bus) = common::flat::setup(cpu_type, code);
+    cpu.set_fpu_type(fpu_type);
+    bus.write_long(14 * 4, HANDLER);
+    cpu.set_a(0, 0x2000);
+    (cpu, bus)
+}
+
+#[test]
+fn test_cpu_type_selects_default_fpu() {
+    let mut cpu = CpuCore::new();
+    assert_eq!(cpu.fpu_type, FpuType::None);
+    for (cpu_type, fpu_type) in [
+        (CpuType::M68010, FpuType::None),
+        (CpuType::M68020, FpuType::M68881),
+        (CpuType::M68EC030, FpuType::M68881),
+        (CpuType::M68LC040, FpuType::None),
+        (CpuType::M68040, FpuType::M68040),
+    ] {
+        cpu.set_cpu_type(cpu_type);
+        assert_eq!(cpu.fpu_type, fpu_type, "{cpu_type:?}");
+    }
+}
+
+#[test]
+fn test_no_fpu_takes_fline() {
+    // FNOP, FSAVE -(A0) and FBF.W all trap on an FPU-less 68030.
+    for code in [
+        [0xF280, 0x0000],
+        [FSAVE_PREDEC_A0, 0x4E71],
+        [0xF280, 0x0002],
+    ] {
+        let (mut cpu, mut bus) = setup(CpuType::M68030, FpuType::None, &code);
+        assert!(matches!(
+            cpu.step(&mut bus),
+            StepResult::FlineTrap { opcode } if opcode == code[0]
+        ));
+        assert_eq!(cpu.a(0), 0x2000);
+    }
+
+    // FMOVE.L #1,FP0 on a 68EC040.
+    let (mut cpu, mut bus) = setup(
+        CpuType::M68EC040,
+        FpuType::None,
+        &[0xF23C, 0x4000, 0x0000, 0x0001],
+    );
+    assert!(matches!(
+        cpu.step(&mut bus),
+        StepResult::FlineTrap { opcode: 0xF23C }
+    ));
+}
+
+#[test]
+fn test_fsave_idle_frame_per_model() {
+    for (fpu_type, header, len) in [
+        (FpuType::M68881, 0x1F18_0000, 28),
+        (FpuType::M68882, 0x2038_0000, 60),
+        (FpuType::M68040, 0x4100_0000, 4),
+    ] {
+        let (mut cpu, mut bus) = setup(CpuType::M68030, fpu_type, &[FSAVE_PREDEC_A0]);
+        cpu.step(&mut bus);
+        assert_eq!(cpu.a(0), 0x2000 - len, "{fpu_type:?}");
+        assert_eq!(bus.read_long(0x2000 - len), header, "{fpu_type:?}");
+    }
+}
+
+#[test]
+fn test_frestore_null_then_fsave_writes_null_frame() {
+    let (mut cpu, mut bus) = setup(
+        CpuType::M68020,
+        FpuType::M68882,
+        &[FRESTORE_A0, FSAVE_PREDEC_A0],
+    );
+    bus.write_long(0x2000, 0);
+    cpu.fpcr = 0x0400;
+    cpu.step(&mut bus);
+    assert_eq!(cpu.fpcr, 0);
+    assert_eq!(cpu.a(0), 0x2000);
+
+    bus.write_long(0x1FFC, 0xFFFF_FFFF);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.a(0), 0x1FFC);
+    assert_eq!(bus.read_long(0x1FFC), 0);
+}
+
+#[test]
+fn test_frestore_postincrement_skips_whole_frame() {
+    for (fpu_type, header, len) in [
+        (FpuType::M68881, 0x1FB4_0000, 184),
+        (FpuType::M68882, 0x2038_0000, 60),
+        (FpuType::M68040, 0x4130_0000, 52),
+    ] {
+        let (mut cpu, mut bus) = setup(CpuType::M68030, fpu_type, &[FRESTORE_POSTINC_A0]);
+        bus.write_long(0x2000, header);
+        cpu.step(&mut bus);
+        assert_eq!(cpu.pc, 0x1002, "{fpu_type:?}");
+        assert_eq!(cpu.a(0), 0x2000 + len, "{fpu_type:?}");
+    }
+}
+
+#[test]
+fn test_frestore_rejects_other_models_frame() {
+    // A 68881 IDLE frame on a 68882, and a 68882 size byte with the 68040 version.
+    for (fpu_type, header) in [
+        (FpuType::M68882, 0x1F18_0000),
+        (FpuType::M68040, 0x4138_0000),
+    ] {
+        let (mut cpu, mut bus) = setup(CpuType::M68030, fpu_type, &[FRESTORE_POSTINC_A0]);
+        bus.write_long(0x2000, header);
+        cpu.step(&mut bus);
+        assert_eq!(cpu.pc, HANDLER, "{fpu_type:?}");
+        assert_eq!(cpu.a(0), 0x2000, "{fpu_type:?}");
+    }
+}
@@ -0,0 +1,88 @@
+//! 80-bit FPU register tests: extended values must survive moves bit-exact and
+//! arithmetic must honour the FPCR rounding mode and precision.
+
+mod common;
+
+use common::flat::{CODE, FlatBus};
+use m68k::core::memory::AddressBus;
+use m68k::fpu::FloatX80;
+use m68k::{CpuCore, CpuType, StepResult};
+
+/// Load `code` at 0x1000 on a 68040 and step through it.
+fn run(code: &[u16], setup: impl FnOnce(&mut CpuCore, &mut FlatBus)) -> (CpuCore, FlatBus) {
+    let (mut cpu, mut bus) = common::flat::setup(CpuType::M68040, code);
+    setup(&mut cpu, &mut bus);
+
+    while cpu.pc < CODE + (code.len() as u32) * 2 {
+        let result = cpu.step(&mut bus);
+        assert!(matches!(result, StepResult::Ok { .. }));
+    }
+    (cpu, bus)
+}
+
+/// FMOVE.L #1,FP0 ; FMOVE.L #3,FP1 ; FMOVE.L D0,FPCR ; FDIV FP1,FP0
+const ONE_THIRD: [u16; 10] = [
+    0xF23C, 0x4000, 0x0000, 0x0001, 0xF23C, 0x4080, 0x0000, 0x0003, 0xF200, 0x9000,
+];
+
+fn one_third(fpcr: u32) -> FloatX80 {
+    let mut code = ONE_THIRD.to_vec();
+    code.extend_from_slice(&[0xF200, 0x0420]);
+    let (cpu, _) = run(&code, |cpu, _| cpu.set_d(0, fpcr));
+    cpu.fpr[0]
+}
+
+#[test]
+fn test_fmove_x_round_trips_full_mantissa() {
+    // FMOVE.X (A0),FP0 ; FMOVE.X FP0,(A1)
+    let (cpu, mut bus) = run(&[0xF210, 0x4800, 0xF211, 0x6800], |cpu, bus| {
+        cpu.set_a(0, 0x2000);
+        cpu.set_a(1, 0x3000);
+        bus.write_long(0x2000, 0xBFFF_0000);
+        bus.write_long(0x2004, 0x8000_0000);
+        bus.write_long(0x2008, 0x0000_0001);
+    });
+
+    assert_eq!(cpu.fpr[0], FloatX80::new(0xBFFF, 0x8000_0000_0000_0001));
+    assert_eq!(bus.read_long(0x3000), 0xBFFF_0000);
+    assert_eq!(bus.read_long(0x3004), 0x8000_0000);
+    assert_eq!(bus.read_long(0x3008), 0x0000_0001);
+}
+
+#[test]
+fn test_fmovecr_pi_is_exact() {
+    // FMOVECR #0,FP1
+    let (cpu, _) = run(&[0xF200, 0x5C80], |_, _| {});
+    assert_eq!(cpu.fpr[1], FloatX80::new(0x4000, 0xC90F_DAA2_2168_C235));
+}
+
+#[test]
+fn test_fdiv_honours_fpcr_precision_and_mode() {
+    assert_eq!(
+        one_third(0x00),
+        FloatX80::new(0x3FFD, 0xAAAA_AAAA_AAAA_AAAB),
+        "extended, round to nearest"
+    );
+    assert_eq!(
+        one_third(0x10),
+        FloatX80::new(0x3FFD, 0xAAAA_AAAA_AAAA_AAAA),
+        "extended, round toward zero"
+    );
+    assert_eq!(
+        one_third(0x40),
+        FloatX80::new(0x3FFD, 0xAAAA_AB00_0000_0000),
+        "single, round to nearest"
+    );
+    assert_eq!(
+        one_third(0x80),
+        FloatX80::new(0x3FFD, 0xAAAA_AAAA_AAAA_A800),
+        "double, round to nearest"
+    );
+}
+
+#[test]
+fn test_fsqrt_two_extended() {
+    // FMOVE.L #2,FP0 ; FSQRT FP0
+    let (cpu, _) = run(&[0xF23C, 0x4000, 0x0000, 0x0002, 0xF200, 0x0004], |_, _| {});
+    assert_eq!(cpu.fpr[0], FloatX80::new(0x3FFF, 0xB504_F333_F9DE_6484));
+}
@@ -0,0 +1,210 @@
+//! Function codes driven on every bus access: program/data, user/supervisor, exception
+//! stacking and MOVES into arbitrary spaces, including CPU space.
+
+mod common;
+
+use common::flat::{CODE, FlatBus, step_n, supervisor_cpu};
+use m68k::core::memory::{AddressBus, BusFault};
+use m68k::{CpuCore, CpuType, StepResult};
+
+/// Memory that logs the (fc, address, write) of each access. CPU space (FC 7) reads
+/// return `0xC0DE` and its writes are only logged.
+struct TestBus {
+    flat: FlatBus,
+    accesses: Vec<(u8, u32, bool)>,
+}
+
+impl TestBus {
+    fn new() -> Self {
+        Self {
+            flat: FlatBus::new(),
+            accesses: Vec::new(),
+        }
+    }
+
+    /// Function codes of the accesses to `addr`.
+    fn fcs_at(&self, addr: u32) -> Vec<(u8, bool)> {
+        self.accesses
+            .iter()
+            .filter(|a| a.1 == addr)
+            .map(|a| (a.0, a.2))
+            .collect()
+    }
+}
+
+impl AddressBus for TestBus {
+    fn read_byte(&mut self, address: u32) -> u8 {
+        self.flat.read_byte(address)
+    }
+
+    fn read_word(&mut self, address: u32) -> u16 {
+        self.flat.read_word(address)
+    }
+
+    fn read_long(&mut self, address: u32) -> u32 {
+        self.flat.read_long(address)
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) {
+        self.flat.write_byte(address, value);
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) {
+        self.flat.write_word(address, value);
+    }
+
+    fn write_long(&mut self, address: u32, value: u32) {
+        self.flat.write_long(address, value);
+    }
+
+    fn try_read_byte_fc(&mut self, fc: u8, address: u32) -> Result<u8, BusFault> {
+        self.accesses.push((fc, address, false));
+        Ok(if fc == 7 {
+            0xDE
+        } else {
+            self.read_byte(address)
+        })
+    }
+
+    fn try_read_word_fc(&mut self, fc: u8, address: u32) -> Result<u16, BusFault> {
+        self.accesses.push((fc, address, false));
+        Ok(if fc == 7 {
+            0xC0DE
+        } else {
+            self.read_word(address)
+        })
+    }
+
+    fn try_read_long_fc(&mut self, fc: u8, address: u32) -> Result<u32, BusFault> {
+        self.accesses.push((fc, address, false));
+        Ok(if fc == 7 {
+            0xC0DE
+        } else {
+            self.read_long(address)
+        })
+    }
+
+    fn try_write_byte_fc(&mut self, fc: u8, address: u32, value: u8) -> Result<(), BusFault> {
+        self.accesses.push((fc, address, true));
+        if fc != 7 {
+            self.write_byte(address, value);
+        }
+        Ok(())
+    }
+
+    fn try_write_word_fc(&mut self, fc: u8, address: u32, value: u16) -> Result<(), BusFault> {
+        self.accesses.push((fc, address, true));
+        if fc != 7 {
+            self.write_word(address, value);
+        }
+        Ok(())
+    }
+
+    fn try_write_long_fc(&mut self, fc: u8, address: u32, value: u32) -> Result<(), BusFault> {
+        self.accesses.push((fc, address, true));
+        if fc != 7 {
+            self.write_long(address, value);
+        }
+        Ok(())
+    }
+}
+
+/// `cpu_type` in supervisor mode with `code` at 0x1000.
+fn setup(cpu_type: CpuType, code: &[u16]) -> (CpuCore, TestBus) {
+    let mut bus = TestBus::new();
+    bus.flat.write_words(CODE, code);
+    (supervisor_cpu(cpu_type), bus)
+}
+
+#[test]
+fn test_program_and_data_spaces_follow_the_s_bit() {
+    // MOVE.W $4000,D0 ; ANDI #$DFFF,SR ; MOVE.W D0,$4000
+    let (mut cpu, mut bus) = setup(
+        CpuType::M68000,
+        &[0x3038, 0x4000, 0x027C, 0xDFFF, 0x31C0, 0x4000],
+    );
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(bus.fcs_at(0x1000), [(6, false)]);
+    assert_eq!(bus.fcs_at(0x4000), [(5, false), (1, true)]);
+    assert_eq!(bus.fcs_at(0x1004), [(6, false)]);
+    assert_eq!(bus.fcs_at(0x1008), [(2, false)], "user program after ANDI");
+}
+
+#[test]
+fn test_exception_stacking_uses_supervisor_data_space() {
+    // ANDI #$DFFF,SR ; TRAP #0
+    let (mut cpu, mut bus) = setup(CpuType::M68000, &[0x027C, 0xDFFF, 0x4E40]);
+    bus.write_long(32 * 4, 0x2000);
+    cpu.step(&mut bus);
+    bus.accesses.clear();
+    let StepResult::TrapInstruction { trap_num } = cpu.step(&mut bus) else {
+        panic!("expected TRAP");
+    };
+    cpu.take_trap_exception(&mut bus, trap_num);
+
+    assert_eq!(cpu.pc, 0x2000);
+    assert_eq!(bus.fcs_at(0x1004), [(2, false)]);
+    assert_eq!(bus.fcs_at(32 * 4), [(5, false)], "vector fetch");
+    assert!(
+        bus.accesses
+            .iter()
+            .filter(|a| a.2)
+            .all(|a| a.0 == 5 && a.1 < 0x8000),
+        "{:x?}",
+        bus.accesses
+    );
+}
+
+#[test]
+fn test_moves_reaches_sfc_and_dfc_spaces() {
+    // MOVES.W $4000,D0 ; MOVES.L D1,$4000
+    let (mut cpu, mut bus) = setup(
+        CpuType::M68010,
+        &[0x0E78, 0x0000, 0x4000, 0x0EB8, 0x1800, 0x4000],
+    );
+    cpu.sfc = 7;
+    cpu.dfc = 1;
+    cpu.set_d(1, 0x1234_5678);
+
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(0) & 0xFFFF, 0xC0DE, "CPU space read");
+    cpu.step(&mut bus);
+    assert_eq!(bus.read_long(0x4000), 0x1234_5678);
+    assert_eq!(bus.fcs_at(0x4000), [(7, false), (1, true)]);
+
+    // Ordinary accesses are back in supervisor data space afterwards.
+    cpu.push_16(&mut bus, 0);
+    assert_eq!(bus.accesses.last(), Some(&(5, 0x7FFE, true)));
+}
+
+#[test]
+fn test_060_mull_form_check_only_fetches_program_space() {
+    // MULS.L D1,D0 ; MULS.L D1,D3:D0
+    let (mut cpu, mut bus) = setup(CpuType::M68060, &[0x4C01, 0x0800, 0x4C01, 0x3C00]);
+    bus.write_long(61 * 4, 0x2000);
+    cpu.step(&mut bus);
+    assert_eq!(bus.fcs_at(0x1002), [(6, false)]);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x2000, "unimplemented integer instruction");
+    assert!(bus.fcs_at(0x1006).is_empty(), "{:x?}", bus.accesses);
+}
+
+#[test]
+fn test_cpu32_table_lookup_check_only_fetches_program_space() {
+    // TBLU.B D2:D3,D1
+    let (mut cpu, mut bus) = setup(CpuType::CPU32, &[0xF802, 0x1003]);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x1004);
+    assert_eq!(bus.fcs_at(0x1002), [(6, false)]);
+}
+
+#[test]
+fn test_coldfire_rem_check_only_fetches_program_space() {
+    // REMS.L D1,D2:D0
+    let (mut cpu, mut bus) = setup(CpuType::ColdFireIsaA, &[0x4C41, 0x0802]);
+    cpu.set_d(0, 7);
+    cpu.set_d(1, 3);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(2), 1);
+    assert_eq!(bus.fcs_at(0x1002), [(6, false)]);
+}
@@ -242,9 +242,9 @@ fn test_gap_dbf_instruction() {
     cpu.pc = 0x1000;
     cpu.set_a(7, 0x8000); // Stack
 
-    // Execute enough cycles
+    // Execute one instruction at a time so the exit check sees every PC
     for _ in 0..20 {
-        cpu.execute(&mut bus, 100);
+        cpu.execute(&mut bus, 1);
         if cpu.pc == 0x1006 || cpu.pc > 0x1010 {
             break;
         }
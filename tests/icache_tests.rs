@@ -0,0 +1,91 @@
+//! 68020/68030 instruction cache: stale opcodes, freeze, clearing through CACR and the
+//! cost of misses.
+
+mod common;
+
+use common::flat::{FlatBus, step_cycles};
+use m68k::core::cache::cacr;
+use m68k::{CpuCore, CpuType};
+
+/// `cpu_type` with cache emulation on, `cacr` loaded and `code` at 0x1000.
+fn setup(cpu_type: CpuType, cacr: u32, code: &[u16]) -> (CpuCore, FlatBus) {
+    let (mut cpu, bus) = common::flat::setup(cpu_type, code);
+    cpu.set_cache_emulation(true);
+    cpu.write_control_register(0x002, cacr);
+    (cpu, bus)
+}
+
+/// Run the instruction at `pc` and return its cycle count.
+fn run_at(cpu: &mut CpuCore, bus: &mut FlatBus, pc: u32) -> i32 {
+    cpu.pc = pc;
+    step_cycles(cpu, bus)
+}
+
+#[test]
+fn test_stale_opcode_served_until_cleared() {
+    // MOVEQ #1,D0
+    let (mut cpu, mut bus) = setup(CpuType::M68020, cacr::ENABLE_I, &[0x7001]);
+    run_at(&mut cpu, &mut bus, 0x1000);
+    bus.write_words(0x1000, &[0x7002]);
+
+    run_at(&mut cpu, &mut bus, 0x1000);
+    assert_eq!(cpu.d(0), 1, "the cached MOVEQ #1 runs");
+
+    cpu.write_control_register(0x002, cacr::ENABLE_I | cacr::CLEAR_I);
+    run_at(&mut cpu, &mut bus, 0x1000);
+    assert_eq!(cpu.d(0), 2);
+    assert_eq!(
+        cpu.read_control_register(0x002),
+        cacr::ENABLE_I,
+        "C reads as 0"
+    );
+
+    // Without the cache model the new opcode is fetched straight away.
+    let (mut cpu, mut bus) = setup(CpuType::M68020, cacr::ENABLE_I, &[0x7001]);
+    cpu.set_cache_emulation(false);
+    run_at(&mut cpu, &mut bus, 0x1000);
+    bus.write_words(0x1000, &[0x7002]);
+    run_at(&mut cpu, &mut bus, 0x1000);
+    assert_eq!(cpu.d(0), 2);
+}
+
+#[test]
+fn test_frozen_cache_does_not_fill() {
+    let (mut cpu, mut bus) = setup(CpuType::M68030, cacr::ENABLE_I | cacr::FREEZE_I, &[0x7001]);
+    run_at(&mut cpu, &mut bus, 0x1000);
+    bus.write_words(0x1000, &[0x7002]);
+    run_at(&mut cpu, &mut bus, 0x1000);
+    assert_eq!(cpu.d(0), 2);
+    assert_eq!((cpu.icache.hits, cpu.icache.misses), (0, 2));
+}
+
+#[test]
+fn test_clear_entry_uses_caar() {
+    // MOVEQ #1,D0 ; NOP ; MOVEQ #1,D1 -- one 68030 cache line.
+    let (mut cpu, mut bus) = setup(CpuType::M68030, cacr::ENABLE_I, &[0x7001, 0x4E71, 0x7201]);
+    for pc in [0x1000, 0x1002, 0x1004] {
+        run_at(&mut cpu, &mut bus, pc);
+    }
+    bus.write_words(0x1000, &[0x7002, 0x4E71, 0x7202]);
+
+    cpu.write_control_register(0x802, 0x1000);
+    cpu.write_control_register(0x002, cacr::ENABLE_I | cacr::CLEAR_ENTRY_I);
+    run_at(&mut cpu, &mut bus, 0x1000);
+    run_at(&mut cpu, &mut bus, 0x1004);
+    assert_eq!(cpu.d(0), 2, "the cleared long word is refetched");
+    assert_eq!(cpu.d(1), 1, "the rest of the line still hits");
+}
+
+#[test]
+fn test_misses_cost_bus_cycles() {
+    for (cpu_type, penalty) in [(CpuType::M68020, 3), (CpuType::M68030, 2)] {
+        let (mut cpu, mut bus) = setup(cpu_type, cacr::ENABLE_I, &[0x7001]);
+        let miss = run_at(&mut cpu, &mut bus, 0x1000);
+        let hit = run_at(&mut cpu, &mut bus, 0x1000);
+        assert_eq!(miss - hit, penalty, "{cpu_type:?}");
+
+        // A disabled cache pays for every fetch.
+        cpu.write_control_register(0x002, 0);
+        assert_eq!(run_at(&mut cpu, &mut bus, 0x1000), miss, "{cpu_type:?}");
+    }
+}
@@ -0,0 +1,131 @@
+//! Per-generation instruction timing: 68000 handler counts, 68020/68030 cache case, the
+//! 68040 tables, and instruction cache misses with the cache model on.
+
+mod common;
+
+use common::flat::{FlatBus, step_cycles};
+use m68k::core::cache::cacr;
+use m68k::core::memory::AddressBus;
+use m68k::{CpuCore, CpuType};
+
+/// `cpu_type` with `code` at 0x1000, A0 pointing at data and every vector at 0x3000.
+fn setup(cpu_type: CpuType, code: &[u16]) -> (CpuCore, FlatBus) {
+    let (mut cpu, mut bus) = common::flat::setup(cpu_type, code);
+    for vector in 2..64 {
+        bus.write_long(vector * 4, 0x3000);
+    }
+    cpu.set_a(0, 0x2000);
+    (cpu, bus)
+}
+
+/// Cycles of the single instruction `code` on `cpu_type`.
+fn cycles_of(cpu_type: CpuType, code: &[u16]) -> i32 {
+    let (mut cpu, mut bus) = setup(cpu_type, code);
+    step_cycles(&mut cpu, &mut bus)
+}
+
+#[test]
+fn test_68000_keeps_handler_counts() {
+    for cpu_type in [CpuType::M68000, CpuType::M68010] {
+        assert_eq!(cycles_of(cpu_type, &[0x2200]), 4, "MOVE.L D0,D1");
+        assert_eq!(cycles_of(cpu_type, &[0x4E71]), 4, "NOP");
+    }
+}
+
+#[test]
+fn test_68020_cache_case() {
+    for cpu_type in [CpuType::M68020, CpuType::M68EC030, CpuType::M68030] {
+        assert_eq!(cycles_of(cpu_type, &[0x7001]), 2, "MOVEQ");
+        assert_eq!(cycles_of(cpu_type, &[0x2210]), 6, "MOVE.L (A0),D1");
+        assert_eq!(cycles_of(cpu_type, &[0x20C1]), 4, "MOVE.L D1,(A0)+");
+        assert_eq!(cycles_of(cpu_type, &[0xD190]), 8, "ADD.L D0,(A0)");
+        assert_eq!(cycles_of(cpu_type, &[0x0680, 0, 1]), 6, "ADDI.L #1,D0");
+        assert_eq!(cycles_of(cpu_type, &[0x43E8, 0x0010]), 3, "LEA (16,A0),A1");
+        assert_eq!(cycles_of(cpu_type, &[0xC2C0]), 27, "MULU.W D0,D1");
+        assert_eq!(cycles_of(cpu_type, &[0x4E75]), 10, "RTS");
+    }
+}
+
+#[test]
+fn test_68040_tables() {
+    assert_eq!(cycles_of(CpuType::M68040, &[0x7001]), 1, "MOVEQ");
+    assert_eq!(cycles_of(CpuType::M68040, &[0x2210]), 2, "MOVE.L (A0),D1");
+    assert_eq!(cycles_of(CpuType::M68040, &[0xC2C0]), 16, "MULU.W D0,D1");
+    assert_eq!(
+        cycles_of(CpuType::M68040, &[0x4C00, 0x1000]),
+        20,
+        "MULU.L D0,D1"
+    );
+}
+
+#[test]
+fn test_branches_and_loops() {
+    // (BEQ.S taken, BNE.S not taken, BNE.W not taken), with Z set.
+    for (cpu_type, expected) in [(CpuType::M68020, [6, 4, 6]), (CpuType::M68040, [2, 3, 3])] {
+        for (code, cycles) in [&[0x6702][..], &[0x6602], &[0x6600, 0x0002]]
+            .into_iter()
+            .zip(expected)
+        {
+            let (mut cpu, mut bus) = setup(cpu_type, code);
+            cpu.set_ccr(0x04);
+            assert_eq!(
+                step_cycles(&mut cpu, &mut bus),
+                cycles,
+                "{cpu_type:?} {code:04X?}"
+            );
+        }
+    }
+
+    // DBF D0: branch while the counter runs, fall through when it expires.
+    let (mut cpu, mut bus) = setup(CpuType::M68020, &[0x51C8, 0xFFFE]);
+    cpu.set_d(0, 1);
+    assert_eq!(step_cycles(&mut cpu, &mut bus), 6);
+    assert_eq!(step_cycles(&mut cpu, &mut bus), 12);
+    assert_eq!(cpu.pc, 0x1004);
+}
+
+#[test]
+fn test_movem_and_full_extension_words() {
+    // MOVEM.L D0-D3,-(A7): 4 + -(An) 3 + four registers at 4 clocks.
+    assert_eq!(cycles_of(CpuType::M68020, &[0x48E7, 0xF000]), 23);
+    // MOVEM.L (A0)+,D0-D1 on the 68040: 3 + one clock per register.
+    assert_eq!(cycles_of(CpuType::M68040, &[0x4CD8, 0x0003]), 5);
+
+    // MOVE.L (A0,D0.L),D1 brief, then ([16,A0]),D1: word base displacement and a
+    // memory indirection on top of the indexed fetch.
+    let brief = cycles_of(CpuType::M68020, &[0x2230, 0x0800]);
+    assert_eq!(brief, 9);
+    let (mut cpu, mut bus) = setup(CpuType::M68020, &[0x2230, 0x0161, 0x0010]);
+    bus.write_long(0x2010, 0x2100);
+    assert_eq!(step_cycles(&mut cpu, &mut bus), brief + 2 + 5);
+}
+
+#[test]
+fn test_exceptions_keep_exception_timing() {
+    // DIVU.W D1,D0 by zero on the 68020 costs the exception, not the divide.
+    let (mut cpu, mut bus) = setup(CpuType::M68020, &[0x80C1]);
+    assert_eq!(step_cycles(&mut cpu, &mut bus), 38);
+    assert_eq!(cpu.pc, 0x3000);
+}
+
+#[test]
+fn test_cache_misses_add_bus_time() {
+    // The 68020 pays three clocks per long word that misses, only with the cache model on.
+    let (mut cpu, mut bus) = setup(CpuType::M68020, &[0x7001]);
+    cpu.set_cache_emulation(true);
+    cpu.write_control_register(0x002, cacr::ENABLE_I);
+    assert_eq!(step_cycles(&mut cpu, &mut bus), 2 + 3);
+    cpu.pc = 0x1000;
+    assert_eq!(step_cycles(&mut cpu, &mut bus), 2);
+
+    // 68040: a line fill on a miss, then hits; a disabled cache pays per fetch.
+    let (mut cpu, mut bus) = setup(CpuType::M68040, &[0x7001]);
+    cpu.set_cache_emulation(true);
+    cpu.write_control_register(0x002, cacr::ENABLE_I_040);
+    assert_eq!(step_cycles(&mut cpu, &mut bus), 1 + 5);
+    cpu.pc = 0x1000;
+    assert_eq!(step_cycles(&mut cpu, &mut bus), 1);
+    cpu.write_control_register(0x002, 0);
+    cpu.pc = 0x1000;
+    assert_eq!(step_cycles(&mut cpu, &mut bus), 1 + 2);
+}
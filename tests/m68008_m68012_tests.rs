@@ -0,0 +1,206 @@
+//! MC68008 and MC68012: address bus widths, the 68008's byte-wide data bus and its timing.
+
+mod common;
+
+use std::collections::HashMap;
+
+use common::flat::{CODE, step_cycles, supervisor_cpu};
+use m68k::core::memory::{AddressBus, BusCycle};
+use m68k::{CpuCore, CpuType, StepResult};
+
+/// Sparse memory that counts the accesses of each width and logs reported bus cycles.
+#[derive(Default)]
+struct TestBus {
+    memory: HashMap<u32, u8>,
+    byte_accesses: usize,
+    wide_accesses: usize,
+    cycles: Vec<BusCycle>,
+}
+
+impl TestBus {
+    fn poke_words(&mut self, addr: u32, words: &[u16]) {
+        for (i, w) in words.iter().enumerate() {
+            let [hi, lo] = w.to_be_bytes();
+            self.memory.insert(addr + i as u32 * 2, hi);
+            self.memory.insert(addr + i as u32 * 2 + 1, lo);
+        }
+    }
+
+    fn peek(&self, addr: u32) -> u8 {
+        self.memory.get(&addr).copied().unwrap_or(0)
+    }
+}
+
+impl AddressBus for TestBus {
+    fn read_byte(&mut self, address: u32) -> u8 {
+        self.byte_accesses += 1;
+        self.peek(address)
+    }
+
+    fn read_word(&mut self, address: u32) -> u16 {
+        self.wide_accesses += 1;
+        u16::from_be_bytes([self.peek(address), self.peek(address + 1)])
+    }
+
+    fn read_long(&mut self, address: u32) -> u32 {
+        self.wide_accesses += 1;
+        u32::from_be_bytes([
+            self.peek(address),
+            self.peek(address + 1),
+            self.peek(address + 2),
+            self.peek(address + 3),
+        ])
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) {
+        self.byte_accesses += 1;
+        self.memory.insert(address, value);
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) {
+        self.wide_accesses += 1;
+        for (i, b) in value.to_be_bytes().into_iter().enumerate() {
+            self.memory.insert(address + i as u32, b);
+        }
+    }
+
+    fn write_long(&mut self, address: u32, value: u32) {
+        self.wide_accesses += 1;
+        for (i, b) in value.to_be_bytes().into_iter().enumerate() {
+            self.memory.insert(address + i as u32, b);
+        }
+    }
+
+    fn bus_cycle(&mut self, cycle: BusCycle) -> u32 {
+        self.cycles.push(cycle);
+        0
+    }
+}
+
+/// `cpu_type` in supervisor mode with `code` at 0x1000.
+fn setup(cpu_type: CpuType, code: &[u16]) -> (CpuCore, TestBus) {
+    let mut bus = TestBus::default();
+    bus.poke_words(CODE, code);
+    (supervisor_cpu(cpu_type), bus)
+}
+
+// MOVE.L (A0),D0
+const MOVE_L_IND_D0: [u16; 1] = [0x2010];
+
+#[test]
+fn test_68008_runs_byte_cycles_only() {
+    let (mut cpu, mut bus) = setup(CpuType::M68008, &[0x2010, 0x2280]); // MOVE.L D0,(A1)
+    bus.poke_words(0x4000, &[0x1234, 0x5678]);
+    cpu.set_a(0, 0x4000);
+    cpu.set_a(1, 0x5000);
+    step_cycles(&mut cpu, &mut bus);
+    step_cycles(&mut cpu, &mut bus);
+
+    assert_eq!(cpu.d(0), 0x1234_5678);
+    assert_eq!(
+        [0x5000, 0x5001, 0x5002, 0x5003].map(|a| bus.peek(a)),
+        [0x12, 0x34, 0x56, 0x78]
+    );
+    assert_eq!(bus.wide_accesses, 0);
+    assert!(bus.byte_accesses >= 12);
+}
+
+#[test]
+fn test_68008_timing_counts_byte_cycles() {
+    // NOP: one word fetch, 4 clocks on the 68000 and 8 on the 68008.
+    let (mut cpu, mut bus) = setup(CpuType::M68000, &[0x4E71]);
+    assert_eq!(step_cycles(&mut cpu, &mut bus), 4);
+    let (mut cpu, mut bus) = setup(CpuType::M68008, &[0x4E71]);
+    assert_eq!(step_cycles(&mut cpu, &mut bus), 8);
+
+    // MOVE.L (A0),D0 runs three more byte cycles: one for the opcode, two for the operand.
+    let (mut cpu, mut bus) = setup(CpuType::M68000, &MOVE_L_IND_D0);
+    cpu.set_a(0, 0x4000);
+    let base = step_cycles(&mut cpu, &mut bus);
+    let (mut cpu, mut bus) = setup(CpuType::M68008, &MOVE_L_IND_D0);
+    cpu.set_a(0, 0x4000);
+    assert_eq!(step_cycles(&mut cpu, &mut bus), base + 12);
+}
+
+#[test]
+fn test_68008_reports_byte_bus_cycles() {
+    let (mut cpu, mut bus) = setup(CpuType::M68008, &MOVE_L_IND_D0);
+    cpu.set_bus_cycle_timing(true);
+    cpu.set_a(0, 0x4000);
+    step_cycles(&mut cpu, &mut bus);
+
+    let cycles: Vec<_> = bus.cycles.iter().map(|c| (c.address, c.offset)).collect();
+    assert_eq!(
+        cycles,
+        vec![
+            (0x1000, 0),
+            (0x1001, 4),
+            (0x4000, 8),
+            (0x4001, 12),
+            (0x4002, 16),
+            (0x4003, 20),
+        ]
+    );
+    assert!(bus.cycles.iter().all(|c| c.size == 1));
+}
+
+#[test]
+fn test_68008_address_bus_width() {
+    // MOVE.B D0,(A0)
+    let (mut cpu, mut bus) = setup(CpuType::M68008, &[0x1080]);
+    cpu.set_d(0, 0xAA);
+    cpu.set_a(0, 0x0012_3456);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(bus.peek(0x0002_3456), 0xAA);
+
+    // The PLCC package drives A20 and A21 as well.
+    let (mut cpu, mut bus) = setup(CpuType::M68008, &[0x1080]);
+    cpu.set_address_bits(22);
+    cpu.set_d(0, 0xAA);
+    cpu.set_a(0, 0x0052_3456);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(bus.peek(0x0012_3456), 0xAA);
+}
+
+#[test]
+fn test_68008_odd_word_access_is_address_error() {
+    let (mut cpu, mut bus) = setup(CpuType::M68008, &MOVE_L_IND_D0);
+    bus.poke_words(0x000C, &[0x0000, 0x2000]);
+    cpu.set_a(0, 0x4001);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x2000);
+}
+
+#[test]
+fn test_68012_address_bus_width() {
+    // MOVE.B D0,(A0)
+    let (mut cpu, mut bus) = setup(CpuType::M68012, &[0x1080]);
+    cpu.set_d(0, 0x55);
+    cpu.set_a(0, 0xFF00_0010);
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(bus.peek(0x7F00_0010), 0x55);
+    assert_eq!(bus.peek(0x0000_0010), 0);
+}
+
+#[test]
+fn test_programming_models() {
+    // MOVEC VBR,D0 is a 68010 instruction.
+    let movec = [0x4E7A, 0x0801];
+    let (mut cpu, mut bus) = setup(CpuType::M68012, &movec);
+    cpu.vbr = 0x1234;
+    step_cycles(&mut cpu, &mut bus);
+    assert_eq!(cpu.d(0), 0x1234);
+
+    let (mut cpu, mut bus) = setup(CpuType::M68008, &movec);
+    assert!(matches!(
+        cpu.step(&mut bus),
+        StepResult::IllegalInstruction { .. }
+    ));
+
+    // EXTB.L is not (68020+).
+    let (mut cpu, mut bus) = setup(CpuType::M68012, &[0x49C0]);
+    assert!(matches!(
+        cpu.step(&mut bus),
+        StepResult::IllegalInstruction { .. }
+    ));
+}
@@ -0,0 +1,167 @@
+//! MC68060 model: PCR and the MOVEC register set, the unimplemented integer
+//! instruction traps (vector 61), the FPU instructions and data types left to
+//! software, and the 12-byte FSAVE frames.
+
+mod common;
+
+use common::flat::{FlatBus, STACK, step_n};
+use m68k::core::memory::AddressBus;
+use m68k::fpu::FpuType;
+use m68k::{CpuCore, CpuType, StepResult};
+
+const HANDLER: u32 = 0x3000;
+
+/// `cpu_type` with `code` at 0x1000, A0 = 0x2000 and vectors 55 and 61 pointing at
+/// `HANDLER`.
+fn setup(cpu_type: CpuType, code: &[u16]) -> (CpuCore, FlatBus) {
+    let (mut cpu, mut bus) = common::flat::setup(cpu_type, code);
+    bus.write_long(55 * 4, HANDLER);
+    bus.write_long(61 * 4, HANDLER);
+    cpu.set_a(0, 0x2000);
+    (cpu, bus)
+}
+
+/// Assert that the last step took `vector` with a format $0 frame holding the
+/// instruction at 0x1000.
+fn assert_pre_instruction_trap(cpu: &CpuCore, bus: &mut FlatBus, vector: u16) {
+    assert_eq!(cpu.pc, HANDLER);
+    assert_eq!(cpu.a(7), STACK - 8);
+    assert_eq!(bus.read_long(STACK - 6), 0x1000);
+    assert_eq!(bus.read_word(STACK - 2), vector << 2);
+}
+
+#[test]
+fn test_pcr_reads_id_and_keeps_only_writable_bits() {
+    // MOVEC PCR,D0 ; MOVEC D1,PCR ; MOVEC PCR,D2
+    let code = [0x4E7A, 0x0808, 0x4E7B, 0x1808, 0x4E7A, 0x2808];
+    let (mut cpu, mut bus) = setup(CpuType::M68060, &code);
+    cpu.set_d(1, 0xFFFF_FFFF);
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(cpu.d(0), 0x0430_0100);
+    assert_eq!(cpu.d(2), 0x0430_0183);
+
+    let (mut cpu, mut bus) = setup(CpuType::M68LC060, &code);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(0) >> 16, 0x0431);
+}
+
+#[test]
+fn test_movec_register_set() {
+    // MOVEC D0,BUSCR ; MOVEC BUSCR,D1 ; MOVEC D0,URP
+    let (mut cpu, mut bus) = setup(
+        CpuType::M68060,
+        &[0x4E7B, 0x0008, 0x4E7A, 0x1008, 0x4E7B, 0x0806],
+    );
+    cpu.set_d(0, 0xA000_0000);
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(cpu.buscr, 0xA000_0000);
+    assert_eq!(cpu.d(1), 0xA000_0000);
+    assert_eq!(cpu.urp, 0xA000_0000);
+
+    // No MSP, CAAR or MMUSR.
+    for reg in [0x803, 0x802, 0x805] {
+        let (mut cpu, mut bus) = setup(CpuType::M68060, &[0x4E7A, reg]);
+        assert!(
+            matches!(
+                cpu.step(&mut bus),
+                StepResult::IllegalInstruction { opcode: 0x4E7A }
+            ),
+            "{reg:#05X}"
+        );
+    }
+}
+
+#[test]
+fn test_no_master_mode() {
+    let (mut cpu, _) = setup(CpuType::M68060, &[]);
+    cpu.set_sr(0x3700);
+    assert_eq!(cpu.get_sr(), 0x2700);
+}
+
+#[test]
+fn test_unimplemented_integer_instructions_trap() {
+    for code in [
+        &[0x0108, 0x0000][..],                 // MOVEP.W 0(A0),D0
+        &[0x0EFC, 0x0000, 0x0000][..],         // CAS2.L
+        &[0x04D0, 0x0800][..],                 // CHK2.L (A0),D0
+        &[0x4C01, 0x2403][..],                 // MULU.L D1,D3:D2
+        &[0x4C41, 0x2403][..],                 // DIVU.L D1,D3:D2
+        &[0x4C01, 0x2C03][..],                 // MULS.L D1,D3:D2
+        &[0x0188, 0x0000, 0x4E71, 0x4E71][..], // MOVEP.W D0,0(A0)
+    ] {
+        let (mut cpu, mut bus) = setup(CpuType::M68060, code);
+        cpu.set_d(1, 3);
+        cpu.step(&mut bus);
+        assert_pre_instruction_trap(&cpu, &mut bus, 61);
+        assert_eq!(cpu.d(2), 0, "{:04X}", code[0]);
+    }
+
+    // The 32-bit forms still execute: MULU.L D1,D2 ; DIVU.L D1,D2
+    let (mut cpu, mut bus) = setup(CpuType::M68060, &[0x4C01, 0x2000, 0x4C41, 0x2002]);
+    cpu.set_d(1, 3);
+    cpu.set_d(2, 7);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(2), 21);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.d(2), 7);
+    assert_eq!(cpu.pc, 0x1008);
+}
+
+#[test]
+fn test_fpu_instructions_left_to_software() {
+    // FSIN FP0,FP1, FMOVECR #0,FP0 and FSEQ D0 take Line-F.
+    for code in [[0xF200, 0x008E], [0xF200, 0x5C00], [0xF240, 0x0001]] {
+        let (mut cpu, mut bus) = setup(CpuType::M68060, &code);
+        assert!(matches!(
+            cpu.step(&mut bus),
+            StepResult::FlineTrap { opcode } if opcode == code[0]
+        ));
+    }
+
+    // FMOVE.P (A0),FP0 takes the unimplemented data type exception.
+    let (mut cpu, mut bus) = setup(CpuType::M68060, &[0xF210, 0x4C00]);
+    cpu.step(&mut bus);
+    assert_pre_instruction_trap(&cpu, &mut bus, 55);
+
+    // FADD FP0,FP1 is implemented.
+    let (mut cpu, mut bus) = setup(CpuType::M68060, &[0xF200, 0x00A2]);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.pc, 0x1004);
+}
+
+#[test]
+fn test_pcr_dfp_and_lc060_disable_fpu() {
+    // MOVEC D0,PCR ; FNOP
+    let (mut cpu, mut bus) = setup(CpuType::M68060, &[0x4E7B, 0x0808, 0xF280, 0x0000]);
+    cpu.set_d(0, 0x02);
+    cpu.step(&mut bus);
+    assert!(matches!(
+        cpu.step(&mut bus),
+        StepResult::FlineTrap { opcode: 0xF280 }
+    ));
+
+    let (mut cpu, mut bus) = setup(CpuType::M68LC060, &[0xF280, 0x0000]);
+    assert_eq!(cpu.fpu_type, FpuType::None);
+    assert!(matches!(
+        cpu.step(&mut bus),
+        StepResult::FlineTrap { opcode: 0xF280 }
+    ));
+}
+
+#[test]
+fn test_fsave_frames_are_three_long_words() {
+    // FSAVE -(A0) ; FRESTORE (A0)+ ; FSAVE -(A0)
+    let (mut cpu, mut bus) = setup(CpuType::M68060, &[0xF320, 0xF358, 0xF320]);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.a(0), 0x2000 - 12);
+    assert_eq!(bus.read_long(0x2000 - 12), 0x0000_6000, "IDLE");
+
+    // Restoring a NULL frame resets the FPU; the next FSAVE writes NULL.
+    bus.write_long(0x2000 - 12, 0);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.a(0), 0x2000);
+    bus.write_long(0x2000 - 12, 0xFFFF_FFFF);
+    cpu.step(&mut bus);
+    assert_eq!(cpu.a(0), 0x2000 - 12);
+    assert_eq!(bus.read_long(0x2000 - 12), 0);
+}
@@ -0,0 +1,305 @@
+//! 68030 PMMU: CRP/SRP table walks and the 22-entry ATC with PFLUSH.
+
+mod common;
+
+use common::flat::{FlatBus, step_n};
+use m68k::core::memory::AddressBus;
+use m68k::{CpuCore, CpuType};
+
+const TABLE_A: u32 = 0x4000;
+const TABLE_B: u32 = 0x4100;
+
+/// 68030 with `code` at 0x1000 and translation enabled: 4K pages, TIA = TIB = 10 bits,
+/// with table B identity-mapping the 64K of memory using page descriptors.
+fn setup(code: &[u16]) -> (CpuCore, FlatBus) {
+    let (mut cpu, mut bus) = common::flat::setup(CpuType::M68030, code);
+
+    bus.write_long(TABLE_A, TABLE_B | 2);
+    for page in 0..16 {
+        bus.write_long(TABLE_B + page * 4, (page << 12) | 1);
+    }
+
+    cpu.mmu_tc = 0x80C0_AA00;
+    cpu.mmu_crp_limit = 0x0000_0002;
+    cpu.mmu_crp_aptr = TABLE_A;
+    cpu.pmmu_enabled = true;
+    (cpu, bus)
+}
+
+/// MOVE.L $5000,D<n>
+fn read_5000(n: u16) -> [u16; 2] {
+    [0x2038 | (n << 9), 0x5000]
+}
+
+#[test]
+fn test_atc_entries_and_pflush_by_fc() {
+    let mut code = Vec::new();
+    code.extend_from_slice(&read_5000(1));
+    code.extend_from_slice(&[0xF000, 0x30F1]); // PFLUSH #1,#7 (user data only)
+    code.extend_from_slice(&read_5000(2));
+    code.extend_from_slice(&[0xF010, 0x38F5]); // PFLUSH #5,#7,(A0)
+    code.extend_from_slice(&read_5000(3));
+    let (mut cpu, mut bus) = setup(&code);
+    cpu.set_a(0, 0x5000);
+    bus.write_long(0x5000, 0x5555_5555);
+    bus.write_long(0x9000, 0x9999_9999);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(1), 0x5555_5555);
+    assert!(cpu.atc.instruction.is_none(), "the 68030 ATC is unified");
+    assert_eq!(cpu.atc.data.capacity(), 22);
+    let mut cached: Vec<_> = cpu
+        .atc
+        .data
+        .entries()
+        .iter()
+        .map(|e| (e.fc, e.logical, e.physical))
+        .collect();
+    cached.sort();
+    assert_eq!(cached, [(5, 0x5000, 0x5000), (6, 0x1000, 0x1000)]);
+
+    bus.write_long(TABLE_B + 5 * 4, 0x9000 | 1);
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(2), 0x5555_5555, "FC 1 does not match the FC 5 entry");
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(3), 0x9999_9999);
+}
+
+#[test]
+fn test_pflusha_and_pmove_flush() {
+    let mut code = Vec::new();
+    code.extend_from_slice(&read_5000(1));
+    code.extend_from_slice(&[0xF000, 0x2400]); // PFLUSHA
+    code.extend_from_slice(&read_5000(2));
+    code.extend_from_slice(&[0xF010, 0x4100]); // PMOVEFD (A0),TC
+    code.extend_from_slice(&read_5000(3));
+    code.extend_from_slice(&[0xF010, 0x4000]); // PMOVE (A0),TC
+    let (mut cpu, mut bus) = setup(&code);
+    cpu.set_a(0, 0x2000);
+    bus.write_long(0x2000, 0x80C0_AA00);
+    bus.write_long(0x9000, 0x9999_9999);
+    let caches_5000 = |cpu: &CpuCore| cpu.atc.data.entries().iter().any(|e| e.logical == 0x5000);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert!(caches_5000(&cpu));
+    bus.write_long(TABLE_B + 5 * 4, 0x9000 | 1);
+    step_n(&mut cpu, &mut bus, 1);
+    assert!(!caches_5000(&cpu));
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(2), 0x9999_9999);
+
+    bus.write_long(TABLE_B + 5 * 4, 0x5000 | 1);
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(3), 0x9999_9999, "PMOVEFD keeps the ATC");
+    step_n(&mut cpu, &mut bus, 1);
+    assert!(!caches_5000(&cpu));
+}
+
+#[test]
+fn test_ptest_fills_mmusr_and_returns_descriptor_address() {
+    // PTESTR #5,(A0),#7,A1 ; PMOVE PSR,(A2) ; PTESTR #5,(A3),#7 ; PTESTR #5,(A0),#1
+    let (mut cpu, mut bus) = setup(&[
+        0xF010, 0x9F35, 0xF012, 0x6200, 0xF013, 0x9E15, 0xF010, 0x8615,
+    ]);
+    cpu.set_a(0, 0x5000);
+    cpu.set_a(2, 0x2000);
+    cpu.set_a(3, 0x6000);
+    // Page 5 is write-protected and modified; page 6 is invalid.
+    bus.write_long(TABLE_B + 5 * 4, 0x5000 | 0x10 | 0x4 | 1);
+    bus.write_long(TABLE_B + 6 * 4, 0);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.mmu_sr, 0x0A02, "W, M and two levels");
+    assert_eq!(cpu.a(1), TABLE_B + 5 * 4);
+    assert_eq!(bus.read_word(0x2000), 0x0A02);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.mmu_sr, 0x0402, "I and two levels");
+
+    // Stopping at level 1 never reaches the page descriptor.
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.mmu_sr, 0x0001);
+}
+
+#[test]
+fn test_pload_and_level_zero_ptest() {
+    // PTESTR #1,(A0),#0 ; PLOADR #1,(A0) ; PTESTR #1,(A0),#0
+    let (mut cpu, mut bus) = setup(&[0xF010, 0x8211, 0xF010, 0x2211, 0xF010, 0x8211]);
+    cpu.set_a(0, 0x5000);
+    bus.write_long(TABLE_B + 5 * 4, 0x5000 | 0x10 | 1);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.mmu_sr, 0x0400, "no ATC entry yet");
+    step_n(&mut cpu, &mut bus, 1);
+    assert!(
+        cpu.atc
+            .data
+            .entries()
+            .iter()
+            .any(|e| e.fc == 1 && e.logical == 0x5000)
+    );
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.mmu_sr, 0x0200, "ATC hit reports M");
+}
+
+#[test]
+fn test_write_protect_and_history_bits() {
+    // MOVE.L $5000,D2 ; MOVE.L D1,$5000 ; MOVE.L D1,$6000
+    let (mut cpu, mut bus) = setup(&[0x2438, 0x5000, 0x21C1, 0x5000, 0x21C1, 0x6000]);
+    bus.write_long(2 * 4, 0x3000);
+    bus.write_long(TABLE_B + 6 * 4, 0x6000 | 4 | 1);
+    cpu.set_d(1, 0xDEAD_BEEF);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(bus.read_long(TABLE_A), TABLE_B | 0x8 | 2);
+    assert_eq!(bus.read_long(TABLE_B + 5 * 4), 0x5000 | 0x8 | 1);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(bus.read_long(TABLE_B + 5 * 4), 0x5000 | 0x18 | 1);
+    assert_eq!(bus.read_long(0x5000), 0xDEAD_BEEF);
+
+    // Write-protected pages take a bus error and are not marked modified.
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, 0x3000);
+    assert_eq!(bus.read_long(TABLE_B + 6 * 4), 0x6000 | 0x8 | 4 | 1);
+    assert_eq!(bus.read_long(0x6000), 0);
+}
+
+#[test]
+fn test_supervisor_only_long_descriptor() {
+    // ANDI #$DFFF,SR ; MOVE.L $4FFC,D1 ; MOVE.L $5000,D2
+    let (mut cpu, mut bus) = setup(&[0x027C, 0xDFFF, 0x2238, 0x4FFC, 0x2438, 0x5000]);
+    bus.write_long(2 * 4, 0x3000);
+    // Table B in 8-byte format: status long, then page address.
+    const TABLE_B_LONG: u32 = 0x4400;
+    bus.write_long(TABLE_A, TABLE_B_LONG | 3);
+    for page in 0..16 {
+        let status = if page == 5 { 0x100 | 1 } else { 1 };
+        bus.write_long(TABLE_B_LONG + page * 8, status);
+        bus.write_long(TABLE_B_LONG + page * 8 + 4, page << 12);
+    }
+    bus.write_long(0x4FFC, 0x4444_4444);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert!(!cpu.is_supervisor());
+    assert_eq!(cpu.d(1), 0x4444_4444);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, 0x3000);
+    assert_eq!(cpu.d(2), 0);
+}
+
+#[test]
+fn test_function_code_lookup_level() {
+    // MOVE.L $5000,D1 with TC.FCL: supervisor data and program go through separate trees.
+    let (mut cpu, mut bus) = setup(&read_5000(1));
+    const FC_TABLE: u32 = 0x4600;
+    const DATA_A: u32 = 0x4800;
+    const DATA_B: u32 = 0x4900;
+    cpu.mmu_tc = 0x81C0_AA00;
+    cpu.mmu_crp_limit = 0x0007_0002;
+    cpu.mmu_crp_aptr = FC_TABLE;
+    bus.write_long(FC_TABLE + 5 * 4, DATA_A | 2);
+    bus.write_long(FC_TABLE + 6 * 4, TABLE_A | 2);
+    bus.write_long(DATA_A, DATA_B | 2);
+    bus.write_long(DATA_B + 5 * 4, 0x9000 | 1);
+    bus.write_long(0x5000, 0x5555_5555);
+    bus.write_long(0x9000, 0x9999_9999);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(1), 0x9999_9999);
+    assert_eq!(cpu.pc, 0x1004);
+}
+
+#[test]
+fn test_table_limits() {
+    // MOVE.L $4000,D1 ; PTESTR #5,(A0),#7 ; MOVE.L $5000,D2
+    let (mut cpu, mut bus) = setup(&[0x2238, 0x4000, 0xF010, 0x9E15, 0x2438, 0x5000]);
+    bus.write_long(2 * 4, 0x3000);
+    cpu.set_a(0, 0x5000);
+    // Table A in 8-byte format; its only descriptor limits table B to indices 0-4.
+    const TABLE_A_LONG: u32 = 0x4800;
+    cpu.mmu_crp_limit = 0x0000_0003;
+    cpu.mmu_crp_aptr = TABLE_A_LONG;
+    bus.write_long(TABLE_A_LONG, 0x0004_0002);
+    bus.write_long(TABLE_A_LONG + 4, TABLE_B);
+    bus.write_long(0x4000, 0x4444_4444);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(1), 0x4444_4444);
+    assert_eq!(cpu.mmu_sr, 0x4401, "L, I and one level");
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, 0x3000, "limit violations are bus errors");
+
+    // A lower limit rejects indices below it.
+    let (mut cpu, mut bus) = setup(&read_5000(1));
+    bus.write_long(2 * 4, 0x3000);
+    cpu.mmu_crp_limit = 0x8001_0002;
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, 0x3000);
+}
+
+#[test]
+fn test_indirect_descriptors() {
+    // MOVE.L $5000,D1 ; MOVE.L $6000,D2 ; PTESTR #5,(A0),#7
+    let (mut cpu, mut bus) = setup(&[0x2238, 0x5000, 0x2438, 0x6000, 0xF010, 0x9E15]);
+    cpu.set_a(0, 0x5000);
+    // Page 5 through a 4-byte indirect descriptor, page 6 through an 8-byte one.
+    bus.write_long(TABLE_B + 5 * 4, 0x4A00 | 2);
+    bus.write_long(0x4A00, 0x9000 | 1);
+    bus.write_long(TABLE_B + 6 * 4, 0x4A10 | 3);
+    bus.write_long(0x4A10, 1);
+    bus.write_long(0x4A14, 0xA000);
+    bus.write_long(0x9000, 0x9999_9999);
+    bus.write_long(0xA000, 0xAAAA_AAAA);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(1), 0x9999_9999);
+    assert_eq!(cpu.d(2), 0xAAAA_AAAA);
+    assert_eq!(
+        bus.read_long(0x4A00),
+        0x9000 | 0x8 | 1,
+        "U lands in the page descriptor"
+    );
+    assert_eq!(bus.read_long(TABLE_B + 5 * 4), 0x4A00 | 2);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.mmu_sr, 0x0003, "indirect descriptors count as a level");
+}
+
+#[test]
+fn test_root_page_descriptor() {
+    // The root pointer maps the whole address space linearly from 0x100.
+    let code = read_5000(1);
+    let (mut cpu, mut bus) = setup(&code);
+    bus.write_words(0x1100, &code);
+    bus.write_long(TABLE_A, 0);
+    cpu.mmu_crp_limit = 0x0000_0001;
+    cpu.mmu_crp_aptr = 0x0100;
+    bus.write_long(0x5100, 0x5151_5151);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(1), 0x5151_5151);
+}
+
+#[test]
+fn test_invalid_configuration_faults() {
+    // PMOVE (A0),TC ; PMOVE (A1),CRP
+    let (mut cpu, mut bus) = setup(&[0xF010, 0x4000, 0xF011, 0x4C00]);
+    bus.write_long(56 * 4, 0x3000);
+    cpu.set_a(0, 0x2000);
+    cpu.set_a(1, 0x2008);
+    // PS 4K + TIA 10 + TIB 8 is only 30 bits.
+    bus.write_long(0x2000, 0x80C0_A800);
+    bus.write_long(0x2008, 0x7FFF_0000);
+    bus.write_long(0x200C, TABLE_A);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, 0x3000);
+    assert_eq!(cpu.mmu_tc, 0x80C0_AA00, "TC is not loaded");
+
+    cpu.pc = 0x1004;
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, 0x3000);
+    assert_eq!(cpu.mmu_crp_limit, 0x0000_0002, "CRP is not loaded");
+}
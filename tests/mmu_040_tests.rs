@@ -0,0 +1,305 @@
+//! Native 68040 MMU: URP/SRP-rooted three-level table walk with 4K and 8K pages.
+
+mod common;
+
+use common::flat::{FlatBus, step_n};
+use m68k::core::memory::AddressBus;
+use m68k::{CpuCore, CpuType};
+
+const ACCESS_FAULT_HANDLER: u32 = 0x3000;
+const ROOT_TABLE: u32 = 0x4000;
+const POINTER_TABLE: u32 = 0x4200;
+const PAGE_TABLE: u32 = 0x4400;
+
+/// `cpu_type` with `code` at 0x1000, URP = SRP = `ROOT_TABLE` and D0 = `tc`.
+///
+/// The root and pointer tables hold a single resident entry each, so every
+/// address below 256K goes through the page table at `PAGE_TABLE`, which
+/// starts out identity-mapping the whole 64K of memory.
+fn setup(cpu_type: CpuType, tc: u32, code: &[u16]) -> (CpuCore, FlatBus) {
+    let (mut cpu, mut bus) = common::flat::setup(cpu_type, code);
+    bus.write_long(2 * 4, ACCESS_FAULT_HANDLER);
+
+    bus.write_long(ROOT_TABLE, POINTER_TABLE | 2);
+    bus.write_long(POINTER_TABLE, PAGE_TABLE | 2);
+    let page_size = if tc & 0x4000 != 0 { 0x2000 } else { 0x1000 };
+    for page in 0..0x10000 / page_size {
+        bus.write_long(PAGE_TABLE + page * 4, (page * page_size) | 1);
+    }
+
+    cpu.set_d(0, tc);
+    cpu.urp = ROOT_TABLE;
+    cpu.srp = ROOT_TABLE;
+    (cpu, bus)
+}
+
+/// MOVEC D0,TC ; MOVE.L $5000,D1 ; MOVE.L $7000,D2
+const READ_5000_7000: [u16; 6] = [0x4E7B, 0x0003, 0x2238, 0x5000, 0x2438, 0x7000];
+
+#[test]
+fn test_4k_pages_remap_and_indirect() {
+    for cpu_type in [CpuType::M68040, CpuType::M68LC040] {
+        let (mut cpu, mut bus) = setup(cpu_type, 0x8000, &READ_5000_7000);
+        // Logical page 5 -> physical 0x8000; page 7 via an indirect descriptor to 0xA000.
+        bus.write_long(PAGE_TABLE + 5 * 4, 0x8000 | 1);
+        bus.write_long(PAGE_TABLE + 7 * 4, 0x4800 | 2);
+        bus.write_long(0x4800, 0xA000 | 3);
+        bus.write_long(0x5000, 0x1111_1111);
+        bus.write_long(0x8000, 0x5555_8888);
+        bus.write_long(0xA000, 0x9999_AAAA);
+
+        step_n(&mut cpu, &mut bus, 3);
+        assert!(
+            cpu.pmmu_enabled,
+            "{cpu_type:?}: TC.E should enable the walk"
+        );
+        assert_eq!(cpu.d(1), 0x5555_8888, "{cpu_type:?}");
+        assert_eq!(cpu.d(2), 0x9999_AAAA, "{cpu_type:?}");
+    }
+}
+
+#[test]
+fn test_8k_pages() {
+    let (mut cpu, mut bus) = setup(CpuType::M68040, 0xC000, &READ_5000_7000);
+    // 8K page 2 (0x4000-0x5FFF) -> 0xC000; 0x5000 lands 0x1000 into it.
+    bus.write_long(PAGE_TABLE + 2 * 4, 0xC000 | 1);
+    bus.write_long(0xD000, 0x0800_0D00);
+    bus.write_long(0x7000, 0x0000_7000);
+
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(cpu.d(1), 0x0800_0D00);
+    assert_eq!(cpu.d(2), 0x0000_7000);
+}
+
+#[test]
+fn test_tc_disabled_is_identity() {
+    let (mut cpu, mut bus) = setup(CpuType::M68040, 0x4000, &READ_5000_7000);
+    bus.write_long(PAGE_TABLE + 5 * 4, 0x8000 | 1);
+    bus.write_long(0x5000, 0x1111_1111);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert!(!cpu.pmmu_enabled);
+    assert_eq!(cpu.d(1), 0x1111_1111);
+}
+
+#[test]
+fn test_write_protect_and_supervisor_pages_fault() {
+    // MOVEC D0,TC ; MOVE.L D1,$6000
+    let (mut cpu, mut bus) = setup(CpuType::M68040, 0x8000, &[0x4E7B, 0x0003, 0x21C1, 0x6000]);
+    cpu.set_d(1, 0xDEAD_BEEF);
+    // Write protection in the pointer descriptor covers every page below it.
+    bus.write_long(POINTER_TABLE, PAGE_TABLE | 4 | 2);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.pc, ACCESS_FAULT_HANDLER);
+    assert_eq!(bus.read_long(0x6000), 0);
+
+    // Reads are still allowed; a user read of a supervisor-only page is not.
+    // MOVEC D0,TC ; ANDI #$DFFF,SR ; MOVE.L $6000,D1 ; MOVE.L $7000,D2
+    let (mut cpu, mut bus) = setup(
+        CpuType::M68040,
+        0x8000,
+        &[
+            0x4E7B, 0x0003, 0x027C, 0xDFFF, 0x2238, 0x6000, 0x2438, 0x7000,
+        ],
+    );
+    bus.write_long(PAGE_TABLE + 6 * 4, 0x6000 | 4 | 1);
+    bus.write_long(PAGE_TABLE + 7 * 4, 0x7000 | 0x80 | 1);
+    bus.write_long(0x6000, 0x6666_6666);
+
+    step_n(&mut cpu, &mut bus, 3);
+    assert!(!cpu.is_supervisor());
+    assert_eq!(cpu.d(1), 0x6666_6666);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, ACCESS_FAULT_HANDLER);
+    assert_eq!(cpu.d(2), 0);
+}
+
+#[test]
+fn test_invalid_descriptor_faults() {
+    let (mut cpu, mut bus) = setup(CpuType::M68040, 0x8000, &READ_5000_7000);
+    bus.write_long(PAGE_TABLE + 5 * 4, 0);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.pc, ACCESS_FAULT_HANDLER);
+}
+
+#[test]
+fn test_atc_holds_translations_until_pflush() {
+    // MOVEC D0,TC ; MOVE.L $5000,D1 ; MOVE.L $5000,D2 ; PFLUSHN (A0) ; MOVE.L $5000,D3 ;
+    // PFLUSH (A0) ; MOVE.L $5000,D4
+    let (mut cpu, mut bus) = setup(
+        CpuType::M68040,
+        0x8000,
+        &[
+            0x4E7B, 0x0003, 0x2238, 0x5000, 0x2438, 0x5000, 0xF500, 0x2638, 0x5000, 0xF508, 0x2838,
+            0x5000,
+        ],
+    );
+    cpu.set_a(0, 0x5000);
+    cpu.dfc = 5;
+    bus.write_long(PAGE_TABLE + 5 * 4, 0x5000 | 0x400 | 1);
+    bus.write_long(0x5000, 0x1111_1111);
+    bus.write_long(0x8000, 0x8888_8888);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(1), 0x1111_1111);
+    let data = cpu.atc.data.entries();
+    assert_eq!(data.len(), 1);
+    assert_eq!((data[0].fc, data[0].logical), (4, 0x5000));
+    assert!(data[0].global);
+    assert_eq!(
+        cpu.atc.instruction.as_ref().unwrap().entries()[0].logical,
+        0x1000,
+        "instruction fetches load the instruction ATC"
+    );
+
+    // The guest remaps the page; the stale translation stays in use.
+    bus.write_long(PAGE_TABLE + 5 * 4, 0x8000 | 0x400 | 1);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(2), 0x1111_1111);
+
+    // PFLUSHN keeps global pages, PFLUSH does not.
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(3), 0x1111_1111);
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(4), 0x8888_8888);
+}
+
+#[test]
+fn test_pflush_matches_dfc_and_pflusha_flushes_everything() {
+    // MOVEC D0,TC ; MOVE.L $5000,D1 ; PFLUSH (A0) ; PFLUSHAN ; PFLUSHA
+    let (mut cpu, mut bus) = setup(
+        CpuType::M68040,
+        0x8000,
+        &[0x4E7B, 0x0003, 0x2238, 0x5000, 0xF508, 0xF510, 0xF518],
+    );
+    cpu.set_a(0, 0x5000);
+    cpu.dfc = 1;
+    bus.write_long(PAGE_TABLE + 5 * 4, 0x5000 | 0x400 | 1);
+
+    step_n(&mut cpu, &mut bus, 3);
+    assert_eq!(
+        cpu.atc.data.entries().len(),
+        1,
+        "user DFC spares supervisor entries"
+    );
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(
+        cpu.atc.data.entries().len(),
+        1,
+        "PFLUSHAN spares global entries"
+    );
+    assert_eq!(cpu.atc.instruction.as_ref().unwrap().entries().len(), 0);
+    step_n(&mut cpu, &mut bus, 1);
+    assert!(cpu.atc.data.entries().is_empty());
+}
+
+#[test]
+fn test_ptest_fills_mmusr_and_loads_atc() {
+    // PTESTR (A0) ; PTESTW (A1) ; PTESTR (A2)
+    let (mut cpu, mut bus) = setup(CpuType::M68040, 0x8000, &[0xF568, 0xF549, 0xF56A]);
+    cpu.dfc = 5;
+    cpu.set_a(0, 0x5123);
+    cpu.set_a(1, 0x6000);
+    cpu.set_a(2, 0x7000);
+    // Page 5 -> 0x8000: global, supervisor, copyback, modified.
+    bus.write_long(PAGE_TABLE + 5 * 4, 0x8000 | 0x400 | 0x80 | 0x20 | 0x10 | 1);
+    bus.write_long(PAGE_TABLE + 6 * 4, 0x6000 | 0x4 | 1);
+    bus.write_long(PAGE_TABLE + 7 * 4, 0);
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.mmusr, 0x8000 | 0x4B0 | 1);
+    let data = cpu.atc.data.entries();
+    assert_eq!((data[0].logical, data[0].physical), (0x5000, 0x8000));
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.mmusr, 0x6000 | 0x4 | 1, "W and R");
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.mmusr, 0, "an invalid page clears R");
+}
+
+#[test]
+fn test_used_and_modified_bits_written_back() {
+    // MOVEC D0,TC ; MOVE.L $5000,D1 ; MOVE.L D1,$5000 ; MOVE.L D1,$6000
+    let (mut cpu, mut bus) = setup(
+        CpuType::M68040,
+        0x8000,
+        &[
+            0x4E7B, 0x0003, 0x2238, 0x5000, 0x21C1, 0x5000, 0x21C1, 0x6000,
+        ],
+    );
+    bus.write_long(PAGE_TABLE + 6 * 4, 0x6000 | 4 | 1);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(bus.read_long(ROOT_TABLE), POINTER_TABLE | 0x8 | 2);
+    assert_eq!(bus.read_long(POINTER_TABLE), PAGE_TABLE | 0x8 | 2);
+    assert_eq!(bus.read_long(PAGE_TABLE + 5 * 4), 0x5000 | 0x8 | 1);
+
+    // The cached entry is clean, so the write goes back to the tables to set M.
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(bus.read_long(PAGE_TABLE + 5 * 4), 0x5000 | 0x18 | 1);
+    assert!(
+        cpu.atc
+            .data
+            .entries()
+            .iter()
+            .any(|e| e.logical == 0x5000 && e.modified)
+    );
+
+    // A refused write leaves M clear.
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, ACCESS_FAULT_HANDLER);
+    assert_eq!(bus.read_long(PAGE_TABLE + 6 * 4), 0x6000 | 0x8 | 4 | 1);
+}
+
+#[test]
+fn test_write_protected_ttr_faults_writes() {
+    // MOVEC D0,TC ; MOVE.L $5000,D1 ; MOVE.L D1,$5000
+    let (mut cpu, mut bus) = setup(
+        CpuType::M68040,
+        0x8000,
+        &[0x4E7B, 0x0003, 0x2238, 0x5000, 0x21C1, 0x5000],
+    );
+    // DTT0 covers 0x00xxxxxx for supervisor accesses, with W set.
+    cpu.dtt0 = 0x0000_A004;
+    bus.write_long(PAGE_TABLE + 5 * 4, 0);
+    bus.write_long(0x5000, 0x5555_5555);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.d(1), 0x5555_5555, "the TTR bypasses the invalid page");
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, ACCESS_FAULT_HANDLER);
+    assert_eq!(bus.read_long(0x5000), 0x5555_5555);
+}
+
+#[test]
+fn test_move16_fault_takes_access_error_frame() {
+    // MOVEC D0,TC ; MOVE16 (A0)+,(A1)+
+    let (mut cpu, mut bus) = setup(CpuType::M68040, 0x8000, &[0x4E7B, 0x0003, 0xF620, 0x9000]);
+    bus.write_long(PAGE_TABLE + 5 * 4, 0);
+    cpu.set_a(0, 0x2000);
+    cpu.set_a(1, 0x5000);
+
+    step_n(&mut cpu, &mut bus, 2);
+    assert_eq!(cpu.pc, ACCESS_FAULT_HANDLER);
+    assert_eq!(
+        (cpu.a(0), cpu.a(1)),
+        (0x2000, 0x5000),
+        "registers rolled back"
+    );
+    let sp = cpu.a(7);
+    assert_eq!(bus.read_word(sp + 6), 0x7008);
+    assert_eq!(
+        bus.read_word(sp + 0x0C),
+        0x046D,
+        "ATC, write, line, MOVE16, TM 5"
+    );
+    assert_eq!(
+        bus.read_word(sp + 0x0E),
+        0,
+        "MOVE16 is restarted, not written back"
+    );
+    assert_eq!(bus.read_long(sp + 0x14), 0x5000);
+}
@@ -0,0 +1,131 @@
+//! 68000/68010 prefetch queue: stale opcodes after self-modifying writes, bus order, refills.
+
+mod common;
+
+use common::flat::{FlatBus, step_n};
+use m68k::core::memory::{AddressBus, BusCycle};
+use m68k::{CpuCore, CpuType};
+
+const ADDRESS_ERROR_HANDLER: u32 = 0x3000;
+const ZERO_DIVIDE_HANDLER: u32 = 0x3800;
+
+/// Memory that logs every reported bus cycle.
+struct TestBus {
+    flat: FlatBus,
+    cycles: Vec<BusCycle>,
+}
+
+impl AddressBus for TestBus {
+    fn read_byte(&mut self, address: u32) -> u8 {
+        self.flat.read_byte(address)
+    }
+
+    fn read_word(&mut self, address: u32) -> u16 {
+        self.flat.read_word(address)
+    }
+
+    fn read_long(&mut self, address: u32) -> u32 {
+        self.flat.read_long(address)
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) {
+        self.flat.write_byte(address, value);
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) {
+        self.flat.write_word(address, value);
+    }
+
+    fn write_long(&mut self, address: u32, value: u32) {
+        self.flat.write_long(address, value);
+    }
+
+    fn bus_cycle(&mut self, cycle: BusCycle) -> u32 {
+        self.cycles.push(cycle);
+        0
+    }
+}
+
+/// `cpu_type` with prefetch emulation set to `prefetch` and `code` at 0x1000.
+fn setup(cpu_type: CpuType, prefetch: bool, code: &[u16]) -> (CpuCore, TestBus) {
+    let (mut cpu, mut flat) = common::flat::setup(cpu_type, code);
+    cpu.set_prefetch_emulation(prefetch);
+    flat.write_long(3 * 4, ADDRESS_ERROR_HANDLER);
+    flat.write_long(5 * 4, ZERO_DIVIDE_HANDLER);
+    flat.write_words(ADDRESS_ERROR_HANDLER, &[0x4E71, 0x4E71]);
+    flat.write_words(ZERO_DIVIDE_HANDLER, &[0x7005, 0x4E71]);
+    let bus = TestBus {
+        flat,
+        cycles: Vec::new(),
+    };
+    (cpu, bus)
+}
+
+#[test]
+fn test_write_to_prefetched_opcode_is_not_seen() {
+    // MOVE.W D1,$1004 ; MOVEQ #1,D0 (overwritten with MOVEQ #2,D0)
+    let code = [0x31C1, 0x1004, 0x7001];
+    for (cpu_type, prefetch, expected) in [
+        (CpuType::M68000, true, 1),
+        (CpuType::M68010, true, 1),
+        (CpuType::M68000, false, 2),
+        // Prefetch emulation only applies to the 68000 and 68010.
+        (CpuType::M68020, true, 2),
+    ] {
+        let (mut cpu, mut bus) = setup(cpu_type, prefetch, &code);
+        cpu.set_d(1, 0x7002);
+        step_n(&mut cpu, &mut bus, 2);
+        assert_eq!(bus.read_word(0x1004), 0x7002);
+        assert_eq!(cpu.d(0), expected, "{cpu_type:?}, prefetch {prefetch}");
+    }
+}
+
+#[test]
+fn test_instruction_stream_read_two_words_ahead() {
+    // NOP ; MOVE.W $4000,D0 ; NOP
+    let (mut cpu, mut bus) = setup(CpuType::M68000, true, &[0x4E71, 0x3038, 0x4000, 0x4E71]);
+    cpu.set_bus_cycle_timing(true);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.prefetch(), Some([0x3038, 0x4000]));
+
+    // The opcode and extension word are already queued: np nr np.
+    bus.cycles.clear();
+    step_n(&mut cpu, &mut bus, 1);
+    let cycles: Vec<_> = bus.cycles.iter().map(|c| (c.address, c.fc)).collect();
+    assert_eq!(cycles, [(0x1006, 6), (0x4000, 5), (0x1008, 6)]);
+    assert_eq!(cpu.prefetch(), Some([0x4E71, 0x0000]));
+}
+
+#[test]
+fn test_queue_refilled_at_branch_and_exception_targets() {
+    // BRA.S *+6 ; (skipped) ; DIVU D1,D0 with D1 = 0
+    let (mut cpu, mut bus) = setup(CpuType::M68000, true, &[0x6004, 0x7001, 0x7002, 0x80C1]);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, 0x1006);
+    assert_eq!(cpu.prefetch(), Some([0x80C1, 0x0000]));
+
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.pc, ZERO_DIVIDE_HANDLER);
+    assert_eq!(cpu.prefetch(), Some([0x7005, 0x4E71]));
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.d(0), 5);
+
+    // Without emulation there is no queue to report.
+    let (mut cpu, mut bus) = setup(CpuType::M68000, false, &[0x4E71]);
+    step_n(&mut cpu, &mut bus, 1);
+    assert_eq!(cpu.prefetch(), None);
+}
+
+#[test]
+fn test_address_error_frame_holds_opcode() {
+    // JMP (A0) to an odd address.
+    for prefetch in [false, true] {
+        let (mut cpu, mut bus) = setup(CpuType::M68000, prefetch, &[0x4ED0, 0x4E71]);
+        cpu.set_a(0, 0x2001);
+        step_n(&mut cpu, &mut bus, 2);
+        assert_eq!(cpu.pc, ADDRESS_ERROR_HANDLER, "prefetch {prefetch}");
+        let sp = cpu.a(7);
+        assert_eq!(bus.read_long(sp + 8), 0x2001, "prefetch {prefetch}");
+        assert_eq!(bus.read_word(sp + 6), 0x4ED0, "prefetch {prefetch}");
+    }
+}
@@ -0,0 +1,341 @@
+//! SCC68070 on-chip peripherals: register map, timers, UART, I²C, DMA and the on-chip
+//! interrupt path.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use m68k::core::memory::AddressBus;
+use m68k::scc68070::dma::{ccr, csr};
+use m68k::scc68070::i2c::{I2cBus, icr, isr};
+use m68k::scc68070::timer::{PRESCALE, tsr};
+use m68k::scc68070::uart::usr;
+use m68k::scc68070::{BASE, ON_CHIP_VECTOR};
+use m68k::{CpuCore, CpuType, Scc68070, StepResult};
+
+const LIR: u32 = BASE + 0x1001;
+const IDR: u32 = BASE + 0x2001;
+const ISR: u32 = BASE + 0x2005;
+const ICR: u32 = BASE + 0x2007;
+const USR: u32 = BASE + 0x2013;
+const UCR: u32 = BASE + 0x2017;
+const UTHR: u32 = BASE + 0x2019;
+const URHR: u32 = BASE + 0x201B;
+const TSR: u32 = BASE + 0x2020;
+const TCR: u32 = BASE + 0x2021;
+const RRR: u32 = BASE + 0x2022;
+const T0: u32 = BASE + 0x2024;
+const T1: u32 = BASE + 0x2026;
+const PICR1: u32 = BASE + 0x2045;
+const PICR2: u32 = BASE + 0x2047;
+const DMA1: u32 = BASE + 0x4040;
+
+/// RAM below 64K, with the peripheral block forwarded the way a host bus would.
+struct HostBus {
+    ram: Vec<u8>,
+    scc: Scc68070,
+}
+
+impl HostBus {
+    fn new() -> Self {
+        Self {
+            ram: vec![0; 0x10000],
+            scc: Scc68070::new(),
+        }
+    }
+}
+
+impl AddressBus for HostBus {
+    fn read_byte(&mut self, address: u32) -> u8 {
+        if self.scc.contains(address) {
+            return self.scc.read_byte(address);
+        }
+        self.ram[(address & 0xFFFF) as usize]
+    }
+
+    fn read_word(&mut self, address: u32) -> u16 {
+        if self.scc.contains(address) {
+            return self.scc.read_word(address);
+        }
+        ((self.read_byte(address) as u16) << 8) | self.read_byte(address + 1) as u16
+    }
+
+    fn read_long(&mut self, address: u32) -> u32 {
+        ((self.read_word(address) as u32) << 16) | self.read_word(address + 2) as u32
+    }
+
+    fn write_byte(&mut self, address: u32, value: u8) {
+        if self.scc.contains(address) {
+            return self.scc.write_byte(address, value);
+        }
+        self.ram[(address & 0xFFFF) as usize] = value;
+    }
+
+    fn write_word(&mut self, address: u32, value: u16) {
+        if self.scc.contains(address) {
+            return self.scc.write_word(address, value);
+        }
+        self.write_byte(address, (value >> 8) as u8);
+        self.write_byte(address + 1, value as u8);
+    }
+
+    fn write_long(&mut self, address: u32, value: u32) {
+        self.write_word(address, (value >> 16) as u16);
+        self.write_word(address + 2, value as u16);
+    }
+
+    fn interrupt_acknowledge(&mut self, level: u8) -> u32 {
+        self.scc.interrupt_acknowledge(level).unwrap_or(0xFFFF_FFFF)
+    }
+}
+
+#[test]
+fn test_register_map() {
+    let mut bus = HostBus::new();
+    bus.write_word(RRR, 0x1234);
+    bus.write_word(T1, 0xBEEF);
+    bus.write_byte(PICR1, 0x35);
+    bus.write_long(DMA1 + 0x0C, 0x0012_3456);
+    bus.write_word(DMA1 + 0x0A, 0x0010);
+
+    assert_eq!(bus.scc.timers.reload, 0x1234);
+    assert_eq!(bus.read_word(T1), 0xBEEF);
+    assert_eq!(bus.read_byte(PICR1), 0x35);
+    assert_eq!(bus.scc.dma[1].mar, 0x0012_3456);
+    assert_eq!(bus.read_long(DMA1 + 0x0C), 0x0012_3456);
+    assert_eq!(bus.scc.dma[1].mtc, 0x0010);
+    assert!(!bus.scc.contains(0x0000_1000));
+}
+
+#[test]
+fn test_timer0_overflow_reloads_and_interrupts() {
+    let mut bus = HostBus::new();
+    bus.write_word(RRR, 0xFF00);
+    bus.write_word(T0, 0xFFFE);
+    bus.write_byte(PICR1, 0x04);
+
+    bus.scc.tick(PRESCALE - 1);
+    assert_eq!(bus.read_word(T0), 0xFFFE);
+    bus.scc.tick(1);
+    assert_eq!(bus.read_word(T0), 0xFFFF);
+    assert_eq!(bus.scc.irq_level(), 0);
+
+    bus.scc.tick(PRESCALE);
+    assert_eq!(bus.read_word(T0), 0xFF00);
+    assert_eq!(bus.read_byte(TSR), tsr::OV0);
+    assert_eq!(bus.scc.irq_level(), 4);
+
+    // TSR flags clear by writing ones.
+    bus.write_byte(TSR, tsr::OV0);
+    assert_eq!(bus.read_byte(TSR), 0);
+    assert_eq!(bus.scc.irq_level(), 0);
+}
+
+#[test]
+fn test_timer_match_capture_and_event_modes() {
+    let mut bus = HostBus::new();
+    // Timer 1 matches T0 = 3; timer 2 captures.
+    bus.write_byte(TCR, 0x12);
+    bus.write_word(T1, 3);
+    bus.scc.tick(PRESCALE * 3);
+    assert_eq!(bus.read_byte(TSR), tsr::MA1);
+
+    bus.scc.timers.input(2);
+    assert_eq!(bus.scc.timers.counter[2], 3);
+    assert_eq!(bus.read_byte(TSR), tsr::MA1 | tsr::CA2);
+
+    // Timer 1 as an event counter.
+    bus.write_byte(TSR, 0xFF);
+    bus.write_byte(TCR, 0x30);
+    bus.write_word(T1, 0xFFFF);
+    bus.scc.timers.input(1);
+    assert_eq!(bus.read_word(T1), 0);
+    assert_eq!(bus.read_byte(TSR), tsr::OV1);
+}
+
+#[test]
+fn test_uart_transmit_and_receive() {
+    let mut bus = HostBus::new();
+    bus.write_byte(PICR2, 0x52);
+    assert_eq!(bus.read_byte(USR) & usr::TXRDY, 0);
+
+    bus.write_byte(UCR, 0x05); // enable receiver and transmitter
+    assert_eq!(bus.read_byte(USR), usr::TXRDY | usr::TXEMT);
+    assert_eq!(bus.scc.irq_level(), 2);
+    bus.write_byte(UTHR, b'O');
+    bus.write_byte(UTHR, b'K');
+    assert_eq!(bus.scc.uart.take_transmitted(), Some(b'O'));
+    assert_eq!(bus.scc.uart.take_transmitted(), Some(b'K'));
+    assert_eq!(bus.scc.uart.take_transmitted(), None);
+
+    bus.scc.uart.receive(0x41);
+    bus.scc.uart.receive(0x42);
+    assert_eq!(
+        bus.read_byte(USR) & (usr::RXRDY | usr::OE),
+        usr::RXRDY | usr::OE
+    );
+    assert_eq!(bus.scc.irq_level(), 5);
+    assert_eq!(bus.read_byte(URHR), 0x41);
+    assert_eq!(bus.read_byte(USR) & usr::RXRDY, 0);
+
+    bus.write_byte(UCR, 0x40); // reset error status
+    assert_eq!(bus.read_byte(USR) & usr::OE, 0);
+}
+
+/// An I²C slave at address 0x50 that records what it receives and sends back a counter.
+#[derive(Default)]
+struct Slave {
+    log: Vec<String>,
+    next: u8,
+}
+
+struct SharedSlave(Rc<RefCell<Slave>>);
+
+impl I2cBus for SharedSlave {
+    fn start(&mut self, address: u8) -> bool {
+        self.0.borrow_mut().log.push(format!("start {address:02X}"));
+        address >> 1 == 0x50
+    }
+
+    fn write(&mut self, byte: u8) -> bool {
+        self.0.borrow_mut().log.push(format!("write {byte:02X}"));
+        true
+    }
+
+    fn read(&mut self, ack: bool) -> u8 {
+        let mut slave = self.0.borrow_mut();
+        slave.log.push(format!("read {ack}"));
+        slave.next += 1;
+        slave.next
+    }
+
+    fn stop(&mut self) {
+        self.0.borrow_mut().log.push("stop".into());
+    }
+}
+
+#[test]
+fn test_i2c_master_transfers() {
+    let mut bus = HostBus::new();
+    let slave = Rc::new(RefCell::new(Slave::default()));
+    bus.scc.i2c.attach(Box::new(SharedSlave(slave.clone())));
+    bus.write_byte(PICR1, 0x30);
+    bus.write_byte(ICR, icr::ESO | icr::ACK);
+
+    // Write 0x7F, then read one byte.
+    bus.write_byte(IDR, 0xA0);
+    bus.write_byte(ISR, isr::MST | isr::TRX | isr::BB);
+    assert_eq!(bus.read_byte(ISR), isr::MST | isr::TRX | isr::BB | isr::PIN);
+    assert_eq!(bus.scc.irq_level(), 3);
+    bus.write_byte(IDR, 0x7F);
+    bus.write_byte(ISR, isr::MST | isr::TRX | isr::BB);
+    assert_eq!(bus.read_byte(ISR) & isr::LRB, 0);
+    bus.write_byte(ISR, isr::MST | isr::BB);
+    assert_eq!(bus.read_byte(IDR), 1);
+    bus.write_byte(ISR, isr::MST);
+    assert_eq!(bus.read_byte(ISR) & (isr::BB | isr::PIN), 0);
+    assert_eq!(
+        slave.borrow().log,
+        ["start A0", "write 7F", "read true", "stop"]
+    );
+
+    // Nobody answers at another address.
+    bus.write_byte(IDR, 0x42);
+    bus.write_byte(ISR, isr::MST | isr::TRX | isr::BB);
+    assert_eq!(bus.read_byte(ISR) & isr::LRB, isr::LRB);
+}
+
+#[test]
+fn test_dma_device_to_memory() {
+    let mut bus = HostBus::new();
+    let mut ram = HostBus::new();
+    bus.write_byte(DMA1 + 0x05, 0x10); // device to memory, words
+    bus.write_byte(DMA1 + 0x06, 0x04); // increment MAR
+    bus.write_word(DMA1 + 0x0A, 2);
+    bus.write_long(DMA1 + 0x0C, 0x3000);
+    bus.write_byte(DMA1 + 0x07, ccr::SO | ccr::INE | 6);
+    assert_eq!(bus.read_byte(DMA1) & csr::CA, csr::CA);
+
+    let channel = &mut bus.scc.dma[1];
+    assert!(channel.request(&mut ram, &mut 0x1122));
+    assert!(channel.request(&mut ram, &mut 0x3344));
+    assert!(!channel.request(&mut ram, &mut 0x5566));
+    assert_eq!(ram.read_long(0x3000), 0x1122_3344);
+    assert_eq!(ram.read_word(0x3004), 0);
+    assert_eq!(bus.read_byte(DMA1), csr::COC);
+    assert_eq!(bus.read_long(DMA1 + 0x0C), 0x3004);
+    assert_eq!(bus.scc.irq_level(), 6);
+
+    bus.write_byte(DMA1, csr::COC);
+    assert_eq!(bus.scc.irq_level(), 0);
+}
+
+#[test]
+fn test_dma_memory_to_device_and_abort() {
+    let mut bus = HostBus::new();
+    let mut ram = HostBus::new();
+    ram.write_word(0x3000, 0xAB00);
+    bus.write_byte(DMA1 + 0x05, 0x80); // memory to device, bytes
+    bus.write_word(DMA1 + 0x0A, 4);
+    bus.write_long(DMA1 + 0x0C, 0x3000);
+    bus.write_byte(DMA1 + 0x07, ccr::SO);
+
+    let mut data = 0;
+    assert!(bus.scc.dma[1].request(&mut ram, &mut data));
+    assert_eq!(data, 0xAB);
+
+    bus.write_byte(DMA1 + 0x07, ccr::SA);
+    assert_eq!(bus.read_byte(DMA1), csr::ERR);
+    assert_eq!(bus.read_byte(DMA1 + 0x01), 0x11);
+    assert!(!bus.scc.dma[1].request(&mut ram, &mut data));
+}
+
+/// Step the CPU the way a host loop would (drive the interrupt level, step, tick) until
+/// it enters code at or above 0x2000. Returns the clocks run.
+fn run_to_handler(cpu: &mut CpuCore, bus: &mut HostBus) -> i32 {
+    let mut clocks = 0;
+    while cpu.pc < 0x2000 {
+        assert!(clocks < 10_000, "no interrupt taken");
+        bus.scc.update_irq(cpu, 0);
+        match cpu.step(bus) {
+            StepResult::Ok { cycles } => {
+                bus.scc.tick(cycles);
+                clocks += cycles;
+            }
+            other => panic!("unexpected {other:?}"),
+        }
+    }
+    clocks
+}
+
+#[test]
+fn test_on_chip_interrupt_reaches_cpu() {
+    let mut cpu = CpuCore::new();
+    cpu.set_cpu_type(CpuType::SCC68070);
+    let mut bus = HostBus::new();
+    // Level 3 on-chip vector -> 0x2000; level 3 autovector -> 0x2100.
+    bus.write_long((ON_CHIP_VECTOR + 3) * 4, 0x2000);
+    bus.write_long((24 + 3) * 4, 0x2100);
+    for i in 0..0x100 {
+        bus.write_word(0x1000 + i * 2, 0x4E71); // NOP
+    }
+    bus.write_word(0x2000, 0x4E71);
+    cpu.pc = 0x1000;
+    cpu.set_a(7, 0x8000);
+    cpu.set_sr(0x2000);
+
+    // Timer 0 one count from overflow, at level 3.
+    bus.write_word(T0, 0xFFFF);
+    bus.write_byte(PICR1, 0x03);
+    assert!(run_to_handler(&mut cpu, &mut bus) >= PRESCALE);
+    assert_eq!(cpu.pc, 0x2000);
+    assert_eq!(cpu.get_sr() & 0x0700, 0x0300);
+    // Format 0 frame with the on-chip vector offset.
+    assert_eq!(bus.read_word(0x8000 - 2), ((ON_CHIP_VECTOR + 3) * 4) as u16);
+
+    // An external INT1 input at level 5 uses the on-chip vectors too; an off-chip
+    // request at a level no on-chip source uses is autovectored.
+    bus.write_byte(LIR, 0x50);
+    bus.scc.set_int_pin(1, true);
+    assert_eq!(bus.scc.interrupt_acknowledge(5), Some(ON_CHIP_VECTOR + 5));
+    assert_eq!(bus.scc.interrupt_acknowledge(6), None);
+}
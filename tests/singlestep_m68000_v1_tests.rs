@@ -83,7 +83,7 @@ const REG_ORDER: [&str; 19] = [
 #[derive(Clone, Debug)]
 struct BinState {
     regs: [u32; REG_ORDER.len()],
-    #[allow(dead_code)]
+    /// IR and IRC: the words at the execution PC and PC + 2.
     prefetch: [u32; 2],
     /// RAM is stored as byte pieces: (address, byte_value)
     ram: Vec<(u32, u8)>,
@@ -618,6 +618,8 @@ fn load_state_68000(cpu: &mut CpuCore, state: &BinState) {
 
     // PC: upstream uses m_au (“next prefetch”), adjust to actual execution PC for our core.
     cpu.pc = mame_au_to_exec_pc(reg(state, "pc"));
+    cpu.set_prefetch_emulation(true);
+    cpu.set_prefetch(cpu.pc, [state.prefetch[0] as u16, state.prefetch[1] as u16]);
 
     // D0-D7, A0-A6
     for i in 0..8 {
@@ -712,9 +714,9 @@ fn check_state_68000(
             "{ctx}: SR mismatch (mask={sr_mask:#06X}) (got={actual_sr:#06X} expected={expected_sr:#06X})"
         ));
     }
-    // PC in these fixtures is MAME's `m_au` (next prefetch address) and is sensitive to prefetch
-    // modeling details. m68k currently doesn't emulate the full prefetch queue, so don't fail
-    // tests solely on PC unless explicitly requested.
+    // PC in these fixtures is MAME's `m_au` (next prefetch address) and, like the prefetch
+    // queue, depends on exactly when the final prefetch runs relative to the instruction's
+    // data cycles. Only check them when explicitly requested.
     if std::env::var("M68K_SST_STRICT_PC").ok().as_deref() == Some("1") {
         let exp_pc = reg(expected, "pc");
         let got_pc = exec_pc_to_mame_au(cpu.pc);
@@ -723,6 +725,13 @@ fn check_state_68000(
                 "{ctx}: PC mismatch (expected MAME m_au) (got={got_pc:#010X} expected={exp_pc:#010X})"
             ));
         }
+        let exp_prefetch = expected.prefetch.map(|w| w as u16);
+        if cpu.prefetch() != Some(exp_prefetch) {
+            return Err(format!(
+                "{ctx}: prefetch mismatch (got={:04X?} expected={exp_prefetch:04X?})",
+                cpu.prefetch()
+            ));
+        }
     }
     // USP/SSP can be affected by the exact sequence of predecrement/postincrement bus cycles on
     // address-error paths. Since we're not bus-cycle accurate, skip these comparisons for such